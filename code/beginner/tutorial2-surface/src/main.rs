@@ -16,7 +16,7 @@ struct WgpuApp {
     queue: wgpu::Queue,
     config: wgpu::SurfaceConfiguration,
     size: winit::dpi::PhysicalSize<u32>,
-    size_changed: bool,
+    resize_debouncer: utils::ResizeDebouncer,
 }
 
 impl WgpuApp {
@@ -65,8 +65,9 @@ impl WgpuApp {
 
         // The instance is a handle to our GPU
         // BackendBit::PRIMARY => Vulkan + Metal + DX12 + Browser WebGPU
+        // 可以用 WGPU_BACKEND 环境变量限制使用的后端（如 `WGPU_BACKEND=vulkan`），未设置时为全部后端
         let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::all(),
+            backends: utils::backend::backends_from_env(),
             ..Default::default()
         });
         let surface = instance.create_surface(window.clone()).unwrap();
@@ -113,7 +114,7 @@ impl WgpuApp {
             queue,
             config,
             size,
-            size_changed: false,
+            resize_debouncer: utils::ResizeDebouncer::new(),
         }
     }
 
@@ -123,20 +124,17 @@ impl WgpuApp {
     /// 当缩放浏览器窗口时, 窗口大小会以高于渲染帧率的频率发生变化，
     /// 如果窗口 size 发生变化就立即调整 surface 大小, 会导致缩放浏览器窗口大小时渲染画面闪烁。
     fn set_window_resized(&mut self, new_size: PhysicalSize<u32>) {
-        if new_size == self.size {
-            return;
-        }
-        self.size = new_size;
-        self.size_changed = true;
+        let current = PhysicalSize::new(self.config.width, self.config.height);
+        self.resize_debouncer.record(new_size, current);
     }
 
     /// 必要的时候调整 surface 大小
     fn resize_surface_if_needed(&mut self) {
-        if self.size_changed {
+        if let Some(size) = self.resize_debouncer.take_if_changed() {
+            self.size = size;
             self.config.width = self.size.width;
             self.config.height = self.size.height;
             self.surface.configure(&self.device, &self.config);
-            self.size_changed = false;
         }
     }
 
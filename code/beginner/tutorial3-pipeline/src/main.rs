@@ -1,31 +1,108 @@
 use app_surface::{AppSurface, SurfaceFrame};
 use std::sync::Arc;
-use utils::framework::{WgpuAppAction, run};
+use utils::config::DemoConfig;
+use utils::framework::{
+    RenderContext, RunConfig, WgpuAppAction, create_app_surface, run_with_config,
+};
+use utils::post::{AntiAliasing, AntiAliasingTargets};
 use winit::dpi::PhysicalSize;
 
 struct WgpuApp {
     app: AppSurface,
     size: PhysicalSize<u32>,
-    size_changed: bool,
+    resize_debouncer: utils::ResizeDebouncer,
     // NEW!
+    shader: wgpu::ShaderModule,
     render_pipeline: wgpu::RenderPipeline,
+    aa_targets: AntiAliasingTargets,
+    /// 可调参数，这里只用到了清屏颜色
+    demo_config: DemoConfig,
+    /// 运行期间修改 `config.toml` 后自动重新加载
+    #[cfg(not(target_arch = "wasm32"))]
+    config_watcher: utils::config::ConfigWatcher,
 }
 
 impl WgpuApp {
     /// 必要的时候调整 surface 大小
     fn resize_surface_if_needed(&mut self) {
-        if self.size_changed {
+        if let Some(size) = self.resize_debouncer.take_if_changed() {
+            self.size = size;
             self.app
                 .resize_surface_by_size((self.size.width, self.size.height));
-            self.size_changed = false;
+            self.aa_targets
+                .resize(&self.app.device, self.size.width, self.size.height);
         }
     }
+
+    /// 以 `sample_count` 创建三角形的渲染管线，切换抗锯齿方式导致采样数变化时需重建
+    fn create_render_pipeline(
+        device: &wgpu::Device,
+        shader: &wgpu::ShaderModule,
+        format: wgpu::TextureFormat,
+        sample_count: u32,
+    ) -> wgpu::RenderPipeline {
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Render Pipeline Layout"),
+                bind_group_layouts: &[],
+                push_constant_ranges: &[],
+            });
+
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Render Pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState {
+                        color: wgpu::BlendComponent::REPLACE,
+                        alpha: wgpu::BlendComponent::REPLACE,
+                    }),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: Some(wgpu::Face::Back),
+                polygon_mode: wgpu::PolygonMode::Fill,
+                // Requires Features::DEPTH_CLIP_CONTROL
+                unclipped_depth: false,
+                // Requires Features::CONSERVATIVE_RASTERIZATION
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            // If the pipeline will be used with a multiview render pass, this
+            // indicates how many array layers the attachments will have.
+            multiview: None,
+            cache: None,
+        })
+    }
 }
 
 impl WgpuAppAction for WgpuApp {
     async fn new(window: Arc<winit::window::Window>) -> Self {
-        // 创建 wgpu 应用
-        let app = AppSurface::new(window).await;
+        Self::new_with_config(window, &RunConfig::default()).await
+    }
+
+    async fn new_with_config(window: Arc<winit::window::Window>, config: &RunConfig) -> Self {
+        // 创建 wgpu 应用，surface 按 `config` 请求的呈现模式配置
+        let app = create_app_surface(window, config).await;
 
         // 创建着色器
         let shader = app
@@ -35,60 +112,18 @@ impl WgpuAppAction for WgpuApp {
                 source: wgpu::ShaderSource::Wgsl(include_str!("shader.wgsl").into()),
             });
 
-        let render_pipeline_layout =
-            app.device
-                .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                    label: Some("Render Pipeline Layout"),
-                    bind_group_layouts: &[],
-                    push_constant_ranges: &[],
-                });
-
-        let render_pipeline = app
-            .device
-            .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-                label: Some("Render Pipeline"),
-                layout: Some(&render_pipeline_layout),
-                vertex: wgpu::VertexState {
-                    module: &shader,
-                    entry_point: Some("vs_main"),
-                    compilation_options: Default::default(),
-                    buffers: &[],
-                },
-                fragment: Some(wgpu::FragmentState {
-                    module: &shader,
-                    entry_point: Some("fs_main"),
-                    compilation_options: Default::default(),
-                    targets: &[Some(wgpu::ColorTargetState {
-                        format: app.config.format.add_srgb_suffix(),
-                        blend: Some(wgpu::BlendState {
-                            color: wgpu::BlendComponent::REPLACE,
-                            alpha: wgpu::BlendComponent::REPLACE,
-                        }),
-                        write_mask: wgpu::ColorWrites::ALL,
-                    })],
-                }),
-                primitive: wgpu::PrimitiveState {
-                    topology: wgpu::PrimitiveTopology::TriangleList,
-                    strip_index_format: None,
-                    front_face: wgpu::FrontFace::Ccw,
-                    cull_mode: Some(wgpu::Face::Back),
-                    polygon_mode: wgpu::PolygonMode::Fill,
-                    // Requires Features::DEPTH_CLIP_CONTROL
-                    unclipped_depth: false,
-                    // Requires Features::CONSERVATIVE_RASTERIZATION
-                    conservative: false,
-                },
-                depth_stencil: None,
-                multisample: wgpu::MultisampleState {
-                    count: 1,
-                    mask: !0,
-                    alpha_to_coverage_enabled: false,
-                },
-                // If the pipeline will be used with a multiview render pass, this
-                // indicates how many array layers the attachments will have.
-                multiview: None,
-                cache: None,
-            });
+        // 抗锯齿的渲染目标，按 F2 键在 None、MSAA 与 FXAA 之间切换
+        let format = app.config.format.add_srgb_suffix();
+        let aa_targets = AntiAliasingTargets::new(
+            &app.device,
+            format,
+            app.config.width,
+            app.config.height,
+            false,
+            AntiAliasing::default(),
+        );
+        let render_pipeline =
+            Self::create_render_pipeline(&app.device, &shader, format, aa_targets.sample_count());
 
         let size = PhysicalSize {
             width: app.config.width,
@@ -98,56 +133,90 @@ impl WgpuAppAction for WgpuApp {
         Self {
             app,
             size,
-            size_changed: false,
+            resize_debouncer: utils::ResizeDebouncer::new(),
+            shader,
             render_pipeline,
+            aa_targets,
+            demo_config: DemoConfig::load(),
+            #[cfg(not(target_arch = "wasm32"))]
+            config_watcher: utils::config::ConfigWatcher::new(),
         }
     }
 
     fn set_window_resized(&mut self, new_size: PhysicalSize<u32>) {
-        if self.app.config.width == new_size.width && self.app.config.height == new_size.height {
-            return;
-        }
-        self.size = new_size;
-        self.size_changed = true;
+        let current = self.get_size();
+        self.resize_debouncer.record(new_size, current);
     }
 
     fn get_size(&self) -> PhysicalSize<u32> {
         PhysicalSize::new(self.app.config.width, self.app.config.height)
     }
 
-    fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
-        self.resize_surface_if_needed();
+    fn anti_aliasing(&self) -> Option<AntiAliasing> {
+        Some(self.aa_targets.mode())
+    }
 
-        let (output, view) = self.app.get_current_frame_view(None);
-        let mut encoder = self
-            .app
-            .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                label: Some("Render Encoder"),
-            });
+    fn set_anti_aliasing(&mut self, mode: AntiAliasing) {
+        if self.aa_targets.set_mode(&self.app.device, mode) {
+            // 采样数变化，渲染管线需以新的采样数重建
+            self.render_pipeline = Self::create_render_pipeline(
+                &self.app.device,
+                &self.shader,
+                self.app.config.format.add_srgb_suffix(),
+                self.aa_targets.sample_count(),
+            );
+        }
+    }
+
+    fn update(&mut self, _dt: std::time::Duration) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(config) = self.config_watcher.check() {
+            self.demo_config = config;
+        }
+    }
 
+    fn render_context(&self) -> Option<RenderContext<'_>> {
+        Some(RenderContext::new(
+            &self.app,
+            self.app.config.format.add_srgb_suffix(),
+        ))
+    }
+
+    fn render_to(&mut self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView) {
+        let [r, g, b, a] = self.demo_config.clear_color;
         {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.1,
-                            g: 0.2,
-                            b: 0.3,
-                            a: 1.0,
-                        }),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
+                color_attachments: &[Some(self.aa_targets.color_attachment(
+                    view,
+                    wgpu::LoadOp::Clear(wgpu::Color {
+                        r: r as f64,
+                        g: g as f64,
+                        b: b as f64,
+                        a: a as f64,
+                    }),
+                ))],
                 ..Default::default()
             });
 
             render_pass.set_pipeline(&self.render_pipeline);
             render_pass.draw(0..3, 0..1);
         }
+        // FXAA 模式下把离屏纹理经 FXAA 绘制到 `view`
+        self.aa_targets.finish(encoder, view);
+    }
+
+    fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
+        self.resize_surface_if_needed();
+
+        let (output, view) = self.app.get_current_frame_view(None);
+        let mut encoder = self
+            .app
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Render Encoder"),
+            });
+        self.render_to(&mut encoder, &view);
 
         self.app.queue.submit(Some(encoder.finish()));
         output.present();
@@ -157,5 +226,10 @@ impl WgpuAppAction for WgpuApp {
 }
 
 pub fn main() -> Result<(), impl std::error::Error> {
-    run::<WgpuApp>("tutorial3-pipeline")
+    // 请求低延迟的 Mailbox 呈现模式，surface 不支持时回退到 Fifo
+    let config = RunConfig {
+        present_mode: Some(wgpu::PresentMode::Mailbox),
+        ..Default::default()
+    };
+    run_with_config::<WgpuApp>("tutorial3-pipeline", config)
 }
@@ -66,7 +66,7 @@ struct WgpuApp {
 
     render_pipeline: wgpu::RenderPipeline,
     size: PhysicalSize<u32>,
-    size_changed: bool,
+    resize_debouncer: utils::ResizeDebouncer,
 
     vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
@@ -81,10 +81,10 @@ struct WgpuApp {
 impl WgpuApp {
     /// 必要的时候调整 surface 大小
     fn resize_surface_if_needed(&mut self) {
-        if self.size_changed {
+        if let Some(size) = self.resize_debouncer.take_if_changed() {
+            self.size = size;
             self.app
                 .resize_surface_by_size((self.size.width, self.size.height));
-            self.size_changed = false;
         }
     }
 }
@@ -215,7 +215,7 @@ impl WgpuAppAction for WgpuApp {
             app,
             render_pipeline,
             size,
-            size_changed: false,
+            resize_debouncer: utils::ResizeDebouncer::new(),
             vertex_buffer,
             index_buffer,
             num_indices,
@@ -227,11 +227,8 @@ impl WgpuAppAction for WgpuApp {
     }
 
     fn set_window_resized(&mut self, new_size: PhysicalSize<u32>) {
-        if self.app.config.width == new_size.width && self.app.config.height == new_size.height {
-            return;
-        }
-        self.size = new_size;
-        self.size_changed = true;
+        let current = self.get_size();
+        self.resize_debouncer.record(new_size, current);
     }
 
     fn get_size(&self) -> PhysicalSize<u32> {
@@ -59,9 +59,11 @@ const INDICES: &[u16] = &[0, 1, 4, 1, 2, 4, 2, 3, 4];
 
 struct WgpuApp {
     app: AppSurface,
-    render_pipeline: wgpu::RenderPipeline,
+    pipelines: utils::ColorSpaceSplit<wgpu::RenderPipeline>,
+    /// 是否以左右分屏对比 sRGB 与线性输出（F3 切换）
+    is_color_space_split: bool,
     size: PhysicalSize<u32>,
-    size_changed: bool,
+    resize_debouncer: utils::ResizeDebouncer,
     // NEW!
     vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
@@ -71,10 +73,10 @@ struct WgpuApp {
 impl WgpuApp {
     /// 必要的时候调整 surface 大小
     fn resize_surface_if_needed(&mut self) {
-        if self.size_changed {
+        if let Some(size) = self.resize_debouncer.take_if_changed() {
+            self.size = size;
             self.app
                 .resize_surface_by_size((self.size.width, self.size.height));
-            self.size_changed = false;
         }
     }
 }
@@ -99,52 +101,54 @@ impl WgpuAppAction for WgpuApp {
                     push_constant_ranges: &[],
                 });
 
-        let render_pipeline = app
-            .device
-            .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-                label: Some("Render Pipeline"),
-                layout: Some(&render_pipeline_layout),
-                vertex: wgpu::VertexState {
-                    module: &shader,
-                    entry_point: Some("vs_main"),
-                    compilation_options: Default::default(),
-                    buffers: &[Vertex::desc()],
-                },
-                fragment: Some(wgpu::FragmentState {
-                    module: &shader,
-                    entry_point: Some("fs_main"),
-                    compilation_options: Default::default(),
-                    targets: &[Some(wgpu::ColorTargetState {
-                        format: app.config.format.add_srgb_suffix(),
-                        blend: Some(wgpu::BlendState {
-                            color: wgpu::BlendComponent::REPLACE,
-                            alpha: wgpu::BlendComponent::REPLACE,
-                        }),
-                        write_mask: wgpu::ColorWrites::ALL,
-                    })],
-                }),
-                primitive: wgpu::PrimitiveState {
-                    topology: wgpu::PrimitiveTopology::TriangleList,
-                    strip_index_format: None,
-                    front_face: wgpu::FrontFace::Ccw,
-                    cull_mode: Some(wgpu::Face::Back),
-                    polygon_mode: wgpu::PolygonMode::Fill,
-                    // Requires Features::DEPTH_CLIP_CONTROL
-                    unclipped_depth: false,
-                    // Requires Features::CONSERVATIVE_RASTERIZATION
-                    conservative: false,
-                },
-                depth_stencil: None,
-                multisample: wgpu::MultisampleState {
-                    count: 1,
-                    mask: !0,
-                    alpha_to_coverage_enabled: false,
-                },
-                // If the pipeline will be used with a multiview render pass, this
-                // indicates how many array layers the attachments will have.
-                multiview: None,
-                cache: None,
-            });
+        // 以 sRGB 与线性两种颜色目标格式各创建一份管线，平时只用 sRGB 的那份
+        let pipelines = utils::ColorSpaceSplit::new(app.config.format, |format| {
+            app.device
+                .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some("Render Pipeline"),
+                    layout: Some(&render_pipeline_layout),
+                    vertex: wgpu::VertexState {
+                        module: &shader,
+                        entry_point: Some("vs_main"),
+                        compilation_options: Default::default(),
+                        buffers: &[Vertex::desc()],
+                    },
+                    fragment: Some(wgpu::FragmentState {
+                        module: &shader,
+                        entry_point: Some("fs_main"),
+                        compilation_options: Default::default(),
+                        targets: &[Some(wgpu::ColorTargetState {
+                            format,
+                            blend: Some(wgpu::BlendState {
+                                color: wgpu::BlendComponent::REPLACE,
+                                alpha: wgpu::BlendComponent::REPLACE,
+                            }),
+                            write_mask: wgpu::ColorWrites::ALL,
+                        })],
+                    }),
+                    primitive: wgpu::PrimitiveState {
+                        topology: wgpu::PrimitiveTopology::TriangleList,
+                        strip_index_format: None,
+                        front_face: wgpu::FrontFace::Ccw,
+                        cull_mode: Some(wgpu::Face::Back),
+                        polygon_mode: wgpu::PolygonMode::Fill,
+                        // Requires Features::DEPTH_CLIP_CONTROL
+                        unclipped_depth: false,
+                        // Requires Features::CONSERVATIVE_RASTERIZATION
+                        conservative: false,
+                    },
+                    depth_stencil: None,
+                    multisample: wgpu::MultisampleState {
+                        count: 1,
+                        mask: !0,
+                        alpha_to_coverage_enabled: false,
+                    },
+                    // If the pipeline will be used with a multiview render pass, this
+                    // indicates how many array layers the attachments will have.
+                    multiview: None,
+                    cache: None,
+                })
+        });
 
         let vertex_buffer = app
             .device
@@ -170,8 +174,9 @@ impl WgpuAppAction for WgpuApp {
         Self {
             app,
             size,
-            size_changed: false,
-            render_pipeline,
+            resize_debouncer: utils::ResizeDebouncer::new(),
+            pipelines,
+            is_color_space_split: false,
             vertex_buffer,
             index_buffer,
             num_indices,
@@ -179,17 +184,22 @@ impl WgpuAppAction for WgpuApp {
     }
 
     fn set_window_resized(&mut self, new_size: PhysicalSize<u32>) {
-        if self.app.config.width == new_size.width && self.app.config.height == new_size.height {
-            return;
-        }
-        self.size = new_size;
-        self.size_changed = true;
+        let current = self.get_size();
+        self.resize_debouncer.record(new_size, current);
     }
 
     fn get_size(&self) -> PhysicalSize<u32> {
         PhysicalSize::new(self.app.config.width, self.app.config.height)
     }
 
+    fn color_space_split(&self) -> Option<bool> {
+        Some(self.is_color_space_split)
+    }
+
+    fn set_color_space_split(&mut self, enabled: bool) {
+        self.is_color_space_split = enabled;
+    }
+
     fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
         self.resize_surface_if_needed();
 
@@ -202,29 +212,36 @@ impl WgpuAppAction for WgpuApp {
                 label: Some("Render Encoder"),
             });
 
-        {
+        let load_op = wgpu::LoadOp::Clear(wgpu::Color {
+            r: 0.1,
+            g: 0.2,
+            b: 0.3,
+            a: 1.0,
+        });
+        let draw = |render_pass: &mut wgpu::RenderPass<'_>, pipeline: &wgpu::RenderPipeline| {
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+            render_pass.draw_indexed(0..self.num_indices, 0, 0..1);
+        };
+
+        if self.is_color_space_split {
+            self.pipelines
+                .render(&mut encoder, &output.texture, load_op, draw);
+        } else {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                     view: &view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.1,
-                            g: 0.2,
-                            b: 0.3,
-                            a: 1.0,
-                        }),
+                        load: load_op,
                         store: wgpu::StoreOp::Store,
                     },
                 })],
                 ..Default::default()
             });
-
-            render_pass.set_pipeline(&self.render_pipeline);
-            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
-            render_pass.draw_indexed(0..self.num_indices, 0, 0..1);
+            draw(&mut render_pass, &self.pipelines.srgb);
         }
 
         self.app.queue.submit(Some(encoder.finish()));
@@ -64,7 +64,7 @@ const INDICES: &[u16] = &[0, 1, 4, 1, 2, 4, 2, 3, 4];
 struct WgpuApp {
     app: AppSurface,
     size: PhysicalSize<u32>,
-    size_changed: bool,
+    resize_debouncer: utils::ResizeDebouncer,
     render_pipeline: wgpu::RenderPipeline,
     vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
@@ -76,10 +76,10 @@ struct WgpuApp {
 impl WgpuApp {
     /// 必要的时候调整 surface 大小
     fn resize_surface_if_needed(&mut self) {
-        if self.size_changed {
+        if let Some(size) = self.resize_debouncer.take_if_changed() {
+            self.size = size;
             self.app
                 .resize_surface_by_size((self.size.width, self.size.height));
-            self.size_changed = false;
         }
     }
 }
@@ -223,27 +223,40 @@ impl WgpuAppAction for WgpuApp {
             index_buffer,
             num_indices,
             size,
-            size_changed: false,
+            resize_debouncer: utils::ResizeDebouncer::new(),
             diffuse_bind_group,
         }
     }
 
     fn set_window_resized(&mut self, new_size: PhysicalSize<u32>) {
-        if self.app.config.width == new_size.width && self.app.config.height == new_size.height {
-            return;
-        }
-        self.size = new_size;
-        self.size_changed = true;
+        let current = self.get_size();
+        self.resize_debouncer.record(new_size, current);
     }
 
     fn get_size(&self) -> PhysicalSize<u32> {
         PhysicalSize::new(self.app.config.width, self.app.config.height)
     }
 
+    fn surface_error_policy(&self) -> utils::SurfaceErrorPolicy {
+        // 部分平台上显示器休眠唤醒后会持续返回 `Timeout`，只有重新配置 surface 才能恢复
+        utils::SurfaceErrorPolicy::default().with_timeout(utils::SurfaceErrorAction::Reconfigure)
+    }
+
+    fn reconfigure_surface(&mut self) {
+        self.app
+            .surface
+            .configure(&self.app.device, &self.app.config);
+    }
+
     fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
         self.resize_surface_if_needed();
 
-        let (output, view) = self.app.get_current_frame_view(None);
+        // 获取失败时把错误交给框架，按 `surface_error_policy` 跳过本帧、重新配置 surface 或退出
+        let output = self.app.surface.get_current_texture()?;
+        let view = output.texture.create_view(&wgpu::TextureViewDescriptor {
+            format: Some(self.app.config.format.add_srgb_suffix()),
+            ..Default::default()
+        });
 
         let mut encoder = self
             .app
@@ -197,7 +197,7 @@ struct WgpuApp {
     index_buffer: wgpu::Buffer,
     num_indices: u32,
     size: PhysicalSize<u32>,
-    size_changed: bool,
+    resize_debouncer: utils::ResizeDebouncer,
     #[allow(dead_code)]
     diffuse_texture: texture::Texture,
     diffuse_bind_group: wgpu::BindGroup,
@@ -211,14 +211,13 @@ struct WgpuApp {
 impl WgpuApp {
     /// 必要的时候调整 surface 大小
     fn resize_surface_if_needed(&mut self) {
-        if self.size_changed {
+        if let Some(size) = self.resize_debouncer.take_if_changed() {
+            self.size = size;
             self.app
                 .resize_surface_by_size((self.size.width, self.size.height));
 
             self.camera_staging.camera.aspect =
                 self.app.config.width as f32 / self.app.config.height as f32;
-
-            self.size_changed = false;
         }
     }
 }
@@ -407,7 +406,7 @@ impl WgpuAppAction for WgpuApp {
             index_buffer,
             num_indices,
             size,
-            size_changed: false,
+            resize_debouncer: utils::ResizeDebouncer::new(),
             diffuse_texture,
             diffuse_bind_group,
             camera_controller,
@@ -419,11 +418,8 @@ impl WgpuAppAction for WgpuApp {
     }
 
     fn set_window_resized(&mut self, new_size: PhysicalSize<u32>) {
-        if self.app.config.width == new_size.width && self.app.config.height == new_size.height {
-            return;
-        }
-        self.size = new_size;
-        self.size_changed = true;
+        let current = self.get_size();
+        self.resize_debouncer.record(new_size, current);
     }
 
     fn get_size(&self) -> PhysicalSize<u32> {
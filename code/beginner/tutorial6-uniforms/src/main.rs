@@ -1,12 +1,9 @@
 use app_surface::{AppSurface, SurfaceFrame};
 use std::sync::Arc;
-use utils::framework::{WgpuAppAction, run};
+use utils::camera::OrbitCamera;
+use utils::framework::{RenderContext, WgpuAppAction, run};
 use wgpu::util::DeviceExt;
-use winit::{
-    dpi::PhysicalSize,
-    event::*,
-    keyboard::{KeyCode, PhysicalKey},
-};
+use winit::{dpi::PhysicalSize, event::*};
 
 mod texture;
 
@@ -65,9 +62,8 @@ const VERTICES: &[Vertex] = &[
 const INDICES: &[u16] = &[0, 1, 4, 1, 2, 4, 2, 3, 4];
 
 struct Camera {
-    eye: glam::Vec3,
-    target: glam::Vec3,
-    up: glam::Vec3,
+    /// 环绕场景中心的轨道相机：鼠标左键拖动旋转，滚轮缩放
+    orbit: OrbitCamera,
     aspect: f32,
     fovy: f32,
     znear: f32,
@@ -76,7 +72,7 @@ struct Camera {
 
 impl Camera {
     fn build_view_projection_matrix(&self) -> glam::Mat4 {
-        let view = glam::Mat4::look_at_rh(self.eye, self.target, self.up);
+        let view = self.orbit.calc_matrix();
         let proj =
             glam::Mat4::perspective_rh(self.fovy.to_radians(), self.aspect, self.znear, self.zfar);
         proj * view
@@ -101,95 +97,6 @@ impl CameraUniform {
     }
 }
 
-struct CameraController {
-    speed: f32,
-    is_up_pressed: bool,
-    is_down_pressed: bool,
-    is_forward_pressed: bool,
-    is_backward_pressed: bool,
-    is_left_pressed: bool,
-    is_right_pressed: bool,
-}
-
-impl CameraController {
-    fn new(speed: f32) -> Self {
-        Self {
-            speed,
-            is_up_pressed: false,
-            is_down_pressed: false,
-            is_forward_pressed: false,
-            is_backward_pressed: false,
-            is_left_pressed: false,
-            is_right_pressed: false,
-        }
-    }
-
-    fn process_events(&mut self, event: &KeyEvent) -> bool {
-        // 直接检查 KeyEvent 的状态
-        let is_pressed = event.state == ElementState::Pressed;
-
-        if event.physical_key == PhysicalKey::Code(KeyCode::Space) {
-            self.is_up_pressed = is_pressed;
-            return true;
-        }
-
-        match event.physical_key {
-            PhysicalKey::Code(KeyCode::ShiftLeft) => {
-                self.is_down_pressed = is_pressed;
-                true
-            }
-            PhysicalKey::Code(KeyCode::KeyW) | PhysicalKey::Code(KeyCode::ArrowUp) => {
-                self.is_forward_pressed = is_pressed;
-                true
-            }
-            PhysicalKey::Code(KeyCode::KeyA) | PhysicalKey::Code(KeyCode::ArrowLeft) => {
-                self.is_left_pressed = is_pressed;
-                true
-            }
-            PhysicalKey::Code(KeyCode::KeyS) | PhysicalKey::Code(KeyCode::ArrowDown) => {
-                self.is_backward_pressed = is_pressed;
-                true
-            }
-            PhysicalKey::Code(KeyCode::KeyD) | PhysicalKey::Code(KeyCode::ArrowRight) => {
-                self.is_right_pressed = is_pressed;
-                true
-            }
-            _ => false,
-        }
-    }
-
-    fn update_camera(&self, camera: &mut Camera) {
-        let forward = camera.target - camera.eye;
-        let forward_norm = forward.normalize();
-        let forward_mag = forward.length();
-
-        // Prevents glitching when camera gets too close to the
-        // center of the scene.
-        if self.is_forward_pressed && forward_mag > self.speed {
-            camera.eye += forward_norm * self.speed;
-        }
-        if self.is_backward_pressed {
-            camera.eye -= forward_norm * self.speed;
-        }
-
-        let right = forward_norm.cross(camera.up);
-
-        // Redo radius calc in case the up/ down is pressed.
-        let forward = camera.target - camera.eye;
-        let forward_mag = forward.length();
-
-        if self.is_right_pressed {
-            // Rescale the distance between the target and eye so
-            // that it doesn't change. The eye therefore still
-            // lies on the circle made by the target and eye.
-            camera.eye = camera.target - (forward + right * self.speed).normalize() * forward_mag;
-        }
-        if self.is_left_pressed {
-            camera.eye = camera.target - (forward - right * self.speed).normalize() * forward_mag;
-        }
-    }
-}
-
 struct WgpuApp {
     app: AppSurface,
     render_pipeline: wgpu::RenderPipeline,
@@ -197,28 +104,27 @@ struct WgpuApp {
     index_buffer: wgpu::Buffer,
     num_indices: u32,
     size: PhysicalSize<u32>,
-    size_changed: bool,
+    resize_debouncer: utils::ResizeDebouncer,
     #[allow(dead_code)]
     diffuse_texture: texture::Texture,
     diffuse_bind_group: wgpu::BindGroup,
     // NEW!
     camera: Camera,
-    camera_controller: CameraController,
     camera_uniform: CameraUniform,
     camera_buffer: wgpu::Buffer,
     camera_bind_group: wgpu::BindGroup,
+    mouse_pressed: bool,
 }
 
 impl WgpuApp {
     /// 必要的时候调整 surface 大小
     fn resize_surface_if_needed(&mut self) {
-        if self.size_changed {
+        if let Some(size) = self.resize_debouncer.take_if_changed() {
+            self.size = size;
             self.app
                 .resize_surface_by_size((self.size.width, self.size.height));
 
             self.camera.aspect = self.app.config.width as f32 / self.app.config.height as f32;
-
-            self.size_changed = false;
         }
     }
 }
@@ -273,15 +179,12 @@ impl WgpuAppAction for WgpuApp {
         });
 
         let camera = Camera {
-            eye: (0.0, 1.0, 2.0).into(),
-            target: (0.0, 0.0, 0.0).into(),
-            up: glam::Vec3::Y,
+            orbit: OrbitCamera::from_eye_target((0.0, 1.0, 2.0).into(), glam::Vec3::ZERO),
             aspect: app.config.width as f32 / app.config.height as f32,
             fovy: 45.0,
             znear: 0.1,
             zfar: 100.0,
         };
-        let camera_controller = CameraController::new(0.2);
 
         let mut camera_uniform = CameraUniform::new();
         camera_uniform.update_view_proj(&camera);
@@ -409,35 +312,69 @@ impl WgpuAppAction for WgpuApp {
             index_buffer,
             num_indices,
             size,
-            size_changed: false,
+            resize_debouncer: utils::ResizeDebouncer::new(),
             diffuse_texture,
             diffuse_bind_group,
             camera,
-            camera_controller,
             camera_buffer,
             camera_bind_group,
             camera_uniform,
+            mouse_pressed: false,
         }
     }
 
     fn set_window_resized(&mut self, new_size: PhysicalSize<u32>) {
-        if self.app.config.width == new_size.width && self.app.config.height == new_size.height {
-            return;
-        }
-        self.size = new_size;
-        self.size_changed = true;
+        let current = self.get_size();
+        self.resize_debouncer.record(new_size, current);
     }
 
     fn get_size(&self) -> PhysicalSize<u32> {
         PhysicalSize::new(self.app.config.width, self.app.config.height)
     }
 
-    fn keyboard_input(&mut self, event: &KeyEvent) -> bool {
-        self.camera_controller.process_events(event)
+    fn mouse_input(&mut self, button: MouseButton, state: ElementState) -> bool {
+        if button == MouseButton::Left {
+            self.mouse_pressed = state == ElementState::Pressed;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn mouse_wheel(&mut self, delta: MouseScrollDelta, _phase: TouchPhase) -> bool {
+        self.camera.orbit.process_scroll(&delta);
+        true
+    }
+
+    fn mouse_motion(&mut self, dx: f64, dy: f64) {
+        if self.mouse_pressed {
+            self.camera.orbit.process_mouse(dx, dy);
+        }
+    }
+
+    fn focused(&mut self, is_focused: bool) {
+        // 失去焦点时可能收不到左键抬起的事件，结束拖动以免相机跟着鼠标转动
+        if !is_focused {
+            self.mouse_pressed = false;
+        }
+    }
+
+    fn save_settings(&self, settings: &mut utils::settings::AppSettings) {
+        settings.camera = Some(utils::settings::CameraSettings {
+            eye: self.camera.orbit.eye().to_array(),
+            target: self.camera.orbit.target.to_array(),
+        });
+    }
+
+    fn restore_settings(&mut self, settings: &utils::settings::AppSettings) {
+        // 相机的 uniform 在下一次 `update` 中写入
+        if let Some(camera) = settings.camera {
+            self.camera.orbit =
+                OrbitCamera::from_eye_target(camera.eye.into(), camera.target.into());
+        }
     }
 
     fn update(&mut self, _dt: instant::Duration) {
-        self.camera_controller.update_camera(&mut self.camera);
         self.camera_uniform.update_view_proj(&self.camera);
         self.app.queue.write_buffer(
             &self.camera_buffer,
@@ -446,6 +383,40 @@ impl WgpuAppAction for WgpuApp {
         );
     }
 
+    fn render_context(&self) -> Option<RenderContext<'_>> {
+        Some(RenderContext::new(
+            &self.app,
+            self.app.config.format.add_srgb_suffix(),
+        ))
+    }
+
+    fn render_to(&mut self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color {
+                        r: 0.1,
+                        g: 0.2,
+                        b: 0.3,
+                        a: 1.0,
+                    }),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            ..Default::default()
+        });
+
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, &self.diffuse_bind_group, &[]);
+        render_pass.set_bind_group(1, &self.camera_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+        render_pass.draw_indexed(0..self.num_indices, 0, 0..1);
+    }
+
     fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
         self.resize_surface_if_needed();
 
@@ -456,33 +427,7 @@ impl WgpuAppAction for WgpuApp {
             .create_command_encoder(&wgpu::CommandEncoderDescriptor {
                 label: Some("Render Encoder"),
             });
-
-        {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Render Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.1,
-                            g: 0.2,
-                            b: 0.3,
-                            a: 1.0,
-                        }),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                ..Default::default()
-            });
-
-            render_pass.set_pipeline(&self.render_pipeline);
-            render_pass.set_bind_group(0, &self.diffuse_bind_group, &[]);
-            render_pass.set_bind_group(1, &self.camera_bind_group, &[]);
-            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
-            render_pass.draw_indexed(0..self.num_indices, 0, 0..1);
-        }
+        self.render_to(&mut encoder, &view);
 
         self.app.queue.submit(Some(encoder.finish()));
         output.present();
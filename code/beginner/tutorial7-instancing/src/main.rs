@@ -257,7 +257,7 @@ struct WgpuApp {
     index_buffer: wgpu::Buffer,
     num_indices: u32,
     size: PhysicalSize<u32>,
-    size_changed: bool,
+    resize_debouncer: utils::ResizeDebouncer,
     #[allow(dead_code)]
     diffuse_texture: texture::Texture,
     diffuse_bind_group: wgpu::BindGroup,
@@ -270,18 +270,19 @@ struct WgpuApp {
     instances: Vec<Instance>,
     #[allow(dead_code)]
     instance_buffer: wgpu::Buffer,
+    /// 上次打印帧延迟的时间，每秒打印一次
+    latency_logged_at: instant::Instant,
 }
 
 impl WgpuApp {
     /// 必要的时候调整 surface 大小
     fn resize_surface_if_needed(&mut self) {
-        if self.size_changed {
+        if let Some(size) = self.resize_debouncer.take_if_changed() {
+            self.size = size;
             self.app
                 .resize_surface_by_size((self.size.width, self.size.height));
 
             self.camera.aspect = self.app.config.width as f32 / self.app.config.height as f32;
-
-            self.size_changed = false;
         }
     }
 }
@@ -499,7 +500,7 @@ impl WgpuAppAction for WgpuApp {
             index_buffer,
             num_indices,
             size,
-            size_changed: false,
+            resize_debouncer: utils::ResizeDebouncer::new(),
             diffuse_texture,
             diffuse_bind_group,
             camera,
@@ -510,15 +511,13 @@ impl WgpuAppAction for WgpuApp {
             // NEW!
             instances,
             instance_buffer,
+            latency_logged_at: instant::Instant::now(),
         }
     }
 
     fn set_window_resized(&mut self, new_size: PhysicalSize<u32>) {
-        if self.app.config.width == new_size.width && self.app.config.height == new_size.height {
-            return;
-        }
-        self.size = new_size;
-        self.size_changed = true;
+        let current = self.get_size();
+        self.resize_debouncer.record(new_size, current);
     }
 
     fn get_size(&self) -> PhysicalSize<u32> {
@@ -539,6 +538,23 @@ impl WgpuAppAction for WgpuApp {
         );
     }
 
+    fn latency_queue(&self) -> Option<&wgpu::Queue> {
+        Some(&self.app.queue)
+    }
+
+    fn set_frame_latency(&mut self, latency: utils::LatencyEstimate) {
+        if self.latency_logged_at.elapsed() < instant::Duration::from_secs(1) {
+            return;
+        }
+        self.latency_logged_at = instant::Instant::now();
+        log::info!(
+            "update -> submit: {:?}, update -> GPU done: {:?} ({} frames)",
+            latency.update_to_submit,
+            latency.update_to_gpu_done,
+            latency.samples
+        );
+    }
+
     fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
         self.resize_surface_if_needed();
 
@@ -66,6 +66,9 @@ const VERTICES: &[Vertex] = &[
 
 const INDICES: &[u16] = &[0, 1, 4, 1, 2, 4, 2, 3, 4];
 
+/// 场景的固定宽高比
+const SCENE_ASPECT: f32 = 16.0 / 9.0;
+
 const NUM_INSTANCES_PER_ROW: u32 = 10;
 const INSTANCE_DISPLACEMENT: glam::Vec3 = glam::Vec3::new(
     NUM_INSTANCES_PER_ROW as f32 * 0.5,
@@ -266,7 +269,7 @@ struct WgpuApp {
     index_buffer: wgpu::Buffer,
     num_indices: u32,
     size: PhysicalSize<u32>,
-    size_changed: bool,
+    resize_debouncer: utils::ResizeDebouncer,
     #[allow(dead_code)]
     diffuse_texture: texture::Texture,
     diffuse_bind_group: wgpu::BindGroup,
@@ -285,19 +288,17 @@ struct WgpuApp {
 impl WgpuApp {
     /// 必要的时候调整 surface 大小
     fn resize_surface_if_needed(&mut self) {
-        if self.size_changed {
+        if let Some(size) = self.resize_debouncer.take_if_changed() {
+            self.size = size;
             self.app
                 .resize_surface_by_size((self.size.width, self.size.height));
 
-            self.camera.aspect = self.app.config.width as f32 / self.app.config.height as f32;
             // NEW!
             self.depth_texture = texture::Texture::create_depth_texture(
                 &self.app.device,
                 &self.app.config,
                 "depth_texture",
             );
-
-            self.size_changed = false;
         }
     }
 }
@@ -355,7 +356,8 @@ impl WgpuAppAction for WgpuApp {
             eye: (0.0, 5.0, 10.0).into(),
             target: (0.0, 0.0, 0.0).into(),
             up: glam::Vec3::Y,
-            aspect: app.config.width as f32 / app.config.height as f32,
+            // 场景始终以固定的宽高比渲染，窗口的多余部分为黑边
+            aspect: SCENE_ASPECT,
             fovy: 45.0,
             znear: 0.1,
             zfar: 100.0,
@@ -526,7 +528,7 @@ impl WgpuAppAction for WgpuApp {
             index_buffer,
             num_indices,
             size,
-            size_changed: false,
+            resize_debouncer: utils::ResizeDebouncer::new(),
             diffuse_texture,
             diffuse_bind_group,
             camera,
@@ -541,11 +543,8 @@ impl WgpuAppAction for WgpuApp {
     }
 
     fn set_window_resized(&mut self, new_size: PhysicalSize<u32>) {
-        if self.app.config.width == new_size.width && self.app.config.height == new_size.height {
-            return;
-        }
-        self.size = new_size;
-        self.size_changed = true;
+        let current = self.get_size();
+        self.resize_debouncer.record(new_size, current);
     }
 
     fn get_size(&self) -> PhysicalSize<u32> {
@@ -566,6 +565,10 @@ impl WgpuAppAction for WgpuApp {
         );
     }
 
+    fn fixed_aspect(&self) -> Option<f32> {
+        Some(SCENE_ASPECT)
+    }
+
     fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
         self.resize_surface_if_needed();
 
@@ -584,12 +587,8 @@ impl WgpuAppAction for WgpuApp {
                     view: &view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.1,
-                            g: 0.2,
-                            b: 0.3,
-                            a: 1.0,
-                        }),
+                        // 黑色清屏，视口之外的区域即为黑边
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
                         store: wgpu::StoreOp::Store,
                     },
                 })],
@@ -603,14 +602,18 @@ impl WgpuAppAction for WgpuApp {
                 }),
                 ..Default::default()
             });
-
-            render_pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
-            render_pass.set_pipeline(&self.render_pipeline);
-            render_pass.set_bind_group(0, &self.diffuse_bind_group, &[]);
-            render_pass.set_bind_group(1, &self.camera_bind_group, &[]);
-            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
-            render_pass.draw_indexed(0..self.num_indices, 0, 0..self.instances.len() as u32);
+            // 窗口尺寸不足一个像素时视口为空，只清屏
+            let size = self.get_size();
+            if self.render_viewport().apply(&mut render_pass, size) {
+                render_pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
+                render_pass.set_pipeline(&self.render_pipeline);
+                render_pass.set_bind_group(0, &self.diffuse_bind_group, &[]);
+                render_pass.set_bind_group(1, &self.camera_bind_group, &[]);
+                render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+                render_pass
+                    .set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+                render_pass.draw_indexed(0..self.num_indices, 0, 0..self.instances.len() as u32);
+            }
         }
 
         self.app.queue.submit(Some(encoder.finish()));
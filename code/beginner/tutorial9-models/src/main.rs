@@ -205,7 +205,7 @@ impl InstanceRaw {
 struct WgpuApp {
     app: AppSurface,
     size: PhysicalSize<u32>,
-    size_changed: bool,
+    resize_debouncer: utils::ResizeDebouncer,
     render_pipeline: wgpu::RenderPipeline,
     obj_model: model::Model,
     camera: Camera,
@@ -222,7 +222,8 @@ struct WgpuApp {
 impl WgpuApp {
     /// 必要的时候调整 surface 大小
     fn resize_surface_if_needed(&mut self) {
-        if self.size_changed {
+        if let Some(size) = self.resize_debouncer.take_if_changed() {
+            self.size = size;
             self.app
                 .resize_surface_by_size((self.size.width, self.size.height));
 
@@ -232,8 +233,6 @@ impl WgpuApp {
                 &self.app.config,
                 "depth_texture",
             );
-
-            self.size_changed = false;
         }
     }
 }
@@ -428,7 +427,7 @@ impl WgpuAppAction for WgpuApp {
         Self {
             app,
             size,
-            size_changed: false,
+            resize_debouncer: utils::ResizeDebouncer::new(),
             render_pipeline,
             obj_model,
             camera,
@@ -443,11 +442,8 @@ impl WgpuAppAction for WgpuApp {
     }
 
     fn set_window_resized(&mut self, new_size: PhysicalSize<u32>) {
-        if self.app.config.width == new_size.width && self.app.config.height == new_size.height {
-            return;
-        }
-        self.size = new_size;
-        self.size_changed = true;
+        let current = self.get_size();
+        self.resize_debouncer.record(new_size, current);
     }
 
     fn get_size(&self) -> PhysicalSize<u32> {
@@ -5,7 +5,7 @@ use glam::{UVec2, Vec2, uvec2};
 pub struct WgpuApp {
     app: AppSurface,
     size: UVec2,
-    size_changed: bool,
+    resize_debouncer: utils::ResizeDebouncer,
 
     /// 粒子生成器
     gen_node: ParticleGen,
@@ -24,7 +24,7 @@ impl WgpuApp {
         Self {
             app,
             size,
-            size_changed: false,
+            resize_debouncer: utils::ResizeDebouncer::new(),
             gen_node,
             particle_ink,
         }
@@ -32,18 +32,18 @@ impl WgpuApp {
 
     /// 必要的时候调整 surface 大小
     fn resize_surface_if_needed(&mut self) {
-        if self.size_changed {
+        if let Some(size) = self.resize_debouncer.take_if_changed() {
+            self.size = uvec2(size.width, size.height);
             self.app.resize_surface_by_size((self.size.x, self.size.y));
 
             self.particle_ink = ParticleInk::new(&self.app, &self.gen_node);
-
-            self.size_changed = false;
         }
     }
 
     pub fn set_window_resized(&mut self, new_size: UVec2) {
-        self.size = new_size;
-        self.size_changed = true;
+        let current = self.get_size();
+        self.resize_debouncer
+            .record(new_size.to_array(), current.to_array());
     }
 
     pub fn get_size(&self) -> UVec2 {
@@ -43,6 +43,15 @@ fn vs_main(@location(0) pos0: vec3f,
     // algorithm based on https://wwwtyro.net/2019/11/18/instanced-lines.html
     let clip_a = scene.mvp * vec4(position_a, 1.);
     let clip_b = scene.mvp * vec4(position_b, 1.);
+
+    // 两端点在 NDC 中重合时，normalize 零向量会得到 NaN；把整条线段的顶点折叠到同一点，不产生片元
+    // CULL_DEGENERATE 与 DEGENERATE_NDC_EPSILON 由 Rust 端在着色器源码前拼接
+    if CULL_DEGENERATE && distance(clip_a.xy / clip_a.w, clip_b.xy / clip_b.w) < DEGENERATE_NDC_EPSILON {
+        var collapsed: VertexOutput;
+        collapsed.position = vec4(0., 0., 0., 1.);
+        collapsed.dis = 0.;
+        return collapsed;
+    }
     let clip = mix(clip_a, clip_b, position.z);
 
     let resolution = scene.viewport_pixels;
@@ -60,8 +69,8 @@ fn vs_main(@location(0) pos0: vec3f,
 
 
     var depth: f32;
-    if hilbert.depth_bias >= 0. {
-        depth = clip.z * (1. - hilbert.depth_bias);
+    if depth_bias() >= 0. {
+        depth = clip.z * (1. - depth_bias());
     } else {
         // depth * (clip.w / depth)^-depth_bias. So that when -depth_bias is 1.0, this is equal to clip.w
         // and when equal to 0.0, it is exactly equal to depth.
@@ -70,7 +79,7 @@ fn vs_main(@location(0) pos0: vec3f,
         // of this value means nothing can be in front of this
         // The reason this uses an exponential function is that it makes it much easier for the
         // user to chose a value that is convenient for them
-        depth = clip.z * exp2(-hilbert.depth_bias * log2(clip.w / clip.z - EPSILON));
+        depth = clip.z * exp2(-depth_bias() * log2(clip.w / clip.z - EPSILON));
     }
 
     var output: VertexOutput;
@@ -0,0 +1,14 @@
+// 画中画小地图的背景：以纯色填满当前视口，遮住其下方已绘制的曲线
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    // 覆盖整个视口的大三角形
+    let uv = vec2f(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    return vec4f(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    // #e6dccb，比主背景 #f2eadd 略深，以区分小地图的边界
+    return vec4f(0.902, 0.863, 0.796, 1.0);
+}
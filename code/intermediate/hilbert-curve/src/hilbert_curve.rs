@@ -23,61 +23,10 @@ impl HilbertCurve {
     }
 
     fn generate_mesh(&mut self) {
-        if self.dimension == 0 {
-            return;
-        }
-
-        let pi_2 = core::f32::consts::FRAC_PI_2;
-
-        let mut base = vec![
-            Vec2::new(-0.5, -0.5),
-            Vec2::new(-0.5, 0.5),
-            Vec2::new(0.5, 0.5),
-            Vec2::new(0.5, -0.5),
-        ];
-
-        // 大于 1 维的希尔博特曲线，点的变换添加由左下角 -> 左上角 -> 右上角 -> 右下角
-        for _ in 1..self.dimension {
-            let mut new_points: Vec<Vec2> = vec![];
-            for i in 0..=3 {
-                // 变换矩阵每个方位不一样
-                // 点的顺序在 左下角 及 右下角 时都需要反转
-                // 点序的反转通过遍历 base 的顺序来实现
-                let mut need_flip = false;
-                let mat = match i {
-                    0 => {
-                        need_flip = true;
-                        Similarity2::new(Vec2::new(-0.5, -0.5), -pi_2, 0.5)
-                    }
-                    1 => Similarity2::new(Vec2::new(-0.5, 0.5), 0., 0.5),
-                    2 => Similarity2::new(Vec2::new(0.5, 0.5), 0., 0.5),
-                    _ => {
-                        need_flip = true;
-                        Similarity2::new(Vec2::new(0.5, -0.5), pi_2, 0.5)
-                    }
-                };
-                // 这种遍历无法倒序进行
-                for k in 0..base.len() {
-                    let mut p: Vec2 = if need_flip {
-                        base[(base.len() - 1) - k]
-                    } else {
-                        base[k]
-                    };
-                    p = mat.matrix.transform_point2(p);
-                    new_points.push(p);
-                }
-            }
-            base = new_points;
-        }
-
-        // 输出最终顶点
-        let mut vertices: Vec<PosOnly> = vec![];
-        for p in &base {
-            vertices.push(PosOnly {
-                pos: [p[0], p[1], 0.],
-            });
-        }
-        self.vertices = vertices;
+        self.vertices = hilbert_vertices(self.dimension)
+            .into_iter()
+            .map(|pos| PosOnly { pos })
+            .collect();
     }
 
     /// 把顶点数翻 4 倍
@@ -92,6 +41,63 @@ impl HilbertCurve {
     }
 }
 
+/// 生成 `order` 阶希尔伯特曲线的顶点
+///
+/// 顶点为 [-1, 1] x [-1, 1] 区域上 `2^order x 2^order` 网格的单元中心，z 恒为 0，共 `4^order` 个；
+/// 顺序即曲线的连接顺序：由左下角象限开始，经左上角、右上角，到右下角象限结束（y 轴向上）。
+/// 输出只由 `order` 决定，与动画所用的阶数上限无关；`order` 为 0 时返回空数组。
+pub fn hilbert_vertices(order: u32) -> Vec<[f32; 3]> {
+    if order == 0 {
+        return vec![];
+    }
+
+    let pi_2 = core::f32::consts::FRAC_PI_2;
+
+    let mut base = vec![
+        Vec2::new(-0.5, -0.5),
+        Vec2::new(-0.5, 0.5),
+        Vec2::new(0.5, 0.5),
+        Vec2::new(0.5, -0.5),
+    ];
+
+    // 大于 1 维的希尔博特曲线，点的变换添加由左下角 -> 左上角 -> 右上角 -> 右下角
+    for _ in 1..order {
+        let mut new_points: Vec<Vec2> = vec![];
+        for i in 0..=3 {
+            // 变换矩阵每个方位不一样
+            // 点的顺序在 左下角 及 右下角 时都需要反转
+            // 点序的反转通过遍历 base 的顺序来实现
+            let mut need_flip = false;
+            let mat = match i {
+                0 => {
+                    need_flip = true;
+                    Similarity2::new(Vec2::new(-0.5, -0.5), -pi_2, 0.5)
+                }
+                1 => Similarity2::new(Vec2::new(-0.5, 0.5), 0., 0.5),
+                2 => Similarity2::new(Vec2::new(0.5, 0.5), 0., 0.5),
+                _ => {
+                    need_flip = true;
+                    Similarity2::new(Vec2::new(0.5, -0.5), pi_2, 0.5)
+                }
+            };
+            // 这种遍历无法倒序进行
+            for k in 0..base.len() {
+                let mut p: Vec2 = if need_flip {
+                    base[(base.len() - 1) - k]
+                } else {
+                    base[k]
+                };
+                p = mat.matrix.transform_point2(p);
+                new_points.push(p);
+            }
+        }
+        base = new_points;
+    }
+
+    // 输出最终顶点
+    base.iter().map(|p| [p.x, p.y, 0.]).collect()
+}
+
 struct Similarity2 {
     matrix: Mat3,
 }
@@ -106,3 +112,57 @@ impl Similarity2 {
         Similarity2 { matrix }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_vertices_eq(actual: &[[f32; 3]], expected: &[[f32; 2]]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!(
+                (a[0] - e[0]).abs() < 1e-5 && (a[1] - e[1]).abs() < 1e-5 && a[2] == 0.0,
+                "{a:?} != {e:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn order_1() {
+        assert_vertices_eq(
+            &hilbert_vertices(1),
+            &[[-0.5, -0.5], [-0.5, 0.5], [0.5, 0.5], [0.5, -0.5]],
+        );
+    }
+
+    #[test]
+    fn order_2() {
+        assert_vertices_eq(
+            &hilbert_vertices(2),
+            &[
+                [-0.75, -0.75],
+                [-0.25, -0.75],
+                [-0.25, -0.25],
+                [-0.75, -0.25],
+                [-0.75, 0.25],
+                [-0.75, 0.75],
+                [-0.25, 0.75],
+                [-0.25, 0.25],
+                [0.25, 0.25],
+                [0.25, 0.75],
+                [0.75, 0.75],
+                [0.75, 0.25],
+                [0.75, -0.25],
+                [0.25, -0.25],
+                [0.25, -0.75],
+                [0.75, -0.75],
+            ],
+        );
+    }
+
+    #[test]
+    fn order_0_is_empty() {
+        assert!(hilbert_vertices(0).is_empty());
+        assert!(HilbertCurve::new(0).vertices.is_empty());
+    }
+}
@@ -1,36 +1,268 @@
 use crate::{hilbert_curve::HilbertCurve, line::Line};
 use app_surface::{AppSurface, SurfaceFrame};
 use std::sync::Arc;
-use utils::{BufferObj, SceneUniform, WgpuAppAction};
-use winit::dpi::PhysicalSize;
+use utils::{BufferObj, DynamicUniformRing, SceneUniform, Viewport, WgpuAppAction};
+use winit::{
+    dpi::PhysicalSize,
+    event::{ElementState, KeyEvent},
+    keyboard::{KeyCode, PhysicalKey},
+};
 
 pub struct HilbertCurveApp {
     app: AppSurface,
     size: PhysicalSize<u32>,
-    size_changed: bool,
+    resize_debouncer: utils::ResizeDebouncer,
     mvp_buffer: BufferObj,
     line: Line,
+    // 每帧动画的 uniform，按动态偏移访问
+    hilbert_buf: DynamicUniformRing<crate::HilbertUniform>,
     // 当前曲线与目标曲线的顶点缓冲区
-    vertex_buffers: Vec<wgpu::Buffer>,
+    vertex_buffers: Vec<BufferObj>,
     // 当前曲线的顶点总数
     curve_vertex_count: usize,
     // 当前动画帧的索引，用于设置缓冲区的动态偏移
     animate_index: u32,
     // 每一个动画阶段的总帧数
     draw_count: u32,
+    // 曲线的最大维度
+    max_dimension: u32,
+    // 到达最大维度后的行为
+    loop_mode: LoopMode,
+    // 动画是否已停止
+    is_finished: bool,
     // 目标曲线维度
     curve_dimention: u32,
     // 是否为升维动画
     is_animation_up: bool,
+    // 是否在右下角显示画中画小地图，按 M 键切换
+    show_minimap: bool,
+    // 小地图的纯色背景，遮住其下方的主画面
+    minimap_background: wgpu::RenderPipeline,
+    // 是否保留上一帧的内容（拖尾效果）
+    keep_previous_frame: bool,
+    // 拖尾效果的持久渲染目标：交换链纹理不保证保留上一帧的内容，所以先累积到这里再复制到 surface
+    trail_view: Option<wgpu::TextureView>,
+    blitter: wgpu::util::TextureBlitter,
+    // 只绘制前 K 个线段实例，用于“逐步画出曲线”的动画；`None` 表示绘制全部
+    visible_instances: Option<u32>,
+}
+
+/// 到达最大维度后的动画行为
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LoopMode {
+    /// 在 1 维与最大维度之间来回变换
+    #[default]
+    PingPong,
+    /// 升到最大维度后再降回 1 维，然后退出，用于录制一个完整的周期
+    Once,
+    /// 升到最大维度后停止
+    Hold,
+}
+
+/// Hilbert 曲线变换动画的配置
+#[derive(Clone, Copy, Debug)]
+pub struct HilbertMorphConfig {
+    /// 相邻维度之间每次过渡的帧数，最少 2 帧
+    pub frames_per_transition: u32,
+    /// 曲线的最大维度，取值范围 [2, 8]
+    pub max_dimension: u32,
+    pub loop_mode: LoopMode,
+    /// 是否在顶点着色器中剔除长度为 0 的线段，见 [`crate::is_degenerate_segment`]
+    pub cull_degenerate_segments: bool,
+}
+
+impl Default for HilbertMorphConfig {
+    fn default() -> Self {
+        Self {
+            frames_per_transition: 60 * 3,
+            max_dimension: 6,
+            loop_mode: LoopMode::PingPong,
+            cull_degenerate_segments: true,
+        }
+    }
+}
+
+/// 过渡动画每一帧的 uniform 数据，按动态偏移依次写入缓冲区
+pub fn morph_uniforms(frames_per_transition: u32) -> Vec<crate::HilbertUniform> {
+    (0..frames_per_transition)
+        .map(|step| crate::HilbertUniform {
+            near_target_ratio: step as f32 / (frames_per_transition - 1) as f32,
+            depth_bias: morph_depth_bias(step),
+        })
+        .collect()
+}
+
+/// 过渡动画第 `step` 帧的深度偏移，支持推送常量时直接以推送常量传入着色器
+pub fn morph_depth_bias(step: u32) -> f32 {
+    1.0 - 0.01 * step as f32
+}
+
+/// 一次过渡（从 `dimension` 升维或降维）完成后，计算下一次过渡的起始维度与方向
+///
+/// 返回 `None` 表示动画停止
+pub fn next_transition(
+    dimension: u32,
+    is_up: bool,
+    max_dimension: u32,
+    loop_mode: LoopMode,
+) -> Option<(u32, bool)> {
+    if is_up {
+        let end = dimension + 1;
+        if end < max_dimension {
+            Some((end, true))
+        } else if loop_mode == LoopMode::Hold {
+            None
+        } else {
+            Some((end, false))
+        }
+    } else {
+        let end = dimension - 1;
+        if end > 1 {
+            Some((end, false))
+        } else if loop_mode == LoopMode::PingPong {
+            Some((end, true))
+        } else {
+            None
+        }
+    }
+}
+
+/// 实际绘制的实例数：`visible` 会被限制在可用的实例数 `available` 之内
+pub fn visible_instance_count(available: u32, visible: Option<u32>) -> u32 {
+    visible.map_or(available, |k| k.min(available))
+}
+
+/// 本帧渲染通道的颜色加载操作
+///
+/// 只有开启拖尾且持久渲染目标已有上一帧的内容（`trail_ready`）时才使用 `Load`，
+/// 刚创建（或因窗口大小变化重建）的渲染目标先以背景色清屏一次。
+pub fn frame_load_op(keep_previous_frame: bool, trail_ready: bool) -> wgpu::LoadOp<wgpu::Color> {
+    if keep_previous_frame && trail_ready {
+        wgpu::LoadOp::Load
+    } else {
+        wgpu::LoadOp::Clear(utils::color_from_hex("#f2eadd").unwrap())
+    }
+}
+
+/// 容纳 `vertex_count` 个曲线顶点所需的顶点缓冲区字节数
+pub fn curve_buffer_size(vertex_count: usize) -> wgpu::BufferAddress {
+    (4 * 3) * vertex_count as wgpu::BufferAddress
 }
 
 impl HilbertCurveApp {
+    /// 设置每帧绘制的线段实例数，超出当前曲线线段数时按线段数绘制
+    pub fn set_visible_instances(&mut self, visible_instances: Option<u32>) {
+        self.visible_instances = visible_instances;
+    }
+
+    /// 把过渡的起止曲线写入两个 ping-pong 顶点缓冲区，容量不足时先扩容
+    fn write_curve_buffers(&mut self, start: &HilbertCurve, target: &HilbertCurve) {
+        let mut encoder = self
+            .app
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("curve buffer resize encoder"),
+            });
+        let mut resized = false;
+        for (buf, curve) in self.vertex_buffers.iter_mut().zip([start, target]) {
+            let size = curve_buffer_size(curve.vertices.len());
+            if size > buf.size {
+                buf.resize(&self.app.device, &mut encoder, size);
+                resized = true;
+            }
+        }
+        // 需先提交扩容时的复制命令，否则复制的旧内容会覆盖下面写入的顶点
+        if resized {
+            self.app.queue.submit(Some(encoder.finish()));
+        }
+        for (buf, curve) in self.vertex_buffers.iter().zip([start, target]) {
+            self.app
+                .queue
+                .write_buffer(&buf.buffer, 0, bytemuck::cast_slice(&curve.vertices));
+        }
+    }
+
+    /// 创建与 surface 同尺寸、同格式的拖尾渲染目标
+    fn create_trail_view(&self) -> wgpu::TextureView {
+        self.app
+            .device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: Some("hilbert trail"),
+                size: wgpu::Extent3d {
+                    width: self.app.config.width,
+                    height: self.app.config.height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: self.app.config.format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                    | wgpu::TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            })
+            .create_view(&wgpu::TextureViewDescriptor::default())
+    }
+
+    /// 设置线段的管线、uniform 与本帧动画的动态偏移
+    fn bind_line(&self, rpass: &mut wgpu::RenderPass<'_>) {
+        rpass.set_pipeline(&self.line.pipeline);
+        rpass.set_bind_group(0, &self.line.bg_setting.bind_group, &[]);
+        let dyn_off = self.hilbert_buf.dynamic_offset(self.animate_index);
+        rpass.set_bind_group(1, &self.line.dy_bg.bind_group, &[dyn_off]);
+        if self.line.push_constant_depth_bias {
+            let depth_bias = morph_depth_bias(self.animate_index);
+            rpass.set_push_constants(
+                wgpu::ShaderStages::VERTEX,
+                0,
+                bytemuck::bytes_of(&depth_bias),
+            );
+        }
+    }
+
+    /// 以纯色填满当前视口的管线，用作小地图的背景
+    fn create_minimap_background(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+    ) -> wgpu::RenderPipeline {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("minimap background shader"),
+            source: wgpu::ShaderSource::Wgsl(
+                include_str!("../assets/minimap_background.wgsl").into(),
+            ),
+        });
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("minimap background pipeline"),
+            layout: None,
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(format.into())],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        })
+    }
+
     /// 必要的时候调整 surface 大小
     fn resize_surface_if_needed(&mut self) {
-        if self.size_changed {
+        if let Some(size) = self.resize_debouncer.take_if_changed() {
+            self.size = size;
             //  需先 resize surface
             self.app
                 .resize_surface_by_size((self.size.width, self.size.height));
+            // 拖尾的渲染目标需与 surface 同尺寸，下次绘制时重建
+            self.trail_view = None;
 
             let viewport = glam::Vec2 {
                 x: self.size.width as f32,
@@ -49,19 +281,21 @@ impl HilbertCurveApp {
                 0,
                 bytemuck::bytes_of(&resized_uniform),
             );
-            self.size_changed = false;
         }
     }
-}
 
-impl WgpuAppAction for HilbertCurveApp {
-    async fn new(window: Arc<winit::window::Window>) -> Self {
+    /// 使用自定义的变换动画配置创建 app
+    pub async fn new_with_config(
+        window: Arc<winit::window::Window>,
+        config: HilbertMorphConfig,
+    ) -> Self {
+        let draw_count = config.frames_per_transition.max(2);
+        let max_dimension = config.max_dimension.clamp(2, 8);
         // 创建 wgpu 应用
         let mut app = AppSurface::new(window).await;
 
-        // 兼容 web
-        let format = app.config.format.remove_srgb_suffix();
-        app.ctx.update_config_format(format);
+        // 兼容 web：仅在 surface 支持时切换为非 sRGB 格式
+        let format = utils::surface_format::use_non_srgb_format(&mut app);
 
         let viewport = glam::Vec2 {
             x: app.config.width as f32,
@@ -80,81 +314,119 @@ impl WgpuAppAction for HilbertCurveApp {
             },
             Some("SceneUniform"),
         );
-        // 动作总帧总
-        let draw_count = 60 * 3;
-        let offset_buffer_size = 256;
-        let hilbert_buf = BufferObj::create_empty_uniform_buffer(
+        // 动态偏移缓冲区的大小由每次过渡的帧数决定
+        let uniforms = morph_uniforms(draw_count);
+        let hilbert_buf = DynamicUniformRing::new(
             &app.device,
-            (draw_count * offset_buffer_size) as wgpu::BufferAddress,
-            offset_buffer_size,
-            true,
+            uniforms.len() as u32,
             Some("动画的动态偏移缓冲区"),
         );
-        // 按动态偏移量填充 uniform 缓冲区
-        let mut depth_bias = 1.0;
-        for step in 0..draw_count {
-            let uniform = crate::HilbertUniform {
-                near_target_ratio: step as f32 / (draw_count - 1) as f32,
-                depth_bias,
-            };
-            app.queue.write_buffer(
-                &hilbert_buf.buffer,
-                offset_buffer_size * (step),
-                bytemuck::bytes_of(&uniform),
-            );
-            depth_bias -= 0.01;
-        }
+        hilbert_buf.write_frames(&app.queue, &uniforms);
 
-        // buffer 大小
-        let size = (4 * 4 * 3) * HilbertCurve::new(5).vertices.len() as u64;
-        // 创建两个 ping-pong 顶点缓冲区
-        let mut vertex_buffers: Vec<wgpu::Buffer> = Vec::with_capacity(2);
-        for _ in 0..2 {
-            let buf = app.device.create_buffer(&wgpu::BufferDescriptor {
-                size,
-                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-                label: Some("vertex buffer"),
-                mapped_at_creation: false,
-            });
-            vertex_buffers.push(buf);
-        }
+        // 创建两个 ping-pong 顶点缓冲区，初始大小容纳 2 维曲线，之后按需扩容
+        let size = curve_buffer_size(HilbertCurve::new(2).vertices.len());
+        let vertex_buffers: Vec<BufferObj> = (0..2)
+            .map(|_| {
+                BufferObj::create_empty_storage_buffer(
+                    &app.device,
+                    size,
+                    wgpu::BufferUsages::VERTEX
+                        | wgpu::BufferUsages::COPY_SRC
+                        | wgpu::BufferUsages::COPY_DST,
+                    Some("vertex buffer"),
+                )
+            })
+            .collect();
 
-        let line = Line::new(&app, &mvp_buffer, &hilbert_buf);
+        let line = Line::new(
+            &app,
+            &mvp_buffer,
+            &hilbert_buf,
+            config.cull_degenerate_segments,
+        );
 
         let size = PhysicalSize::new(app.config.width, app.config.height);
+        let blitter = wgpu::util::TextureBlitter::new(&app.device, format);
+        let minimap_background = Self::create_minimap_background(&app.device, format);
 
         Self {
             app,
             size,
-            size_changed: false,
+            resize_debouncer: utils::ResizeDebouncer::new(),
             mvp_buffer,
             line,
+            hilbert_buf,
             vertex_buffers,
             curve_vertex_count: 0,
             animate_index: 0,
-            draw_count: draw_count as u32,
+            draw_count,
+            max_dimension,
+            loop_mode: config.loop_mode,
+            is_finished: false,
             curve_dimention: 1,
             is_animation_up: true,
+            show_minimap: true,
+            minimap_background,
+            keep_previous_frame: false,
+            trail_view: None,
+            blitter,
+            visible_instances: None,
         }
     }
+}
+
+impl WgpuAppAction for HilbertCurveApp {
+    async fn new(window: Arc<winit::window::Window>) -> Self {
+        Self::new_with_config(window, HilbertMorphConfig::default()).await
+    }
 
     fn set_window_resized(&mut self, new_size: PhysicalSize<u32>) {
-        if self.app.config.width == new_size.width && self.app.config.height == new_size.height {
-            return;
-        }
-        self.size = new_size;
-        self.size_changed = true;
+        let current = self.get_size();
+        self.resize_debouncer.record(new_size, current);
     }
 
     fn get_size(&self) -> PhysicalSize<u32> {
         PhysicalSize::new(self.app.config.width, self.app.config.height)
     }
 
-    fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
-        // —— 1. 处理窗口大小变化 ——
-        self.resize_surface_if_needed();
+    fn keyboard_input(&mut self, event: &KeyEvent) -> bool {
+        if event.state != ElementState::Pressed || event.repeat {
+            return false;
+        }
+        match event.physical_key {
+            // 按 L 键在清屏与保留上一帧内容之间切换
+            PhysicalKey::Code(KeyCode::KeyL) => {
+                self.keep_previous_frame = !self.keep_previous_frame;
+                if !self.keep_previous_frame {
+                    self.trail_view = None;
+                }
+                true
+            }
+            // 按 M 键显示/隐藏画中画小地图
+            PhysicalKey::Code(KeyCode::KeyM) => {
+                self.show_minimap = !self.show_minimap;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn should_exit(&self) -> bool {
+        self.is_finished && self.loop_mode == LoopMode::Once
+    }
+
+    fn color_load_op(&self) -> wgpu::LoadOp<wgpu::Color> {
+        frame_load_op(self.keep_previous_frame, self.trail_view.is_some())
+    }
+
+    /// 动画会一直重绘，窗口在后台时暂停以免空耗 GPU
+    fn pause_when_unfocused(&self) -> bool {
+        true
+    }
 
-        // —— 2. 首次调用：立即填充 1维 → 2维 的 start/target 缓冲 ——
+    /// 每次调用推进一帧动画，使用 `RunConfig::fixed_timestep` 时动画速度与刷新率无关
+    fn update(&mut self, _dt: instant::Duration) {
+        // —— 1. 首次调用：立即填充 1维 → 2维 的 start/target 缓冲 ——
         if self.curve_vertex_count == 0 {
             // 确定下一个目标维度（初始 self.curve_dimention==1，is_animation_up==true）
             let next_dim = self.curve_dimention + 1;
@@ -166,39 +438,35 @@ impl WgpuAppAction for HilbertCurveApp {
 
             // 更新实例数，并写入两个 vertex buffer
             self.curve_vertex_count = target_curve.vertices.len();
-            self.app.queue.write_buffer(
-                &self.vertex_buffers[0],
-                0,
-                bytemuck::cast_slice(&start_curve.vertices),
-            );
-            self.app.queue.write_buffer(
-                &self.vertex_buffers[1],
-                0,
-                bytemuck::cast_slice(&target_curve.vertices),
-            );
+            self.write_curve_buffers(&start_curve, &target_curve);
         }
 
-        // —— 3. 推进动画索引 ——
-        self.animate_index = (self.animate_index + 1) % self.draw_count;
-
-        // —— 4. 每当 animate_index 回到 0，就切换维度并准备下一次过渡 ——
-        if self.animate_index == 0 {
-            // 更新维度状态
-            if self.is_animation_up {
-                if self.curve_dimention < 6 {
-                    self.curve_dimention += 1;
-                } else {
-                    self.is_animation_up = false;
-                    self.curve_dimention -= 1;
-                }
+        // —— 2. 推进动画索引 ——
+        // 动画停止后保持在最后一帧
+        let next_index = (self.animate_index + 1) % self.draw_count;
+        let transition = if next_index == 0 && !self.is_finished {
+            next_transition(
+                self.curve_dimention,
+                self.is_animation_up,
+                self.max_dimension,
+                self.loop_mode,
+            )
+        } else {
+            None
+        };
+        if !self.is_finished {
+            if next_index == 0 && transition.is_none() {
+                self.is_finished = true;
             } else {
-                if self.curve_dimention > 1 {
-                    self.curve_dimention -= 1;
-                } else {
-                    self.is_animation_up = true;
-                    self.curve_dimention += 1;
-                }
+                self.animate_index = next_index;
             }
+        }
+
+        // —— 3. 每当 animate_index 回到 0，就切换维度并准备下一次过渡 ——
+        if let Some((dimension, is_up)) = transition {
+            // 更新维度状态
+            self.curve_dimention = dimension;
+            self.is_animation_up = is_up;
 
             // 计算下一次过渡的目标维度
             let next_dim = if self.is_animation_up {
@@ -217,19 +485,15 @@ impl WgpuAppAction for HilbertCurveApp {
 
             // 更新实例数 & 写缓冲
             self.curve_vertex_count = target_curve.vertices.len();
-            self.app.queue.write_buffer(
-                &self.vertex_buffers[0],
-                0,
-                bytemuck::cast_slice(&start_curve.vertices),
-            );
-            self.app.queue.write_buffer(
-                &self.vertex_buffers[1],
-                0,
-                bytemuck::cast_slice(&target_curve.vertices),
-            );
+            self.write_curve_buffers(&start_curve, &target_curve);
         }
+    }
+
+    fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
+        // —— 1. 处理窗口大小变化 ——
+        self.resize_surface_if_needed();
 
-        // —— 5. 真正开始绘制 ——
+        // —— 2. 真正开始绘制 ——
         let output = self.app.surface.get_current_texture()?;
         let view = output
             .texture
@@ -241,14 +505,21 @@ impl WgpuAppAction for HilbertCurveApp {
                 label: Some("Render Encoder"),
             });
 
+        // 需在创建拖尾的渲染目标之前取得加载操作，新建的渲染目标先清屏
+        let load_op = self.color_load_op();
+        if self.keep_previous_frame && self.trail_view.is_none() {
+            self.trail_view = Some(self.create_trail_view());
+        }
+        let target_view = self.trail_view.as_ref().unwrap_or(&view);
+
         {
             let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Hilbert Render"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
+                    view: target_view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(utils::unpack_u32_to_color(0xf2eaddff)),
+                        load: load_op,
                         store: wgpu::StoreOp::Store,
                     },
                 })],
@@ -256,20 +527,37 @@ impl WgpuAppAction for HilbertCurveApp {
             });
 
             // 绑定 pipeline + uniform
-            rpass.set_pipeline(&self.line.pipeline);
-            rpass.set_bind_group(0, &self.line.bg_setting.bind_group, &[]);
-            let dyn_off: wgpu::DynamicOffset = 256 * self.animate_index;
-            rpass.set_bind_group(1, &self.line.dy_bg.bind_group, &[dyn_off]);
+            self.bind_line(&mut rpass);
 
             // 绑定 4 个实例流的顶点缓冲
-            let instance_count = (self.curve_vertex_count as u32).saturating_sub(1);
-            rpass.set_vertex_buffer(0, self.vertex_buffers[0].slice(..));
-            rpass.set_vertex_buffer(1, self.vertex_buffers[0].slice(12..));
-            rpass.set_vertex_buffer(2, self.vertex_buffers[1].slice(..));
-            rpass.set_vertex_buffer(3, self.vertex_buffers[1].slice(12..));
+            let instance_count = visible_instance_count(
+                (self.curve_vertex_count as u32).saturating_sub(1),
+                self.visible_instances,
+            );
+            rpass.set_vertex_buffer(0, self.vertex_buffers[0].buffer.slice(..));
+            rpass.set_vertex_buffer(1, self.vertex_buffers[0].buffer.slice(12..));
+            rpass.set_vertex_buffer(2, self.vertex_buffers[1].buffer.slice(..));
+            rpass.set_vertex_buffer(3, self.vertex_buffers[1].buffer.slice(12..));
 
             // 绘制所有线段实例
             rpass.draw(0..6, 0..instance_count);
+
+            // 画中画：在右下角象限先绘制背景，再绘制一次曲线
+            // `LoadOp::Clear` 不受视口影响，所以用纯色的背景遮住象限中已绘制的曲线
+            let viewport = Viewport::bottom_right_quadrant(self.size);
+            if self.show_minimap && viewport.apply(&mut rpass, self.size) {
+                rpass.set_pipeline(&self.minimap_background);
+                rpass.draw(0..3, 0..1);
+
+                // 背景管线的布局不同，需重新绑定线段的管线与 uniform
+                self.bind_line(&mut rpass);
+                rpass.draw(0..6, 0..instance_count);
+            }
+        }
+
+        if let Some(trail_view) = self.trail_view.as_ref() {
+            self.blitter
+                .copy(&self.app.device, &mut encoder, trail_view, &view);
         }
 
         // 提交并呈现
@@ -279,3 +567,53 @@ impl WgpuAppAction for HilbertCurveApp {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn load_op_toggle() {
+        let is_clear = |op: wgpu::LoadOp<wgpu::Color>| matches!(op, wgpu::LoadOp::Clear(_));
+        assert!(is_clear(frame_load_op(false, false)));
+        assert!(is_clear(frame_load_op(false, true)));
+        // 刚开启拖尾时渲染目标还没有内容，先清屏
+        assert!(is_clear(frame_load_op(true, false)));
+        assert_eq!(frame_load_op(true, true), wgpu::LoadOp::Load);
+    }
+
+    #[test]
+    fn uniform_count_follows_frames() {
+        let uniforms = morph_uniforms(45);
+        assert_eq!(uniforms.len(), 45);
+        assert_eq!(uniforms[0].near_target_ratio, 0.0);
+        assert_eq!(uniforms[44].near_target_ratio, 1.0);
+    }
+
+    #[test]
+    fn transitions_by_loop_mode() {
+        let run = |mode| {
+            let mut state = Some((1, true));
+            let mut dims = vec![];
+            while let Some((dim, is_up)) = state {
+                dims.push(dim);
+                if dims.len() == 8 {
+                    break;
+                }
+                state = next_transition(dim, is_up, 3, mode);
+            }
+            dims
+        };
+        assert_eq!(run(LoopMode::PingPong), vec![1, 2, 3, 2, 1, 2, 3, 2]);
+        assert_eq!(run(LoopMode::Once), vec![1, 2, 3, 2]);
+        assert_eq!(run(LoopMode::Hold), vec![1, 2]);
+    }
+
+    #[test]
+    fn visible_instances_clamped() {
+        assert_eq!(visible_instance_count(63, None), 63);
+        assert_eq!(visible_instance_count(63, Some(10)), 10);
+        assert_eq!(visible_instance_count(63, Some(1000)), 63);
+        assert_eq!(visible_instance_count(0, Some(5)), 0);
+    }
+}
@@ -1,8 +1,13 @@
 mod hilbert_curve_app;
-pub use hilbert_curve_app::HilbertCurveApp;
+pub use hilbert_curve_app::{
+    HilbertCurveApp, HilbertMorphConfig, LoopMode, morph_depth_bias, morph_uniforms,
+    next_transition, visible_instance_count,
+};
 
 mod hilbert_curve;
+pub use hilbert_curve::hilbert_vertices;
 mod line;
+pub use line::{DEGENERATE_NDC_EPSILON, is_degenerate_segment};
 
 use bytemuck::{Pod, Zeroable};
 
@@ -1,18 +1,45 @@
 use app_surface::AppSurface;
 use utils::{
     BufferObj,
-    node::{BindGroupData, BindGroupSetting, DynamicUniformBindGroup},
+    node::{BindGroupData, BindGroupSetting, DynamicUniformBindGroup, check_push_constant_ranges},
 };
 use wgpu::{MultisampleState, RenderPipeline, ShaderStages, VertexFormat};
 
+/// 判定线段长度为 0 的 NDC 距离阈值
+///
+/// NDC 的 x、y 范围都是 [-1, 1]，4K 宽度下一个像素约为 `2 / 3840 ≈ 5.2e-4`，
+/// 这个阈值远小于一个像素，只会剔除两端点重合（或仅差浮点误差）的线段，不会影响可见的短线段。
+pub const DEGENERATE_NDC_EPSILON: f32 = 1e-5;
+
+/// 线段两端点投影到 NDC 后是否重合，与着色器中的判定一致
+///
+/// 变换动画中，升维时起始曲线的每个顶点被复制了 4 份（`four_times_vertices`），
+/// 过渡开始时这些重复顶点之间的线段长度为 0，着色器中 `normalize` 零向量会得到 NaN，可能画出杂线。
+pub fn is_degenerate_segment(mvp: glam::Mat4, a: glam::Vec3, b: glam::Vec3) -> bool {
+    let ndc_a = mvp.project_point3(a).truncate();
+    let ndc_b = mvp.project_point3(b).truncate();
+    ndc_a.distance(ndc_b) < DEGENERATE_NDC_EPSILON
+}
+
 pub struct Line {
     pub bg_setting: BindGroupSetting,
     pub dy_bg: DynamicUniformBindGroup,
     pub pipeline: RenderPipeline,
+    // 为 true 时深度偏移通过推送常量传入，否则仍读取动态偏移 uniform 中的 depth_bias
+    pub push_constant_depth_bias: bool,
 }
 
 impl Line {
-    pub fn new(app: &AppSurface, mvp_buffer: &BufferObj, hilbert_buf: &BufferObj) -> Self {
+    /// `cull_degenerate` 为 true 时，着色器把长度为 0 的线段折叠为一个点，不产生任何片元
+    ///
+    /// 设备支持推送常量时，每帧的深度偏移改用推送常量传入（见 [`Line::push_constant_depth_bias`]）；
+    /// WebGPU/WebGL 等不支持的后端回退为动态偏移 uniform。
+    pub fn new(
+        app: &AppSurface,
+        mvp_buffer: &BufferObj,
+        hilbert_buf: &BufferObj,
+        cull_degenerate: bool,
+    ) -> Self {
         // 准备绑定组需要的数据
         let bind_group_data = BindGroupData {
             uniforms: vec![mvp_buffer],
@@ -26,19 +53,31 @@ impl Line {
 
         let dy_bg =
             DynamicUniformBindGroup::new(&app.device, vec![(hilbert_buf, ShaderStages::VERTEX)]);
+        let push_constant_ranges = [wgpu::PushConstantRange {
+            stages: ShaderStages::VERTEX,
+            range: 0..core::mem::size_of::<f32>() as u32,
+        }];
+        let push_constant_depth_bias =
+            check_push_constant_ranges(&app.device, &push_constant_ranges).is_ok();
         let pipeline_layout = app
             .device
             .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                 label: None,
                 bind_group_layouts: &[&bg_setting.bind_group_layout, &dy_bg.bind_group_layout],
-                push_constant_ranges: &[],
+                push_constant_ranges: if push_constant_depth_bias {
+                    &push_constant_ranges
+                } else {
+                    &[]
+                },
             });
         // 着色器
         let shader = app
             .device
             .create_shader_module(wgpu::ShaderModuleDescriptor {
                 label: Some("hilbert shader"),
-                source: wgpu::ShaderSource::Wgsl(include_str!("../assets/hilbert.wgsl").into()),
+                source: wgpu::ShaderSource::Wgsl(
+                    line_shader_source(cull_degenerate, push_constant_depth_bias).into(),
+                ),
             });
 
         let mut buffers: Vec<wgpu::VertexBufferLayout> = Vec::with_capacity(4);
@@ -90,6 +129,52 @@ impl Line {
             bg_setting,
             dy_bg,
             pipeline,
+            push_constant_depth_bias,
         }
     }
 }
+
+fn line_shader_source(cull_degenerate: bool, push_constant_depth_bias: bool) -> String {
+    // 着色器通过 depth_bias() 读取深度偏移，由这里决定数据来源
+    let depth_bias = if push_constant_depth_bias {
+        "struct HilbertPushConstants { depth_bias: f32 };\nvar<push_constant> push_constants: HilbertPushConstants;\nfn depth_bias() -> f32 { return push_constants.depth_bias; }\n"
+    } else {
+        "fn depth_bias() -> f32 { return hilbert.depth_bias; }\n"
+    };
+    format!(
+        "const CULL_DEGENERATE: bool = {cull_degenerate};\nconst DEGENERATE_NDC_EPSILON: f32 = {DEGENERATE_NDC_EPSILON:?};\n{depth_bias}"
+    ) + include_str!("../assets/hilbert.wgsl")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hilbert_curve::HilbertCurve;
+
+    #[test]
+    fn coincident_segments_detected() {
+        // 升维过渡的起点：1 维曲线的每个顶点重复 4 次
+        let mut curve = HilbertCurve::new(1);
+        curve.four_times_vertices();
+        let degenerate = curve
+            .vertices
+            .windows(2)
+            .filter(|pair| {
+                is_degenerate_segment(
+                    glam::Mat4::IDENTITY,
+                    glam::Vec3::from(pair[0].pos),
+                    glam::Vec3::from(pair[1].pos),
+                )
+            })
+            .count();
+        // 16 个顶点、15 条线段，其中每组重复顶点内部的 3 条长度为 0
+        assert_eq!(curve.vertices.len() - 1, 15);
+        assert_eq!(degenerate, 12);
+
+        let source = line_shader_source(true, false);
+        assert!(source.starts_with("const CULL_DEGENERATE: bool = true;"));
+        assert!(source.contains("DEGENERATE_NDC_EPSILON: f32 = 1e-5;"));
+        assert!(source.contains("return hilbert.depth_bias;"));
+        assert!(line_shader_source(true, true).contains("var<push_constant>"));
+    }
+}
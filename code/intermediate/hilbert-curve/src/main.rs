@@ -1,5 +1,11 @@
 use hilbert_curve::HilbertCurveApp;
+use utils::RunConfig;
 
 pub fn main() -> Result<(), impl std::error::Error> {
-    utils::run::<HilbertCurveApp>("hilbert-curve")
+    // 动画按 60 Hz 的固定步长推进，与显示器刷新率无关
+    let config = RunConfig {
+        fixed_timestep: Some(instant::Duration::from_secs(1) / 60),
+        ..Default::default()
+    };
+    utils::run_with_config::<HilbertCurveApp>("hilbert-curve", config)
 }
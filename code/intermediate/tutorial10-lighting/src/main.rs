@@ -30,7 +30,7 @@ struct Camera {
 
 impl Camera {
     fn build_view_projection_matrix(&self) -> glam::Mat4 {
-        let view = glam::Mat4::look_at_rh(self.eye, self.target, self.up);
+        let view = utils::matrix_helper::look_at(self.eye, self.target, self.up);
         let proj =
             glam::Mat4::perspective_rh(self.fovy.to_radians(), self.aspect, self.znear, self.zfar);
         proj * view
@@ -237,7 +237,7 @@ struct LightUniform {
 struct WgpuApp {
     app: AppSurface,
     size: PhysicalSize<u32>,
-    size_changed: bool,
+    resize_debouncer: utils::ResizeDebouncer,
     render_pipeline: wgpu::RenderPipeline,
     obj_model: model::Model,
     camera: Camera,
@@ -258,7 +258,8 @@ struct WgpuApp {
 impl WgpuApp {
     /// 必要的时候调整 surface 大小
     fn resize_surface_if_needed(&mut self) {
-        if self.size_changed {
+        if let Some(size) = self.resize_debouncer.take_if_changed() {
+            self.size = size;
             //  需先 resize surface
             self.app
                 .resize_surface_by_size((self.size.width, self.size.height));
@@ -269,8 +270,6 @@ impl WgpuApp {
                 &self.app.config,
                 "depth_texture",
             );
-
-            self.size_changed = false;
         }
     }
 }
@@ -550,7 +549,7 @@ impl WgpuAppAction for WgpuApp {
         Self {
             app,
             size,
-            size_changed: false,
+            resize_debouncer: utils::ResizeDebouncer::new(),
             render_pipeline,
             obj_model,
             camera,
@@ -569,11 +568,8 @@ impl WgpuAppAction for WgpuApp {
     }
 
     fn set_window_resized(&mut self, new_size: PhysicalSize<u32>) {
-        if self.app.config.width == new_size.width && self.app.config.height == new_size.height {
-            return;
-        }
-        self.size = new_size;
-        self.size_changed = true;
+        let current = self.get_size();
+        self.resize_debouncer.record(new_size, current);
     }
 
     fn get_size(&self) -> PhysicalSize<u32> {
@@ -1,6 +1,5 @@
 use core::f32::consts::FRAC_PI_2;
 use core::time::Duration;
-use winit::dpi::PhysicalPosition;
 use winit::{
     event::*,
     keyboard::{Key, KeyCode, NamedKey, PhysicalKey},
@@ -9,6 +8,7 @@ use winit::{
 const SAFE_FRAC_PI_2: f32 = FRAC_PI_2 - 0.0001;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Camera {
     pub position: glam::Vec3,
     yaw: f32,
@@ -25,17 +25,30 @@ impl Camera {
     }
 
     pub fn calc_matrix(&self) -> glam::Mat4 {
+        glam::Mat4::look_to_rh(self.position, self.direction(), glam::Vec3::Y)
+    }
+
+    /// 相机的朝向（单位向量）
+    pub fn direction(&self) -> glam::Vec3 {
         let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
         let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        glam::Vec3::new(cos_pitch * cos_yaw, sin_pitch, cos_pitch * sin_yaw).normalize()
+    }
 
-        glam::Mat4::look_to_rh(
-            self.position,
-            glam::Vec3::new(cos_pitch * cos_yaw, sin_pitch, cos_pitch * sin_yaw).normalize(),
-            glam::Vec3::Y,
-        )
+    /// 让相机位于 `position` 并看向 `target`，俯仰角限制在 ±90° 以内
+    ///
+    /// 两点重合时只更新位置，保持原来的朝向。
+    pub fn look_at(&mut self, position: glam::Vec3, target: glam::Vec3) {
+        self.position = position;
+        let Some(direction) = (target - position).try_normalize() else {
+            return;
+        };
+        self.yaw = direction.z.atan2(direction.x);
+        self.pitch = direction.y.asin().clamp(-SAFE_FRAC_PI_2, SAFE_FRAC_PI_2);
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Projection {
     aspect: f32,
     fovy: f32,
@@ -57,11 +70,40 @@ impl Projection {
         self.aspect = width as f32 / height as f32;
     }
 
+    /// 垂直视场角（弧度）
+    pub fn fovy(&self) -> f32 {
+        self.fovy
+    }
+
     pub fn calc_matrix(&self) -> glam::Mat4 {
         glam::Mat4::perspective_rh(self.fovy, self.aspect, self.znear, self.zfar)
     }
 }
 
+#[cfg(feature = "serde")]
+impl Camera {
+    /// 序列化为 JSON，可粘贴到 issue 中复现完全相同的画面
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Camera is always serializable")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Projection {
+    /// 序列化为 JSON，与 `Camera::to_json` 的结果一起才能确定完整的画面（视场角、宽高比与裁剪面）
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Projection is always serializable")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
 #[derive(Debug)]
 pub struct CameraController {
     amount_left: f32,
@@ -140,15 +182,28 @@ impl CameraController {
     }
 
     pub fn process_scroll(&mut self, delta: &MouseScrollDelta) {
-        self.scroll = match delta {
-            // I'm assuming a line is about 100 pixels
-            MouseScrollDelta::LineDelta(_, scroll) => -scroll * 0.5,
-            MouseScrollDelta::PixelDelta(PhysicalPosition { y: scroll, .. }) => -*scroll as f32,
-        };
+        // 触控板的 PixelDelta 与滚轮的 LineDelta 统一换算为行数，每行缩放 0.5
+        self.scroll = -utils::scroll_lines(delta) * 0.5;
+    }
+
+    /// 双指缩放：`scale` 为两指间距与上一次的比值，张开（大于 1）时向前移动，每张开一倍相当于滚动 2 行
+    pub fn process_pinch(&mut self, scale: f64) {
+        if scale > 0.0 {
+            self.scroll = scale.log2() as f32;
+        }
     }
 
     pub fn update_camera(&mut self, camera: &mut Camera, dt: Duration) {
+        self.update_camera_with_fov(camera, REFERENCE_FOVY, dt);
+    }
+
+    /// 与 `update_camera` 相同，但鼠标旋转的灵敏度随视场角缩放
+    ///
+    /// 缩放系数为 `tan(fovy / 2) / tan(REFERENCE_FOVY / 2)`，即屏幕上同样的鼠标移动距离
+    /// 对应的画面内容移动比例保持不变：视场角越小（放大/瞄准时）旋转越慢。
+    pub fn update_camera_with_fov(&mut self, camera: &mut Camera, fovy: f32, dt: Duration) {
         let dt = dt.as_secs_f32();
+        let rotate_sensitivity = self.sensitivity * fov_sensitivity_scale(fovy);
 
         // Move forward/backward and left/right
         let (yaw_sin, yaw_cos) = camera.yaw.sin_cos();
@@ -172,8 +227,8 @@ impl CameraController {
         camera.position.y += (self.amount_up - self.amount_down) * self.speed * dt;
 
         // Rotate
-        camera.yaw += self.rotate_horizontal * self.sensitivity * dt;
-        camera.pitch += -self.rotate_vertical * self.sensitivity * dt;
+        camera.yaw += self.rotate_horizontal * rotate_sensitivity * dt;
+        camera.pitch += -self.rotate_vertical * rotate_sensitivity * dt;
 
         // If process_mouse isn't called every frame, these values
         // will not get set to zero, and the camera will rotate
@@ -185,3 +240,80 @@ impl CameraController {
         camera.pitch = camera.pitch.clamp(-SAFE_FRAC_PI_2, SAFE_FRAC_PI_2);
     }
 }
+
+/// 鼠标灵敏度的参考视场角（弧度），此视场角下灵敏度不缩放
+pub const REFERENCE_FOVY: f32 = 45.0 * core::f32::consts::PI / 180.0;
+
+/// 视场角对应的鼠标灵敏度缩放系数
+pub fn fov_sensitivity_scale(fovy: f32) -> f32 {
+    (fovy / 2.0).tan() / (REFERENCE_FOVY / 2.0).tan()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn smaller_fov_rotates_slower() {
+        let yaw_delta = |fovy_degrees: f32| {
+            let mut camera = Camera::new((0.0, 0.0, 0.0), 0.0, 0.0);
+            let mut controller = CameraController::new(4.0, 0.4);
+            controller.process_mouse(10.0, 0.0);
+            controller.update_camera_with_fov(
+                &mut camera,
+                fovy_degrees.to_radians(),
+                Duration::from_millis(16),
+            );
+            camera.yaw
+        };
+        assert!(yaw_delta(20.0) < yaw_delta(45.0));
+        assert!(yaw_delta(45.0) < yaw_delta(90.0));
+        assert!((fov_sensitivity_scale(REFERENCE_FOVY) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn look_at_restores_direction() {
+        let camera = Camera::new((0.0, 5.0, 10.0), -90.0, -20.0);
+        let mut restored = Camera::new((0.0, 0.0, 0.0), 0.0, 0.0);
+        restored.look_at(camera.position, camera.position + camera.direction());
+        assert!(
+            restored
+                .calc_matrix()
+                .abs_diff_eq(camera.calc_matrix(), 1e-5)
+        );
+
+        // 两点重合时保持原来的朝向
+        restored.look_at(glam::Vec3::ONE, glam::Vec3::ONE);
+        assert_eq!(restored.position, glam::Vec3::ONE);
+        assert!(restored.direction().abs_diff_eq(camera.direction(), 1e-5));
+    }
+
+    #[test]
+    fn pinch_moves_along_view_direction() {
+        let moved = |scale: f64| {
+            let mut camera = Camera::new((0.0, 0.0, 0.0), 0.0, 0.0);
+            let mut controller = CameraController::new(4.0, 0.4);
+            controller.process_pinch(scale);
+            controller.update_camera(&mut camera, Duration::from_millis(16));
+            camera.position.dot(camera.direction())
+        };
+        assert!(moved(2.0) > 0.0);
+        assert!(moved(0.5) < 0.0);
+        assert_eq!(moved(1.0), 0.0);
+        assert_eq!(moved(0.0), 0.0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn camera_json_round_trip() {
+        let camera = Camera::new((0.0, 5.0, 10.0), -90.0, -20.0);
+        let restored = Camera::from_json(&camera.to_json()).unwrap();
+        assert_eq!(restored.calc_matrix(), camera.calc_matrix());
+
+        let projection = Projection::new(800, 600, 45.0, 0.1, 100.0);
+        let restored = Projection::from_json(&projection.to_json()).unwrap();
+        assert_eq!(restored.calc_matrix(), projection.calc_matrix());
+
+        assert!(Camera::from_json("{}").is_err());
+    }
+}
@@ -126,7 +126,7 @@ struct LightUniform {
 struct WgpuApp {
     app: AppSurface,
     size: PhysicalSize<u32>,
-    size_changed: bool,
+    resize_debouncer: utils::ResizeDebouncer,
     render_pipeline: wgpu::RenderPipeline,
     obj_model: model::Model,
     camera: camera::Camera,                      // UPDATED!
@@ -147,12 +147,15 @@ struct WgpuApp {
     debug_material: model::Material,
     // NEW!
     mouse_pressed: bool,
+    /// 上一次触摸事件时两指之间的距离，不足两指时为 `None`
+    pinch_distance: Option<f64>,
 }
 
 impl WgpuApp {
     /// 必要的时候调整 surface 大小
     fn resize_surface_if_needed(&mut self) {
-        if self.size_changed {
+        if let Some(size) = self.resize_debouncer.take_if_changed() {
+            self.size = size;
             //  需先 resize surface
             self.app
                 .resize_surface_by_size((self.size.width, self.size.height));
@@ -163,8 +166,6 @@ impl WgpuApp {
                 &self.app.config,
                 "depth_texture",
             );
-
-            self.size_changed = false;
         }
     }
 }
@@ -485,7 +486,7 @@ impl WgpuAppAction for WgpuApp {
         Self {
             app,
             size,
-            size_changed: false,
+            resize_debouncer: utils::ResizeDebouncer::new(),
             render_pipeline,
             obj_model,
             camera,
@@ -505,15 +506,13 @@ impl WgpuAppAction for WgpuApp {
             debug_material,
             // NEW!
             mouse_pressed: false,
+            pinch_distance: None,
         }
     }
 
     fn set_window_resized(&mut self, new_size: PhysicalSize<u32>) {
-        if self.app.config.width == new_size.width && self.app.config.height == new_size.height {
-            return;
-        }
-        self.size = new_size;
-        self.size_changed = true;
+        let current = self.get_size();
+        self.resize_debouncer.record(new_size, current);
     }
 
     fn get_size(&self) -> PhysicalSize<u32> {
@@ -530,7 +529,7 @@ impl WgpuAppAction for WgpuApp {
         true
     }
 
-    fn mouse_click(&mut self, state: ElementState, button: MouseButton) -> bool {
+    fn mouse_input(&mut self, button: MouseButton, state: ElementState) -> bool {
         if button == MouseButton::Left {
             self.mouse_pressed = state == ElementState::Pressed;
             true
@@ -544,19 +543,38 @@ impl WgpuAppAction for WgpuApp {
         true
     }
 
-    fn device_input(&mut self, event: &DeviceEvent) -> bool {
-        if let DeviceEvent::MouseMotion { delta } = event {
-            if self.mouse_pressed {
-                self.camera_controller.process_mouse(delta.0, delta.1);
-                return true;
-            }
+    fn touches_changed(&mut self, touches: &utils::ActiveTouches) {
+        let distance = touches.pinch_distance();
+        if let (Some(previous), Some(current)) = (self.pinch_distance, distance) {
+            self.camera_controller.process_pinch(current / previous);
+        }
+        self.pinch_distance = distance;
+    }
+
+    fn mouse_motion(&mut self, dx: f64, dy: f64) {
+        if self.mouse_pressed {
+            self.camera_controller.process_mouse(dx, dy);
+        }
+    }
+
+    fn save_settings(&self, settings: &mut utils::settings::AppSettings) {
+        settings.camera = Some(utils::settings::CameraSettings {
+            eye: self.camera.position.to_array(),
+            target: (self.camera.position + self.camera.direction()).to_array(),
+        });
+    }
+
+    fn restore_settings(&mut self, settings: &utils::settings::AppSettings) {
+        // 相机的 uniform 在下一次 `update` 中写入
+        if let Some(camera) = settings.camera {
+            self.camera.look_at(camera.eye.into(), camera.target.into());
         }
-        false
     }
 
     fn update(&mut self, dt: core::time::Duration) {
         // UPDATED!
-        self.camera_controller.update_camera(&mut self.camera, dt);
+        self.camera_controller
+            .update_camera_with_fov(&mut self.camera, self.projection.fovy(), dt);
         self.camera_uniform
             .update_view_proj(&self.camera, &self.projection);
         self.app.queue.write_buffer(
@@ -0,0 +1,17 @@
+// 仍在移动的粒子列表
+struct AliveList {
+  count: atomic<u32>,
+  indices: array<u32>,
+};
+
+@group(0) @binding(0) var<storage, read_write> alive_in: AliveList;
+@group(0) @binding(1) var<storage, read_write> alive_out: AliveList;
+@group(0) @binding(2) var<storage, read_write> dispatch_args: array<u32>;
+
+// 按本帧仍在移动的粒子数写入下一帧的间接派发参数
+@compute @workgroup_size(1)
+fn cs_main() {
+  dispatch_args[0] = (atomicLoad(&alive_out.count) + 63u) / 64u;
+  // alive_in 在下一帧作为 alive_out 使用，需先清空
+  atomicStore(&alive_in.count, 0u);
+}
@@ -0,0 +1,37 @@
+// 计算每个粒子的视图空间深度，作为排序键
+struct MVPMatUniform {
+    mvp: mat4x4f,
+};
+
+struct Particle {
+    pos: vec2f,
+    init_pos: vec2f,
+    uv_pos: vec2f,
+    target_pos: vec2f,
+    speed_factor: vec2f,
+};
+
+struct SortKey {
+    key: f32,
+    index: u32,
+};
+
+@group(0) @binding(0) var<uniform> mat_uniform: MVPMatUniform;
+@group(0) @binding(1) var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(2) var<storage, read_write> keys: array<SortKey>;
+
+@compute @workgroup_size(64)
+fn cs_main(@builtin(global_invocation_id) gid: vec3u) {
+    let index = gid.x;
+    if (index >= arrayLength(&keys)) {
+        return;
+    }
+    if (index >= arrayLength(&particles)) {
+        // 填充元素，降序排序时排在末尾
+        keys[index] = SortKey(-3.402823e38, index);
+        return;
+    }
+    // 透视投影后的 w 分量即视图空间中到相机的距离
+    let clip = mat_uniform.mvp * vec4f(particles[index].pos, 0.0, 1.0);
+    keys[index] = SortKey(clip.w, index);
+}
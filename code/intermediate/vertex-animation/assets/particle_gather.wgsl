@@ -0,0 +1,26 @@
+// 按排序结果把粒子复制到用于绘制的实例缓冲区
+struct Particle {
+    pos: vec2f,
+    init_pos: vec2f,
+    uv_pos: vec2f,
+    target_pos: vec2f,
+    speed_factor: vec2f,
+};
+
+struct SortKey {
+    key: f32,
+    index: u32,
+};
+
+@group(0) @binding(0) var<storage, read_write> keys: array<SortKey>;
+@group(0) @binding(1) var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(2) var<storage, read_write> sorted_particles: array<Particle>;
+
+@compute @workgroup_size(64)
+fn cs_main(@builtin(global_invocation_id) gid: vec3u) {
+    let index = gid.x;
+    if (index >= arrayLength(&sorted_particles)) {
+        return;
+    }
+    sorted_particles[index] = particles[keys[index].index];
+}
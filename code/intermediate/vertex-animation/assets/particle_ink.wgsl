@@ -7,6 +7,8 @@ struct MVPMatUniform {
 struct VertexOutput {
     @builtin(position) position: vec4f,
     @location(0) uv: vec2f,
+    // 四边形内的局部 uv，覆盖 [0, 1]
+    @location(1) local_uv: vec2f,
 };
 
 @vertex
@@ -22,11 +24,12 @@ fn vs_main(
     var out: VertexOutput;
     out.position = mat_uniform.mvp * vec4f(p_pos + pos.xy, 0.0, 1.0);
     out.uv = p_uv + uv_offset;
+    out.local_uv = vec2f(step(0.0, pos.x), 1.0 - step(0.0, pos.y));
     return out;
 }
 
 @group(0) @binding(1) var animate_texture: texture_2d<f32>;
-@group(0) @binding(2) var tex_sampler: sampler;
+// tex_sampler 的绑定位置与是否启用软粒子有关，由 ink_shader_source 生成
 
 
 struct ParticleFrameUniform {
@@ -36,11 +39,12 @@ struct ParticleFrameUniform {
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4f {
+    let mask = shape_alpha(in.local_uv);
     var out_color: vec4f = textureSample(animate_texture, tex_sampler, in.uv);
     if (out_color.r > 0.55) {
         out_color.a = 0.0;
     } else {
-        out_color.a *= particleFrame.frame_alpha;
+        out_color.a *= particleFrame.frame_alpha * mask * soft_fade(in.position);
     }
     return out_color;
 }
\ No newline at end of file
@@ -0,0 +1,58 @@
+struct MVPMatUniform {
+    mvp: mat4x4f,
+};
+
+@group(0) @binding(0) var<uniform> mat_uniform: MVPMatUniform;
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    // 源纹理的采样坐标：粒子中心的 uv
+    @location(0) uv: vec2f,
+    // 精灵纹理的采样坐标：整个四边形覆盖 [0, 1]
+    @location(1) sprite_uv: vec2f,
+};
+
+@vertex
+fn vs_main(
+    @location(0) p_pos: vec2f,
+    @location(1) p_init_pos: vec2f,
+    @location(2) p_uv: vec2f,
+    @location(3) p_target: vec2f,
+    @location(4) p_speed: vec2f,
+    @location(5) pos: vec3f,
+    @location(6) uv_offset: vec2f,
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = mat_uniform.mvp * vec4f(p_pos + pos.xy, 0.0, 1.0);
+    out.uv = p_uv + uv_offset;
+    // 四边形顶点以粒子中心对称，由顶点坐标的符号得到精灵 uv（纹理坐标 +y 轴向下）
+    out.sprite_uv = vec2f(step(0.0, pos.x), 1.0 - step(0.0, pos.y));
+    return out;
+}
+
+@group(0) @binding(1) var animate_texture: texture_2d<f32>;
+@group(0) @binding(2) var sprite_texture: texture_2d<f32>;
+// tex_sampler 的绑定位置与是否启用软粒子有关，由 ink_shader_source 生成
+
+
+struct ParticleFrameUniform {
+   frame_alpha: f32,
+};
+@group(1) @binding(0) var<uniform> particleFrame: ParticleFrameUniform;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4f {
+    let mask = shape_alpha(in.sprite_uv);
+    var out_color: vec4f = textureSample(animate_texture, tex_sampler, in.uv);
+    let sprite = textureSample(sprite_texture, tex_sampler, in.sprite_uv);
+    if (out_color.r > 0.55) {
+        out_color.a = 0.0;
+    } else {
+        out_color.a *= particleFrame.frame_alpha * sprite.a * mask * soft_fade(in.position);
+    }
+    out_color = vec4f(out_color.rgb * sprite.rgb, out_color.a);
+    if (PREMULTIPLIED_ALPHA) {
+        out_color = vec4f(out_color.rgb * out_color.a, out_color.a);
+    }
+    return out_color;
+}
@@ -21,23 +21,37 @@ struct Particle {
 };
 
 
+// 仍在移动的粒子列表
+struct AliveList {
+  count: atomic<u32>,
+  indices: array<u32>,
+};
+
 @group(0) @binding(0) var<uniform> params: ParticleUniform;
 @group(0) @binding(1) var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(2) var<storage, read_write> alive_in: AliveList;
+@group(0) @binding(3) var<storage, read_write> alive_out: AliveList;
 
+// 由上一帧写入的间接派发参数决定工作组数量，只处理 alive_in 中的粒子
 @compute @workgroup_size(64)
 fn cs_main(@builtin(global_invocation_id) global_invocation_id: vec3<u32>) {
-  let total = arrayLength(&particles);
-  let index = global_invocation_id.x;
-  if (index > total) {
+  if (global_invocation_id.x >= atomicLoad(&alive_in.count)) {
     return;
   }
   // 1，找出对应编号的粒子
   // 2，更新粒子的位置
+  let index = alive_in.indices[global_invocation_id.x];
   var particle: Particle = particles[index];
   var move_dis = (particle.target_pos - particle.pos) * particle.speed_factor.x;
-  // var move_dis = (particle.target_pos - particle.pos) * 0.045;
-
   particle.pos += move_dis;
 
+  // 3，距目标不足半个像素的粒子直接放到目标位置，之后的帧不再处理它
+  let remaining = abs(particle.target_pos - particle.pos);
+  if (all(remaining < params.pixel_distance * 0.5)) {
+    particle.pos = particle.target_pos;
+  } else {
+    alive_out.indices[atomicAdd(&alive_out.count, 1u)] = index;
+  }
+
   particles[index] = particle;
-}
\ No newline at end of file
+}
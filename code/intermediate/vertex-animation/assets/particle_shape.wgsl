@@ -0,0 +1,21 @@
+// 粒子形状：由四边形内的局部 uv 计算 alpha 遮罩
+// PARTICLE_SHAPE 与 EDGE_SOFTNESS 常量由 ParticleShape 生成
+
+fn shape_alpha(local_uv: vec2f) -> f32 {
+    // 到四边形中心的距离，边缘处为 0.5
+    let d = length(local_uv - vec2f(0.5));
+    switch PARTICLE_SHAPE {
+        case 1u: {
+            // 圆形：用屏幕空间导数做约一个像素宽的抗锯齿
+            let aa = fwidth(d);
+            return 1.0 - smoothstep(0.5 - aa, 0.5, d);
+        }
+        case 2u: {
+            // 柔边圆形：在 [0.5 - EDGE_SOFTNESS, 0.5] 之间渐隐
+            return 1.0 - smoothstep(0.5 - EDGE_SOFTNESS, 0.5, d);
+        }
+        default: {
+            return 1.0;
+        }
+    }
+}
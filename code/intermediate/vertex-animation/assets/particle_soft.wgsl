@@ -0,0 +1,15 @@
+// 软粒子：按场景深度与粒子深度线性化后的差值渐隐
+// DEPTH_NEAR、DEPTH_FAR、SOFT_FADE_DISTANCE 常量与 scene_depth 绑定由 SoftFade 生成
+
+// 把透视投影写入的 [0, 1] 深度还原为视图空间的距离
+fn linear_depth(depth: f32) -> f32 {
+    return DEPTH_NEAR * DEPTH_FAR / (DEPTH_FAR - depth * (DEPTH_FAR - DEPTH_NEAR));
+}
+
+fn soft_fade(frag_coord: vec4f) -> f32 {
+    // 场景深度纹理与渲染目标尺寸一致，直接按片元的像素坐标读取
+    let scene = textureLoad(scene_depth, vec2i(frag_coord.xy), 0);
+    let delta = linear_depth(scene) - linear_depth(frag_coord.z);
+    // 粒子在场景几何体之后时 delta 为负，完全透明
+    return saturate(delta / SOFT_FADE_DISTANCE);
+}
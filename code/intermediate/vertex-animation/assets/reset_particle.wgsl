@@ -21,19 +21,33 @@ struct Particle {
 };
 
 
+// 仍在移动的粒子列表
+struct AliveList {
+  count: atomic<u32>,
+  indices: array<u32>,
+};
+
 @group(0) @binding(0) var<uniform> params: ParticleUniform;
 @group(0) @binding(1) var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(2) var<storage, read_write> alive: AliveList;
+@group(0) @binding(3) var<storage, read_write> dispatch_args: array<u32>;
 
 @compute @workgroup_size(64)
 fn cs_main(@builtin(global_invocation_id) global_invocation_id: vec3<u32>) {
   let total = arrayLength(&particles);
   let index = global_invocation_id.x;
-  if (index > total) {
+  if (index >= total) {
     return;
   }
+  if (index == 0u) {
+    // 所有粒子重新开始移动
+    atomicStore(&alive.count, total);
+    dispatch_args[0] = (total + 63u) / 64u;
+  }
   // 1，找出对应编号的粒子
   // 2，更新粒子的位置
   var particle: Particle = particles[index];
   particle.pos = particle.init_pos;
   particles[index] = particle;
-}
\ No newline at end of file
+  alive.indices[index] = index;
+}
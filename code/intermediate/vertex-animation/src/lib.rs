@@ -6,6 +6,10 @@ mod vertex_ani_app;
 pub use vertex_ani_app::VertexAnimationApp;
 
 mod particle_ink;
+pub use particle_ink::{
+    ParticleInk, ParticleInkOptions, ParticleSampling, ParticleShape, ParticleSprite, SoftFade,
+    SoftParticles,
+};
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Pod, Zeroable)]
@@ -1,5 +1,11 @@
+use utils::RunConfig;
 use vertex_animation::VertexAnimationApp;
 
 pub fn main() -> Result<(), impl std::error::Error> {
-    utils::run::<VertexAnimationApp>("Vertex Animation")
+    // 动画按 60 Hz 的固定步长推进，与显示器刷新率无关
+    let config = RunConfig {
+        fixed_timestep: Some(instant::Duration::from_secs(1) / 60),
+        ..Default::default()
+    };
+    utils::run_with_config::<VertexAnimationApp>("Vertex Animation", config)
 }
@@ -2,39 +2,344 @@ use crate::{MoveParticle, ParticleFrameUniform, ParticleUniform};
 use app_surface::AppSurface;
 use rand::Rng;
 use utils::{
-    AnyTexture, BufferObj,
+    AnyTexture, BufferObj, DynamicUniformRing,
     matrix_helper::FullscreenFactor,
-    node::{BindGroupData, ComputeNode, ViewNode, ViewNodeBuilder},
+    node::{BindGroupData, BitonicSortNode, ComputeNode, SortKey, ViewNode, ViewNodeBuilder},
     vertex::PosTex,
 };
 
+/// 粒子精灵
+///
+/// 每个粒子绘制为一个以粒子中心对称的四边形：
+/// - 精灵纹理的 uv 覆盖整个四边形（左上角为 (0, 0)，右下角为 (1, 1)）；
+/// - 源纹理只在粒子中心附近采样，颜色再与精灵纹理相乘，所以精灵尺寸不影响源纹理的采样位置。
+pub struct ParticleSprite<'a> {
+    pub texture: &'a AnyTexture,
+    /// 精灵的逻辑像素尺寸
+    pub size: f32,
+    /// 是否使用预乘 alpha 混合
+    pub premultiplied_alpha: bool,
+}
+
+/// 粒子形状
+///
+/// 无需纹理，由片元着色器根据四边形内的局部 uv 到中心的距离计算 alpha。
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ParticleShape {
+    /// 方形，即整个四边形
+    #[default]
+    Square,
+    /// 边缘做约一个像素宽抗锯齿的圆形
+    Circle,
+    /// 柔边圆形
+    ///
+    /// `edge_softness` 为渐隐区域的宽度，以局部 uv 为单位，取值范围 (0, 0.5]：
+    /// 到中心的距离在 [0.5 - edge_softness, 0.5] 之间时 alpha 由 1 平滑过渡到 0，0.5 时整个粒子都是渐变的。
+    SoftCircle { edge_softness: f32 },
+}
+
+impl ParticleShape {
+    fn shader_constants(&self) -> String {
+        let (shape, edge_softness) = match self {
+            ParticleShape::Square => (0, 0.0),
+            ParticleShape::Circle => (1, 0.0),
+            ParticleShape::SoftCircle { edge_softness } => (2, edge_softness.clamp(0.0, 0.5)),
+        };
+        format!(
+            "const PARTICLE_SHAPE: u32 = {shape}u;\nconst EDGE_SOFTNESS: f32 = {edge_softness:?};\n"
+        )
+    }
+}
+
+/// 源纹理的采样方式
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ParticleSampling {
+    /// 最近邻采样
+    Nearest,
+    /// 双线性采样
+    #[default]
+    Bilinear,
+}
+
+impl ParticleSampling {
+    fn create_sampler(&self, device: &wgpu::Device) -> wgpu::Sampler {
+        match self {
+            ParticleSampling::Nearest => utils::default_sampler(device),
+            ParticleSampling::Bilinear => utils::bilinear_sampler(device),
+        }
+    }
+}
+
+/// 软粒子的渐隐参数
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SoftFade {
+    /// 与生成场景深度的透视投影一致的近平面
+    pub near: f32,
+    /// 与生成场景深度的透视投影一致的远平面
+    pub far: f32,
+    /// 粒子与场景几何体的视图空间距离小于此值时开始渐隐
+    pub fade_distance: f32,
+}
+
+impl SoftFade {
+    fn shader_constants(&self) -> String {
+        format!(
+            "const DEPTH_NEAR: f32 = {:?};\nconst DEPTH_FAR: f32 = {:?};\nconst SOFT_FADE_DISTANCE: f32 = {:?};\n",
+            self.near,
+            self.far,
+            self.fade_distance.max(f32::EPSILON)
+        )
+    }
+}
+
+/// 软粒子
+///
+/// 按粒子与场景深度缓冲区线性化后的深度差渐隐 alpha，避免粒子与不透明几何体相交处出现硬边。
+///
+/// # NOTE:
+/// 场景需先把深度渲染到 `depth`，粒子通道只采样（不写入）它：
+/// 同一纹理不能在一个通道中既作为深度附件又被绑定采样，所以软粒子模式下粒子管线不使用深度附件，
+/// 被几何体遮挡的粒子由渐隐系数（深度差为负时为 0）隐藏。
+/// `depth` 的格式为 `utils::DEPTH_FORMAT`，尺寸与渲染目标一致，且创建时需包含 `TextureUsages::TEXTURE_BINDING`。
+pub struct SoftParticles<'a> {
+    pub depth: &'a AnyTexture,
+    pub fade: SoftFade,
+}
+
+/// 创建 `ParticleInk` 的可选配置
+#[derive(Default)]
+pub struct ParticleInkOptions<'a> {
+    pub sprite: Option<ParticleSprite<'a>>,
+    pub shape: ParticleShape,
+    pub sampling: ParticleSampling,
+    pub soft_particles: Option<SoftParticles<'a>>,
+}
+
 // 粒子墨水
 pub struct ParticleInk {
     particle_count: usize,
     particle_buffer: BufferObj,
     // 重置粒子状态的节点
     reset_node: ComputeNode,
-    // 移动粒子的节点
-    move_node: ComputeNode,
+    // 只移动仍未到达目标的粒子
+    alive_culling: AliveCulling,
     display_node: ViewNode,
+    // 按深度排序
+    depth_sort: DepthSort,
+    is_depth_sort_enabled: bool,
+    // 本帧是否已录制粒子移动的计算通道
+    is_moved: bool,
+    // 软粒子模式下粒子通道不使用深度附件
+    is_soft_particles: bool,
 
+    // 下一步模拟的动画帧索引
     animate_index: u32,
+    // 最近一步模拟的动画帧索引，绘制时按它设置动态偏移
+    display_index: u32,
+    // 已调用 `step` 但还未录制移动的步数
+    pending_steps: u32,
+    // 本轮动画已在最近一次 cal_particles_move 中完成
+    is_completed: bool,
     frame_count: u32,
+    // 每帧动画的 uniform，按动态偏移访问
+    frame_buf: DynamicUniformRing<ParticleFrameUniform>,
+}
+
+/// 剔除已到达目标的粒子，移动通道只派发仍在移动的粒子所需的工作组
+///
+/// 两个存活列表 `lists` 轮流作为输入与输出：移动通道按间接派发参数处理输入列表中的粒子，
+/// 把仍未到达目标的粒子追加到输出列表；随后计数通道按输出列表的长度写入下一帧的间接派发参数，并清空输入列表。
+/// 重置粒子状态时所有粒子写入 `lists[0]`，所以 `parity` 同时归零。
+struct AliveCulling {
+    lists: [BufferObj; 2],
+    dispatch_args: BufferObj,
+    // move_nodes[i] 与 count_nodes[i] 以 lists[i] 为输入
+    move_nodes: [ComputeNode; 2],
+    count_nodes: [ComputeNode; 2],
+    parity: usize,
+}
+
+impl AliveCulling {
+    fn new(
+        device: &wgpu::Device,
+        particle_uniform_buf: &BufferObj,
+        particle_buffer: &BufferObj,
+        particle_count: u32,
+    ) -> Self {
+        // 粒子数 + 粒子索引
+        let list_size = (4 + particle_count as usize * 4) as wgpu::BufferAddress;
+        let create_list = |label| {
+            BufferObj::create_empty_storage_buffer(
+                device,
+                list_size,
+                wgpu::BufferUsages::STORAGE,
+                Some(label),
+            )
+        };
+        let lists = [create_list("存活粒子列表 0"), create_list("存活粒子列表 1")];
+        let workgroup_count = (particle_count.div_ceil(64), 1, 1);
+        let dispatch_args = BufferObj::create_indirect_buffer(
+            device,
+            workgroup_count,
+            Some("粒子移动的间接派发参数"),
+        );
+
+        let create_shader = |wgsl: &'static str| -> wgpu::ShaderModule {
+            device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: None,
+                source: wgpu::ShaderSource::Wgsl(wgsl.into()),
+            })
+        };
+        let move_shader = create_shader(include_str!("../assets/particle_move.wgsl"));
+        let count_shader = create_shader(include_str!("../assets/particle_alive_count.wgsl"));
+        let create_nodes = |input: &BufferObj, output: &BufferObj| {
+            let bind_group_data = BindGroupData {
+                uniforms: vec![particle_uniform_buf],
+                storage_buffers: vec![particle_buffer, input, output],
+                workgroup_count,
+                ..Default::default()
+            };
+            let move_node = ComputeNode::new(device, &bind_group_data, &move_shader);
+            let bind_group_data = BindGroupData {
+                storage_buffers: vec![input, output, &dispatch_args],
+                workgroup_count: (1, 1, 1),
+                ..Default::default()
+            };
+            let count_node = ComputeNode::new(device, &bind_group_data, &count_shader);
+            (move_node, count_node)
+        };
+        let (move0, count0) = create_nodes(&lists[0], &lists[1]);
+        let (move1, count1) = create_nodes(&lists[1], &lists[0]);
+
+        Self {
+            lists,
+            dispatch_args,
+            move_nodes: [move0, move1],
+            count_nodes: [count0, count1],
+            parity: 0,
+        }
+    }
+
+    fn move_by_pass<'a, 'b: 'a>(&'b mut self, cpass: &mut wgpu::ComputePass<'a>) {
+        let parity = self.parity;
+        self.move_nodes[parity].dispatch_indirect(cpass, &self.dispatch_args.buffer, 0, None);
+        self.count_nodes[parity].compute_by_pass(cpass);
+        self.parity = 1 - parity;
+    }
+}
+
+/// 粒子按视图空间深度从远到近排序，保证半透明粒子的混合顺序正确
+///
+/// 每帧依次执行：计算深度键 -> 双调排序 -> 按排序结果把粒子复制到实例缓冲区。
+/// 深度键由 MVP 矩阵计算（透视投影后的 w 分量即视图空间深度），所以视图矩阵变化时排序结果会随之更新。
+struct DepthSort {
+    _keys_buffer: BufferObj,
+    sorted_buffer: BufferObj,
+    key_node: ComputeNode,
+    sort_node: BitonicSortNode,
+    gather_node: ComputeNode,
+}
+
+impl DepthSort {
+    fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        mvp_buf: &BufferObj,
+        particle_buffer: &BufferObj,
+        particle_count: u32,
+    ) -> Self {
+        let padded_count = BitonicSortNode::padded_len(particle_count);
+        let keys_buffer = BufferObj::create_empty_storage_buffer(
+            device,
+            (padded_count as usize * core::mem::size_of::<SortKey>()) as wgpu::BufferAddress,
+            wgpu::BufferUsages::STORAGE,
+            Some("粒子深度排序键"),
+        );
+        let sorted_buffer = BufferObj::create_empty_storage_buffer(
+            device,
+            particle_buffer.size,
+            wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::STORAGE,
+            Some("排序后的粒子缓冲区"),
+        );
+
+        let create_shader = |wgsl: &'static str| -> wgpu::ShaderModule {
+            device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: None,
+                source: wgpu::ShaderSource::Wgsl(wgsl.into()),
+            })
+        };
+        let key_shader = create_shader(include_str!("../assets/particle_depth_key.wgsl"));
+        let gather_shader = create_shader(include_str!("../assets/particle_gather.wgsl"));
+
+        let bind_group_data = BindGroupData {
+            uniforms: vec![mvp_buf],
+            storage_buffers: vec![particle_buffer, &keys_buffer],
+            workgroup_count: (padded_count.div_ceil(64), 1, 1),
+            ..Default::default()
+        };
+        let key_node = ComputeNode::new(device, &bind_group_data, &key_shader);
+        let sort_node = BitonicSortNode::new(device, queue, &keys_buffer, padded_count, true);
+        let bind_group_data = BindGroupData {
+            storage_buffers: vec![&keys_buffer, particle_buffer, &sorted_buffer],
+            workgroup_count: (particle_count.div_ceil(64), 1, 1),
+            ..Default::default()
+        };
+        let gather_node = ComputeNode::new(device, &bind_group_data, &gather_shader);
+
+        Self {
+            _keys_buffer: keys_buffer,
+            sorted_buffer,
+            key_node,
+            sort_node,
+            gather_node,
+        }
+    }
+
+    fn sort_by_pass<'a, 'b: 'a>(&'b self, cpass: &mut wgpu::ComputePass<'a>) {
+        self.key_node.compute_by_pass(cpass);
+        self.sort_node.sort_by_pass(cpass);
+        self.gather_node.compute_by_pass(cpass);
+    }
 }
 
 impl ParticleInk {
-    pub fn new(
+    pub fn new(app: &AppSurface, mvp_buf: &BufferObj, texture_view: &AnyTexture) -> Self {
+        Self::new_with_options(app, mvp_buf, texture_view, ParticleInkOptions::default())
+    }
+
+    pub fn new_with_options(
         app: &AppSurface,
         mvp_buf: &BufferObj,
         texture_view: &AnyTexture,
-        sampler: &wgpu::Sampler,
+        options: ParticleInkOptions,
+    ) -> Self {
+        Self::create(
+            &app.device,
+            &app.queue,
+            &app.config,
+            app.scale_factor,
+            mvp_buf,
+            texture_view,
+            options,
+        )
+    }
+
+    /// 不依赖 `AppSurface` 创建，`config` 提供渲染目标的尺寸与格式
+    fn create(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        config: &wgpu::SurfaceConfiguration,
+        scale_factor: f32,
+        mvp_buf: &BufferObj,
+        texture_view: &AnyTexture,
+        options: ParticleInkOptions,
     ) -> Self {
         let frame_count = 180;
 
-        let w = app.config.width;
-        let h = app.config.height;
+        let w = config.width;
+        let h = config.height;
         // 粒子像素尺寸
-        let particle_point_size = app.scale_factor * 1.0;
+        let particle_point_size = scale_factor * 1.0;
         let particle_num = wgpu::Extent3d {
             width: w / particle_point_size as u32,
             height: h / particle_point_size as u32,
@@ -43,17 +348,21 @@ impl ParticleInk {
         let fovy: f32 = 45.0_f32.to_radians();
         let factor = utils::matrix_helper::fullscreen_factor(
             glam::Vec2 {
-                x: app.config.width as f32,
-                y: app.config.height as f32,
+                x: config.width as f32,
+                y: config.height as f32,
             },
             fovy,
         );
 
         // 粒子的顶点数据
-        let half_x = particle_point_size / 2.0 * (2.0 / w as f32 * factor.sx);
-        let half_y = particle_point_size / 2.0 * (2.0 / h as f32 * factor.sy);
-        let uv_x = (1.0 / particle_num.width as f32) * particle_point_size / 2.0 / w as f32;
-        let uv_y = (1.0 / particle_num.height as f32) * particle_point_size / 2.0 / h as f32;
+        // 有精灵时四边形使用精灵尺寸，否则与粒子间距一致
+        let quad_size = options
+            .sprite
+            .as_ref()
+            .map_or(particle_point_size, |s| s.size * scale_factor);
+        let half_x = quad_size / 2.0 * (2.0 / w as f32 * factor.sx);
+        let half_y = quad_size / 2.0 * (2.0 / h as f32 * factor.sy);
+        let (uv_x, uv_y) = particle_uv_offset(particle_num, options.sampling);
         let vertex_buffer_data = vec![
             PosTex {
                 pos: [half_x, half_y, 0.0],
@@ -77,7 +386,7 @@ impl ParticleInk {
         // 粒子数据的存储缓冲区
         let particle_data = init_particles(particle_num, factor);
         let particle_buffer = BufferObj::create_buffer(
-            &app.device,
+            device,
             Some(&particle_data),
             None,
             wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::STORAGE,
@@ -85,137 +394,189 @@ impl ParticleInk {
         );
 
         let particle_uniform_buf = BufferObj::create_uniform_buffer(
-            &app.device,
+            device,
             &ParticleUniform {
                 particle_num: [particle_num.width, particle_num.height],
-                canvas_size: [w as f32, app.config.height as f32],
+                canvas_size: [w as f32, config.height as f32],
                 pixel_distance: [2.0 * factor.sx / w as f32, 2.0 * factor.sy / h as f32],
             },
             None,
         );
-        // 注意，layout 与 MoveParticle 的字段需要一致
-        let particle_attributes = wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2, 2 => Float32x2, 3 => Float32x2, 4 => Float32x2];
-        let vertex_attributes = wgpu::vertex_attr_array![5 => Float32x3, 6 => Float32x2];
-        let vertex_buffer_layouts = vec![
-            wgpu::VertexBufferLayout {
-                array_stride: 4 * 10,
-                step_mode: wgpu::VertexStepMode::Instance,
-                attributes: &particle_attributes,
-            },
-            wgpu::VertexBufferLayout {
-                array_stride: 4 * 5,
-                step_mode: wgpu::VertexStepMode::Vertex,
-                attributes: &vertex_attributes,
-            },
-        ];
-
-        let frame_buf = BufferObj::create_empty_uniform_buffer(
-            &app.device,
-            (frame_count * 256) as wgpu::BufferAddress,
-            256,
-            true,
-            Some("粒子动画的动态偏移缓冲区"),
-        );
-        // 按动态偏移量填充 uniform 缓冲区
-        let uniforms = init_frame_uniforms(frame_count);
-        for step in 0..frame_count {
-            app.queue.write_buffer(
-                &frame_buf.buffer,
-                256 * step as u64,
-                bytemuck::bytes_of(&uniforms[step as usize]),
-            );
-        }
+        let frame_buf =
+            DynamicUniformRing::new(device, frame_count, Some("粒子动画的动态偏移缓冲区"));
+        frame_buf.write_frames(queue, &init_frame_uniforms(frame_count));
 
         // 着色器
-        let (ink_shader, move_shader, reset_shader) = {
-            let create_shader = |wgsl: &'static str| -> wgpu::ShaderModule {
-                app.device
-                    .create_shader_module(wgpu::ShaderModuleDescriptor {
-                        label: None,
-                        source: wgpu::ShaderSource::Wgsl(wgsl.into()),
-                    })
-            };
-            (
-                create_shader(include_str!("../assets/particle_ink.wgsl")),
-                create_shader(include_str!("../assets/particle_move.wgsl")),
-                create_shader(include_str!("../assets/reset_particle.wgsl")),
-            )
+        let create_shader = |wgsl: std::borrow::Cow<'static, str>| -> wgpu::ShaderModule {
+            device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: None,
+                source: wgpu::ShaderSource::Wgsl(wgsl),
+            })
         };
+        let reset_shader = create_shader(include_str!("../assets/reset_particle.wgsl").into());
 
-        // 准备绑定组需要的数据
-        // let sampler2 = utils::default_sampler(&app.device);
-        let bind_group_data = BindGroupData {
-            uniforms: vec![mvp_buf],
-            inout_tv: vec![(texture_view, None)],
-            samplers: vec![sampler],
-            visibilitys: vec![
-                wgpu::ShaderStages::VERTEX,
-                wgpu::ShaderStages::FRAGMENT,
-                wgpu::ShaderStages::FRAGMENT,
-            ],
-            // 配置动态偏移缓冲区
-            dynamic_uniforms: vec![&frame_buf],
-            dynamic_uniform_visibilitys: vec![wgpu::ShaderStages::FRAGMENT],
-            ..Default::default()
-        };
-        let format = app.config.format.remove_srgb_suffix();
-        let builder = ViewNodeBuilder::<PosTex>::new(bind_group_data, &ink_shader)
-            .with_vertices_and_indices((vertex_buffer_data, index_data))
-            .with_vertex_buffer_layouts(vertex_buffer_layouts)
-            .with_use_depth_stencil(true)
-            .with_color_format(format);
-        let display_node = builder.build(&app.device);
+        let display_node = create_display_node(
+            device,
+            config.format.remove_srgb_suffix(),
+            mvp_buf,
+            &frame_buf,
+            texture_view,
+            &options,
+            (vertex_buffer_data, index_data),
+        );
+
+        let particle_count = particle_num.width * particle_num.height;
+        let alive_culling = AliveCulling::new(
+            device,
+            &particle_uniform_buf,
+            &particle_buffer,
+            particle_count,
+        );
 
         // 准备绑定组需要的数据
+        // 重置时所有粒子写入第 0 个存活列表
         let bind_group_data = BindGroupData {
             uniforms: vec![&particle_uniform_buf],
-            storage_buffers: vec![&particle_buffer],
-            visibilitys: vec![wgpu::ShaderStages::COMPUTE],
-            workgroup_count: (
-                ((particle_num.width * particle_num.height) as f32 / 64.0).ceil() as u32,
-                1,
-                1,
-            ),
+            storage_buffers: vec![
+                &particle_buffer,
+                &alive_culling.lists[0],
+                &alive_culling.dispatch_args,
+            ],
+            workgroup_count: (particle_count.div_ceil(64), 1, 1),
             ..Default::default()
         };
-        let move_node = ComputeNode::new(&app.device, &bind_group_data, &move_shader);
-        let reset_node = ComputeNode::new(&app.device, &bind_group_data, &reset_shader);
+        let reset_node = ComputeNode::new(device, &bind_group_data, &reset_shader);
+
+        let depth_sort = DepthSort::new(device, queue, mvp_buf, &particle_buffer, particle_count);
 
         Self {
-            particle_count: (particle_num.width * particle_num.height) as usize,
+            particle_count: particle_count as usize,
             particle_buffer,
             display_node,
-            move_node,
             reset_node,
+            alive_culling,
+            depth_sort,
+            is_depth_sort_enabled: false,
+            is_moved: false,
+            is_soft_particles: options.soft_particles.is_some(),
             animate_index: 0,
+            display_index: 0,
+            pending_steps: 0,
+            is_completed: false,
             frame_count,
+            frame_buf,
         }
     }
 
+    /// 推进一步模拟，在 app 的 `update` 中调用
+    ///
+    /// 粒子的移动在下一次 `cal_particles_move` 中录制；使用固定步长时一帧可能调用 0 次或多次，
+    /// 动画速度因此只取决于真实时间，与 `render` 的调用频率无关。
+    pub fn step(&mut self) {
+        self.pending_steps += 1;
+    }
+
+    /// 按正确的顺序录制一帧：先计算粒子移动，再开启渲染通道绘制粒子
+    ///
+    /// 返回本轮动画是否已完成
+    ///
+    /// # NOTE:
+    /// 软粒子模式下粒子通道只采样场景深度，不使用 `depth_view` 作为深度附件。
+    pub fn frame(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        frame_view: &wgpu::TextureView,
+        depth_view: &wgpu::TextureView,
+        load_op: wgpu::LoadOp<wgpu::Color>,
+    ) -> bool {
+        self.cal_particles_move(encoder);
+
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("particle ink rpass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: frame_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: load_op,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: if self.is_soft_particles {
+                None
+            } else {
+                Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                })
+            },
+            ..Default::default()
+        });
+        self.enter_frame(&mut rpass)
+    }
+
     // cal_particles_move 无法直接写进 enter_frame 中：
-    // rpass 已经对 encoder 有可变引用了， 无法同时传递 rpass 与创建它的 encoder
+    // rpass 已经对 encoder 有可变引用了， 无法同时传递 rpass 与创建它的 encoder。
+    // 需要与其它节点共用渲染通道时，必须先调用 cal_particles_move 再调用 enter_frame，否则会绘制上一帧的粒子数据；
+    // 不需要共用渲染通道时，使用 `frame` 即可。
+    //
+    // 每个未录制的 `step` 各录制一次移动；本轮动画完成时丢弃剩余的步数，下一轮从重置粒子开始。
     pub fn cal_particles_move(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        self.is_moved = true;
         let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
-        if self.animate_index == 0 {
-            // 重置粒子状态
-            self.reset_node.compute_by_pass(&mut cpass);
+        for _ in 0..std::mem::take(&mut self.pending_steps) {
+            if self.animate_index == 0 {
+                // 重置粒子状态
+                self.reset_node.compute_by_pass(&mut cpass);
+                self.alive_culling.parity = 0;
+            }
+            self.alive_culling.move_by_pass(&mut cpass);
+            self.display_index = self.animate_index;
+            self.animate_index += 1;
+            if self.animate_index == self.frame_count {
+                // 当前动画完成，重置状态
+                self.animate_index = 0;
+                self.is_completed = true;
+                break;
+            }
         }
-        self.move_node.compute_by_pass(&mut cpass);
+        if self.is_depth_sort_enabled {
+            self.depth_sort.sort_by_pass(&mut cpass);
+        }
+    }
+
+    /// 是否每帧按深度从远到近对粒子排序
+    pub fn enable_depth_sort(&mut self, enabled: bool) {
+        self.is_depth_sort_enabled = enabled;
     }
 
+    /// 绘制最近一步模拟的粒子，返回本轮动画是否已完成
     pub fn enter_frame<'a, 'b: 'a>(&'b mut self, rpass: &mut wgpu::RenderPass<'a>) -> bool {
+        debug_assert!(
+            self.is_moved,
+            "cal_particles_move must be recorded before enter_frame"
+        );
+        self.is_moved = false;
         let display_node = &self.display_node;
         rpass.set_pipeline(&display_node.pipeline);
         rpass.set_bind_group(0, &display_node.bg_setting.bind_group, &[]);
         rpass.set_index_buffer(display_node.index_buf.slice(..), wgpu::IndexFormat::Uint32);
-        rpass.set_vertex_buffer(0, self.particle_buffer.buffer.slice(..));
+        let instance_buf = if self.is_depth_sort_enabled {
+            &self.depth_sort.sorted_buffer
+        } else {
+            &self.particle_buffer
+        };
+        rpass.set_vertex_buffer(0, instance_buf.buffer.slice(..));
         let vertex_buf = display_node.vertex_buf.as_ref().unwrap();
         rpass.set_vertex_buffer(1, vertex_buf.buffer.slice(..));
         let node = &display_node.dy_uniform_bg.as_ref().unwrap();
         rpass.set_bind_group(
             1,
             &node.bind_group,
-            &[256 * self.animate_index as wgpu::DynamicOffset],
+            &[self.frame_buf.dynamic_offset(self.display_index)],
         );
 
         rpass.draw_indexed(
@@ -224,15 +585,142 @@ impl ParticleInk {
             0..self.particle_count as u32,
         );
 
-        let mut is_completed = false;
-        self.animate_index += 1;
-        if self.animate_index == self.frame_count {
-            // 当前动画完成，重置状态
-            self.animate_index = 0;
-            is_completed = true;
-        }
-        is_completed
+        std::mem::take(&mut self.is_completed)
+    }
+}
+
+/// 粒子四边形顶点相对粒子中心 uv 的偏移 (uv_x, uv_y)
+///
+/// # NOTE:
+/// 粒子中心的 uv（`init_particles` 中的 `uv_pos`）位于粒子网格单元的中心，即 `(x + 0.5) / particle_num`：
+/// - 最近邻采样：单元内任意位置都落在同一个纹素上（源纹理与粒子网格尺寸一致时），
+///   所以四边形可以覆盖整个单元，偏移为半个单元（half-texel）；
+/// - 双线性采样：只有正好在纹素中心采样时才不会混入相邻纹素的颜色，
+///   所以偏移为 0，四边形内所有片元都采样粒子中心。
+fn particle_uv_offset(particle_num: wgpu::Extent3d, sampling: ParticleSampling) -> (f32, f32) {
+    match sampling {
+        ParticleSampling::Nearest => (
+            0.5 / particle_num.width as f32,
+            0.5 / particle_num.height as f32,
+        ),
+        ParticleSampling::Bilinear => (0.0, 0.0),
+    }
+}
+
+/// 创建绘制粒子的节点
+///
+/// 绑定组 0 依次为：MVP 矩阵、源纹理、精灵纹理（可选）、场景深度（软粒子，可选）、采样器
+fn create_display_node(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    mvp_buf: &BufferObj,
+    frame_buf: &BufferObj,
+    texture_view: &AnyTexture,
+    options: &ParticleInkOptions,
+    vertices_and_indices: (Vec<PosTex>, Vec<u32>),
+) -> ViewNode {
+    let sprite = options.sprite.as_ref();
+    let soft_particles = options.soft_particles.as_ref();
+    let ink_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: None,
+        source: wgpu::ShaderSource::Wgsl(
+            ink_shader_source(
+                options.shape,
+                sprite.map(|s| s.premultiplied_alpha),
+                soft_particles.map(|s| s.fade),
+            )
+            .into(),
+        ),
+    });
+
+    // 注意，layout 与 MoveParticle 的字段需要一致
+    let particle_attributes = wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2, 2 => Float32x2, 3 => Float32x2, 4 => Float32x2];
+    let vertex_attributes = wgpu::vertex_attr_array![5 => Float32x3, 6 => Float32x2];
+    let vertex_buffer_layouts = vec![
+        wgpu::VertexBufferLayout {
+            array_stride: 4 * 10,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &particle_attributes,
+        },
+        wgpu::VertexBufferLayout {
+            array_stride: 4 * 5,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &vertex_attributes,
+        },
+    ];
+
+    // 准备绑定组需要的数据
+    let sampler = options.sampling.create_sampler(device);
+    let mut inout_tv = vec![(texture_view, None)];
+    let mut visibilitys = vec![wgpu::ShaderStages::VERTEX, wgpu::ShaderStages::FRAGMENT];
+    if let Some(sprite) = sprite {
+        inout_tv.push((sprite.texture, None));
+        visibilitys.push(wgpu::ShaderStages::FRAGMENT);
+    }
+    // 深度格式的纹理以 TextureSampleType::Depth 绑定
+    if let Some(soft_particles) = soft_particles {
+        inout_tv.push((soft_particles.depth, None));
+        visibilitys.push(wgpu::ShaderStages::FRAGMENT);
+    }
+    // 采样器
+    visibilitys.push(wgpu::ShaderStages::FRAGMENT);
+    let bind_group_data = BindGroupData {
+        uniforms: vec![mvp_buf],
+        inout_tv,
+        samplers: vec![&sampler],
+        visibilitys,
+        // 配置动态偏移缓冲区
+        dynamic_uniforms: vec![frame_buf],
+        dynamic_uniform_visibilitys: vec![wgpu::ShaderStages::FRAGMENT],
+        ..Default::default()
+    };
+    let blend_state = if sprite.is_some_and(|s| s.premultiplied_alpha) {
+        wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING
+    } else {
+        wgpu::BlendState::ALPHA_BLENDING
+    };
+    ViewNodeBuilder::<PosTex>::new(bind_group_data, &ink_shader)
+        .with_vertices_and_indices(vertices_and_indices)
+        .with_vertex_buffer_layouts(vertex_buffer_layouts)
+        .with_color_blend_state(Some(blend_state))
+        // 软粒子只采样场景深度，不能同时作为深度附件
+        .with_use_depth_stencil(soft_particles.is_none())
+        .with_color_format(format)
+        .build(device)
+}
+
+/// 绘制粒子的着色器源码
+///
+/// `sprite_premultiplied_alpha` 为 `None` 时不使用精灵纹理，`soft_fade` 为 `None` 时不启用软粒子
+fn ink_shader_source(
+    shape: ParticleShape,
+    sprite_premultiplied_alpha: Option<bool>,
+    soft_fade: Option<SoftFade>,
+) -> String {
+    let mut wgsl = shape.shader_constants();
+    wgsl += include_str!("../assets/particle_shape.wgsl");
+    // 绑定 0 为 MVP 矩阵，之后依次为源纹理、精灵纹理
+    let mut binding = if sprite_premultiplied_alpha.is_some() {
+        3
+    } else {
+        2
+    };
+    if let Some(soft_fade) = soft_fade {
+        wgsl += &soft_fade.shader_constants();
+        wgsl += &format!("@group(0) @binding({binding}) var scene_depth: texture_depth_2d;\n");
+        wgsl += include_str!("../assets/particle_soft.wgsl");
+        binding += 1;
+    } else {
+        wgsl += "fn soft_fade(frag_coord: vec4f) -> f32 {\n    return 1.0;\n}\n";
+    }
+    wgsl += &format!("@group(0) @binding({binding}) var tex_sampler: sampler;\n");
+    if let Some(premultiplied_alpha) = sprite_premultiplied_alpha {
+        wgsl += &format!("const PREMULTIPLIED_ALPHA: bool = {premultiplied_alpha};\n");
+        wgsl += include_str!("../assets/particle_ink_sprite.wgsl");
+    } else {
+        wgsl += include_str!("../assets/particle_ink.wgsl");
     }
+    wgsl
 }
 
 pub fn init_particles(particle: wgpu::Extent3d, factor: FullscreenFactor) -> Vec<MoveParticle> {
@@ -280,3 +768,302 @@ pub fn init_frame_uniforms(frame_cunt: u32) -> Vec<ParticleFrameUniform> {
     }
     arr
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn uv_offset_by_sampling() {
+        let grid = |width, height| wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 0,
+        };
+        assert_eq!(
+            particle_uv_offset(grid(800, 600), ParticleSampling::Nearest),
+            (0.5 / 800.0, 0.5 / 600.0)
+        );
+        assert_eq!(
+            particle_uv_offset(grid(400, 250), ParticleSampling::Nearest),
+            (0.00125, 0.002)
+        );
+        assert_eq!(
+            particle_uv_offset(grid(800, 600), ParticleSampling::Bilinear),
+            (0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn shader_source_for_each_shape() {
+        let shapes = [
+            (ParticleShape::Square, "PARTICLE_SHAPE: u32 = 0u"),
+            (ParticleShape::Circle, "PARTICLE_SHAPE: u32 = 1u"),
+            (
+                ParticleShape::SoftCircle { edge_softness: 0.8 },
+                "PARTICLE_SHAPE: u32 = 2u",
+            ),
+        ];
+        for (shape, expected) in shapes {
+            for sprite in [None, Some(true)] {
+                let wgsl = ink_shader_source(shape, sprite, None);
+                assert!(wgsl.contains(expected));
+                assert!(wgsl.contains("fn shape_alpha"));
+                assert_eq!(wgsl.contains("PREMULTIPLIED_ALPHA"), sprite.is_some());
+            }
+        }
+        // edge_softness 被限制在 (0, 0.5]
+        assert!(
+            ParticleShape::SoftCircle { edge_softness: 0.8 }
+                .shader_constants()
+                .contains("EDGE_SOFTNESS: f32 = 0.5")
+        );
+    }
+
+    #[test]
+    fn shader_source_is_valid_wgsl() {
+        let fade = SoftFade {
+            near: 0.1,
+            far: 100.0,
+            fade_distance: 0.5,
+        };
+        let shapes = [
+            ParticleShape::Square,
+            ParticleShape::Circle,
+            ParticleShape::SoftCircle {
+                edge_softness: 0.25,
+            },
+        ];
+        for shape in shapes {
+            for sprite in [None, Some(false), Some(true)] {
+                for soft in [None, Some(fade)] {
+                    let wgsl = ink_shader_source(shape, sprite, soft);
+                    let module = naga::front::wgsl::parse_str(&wgsl).unwrap_or_else(|e| {
+                        panic!("{shape:?} {sprite:?} {soft:?}: {}", e.emit_to_string(&wgsl))
+                    });
+                    naga::valid::Validator::new(
+                        naga::valid::ValidationFlags::all(),
+                        naga::valid::Capabilities::all(),
+                    )
+                    .validate(&module)
+                    .unwrap_or_else(|e| {
+                        panic!("{shape:?} {sprite:?} {soft:?}: {}", e.emit_to_string(&wgsl))
+                    });
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn shader_source_soft_particles_bindings() {
+        let fade = SoftFade {
+            near: 0.1,
+            far: 100.0,
+            fade_distance: 0.5,
+        };
+        let wgsl = ink_shader_source(ParticleShape::Square, None, Some(fade));
+        assert!(wgsl.contains("@binding(2) var scene_depth: texture_depth_2d"));
+        assert!(wgsl.contains("@binding(3) var tex_sampler"));
+        assert!(wgsl.contains("SOFT_FADE_DISTANCE: f32 = 0.5"));
+
+        let wgsl = ink_shader_source(ParticleShape::Square, Some(false), Some(fade));
+        assert!(wgsl.contains("@binding(3) var scene_depth: texture_depth_2d"));
+        assert!(wgsl.contains("@binding(4) var tex_sampler"));
+
+        // 未启用软粒子时渐隐系数恒为 1
+        let wgsl = ink_shader_source(ParticleShape::Square, None, None);
+        assert!(!wgsl.contains("scene_depth"));
+        assert!(wgsl.contains("@binding(2) var tex_sampler"));
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod gpu_test {
+    use super::*;
+
+    #[test]
+    fn build_soft_particle_node() {
+        let Some((device, _queue)) = utils::test_device() else {
+            return;
+        };
+
+        let extent = wgpu::Extent3d {
+            width: 4,
+            height: 4,
+            depth_or_array_layers: 1,
+        };
+        let source = utils::load_texture::empty(
+            &device,
+            wgpu::TextureFormat::Rgba8Unorm,
+            extent,
+            None,
+            wgpu::TextureUsages::TEXTURE_BINDING,
+            None,
+        );
+        let depth = utils::load_texture::empty(
+            &device,
+            utils::DEPTH_FORMAT,
+            extent,
+            None,
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            None,
+        );
+        let mvp_buf = BufferObj::create_uniform_buffer(
+            &device,
+            &utils::MVPMatUniform {
+                mvp: glam::Mat4::IDENTITY.to_cols_array_2d(),
+            },
+            None,
+        );
+        let frame_buf = BufferObj::create_empty_uniform_buffer(&device, 256, 256, true, None);
+
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        for sprite in [None, Some(&source)] {
+            let options = ParticleInkOptions {
+                sprite: sprite.map(|texture| ParticleSprite {
+                    texture,
+                    size: 4.0,
+                    premultiplied_alpha: true,
+                }),
+                soft_particles: Some(SoftParticles {
+                    depth: &depth,
+                    fade: SoftFade {
+                        near: 0.1,
+                        far: 100.0,
+                        fade_distance: 0.5,
+                    },
+                }),
+                ..Default::default()
+            };
+            let _node = create_display_node(
+                &device,
+                wgpu::TextureFormat::Rgba8Unorm,
+                &mvp_buf,
+                &frame_buf,
+                &source,
+                &options,
+                (
+                    vec![<PosTex as bytemuck::Zeroable>::zeroed(); 4],
+                    vec![0, 1, 2, 0, 2, 3],
+                ),
+            );
+        }
+        let error = pollster::block_on(device.pop_error_scope());
+        assert!(error.is_none(), "{error:?}");
+    }
+
+    #[test]
+    fn frame_records_move_before_render() {
+        let Some((device, queue)) = utils::test_device() else {
+            return;
+        };
+
+        let extent = wgpu::Extent3d {
+            width: 8,
+            height: 8,
+            depth_or_array_layers: 1,
+        };
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            width: extent.width,
+            height: extent.height,
+            present_mode: wgpu::PresentMode::Fifo,
+            desired_maximum_frame_latency: 2,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            view_formats: vec![],
+        };
+        let source = utils::load_texture::empty(
+            &device,
+            wgpu::TextureFormat::Rgba8Unorm,
+            extent,
+            None,
+            wgpu::TextureUsages::TEXTURE_BINDING,
+            None,
+        );
+        let target = utils::load_texture::empty(
+            &device,
+            config.format,
+            extent,
+            None,
+            wgpu::TextureUsages::RENDER_ATTACHMENT,
+            None,
+        );
+        let depth = utils::load_texture::empty(
+            &device,
+            utils::DEPTH_FORMAT,
+            extent,
+            None,
+            wgpu::TextureUsages::RENDER_ATTACHMENT,
+            None,
+        );
+        let mvp_buf = BufferObj::create_uniform_buffer(
+            &device,
+            &utils::MVPMatUniform {
+                mvp: glam::Mat4::IDENTITY.to_cols_array_2d(),
+            },
+            None,
+        );
+
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let mut ink = ParticleInk::create(
+            &device,
+            &queue,
+            &config,
+            1.0,
+            &mvp_buf,
+            &source,
+            ParticleInkOptions::default(),
+        );
+        ink.enable_depth_sort(true);
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        // 前两帧：第 0 帧会先重置粒子，之后按存活列表间接派发
+        for _ in 0..2 {
+            ink.step();
+            let completed = ink.frame(
+                &mut encoder,
+                &target.tex_view,
+                &depth.tex_view,
+                wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+            );
+            assert!(!completed);
+            assert!(!ink.is_moved);
+        }
+        queue.submit(Some(encoder.finish()));
+        let error = pollster::block_on(device.pop_error_scope());
+        assert!(error.is_none(), "{error:?}");
+        assert_eq!(ink.animate_index, 2);
+        assert_eq!(ink.display_index, 1);
+
+        // 没有 step 时只重绘最近一步，不推进动画
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        ink.frame(
+            &mut encoder,
+            &target.tex_view,
+            &depth.tex_view,
+            wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+        );
+        queue.submit(Some(encoder.finish()));
+        assert_eq!(ink.animate_index, 2);
+
+        // 没有先录制 cal_particles_move 就开始绘制会绘制上一帧的粒子，debug 构建下直接断言失败
+        if cfg!(debug_assertions) {
+            let mut encoder =
+                device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: None,
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: &target.tex_view,
+                        resolve_target: None,
+                        ops: wgpu::Operations::default(),
+                    })],
+                    ..Default::default()
+                });
+                ink.enter_frame(&mut rpass);
+            }));
+            assert!(result.is_err());
+            assert_eq!(ink.animate_index, 2);
+        }
+    }
+}
@@ -3,22 +3,29 @@ use app_surface::{AppSurface, SurfaceFrame};
 use core::f32::consts::FRAC_PI_2;
 use std::sync::Arc;
 use utils::{
-    AnyTexture, BufferObj, MVPMatUniform, Plane, WgpuAppAction,
+    AnyTexture, BufferObj, DynamicUniformRing, MVPMatUniform, Plane, WgpuAppAction,
     node::{BindGroupData, BufferlessFullscreenNode, ViewNode, ViewNodeBuilder},
     vertex::PosTex,
 };
 use wgpu::Sampler;
-use winit::dpi::PhysicalSize;
+use winit::{
+    dpi::PhysicalSize,
+    event::{ElementState, KeyEvent},
+    keyboard::{KeyCode, PhysicalKey},
+};
 
 pub struct VertexAnimationApp {
     app: AppSurface,
     // 窗口大小
     size: PhysicalSize<u32>,
-    size_changed: bool,
+    resize_debouncer: utils::ResizeDebouncer,
     // 背景图节点
     bg_node: BufferlessFullscreenNode,
     // 翻页动画节点
     turning_node: ViewNode,
+    turning_buf: DynamicUniformRing<TurningDynamicUniform>,
+    // 翻页网格的光栅化模式，按 W 键在填充与线框之间切换
+    polygon_mode: wgpu::PolygonMode,
     // 粒子动画节点
     particle_ink: Option<ParticleInk>,
     mvp_buffer: BufferObj,
@@ -38,9 +45,8 @@ impl WgpuAppAction for VertexAnimationApp {
         // 创建 wgpu 应用
         let mut app = AppSurface::new(window).await;
 
-        // 兼容 web
-        let format = app.config.format.remove_srgb_suffix();
-        app.ctx.update_config_format(format);
+        // 兼容 web：仅在 surface 支持时切换为非 sRGB 格式
+        let format = utils::surface_format::use_non_srgb_format(&mut app);
 
         let fovy: f32 = 45.0_f32.to_radians();
         let (p_matrix, mv_matrix) = utils::matrix_helper::perspective_fullscreen_mvp(
@@ -66,30 +72,19 @@ impl WgpuAppAction for VertexAnimationApp {
 
         let sampler = utils::bilinear_sampler(&app.device);
         // 着色器
-        let (turning_shader, bg_shader) = {
-            let create_shader = |wgsl: &'static str| -> wgpu::ShaderModule {
-                app.device
-                    .create_shader_module(wgpu::ShaderModuleDescriptor {
-                        label: None,
-                        source: wgpu::ShaderSource::Wgsl(wgsl.into()),
-                    })
-            };
-            (
-                create_shader(include_str!("../assets/page_turning.wgsl")),
-                create_shader(include_str!("../assets/bg_draw.wgsl")),
-            )
-        };
+        let (turning_shader, bg_shader) = (
+            Self::create_turning_shader(&app.device),
+            app.device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: None,
+                    source: wgpu::ShaderSource::Wgsl(include_str!("../assets/bg_draw.wgsl").into()),
+                }),
+        );
 
         // 翻页动作总帧总
-        let draw_count = 60 * 3;
-        let offset_buffer_size = 256;
-        let turning_buf = BufferObj::create_empty_uniform_buffer(
-            &app.device,
-            (draw_count * offset_buffer_size) as wgpu::BufferAddress,
-            offset_buffer_size,
-            true,
-            Some("翻页动画的动态偏移缓冲区"),
-        );
+        let draw_count: u32 = 60 * 3;
+        let turning_buf =
+            DynamicUniformRing::new(&app.device, draw_count, Some("翻页动画的动态偏移缓冲区"));
 
         let start_pos = glam::Vec2::new(1.0, 0.0);
         //  从右往左下角翻页
@@ -99,41 +94,19 @@ impl WgpuAppAction for VertexAnimationApp {
         // 按动态偏移量填充 uniform 缓冲区
         for step in 0..draw_count {
             let radius = 1.0 / 8.0;
-            let data = Self::step_turning_data(radius, step as u32, draw_count as u32, gap_pos);
-            app.queue.write_buffer(
-                &turning_buf.buffer,
-                offset_buffer_size * (step),
-                bytemuck::bytes_of(&data),
-            );
+            let data = Self::step_turning_data(radius, step, draw_count, gap_pos);
+            turning_buf.write_frame(&app.queue, step, &data);
         }
 
-        // 平面网格
-        let (vertices, indices) = Plane::new(300, 300).generate_vertices();
-
-        // 准备绑定组需要的数据
-        let bind_group_data = BindGroupData {
-            uniforms: vec![&mvp_buffer],
-            inout_tv: vec![(&paper_tex, None)],
-            samplers: vec![&sampler],
-            visibilitys: vec![
-                wgpu::ShaderStages::VERTEX,
-                wgpu::ShaderStages::FRAGMENT,
-                wgpu::ShaderStages::FRAGMENT,
-            ],
-            // 配置动态偏移缓冲区
-            dynamic_uniforms: vec![&turning_buf],
-            dynamic_uniform_visibilitys: vec![
-                wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
-            ],
-            ..Default::default()
-        };
-
-        let builder = ViewNodeBuilder::<PosTex>::new(bind_group_data, &turning_shader)
-            .with_vertices_and_indices((vertices, indices))
-            .with_use_depth_stencil(true)
-            .with_cull_mode(None)
-            .with_color_format(format);
-        let turning_node = builder.build(&app.device);
+        let polygon_mode = wgpu::PolygonMode::Fill;
+        let turning_node = Self::create_turning_node(
+            &app,
+            &turning_shader,
+            (&mvp_buffer, &turning_buf),
+            &paper_tex,
+            &sampler,
+            polygon_mode,
+        );
 
         // 准备绑定组需要的数据
         let bind_group_data = BindGroupData {
@@ -156,9 +129,11 @@ impl WgpuAppAction for VertexAnimationApp {
         Self {
             app,
             size,
-            size_changed: true,
+            resize_debouncer: utils::ResizeDebouncer::with_pending(size),
             bg_node,
             turning_node,
+            turning_buf,
+            polygon_mode,
             particle_ink: None,
             mvp_buffer,
             paper_tex,
@@ -166,22 +141,72 @@ impl WgpuAppAction for VertexAnimationApp {
             depth_tex_view: None,
             is_particle_ink_phase: true,
             animate_index: 0,
-            draw_count: draw_count as u32,
+            draw_count,
         }
     }
 
     fn set_window_resized(&mut self, new_size: PhysicalSize<u32>) {
-        if self.app.config.width == new_size.width && self.app.config.height == new_size.height {
-            return;
-        }
-        self.size = new_size;
-        self.size_changed = true;
+        let current = self.get_size();
+        self.resize_debouncer.record(new_size, current);
     }
 
     fn get_size(&self) -> PhysicalSize<u32> {
         PhysicalSize::new(self.app.config.width, self.app.config.height)
     }
 
+    fn keyboard_input(&mut self, event: &KeyEvent) -> bool {
+        // 按 W 键切换翻页网格的线框模式，设备不支持 POLYGON_MODE_LINE 时仍以填充模式绘制
+        if event.physical_key == PhysicalKey::Code(KeyCode::KeyW)
+            && event.state == ElementState::Pressed
+            && !event.repeat
+        {
+            self.polygon_mode = match self.polygon_mode {
+                wgpu::PolygonMode::Fill => wgpu::PolygonMode::Line,
+                _ => wgpu::PolygonMode::Fill,
+            };
+            let shader = Self::create_turning_shader(&self.app.device);
+            self.turning_node = Self::create_turning_node(
+                &self.app,
+                &shader,
+                (&self.mvp_buffer, &self.turning_buf),
+                &self.paper_tex,
+                &self.sampler,
+                self.polygon_mode,
+            );
+            return true;
+        }
+        false
+    }
+
+    fn scale_factor_changed(&mut self, scale_factor: f64) {
+        self.app.scale_factor = scale_factor as f32;
+        // 粒子的像素尺寸按缩放因子换算，需重建粒子节点
+        if self.particle_ink.is_some() {
+            self.particle_ink = Some(ParticleInk::new(
+                &self.app,
+                &self.mvp_buffer,
+                &self.paper_tex,
+            ));
+            self.is_particle_ink_phase = true;
+        }
+    }
+
+    fn update(&mut self, _dt: instant::Duration) {
+        if self.is_particle_ink_phase {
+            if let Some(particle_ink) = self.particle_ink.as_mut() {
+                particle_ink.step();
+            }
+        } else {
+            // 推进翻页动画，循环执行
+            self.animate_index += 1;
+            if self.animate_index == self.draw_count {
+                // 本次翻页动画完成，重置状态
+                self.animate_index = 0;
+                self.is_particle_ink_phase = true;
+            }
+        }
+    }
+
     fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
         self.resize_surface_if_needed();
 
@@ -235,13 +260,6 @@ impl WgpuAppAction for VertexAnimationApp {
                 // 执行翻页动画
                 self.turning_node
                     .draw_rpass_by_offset(&mut rpass, self.animate_index, 1);
-                // 循环执行动画
-                self.animate_index += 1;
-                if self.animate_index == self.draw_count {
-                    // 本次翻页动画完成，重置状态
-                    self.animate_index = 0;
-                    self.is_particle_ink_phase = true
-                }
             }
         }
         self.app.queue.submit(Some(encoder.finish()));
@@ -252,9 +270,56 @@ impl WgpuAppAction for VertexAnimationApp {
 }
 
 impl VertexAnimationApp {
+    fn create_turning_shader(device: &wgpu::Device) -> wgpu::ShaderModule {
+        device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(include_str!("../assets/page_turning.wgsl").into()),
+        })
+    }
+
+    /// 创建翻页动画节点，`uniforms` 依次为 MVP 矩阵与翻页动画的动态偏移缓冲区
+    fn create_turning_node(
+        app: &AppSurface,
+        shader: &wgpu::ShaderModule,
+        uniforms: (&BufferObj, &BufferObj),
+        paper_tex: &AnyTexture,
+        sampler: &Sampler,
+        polygon_mode: wgpu::PolygonMode,
+    ) -> ViewNode {
+        // 平面网格
+        let (vertices, indices) = Plane::new(300, 300).generate_vertices();
+
+        // 准备绑定组需要的数据
+        let bind_group_data = BindGroupData {
+            uniforms: vec![uniforms.0],
+            inout_tv: vec![(paper_tex, None)],
+            samplers: vec![sampler],
+            visibilitys: vec![
+                wgpu::ShaderStages::VERTEX,
+                wgpu::ShaderStages::FRAGMENT,
+                wgpu::ShaderStages::FRAGMENT,
+            ],
+            // 配置动态偏移缓冲区
+            dynamic_uniforms: vec![uniforms.1],
+            dynamic_uniform_visibilitys: vec![
+                wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+            ],
+            ..Default::default()
+        };
+
+        ViewNodeBuilder::<PosTex>::new(bind_group_data, shader)
+            .with_vertices_and_indices((vertices, indices))
+            .with_use_depth_stencil(true)
+            .with_cull_mode(None)
+            .with_polygon_mode(polygon_mode)
+            .with_color_format(app.config.format)
+            .build(&app.device)
+    }
+
     /// 必要的时候调整 surface 大小
     fn resize_surface_if_needed(&mut self) {
-        if self.size_changed {
+        if let Some(size) = self.resize_debouncer.take_if_changed() {
+            self.size = size;
             //  需先 resize surface
             self.app
                 .resize_surface_by_size((self.size.width, self.size.height));
@@ -278,11 +343,8 @@ impl VertexAnimationApp {
                 &self.app,
                 &self.mvp_buffer,
                 &self.paper_tex,
-                &self.sampler,
             ));
             self.is_particle_ink_phase = true;
-
-            self.size_changed = false;
         }
     }
 
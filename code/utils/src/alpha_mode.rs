@@ -0,0 +1,77 @@
+//! surface 的 alpha 合成方式
+//!
+//! 默认的 `Opaque` 会忽略输出颜色的 alpha，画面总是不透明的；
+//! 选择 `PreMultiplied` 或 `PostMultiplied` 后，surface 的透明区域会显示其下方的内容。
+//!
+//! # NOTE:
+//! - Web 平台上 surface 下方即为网页：WebGPU 后端把 alpha 合成方式映射为 canvas 上下文配置的 `alphaMode`
+//!   （只支持 `opaque` 与 `premultiplied`），清屏颜色的 alpha 小于 1 时网页内容会从 canvas 后面透出来；
+//! - `PreMultiplied` 要求着色器输出的 rgb 已乘以 alpha，否则半透明区域会偏亮；
+//! - 原生平台上能否透明还取决于窗口本身（如 `WindowAttributes::with_transparent`）及平台的合成器。
+
+use app_surface::AppSurface;
+use wgpu::CompositeAlphaMode;
+
+/// 从 surface 支持的合成方式中选择：支持 `preferred` 时使用它，否则打印警告并回退到 `Opaque`，
+/// 连 `Opaque` 也不支持时使用列表中的第一项
+pub fn select_alpha_mode(
+    preferred: CompositeAlphaMode,
+    supported: &[CompositeAlphaMode],
+) -> CompositeAlphaMode {
+    if supported.contains(&preferred) {
+        return preferred;
+    }
+    let fallback = if supported.contains(&CompositeAlphaMode::Opaque) {
+        CompositeAlphaMode::Opaque
+    } else {
+        supported
+            .first()
+            .copied()
+            .unwrap_or(CompositeAlphaMode::Auto)
+    };
+    log::warn!(
+        "surface does not support alpha mode {preferred:?} (supported: {supported:?}), fall back to {fallback:?}"
+    );
+    fallback
+}
+
+/// 按 surface 的能力选择并应用 alpha 合成方式，返回实际使用的方式
+///
+/// 在 app 的 `new` 中创建 `AppSurface` 之后调用。
+pub fn configure_alpha_mode(
+    app: &mut AppSurface,
+    preferred: CompositeAlphaMode,
+) -> CompositeAlphaMode {
+    let caps = app.surface.get_capabilities(&app.adapter);
+    let alpha_mode = select_alpha_mode(preferred, &caps.alpha_modes);
+    if app.config.alpha_mode != alpha_mode {
+        app.config.alpha_mode = alpha_mode;
+        app.surface.configure(&app.device, &app.config);
+    }
+    alpha_mode
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn select_from_caps() {
+        let caps = [
+            CompositeAlphaMode::Opaque,
+            CompositeAlphaMode::PreMultiplied,
+        ];
+        assert_eq!(
+            select_alpha_mode(CompositeAlphaMode::PreMultiplied, &caps),
+            CompositeAlphaMode::PreMultiplied
+        );
+        assert_eq!(
+            select_alpha_mode(CompositeAlphaMode::PostMultiplied, &caps),
+            CompositeAlphaMode::Opaque
+        );
+        assert_eq!(
+            select_alpha_mode(CompositeAlphaMode::Opaque, &[CompositeAlphaMode::Inherit]),
+            CompositeAlphaMode::Inherit
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use image::{DynamicImage, ImageResult};
+
+/// 图片解码任务的句柄
+///
+/// 在 native 平台上解码在后台线程中执行；wasm 上没有可用的线程，解码在创建句柄时同步完成。
+///
+/// # NOTE:
+/// 只有解码可以放到后台线程，纹理的创建与上传（`queue.write_texture`）仍需在持有 device/queue 的主线程上进行：
+/// 先用 `join` 取回解码结果，再交给 `load_texture` 中的函数上传。
+pub struct DecodeHandle {
+    #[cfg(not(target_arch = "wasm32"))]
+    handle: std::thread::JoinHandle<ImageResult<DynamicImage>>,
+    #[cfg(target_arch = "wasm32")]
+    result: ImageResult<DynamicImage>,
+}
+
+impl DecodeHandle {
+    /// 等待解码完成
+    pub fn join(self) -> ImageResult<DynamicImage> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.handle.join().expect("图片解码线程 panic")
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.result
+        }
+    }
+}
+
+/// 解码内存中的图片（PNG/JPEG 等，格式由文件头自动识别）
+pub fn decode_on_thread(bytes: Vec<u8>) -> DecodeHandle {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        DecodeHandle {
+            handle: std::thread::spawn(move || image::load_from_memory(&bytes)),
+        }
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        DecodeHandle {
+            result: image::load_from_memory(&bytes),
+        }
+    }
+}
+
+/// 并行解码多张图片，结果与输入顺序一致
+pub fn decode_all(images: Vec<Vec<u8>>) -> Vec<ImageResult<DynamicImage>> {
+    let handles: Vec<DecodeHandle> = images.into_iter().map(decode_on_thread).collect();
+    handles.into_iter().map(DecodeHandle::join).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decode_in_order() {
+        let encode = |width: u32| {
+            let mut bytes = vec![];
+            DynamicImage::ImageRgba8(image::RgbaImage::new(width, 2))
+                .write_to(
+                    &mut std::io::Cursor::new(&mut bytes),
+                    image::ImageFormat::Png,
+                )
+                .unwrap();
+            bytes
+        };
+        let results = decode_all(vec![encode(1), encode(2), vec![0, 1, 2]]);
+        assert_eq!(results[0].as_ref().unwrap().width(), 1);
+        assert_eq!(results[1].as_ref().unwrap().width(), 2);
+        assert!(results[2].is_err());
+    }
+}
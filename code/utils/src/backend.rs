@@ -0,0 +1,54 @@
+/// 选择图形后端的环境变量名，与 wgpu 自身的约定一致
+///
+/// 取值为逗号分隔的后端名（不区分大小写），由 [`wgpu::Backends::from_comma_list`] 解析：`vulkan`、`dx12`、`metal`、`gl`、`webgpu`，
+/// 也可以是 `primary`、`secondary`。例如：`WGPU_BACKEND=vulkan cargo run --bin xxx`
+///
+/// # NOTE:
+/// 只对自己创建 `Instance` 的地方生效（如 tutorial2-surface）；
+/// 基于 `WgpuAppAction` 的 app 由 `AppSurface` 创建 instance，后端由 app-surface 自行选择。
+pub const BACKEND_ENV_VAR: &str = "WGPU_BACKEND";
+
+/// 从 `WGPU_BACKEND` 环境变量读取后端，未设置时使用全部后端
+///
+/// 取值中没有可识别的后端名时打印错误并回退到全部后端，而不是创建一个没有任何后端的 instance；
+/// wasm 上没有环境变量，总是返回全部后端。
+pub fn backends_from_env() -> wgpu::Backends {
+    match wgpu::Backends::from_env() {
+        Some(backends) if backends.is_empty() => {
+            log::error!(
+                "{BACKEND_ENV_VAR} contains no known backend, expected a comma separated list of: vulkan, dx12, metal, gl, webgpu, primary, secondary; falling back to all backends"
+            );
+            wgpu::Backends::all()
+        }
+        Some(backends) => {
+            log::info!("Backends from {BACKEND_ENV_VAR}: {backends:?}");
+            backends
+        }
+        None => wgpu::Backends::all(),
+    }
+}
+
+/// 使用指定后端创建 `Instance`
+pub fn create_instance(backends: wgpu::Backends) -> wgpu::Instance {
+    wgpu::Instance::new(&wgpu::InstanceDescriptor {
+        backends,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn parse_backend_list() {
+        // `backends_from_env` 依赖 wgpu 的解析规则：未知的名字被忽略，全部未知时结果为空
+        assert_eq!(
+            wgpu::Backends::from_comma_list("vulkan"),
+            wgpu::Backends::VULKAN
+        );
+        assert_eq!(
+            wgpu::Backends::from_comma_list("Metal,GL"),
+            wgpu::Backends::METAL | wgpu::Backends::GL
+        );
+        assert!(wgpu::Backends::from_comma_list("dx11").is_empty());
+    }
+}
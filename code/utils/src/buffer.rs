@@ -1,6 +1,39 @@
 use bytemuck::Pod;
+use std::marker::PhantomData;
 use wgpu::util::DeviceExt;
 
+/// 缓冲区读回失败的原因
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReadBackError {
+    /// 缓冲区没有 `BufferUsages::COPY_SRC`，无法复制到暂存缓冲区
+    MissingCopySrc,
+    /// 缓冲区大小不是元素大小的整数倍
+    SizeMismatch {
+        size: wgpu::BufferAddress,
+        element_size: usize,
+    },
+    /// 映射暂存缓冲区失败
+    Map(wgpu::BufferAsyncError),
+    /// 轮询后映射仍未完成（如 Web 平台上无法阻塞等待）
+    NotReady,
+}
+
+impl core::fmt::Display for ReadBackError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::MissingCopySrc => write!(f, "buffer is missing BufferUsages::COPY_SRC"),
+            Self::SizeMismatch { size, element_size } => write!(
+                f,
+                "buffer size {size} is not a multiple of element size {element_size}"
+            ),
+            Self::Map(e) => write!(f, "failed to map staging buffer: {e}"),
+            Self::NotReady => write!(f, "staging buffer is not mapped yet"),
+        }
+    }
+}
+
+impl std::error::Error for ReadBackError {}
+
 /// 场景数据（顶点/索引/模型...）缓冲区对象
 ///
 pub struct BufferObj {
@@ -12,13 +45,148 @@ pub struct BufferObj {
     // 已占用的坑位，若要计算字节数，需 used_count * 坑位字节长度
     // 对于需要按索引来计算偏移量的 buffer, 不使用 used_count，比如 ModelUniformData buffer
     pub used_count: u64,
+    // 创建时的标签，用于 `resize` 时创建同名的新缓冲区
+    pub label: Option<&'static str>,
+    // 动态偏移 uniform 缓冲区相邻槽位之间的字节距离，已按设备的偏移对齐；非动态偏移的缓冲区为 0
+    pub dynamic_offset_stride: wgpu::BufferAddress,
 }
 
+/// [`BufferObj::clear`] 非零填充时图案缓冲区的最大字节数
+pub const CLEAR_PATTERN_SIZE: wgpu::BufferAddress = 4096;
+
 #[allow(dead_code)]
 impl BufferObj {
     pub fn used_bytes(&self) -> u64 {
         self.used_count * 4
     }
+    /// 在 GPU 上把整个缓冲区的每个字节都设为 `value`，无需经 CPU 读写缓冲区的内容
+    ///
+    /// `value` 为 0 时使用 `encoder.clear_buffer`；否则创建一个以 `value` 填充的小图案缓冲区，
+    /// 再把它逐段复制到整个缓冲区。
+    ///
+    /// # NOTE:
+    /// - 缓冲区需要 `BufferUsages::COPY_DST`（通过 `create_buffer` 等方法创建的缓冲区都已带有）；
+    /// - `clear_buffer` 与缓冲区复制的偏移量和长度都需是 `COPY_BUFFER_ALIGNMENT`（4 字节）的整数倍，
+    ///   所以缓冲区的 `size` 需按 4 字节对齐；
+    /// - 非零填充的开销：上传至多 [`CLEAR_PATTERN_SIZE`] 字节的图案，并录制 `size / CLEAR_PATTERN_SIZE`
+    ///   （向上取整）次缓冲区复制，不会在 CPU 上分配与缓冲区等大的内存。
+    pub fn clear(&self, device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder, value: u8) {
+        assert!(
+            self.size % wgpu::COPY_BUFFER_ALIGNMENT == 0,
+            "缓冲区大小需是 4 字节的整数倍"
+        );
+        if value == 0 {
+            encoder.clear_buffer(&self.buffer, 0, Some(self.size));
+            return;
+        }
+        let pattern_size = self.size.min(CLEAR_PATTERN_SIZE);
+        if pattern_size == 0 {
+            return;
+        }
+        let pattern = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("buffer fill pattern"),
+            contents: &vec![value; pattern_size as usize],
+            usage: wgpu::BufferUsages::COPY_SRC,
+        });
+        for offset in (0..self.size).step_by(pattern_size as usize) {
+            let len = pattern_size.min(self.size - offset);
+            encoder.copy_buffer_to_buffer(&pattern, 0, &self.buffer, offset, len);
+        }
+    }
+
+    /// 把整个缓冲区的内容复制到暂存缓冲区，阻塞等待映射后读回为 `Vec<T>`，用于调试 SSBO 等 GPU 数据
+    ///
+    /// # NOTE:
+    /// - 缓冲区需要 `BufferUsages::COPY_SRC`，否则返回 [`ReadBackError::MissingCopySrc`]；
+    /// - Web 平台上 `poll_wait` 不会阻塞，映射来不及完成，返回 [`ReadBackError::NotReady`]。
+    pub fn read_back<T: Pod>(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) -> Result<Vec<T>, ReadBackError> {
+        if !self.buffer.usage().contains(wgpu::BufferUsages::COPY_SRC) {
+            return Err(ReadBackError::MissingCopySrc);
+        }
+        let element_size = core::mem::size_of::<T>();
+        if element_size == 0 || self.size % element_size as wgpu::BufferAddress != 0 {
+            return Err(ReadBackError::SizeMismatch {
+                size: self.size,
+                element_size,
+            });
+        }
+        let staging = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("read back staging buffer"),
+            size: self.size,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("read back encoder"),
+        });
+        encoder.copy_buffer_to_buffer(&self.buffer, 0, &staging, 0, self.size);
+        queue.submit(Some(encoder.finish()));
+
+        let slice = staging.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        crate::poll::poll_wait(device);
+        match receiver.try_recv() {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => return Err(ReadBackError::Map(e)),
+            Err(_) => return Err(ReadBackError::NotReady),
+        }
+
+        let data = slice.get_mapped_range();
+        let items = data
+            .chunks_exact(element_size)
+            .map(bytemuck::pod_read_unaligned)
+            .collect();
+        drop(data);
+        staging.unmap();
+        Ok(items)
+    }
+
+    /// 把缓冲区调整为 `new_size` 字节，并保留原有内容的前 `min(旧大小, 新大小)` 字节
+    ///
+    /// 新缓冲区沿用原来的 `usage` 与 `label`，复制命令录制到 `encoder` 中，提交之后才生效。
+    ///
+    /// # NOTE:
+    /// - 需要保留内容时，缓冲区需要 `BufferUsages::COPY_SRC | BufferUsages::COPY_DST`；
+    /// - 复制在 `encoder` 提交时执行，而 `queue.write_buffer` 会在下一次提交之前执行，
+    ///   所以要先提交 `encoder` 再写入新内容，否则写入的数据会被复制的旧内容覆盖；
+    /// - 引用了旧缓冲区的绑定组需要重新创建。
+    pub fn resize(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        new_size: wgpu::BufferAddress,
+    ) {
+        if new_size == self.size {
+            return;
+        }
+        let usage = self.buffer.usage();
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: self.label,
+            size: new_size,
+            usage,
+            mapped_at_creation: false,
+        });
+        // 复制的长度需按 COPY_BUFFER_ALIGNMENT 对齐
+        let copy_size =
+            self.size.min(new_size) / wgpu::COPY_BUFFER_ALIGNMENT * wgpu::COPY_BUFFER_ALIGNMENT;
+        if copy_size > 0 {
+            assert!(
+                usage.contains(wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST),
+                "保留缓冲区内容需要 COPY_SRC | COPY_DST"
+            );
+            encoder.copy_buffer_to_buffer(&self.buffer, 0, &buffer, 0, copy_size);
+        }
+        self.buffer = buffer;
+        self.size = new_size;
+    }
+
     pub fn create_by_buffer(buffer: wgpu::Buffer, size: u64) -> Self {
         BufferObj {
             buffer,
@@ -27,6 +195,8 @@ impl BufferObj {
             has_dynamic_offset: false,
             read_only: true,
             used_count: 0,
+            label: None,
+            dynamic_offset_stride: 0,
         }
     }
 
@@ -66,22 +236,74 @@ impl BufferObj {
             has_dynamic_offset: false,
             read_only: false,
             used_count: 0,
+            label,
+            dynamic_offset_stride: 0,
         }
     }
 
+    /// 创建内容全为 0 的 uniform 缓冲区
+    ///
+    /// `is_dynamic` 为 true 时，每个槽位存放 `min_binding_size` 字节的 uniform，
+    /// 槽位间距 `dynamic_offset_stride` 为 `min_binding_size` 按设备的 `min_uniform_buffer_offset_alignment` 向上对齐，
+    /// 写入第 i 个槽位与绘制时的动态偏移都应使用 `i * dynamic_offset_stride`。
     pub fn create_empty_uniform_buffer(
         device: &wgpu::Device,
         size: wgpu::BufferAddress,
         min_binding_size: u64,
         is_dynamic: bool,
         label: Option<&'static str>,
+    ) -> Self {
+        Self::empty_uniform_buffer(
+            device,
+            size,
+            min_binding_size,
+            is_dynamic,
+            wgpu::BufferUsages::empty(),
+            label,
+        )
+    }
+
+    /// 与 [`BufferObj::create_empty_uniform_buffer`] 相同，但额外带有 `BufferUsages::COPY_SRC`，
+    /// 可以用 [`BufferObj::read_back`] 读回内容
+    ///
+    /// # NOTE:
+    /// 只用于调试与测试，正常渲染使用的 uniform 缓冲区不需要 `COPY_SRC`。
+    pub fn create_readable_uniform_buffer(
+        device: &wgpu::Device,
+        size: wgpu::BufferAddress,
+        min_binding_size: u64,
+        is_dynamic: bool,
+        label: Option<&'static str>,
+    ) -> Self {
+        Self::empty_uniform_buffer(
+            device,
+            size,
+            min_binding_size,
+            is_dynamic,
+            wgpu::BufferUsages::COPY_SRC,
+            label,
+        )
+    }
+
+    fn empty_uniform_buffer(
+        device: &wgpu::Device,
+        size: wgpu::BufferAddress,
+        min_binding_size: u64,
+        is_dynamic: bool,
+        extra_usage: wgpu::BufferUsages,
+        label: Option<&'static str>,
     ) -> Self {
         let buffer = device.create_buffer(&wgpu::BufferDescriptor {
             size,
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST | extra_usage,
             label,
             mapped_at_creation: false,
         });
+        let dynamic_offset_stride = if is_dynamic {
+            dynamic_offset_stride(device, min_binding_size)
+        } else {
+            0
+        };
         BufferObj {
             buffer,
             size,
@@ -89,9 +311,28 @@ impl BufferObj {
             has_dynamic_offset: is_dynamic,
             read_only: true,
             used_count: 0,
+            label,
+            dynamic_offset_stride,
         }
     }
 
+    /// 创建存放一组间接派发参数（3 个 `u32`：`[x, y, z]`）的缓冲区，供 `ComputeNode::dispatch_indirect` 使用
+    ///
+    /// 带有 `STORAGE` 用途，计算着色器可以把它绑定为 `array<u32>` 写入下一次派发的工作组数量。
+    pub fn create_indirect_buffer(
+        device: &wgpu::Device,
+        workgroup_count: (u32, u32, u32),
+        label: Option<&'static str>,
+    ) -> Self {
+        BufferObj::create_buffer(
+            device,
+            Some(&[workgroup_count.0, workgroup_count.1, workgroup_count.2][..]),
+            None,
+            wgpu::BufferUsages::INDIRECT | wgpu::BufferUsages::STORAGE,
+            label,
+        )
+    }
+
     pub fn create_uniform_buffer<T>(
         device: &wgpu::Device,
         uniform: &T,
@@ -157,6 +398,378 @@ impl BufferObj {
             read_only: false,
             // TODO: 待计算正确的 used_count
             used_count: 0,
+            label,
+            dynamic_offset_stride: 0,
+        }
+    }
+}
+
+/// 按元素类型 `T` 读写的缓冲区对象
+///
+/// 记录以 `T` 为单位的 `len` 与 `capacity`，写入时由索引计算字节偏移并检查越界，
+/// 不需要再手写 `256 * step` 之类的偏移量。通过 `Deref` 可以在需要 `&BufferObj` 的地方直接使用。
+///
+/// # NOTE:
+/// 按帧取不同 uniform 的动态偏移缓冲区请使用 [`DynamicUniformRing`]，它的元素间距需按设备的偏移对齐。
+pub struct TypedBufferObj<T: Pod> {
+    pub obj: BufferObj,
+    // 有效元素个数，由创建时的数据或 `set_len` 决定
+    len: u64,
+    // 可容纳的元素个数
+    capacity: u64,
+    // 相邻元素之间的字节距离
+    stride: wgpu::BufferAddress,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Pod> TypedBufferObj<T> {
+    /// 以 `slice` 的内容创建缓冲区，`len` 与 `capacity` 都等于 `slice.len()`
+    pub fn create_buffer(
+        device: &wgpu::Device,
+        slice: &[T],
+        usage: wgpu::BufferUsages,
+        label: Option<&'static str>,
+    ) -> Self {
+        let obj = BufferObj::create_buffer(device, Some(slice), None, usage, label);
+        let len = slice.len() as u64;
+        Self::from_obj(
+            obj,
+            len,
+            len,
+            core::mem::size_of::<T>() as wgpu::BufferAddress,
+        )
+    }
+
+    /// 创建可容纳 `capacity` 个元素的空缓冲区，`len` 为 0
+    pub fn create_empty_buffer(
+        device: &wgpu::Device,
+        capacity: u64,
+        usage: wgpu::BufferUsages,
+        label: Option<&'static str>,
+    ) -> Self {
+        let stride = core::mem::size_of::<T>() as wgpu::BufferAddress;
+        let mut obj = BufferObj::create_empty_storage_buffer(
+            device,
+            capacity * stride,
+            usage | wgpu::BufferUsages::COPY_DST,
+            label,
+        );
+        obj.min_binding_size = wgpu::BufferSize::new(stride);
+        Self::from_obj(obj, 0, capacity, stride)
+    }
+
+    fn from_obj(obj: BufferObj, len: u64, capacity: u64, stride: wgpu::BufferAddress) -> Self {
+        Self {
+            obj,
+            len,
+            capacity,
+            stride,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    /// 设置有效元素个数，不能超过 `capacity`
+    pub fn set_len(&mut self, len: u64) {
+        assert!(
+            len <= self.capacity,
+            "长度 {len} 超出缓冲区容量 {}",
+            self.capacity
+        );
+        self.len = len;
+    }
+
+    pub fn stride(&self) -> wgpu::BufferAddress {
+        self.stride
+    }
+
+    /// 第 `index` 个元素在缓冲区中的字节偏移
+    pub fn offset_of(&self, index: u64) -> wgpu::BufferAddress {
+        assert!(
+            index < self.capacity,
+            "索引 {index} 超出缓冲区容量 {}",
+            self.capacity
+        );
+        index * self.stride
+    }
+
+    /// 第 `index` 个元素的动态偏移量，用于 `set_bind_group`
+    pub fn dynamic_offset(&self, index: u32) -> wgpu::DynamicOffset {
+        self.offset_of(index as u64) as wgpu::DynamicOffset
+    }
+
+    /// 写入第 `index` 个元素
+    pub fn write(&self, queue: &wgpu::Queue, index: u64, value: &T) {
+        let offset = self.offset_of(index);
+        queue.write_buffer(&self.obj.buffer, offset, bytemuck::bytes_of(value));
+    }
+
+    /// 从第 `start` 个元素开始连续写入 `values`
+    pub fn write_slice(&self, queue: &wgpu::Queue, start: u64, values: &[T]) {
+        let end = start + values.len() as u64;
+        assert!(
+            end <= self.capacity,
+            "写入范围 {start}..{end} 超出缓冲区容量 {}",
+            self.capacity
+        );
+        if values.is_empty() {
+            return;
+        }
+        if self.stride == core::mem::size_of::<T>() as wgpu::BufferAddress {
+            queue.write_buffer(
+                &self.obj.buffer,
+                start * self.stride,
+                bytemuck::cast_slice(values),
+            );
+        } else {
+            // 元素之间有对齐填充，需逐个写入
+            for (i, value) in values.iter().enumerate() {
+                queue.write_buffer(
+                    &self.obj.buffer,
+                    (start + i as u64) * self.stride,
+                    bytemuck::bytes_of(value),
+                );
+            }
+        }
+    }
+}
+
+impl<T: Pod> std::ops::Deref for TypedBufferObj<T> {
+    type Target = BufferObj;
+
+    fn deref(&self) -> &BufferObj {
+        &self.obj
+    }
+}
+
+/// 存放 `slot_size` 字节 uniform 的动态偏移槽位间距：按设备的 `min_uniform_buffer_offset_alignment` 向上对齐
+fn dynamic_offset_stride(
+    device: &wgpu::Device,
+    slot_size: wgpu::BufferAddress,
+) -> wgpu::BufferAddress {
+    let alignment = device.limits().min_uniform_buffer_offset_alignment as wgpu::BufferAddress;
+    slot_size.max(1).div_ceil(alignment) * alignment
+}
+
+/// 每帧一个槽位的动态偏移 uniform 缓冲区
+///
+/// 槽位间距（`stride`）按设备实际的 `min_uniform_buffer_offset_alignment` 向上对齐，
+/// 不再假设为 256 字节；绘制第 `index` 帧时把 [`DynamicUniformRing::dynamic_offset`] 传给 `set_bind_group`。
+pub struct DynamicUniformRing<T: Pod> {
+    buf: TypedBufferObj<T>,
+}
+
+impl<T: Pod> DynamicUniformRing<T> {
+    /// 创建可容纳 `frame_count` 帧 uniform 的缓冲区，内容全为 0
+    pub fn new(device: &wgpu::Device, frame_count: u32, label: Option<&'static str>) -> Self {
+        Self::with_constructor(
+            device,
+            frame_count,
+            label,
+            BufferObj::create_empty_uniform_buffer,
+        )
+    }
+
+    /// 与 [`DynamicUniformRing::new`] 相同，但缓冲区带有 `COPY_SRC`，可用于调试时读回各帧的内容，
+    /// 见 [`BufferObj::create_readable_uniform_buffer`]
+    pub fn new_readable(
+        device: &wgpu::Device,
+        frame_count: u32,
+        label: Option<&'static str>,
+    ) -> Self {
+        Self::with_constructor(
+            device,
+            frame_count,
+            label,
+            BufferObj::create_readable_uniform_buffer,
+        )
+    }
+
+    fn with_constructor(
+        device: &wgpu::Device,
+        frame_count: u32,
+        label: Option<&'static str>,
+        create: fn(
+            &wgpu::Device,
+            wgpu::BufferAddress,
+            u64,
+            bool,
+            Option<&'static str>,
+        ) -> BufferObj,
+    ) -> Self {
+        let size = core::mem::size_of::<T>() as wgpu::BufferAddress;
+        let stride = dynamic_offset_stride(device, size);
+        let capacity = frame_count as u64;
+        let obj = create(device, capacity * stride, size, true, label);
+        Self {
+            buf: TypedBufferObj::from_obj(obj, capacity, capacity, stride),
+        }
+    }
+
+    pub fn frame_count(&self) -> u32 {
+        self.buf.capacity() as u32
+    }
+
+    pub fn stride(&self) -> wgpu::BufferAddress {
+        self.buf.stride()
+    }
+
+    /// 写入第 `index` 帧的 uniform
+    pub fn write_frame(&self, queue: &wgpu::Queue, index: u32, value: &T) {
+        self.buf.write(queue, index as u64, value);
+    }
+
+    /// 从第 0 帧开始依次写入每一帧的 uniform
+    pub fn write_frames(&self, queue: &wgpu::Queue, values: &[T]) {
+        self.buf.write_slice(queue, 0, values);
+    }
+
+    /// 第 `index` 帧的动态偏移量
+    pub fn dynamic_offset(&self, index: u32) -> wgpu::DynamicOffset {
+        self.buf.dynamic_offset(index)
+    }
+}
+
+impl<T: Pod> std::ops::Deref for DynamicUniformRing<T> {
+    type Target = BufferObj;
+
+    fn deref(&self) -> &BufferObj {
+        &self.buf
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn clear_to_zero() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        // 两段完整的图案再加 12 字节的尾部，覆盖非零填充的分段复制
+        let data: Vec<u32> = (1..=(CLEAR_PATTERN_SIZE as u32 / 2 + 3)).collect();
+        let buf = BufferObj::create_buffer(
+            &device,
+            Some(&data),
+            None,
+            wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            None,
+        );
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: buf.size,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let read = |value: u8| {
+            let mut encoder =
+                device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            buf.clear(&device, &mut encoder, value);
+            encoder.copy_buffer_to_buffer(&buf.buffer, 0, &readback, 0, buf.size);
+            queue.submit(Some(encoder.finish()));
+            let slice = readback.slice(..);
+            slice.map_async(wgpu::MapMode::Read, |_| {});
+            crate::poll::poll_wait(&device);
+            let bytes = slice.get_mapped_range().to_vec();
+            readback.unmap();
+            bytes
+        };
+
+        assert!(read(0).iter().all(|b| *b == 0));
+        assert!(read(0xab).iter().all(|b| *b == 0xab));
+    }
+
+    #[test]
+    fn read_back_round_trip() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        let data: Vec<[f32; 2]> = (0..16).map(|i| [i as f32, -(i as f32)]).collect();
+        let buf = BufferObj::create_buffer(
+            &device,
+            Some(&data),
+            None,
+            wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            None,
+        );
+        assert_eq!(buf.read_back::<[f32; 2]>(&device, &queue), Ok(data.clone()));
+
+        let no_copy_src = BufferObj::create_storage_buffer(&device, &data, None);
+        assert_eq!(
+            no_copy_src.read_back::<[f32; 2]>(&device, &queue),
+            Err(ReadBackError::MissingCopySrc)
+        );
+    }
+
+    #[test]
+    fn resize_preserves_contents() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        let mut buf = BufferObj::create_buffer(
+            &device,
+            Some(&[1u32, 2, 3, 4]),
+            None,
+            wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            Some("resized"),
+        );
+        let resize = |buf: &mut BufferObj, size| {
+            let mut encoder =
+                device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            buf.resize(&device, &mut encoder, size);
+            queue.submit(Some(encoder.finish()));
+        };
+
+        resize(&mut buf, 32);
+        assert_eq!(buf.size, 32);
+        assert_eq!(buf.label, Some("resized"));
+        assert_eq!(
+            buf.read_back::<u32>(&device, &queue),
+            Ok(vec![1, 2, 3, 4, 0, 0, 0, 0])
+        );
+
+        resize(&mut buf, 8);
+        assert_eq!(buf.read_back::<u32>(&device, &queue), Ok(vec![1, 2]));
+    }
+
+    #[test]
+    fn dynamic_uniform_ring_offsets() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        let ring = DynamicUniformRing::<[u32; 4]>::new_readable(&device, 3, None);
+        ring.write_frames(&queue, &[[1; 4], [2; 4], [3; 4]]);
+        ring.write_frame(&queue, 1, &[7; 4]);
+        let stride = device.limits().min_uniform_buffer_offset_alignment as u64;
+        assert_eq!((ring.frame_count(), ring.stride()), (3, stride));
+        assert_eq!(ring.dynamic_offset(2) as u64, 2 * stride);
+        assert_eq!(ring.size, 3 * stride);
+        assert!(ring.has_dynamic_offset);
+        assert_eq!(ring.dynamic_offset_stride, stride);
+
+        // 每个槽位的开头是写入的值，槽位之间的对齐填充保持为 0
+        let words = ring.read_back::<u32>(&device, &queue).unwrap();
+        let slot_words = (stride / 4) as usize;
+        for (frame, expected) in [1, 7, 3].into_iter().enumerate() {
+            let slot = &words[frame * slot_words..(frame + 1) * slot_words];
+            assert_eq!(slot[..4], [expected; 4]);
+            assert!(slot[4..].iter().all(|w| *w == 0));
         }
     }
 }
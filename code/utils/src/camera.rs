@@ -0,0 +1,125 @@
+use crate::matrix_helper::{SAFE_FRAC_PI_2, orbit};
+use glam::{Mat4, Vec3};
+use winit::event::MouseScrollDelta;
+
+/// 环绕目标点的轨道相机，用于观察位于场景中心的物体
+///
+/// 鼠标拖动改变偏航角与俯仰角，滚轮缩放到目标点的距离；`yaw`、`pitch` 的约定与 [`crate::matrix_helper::orbit`] 一致。
+///
+/// # NOTE:
+/// 与 tutorial12-camera 中的 `CameraController` 不同，这里没有按帧累积的移动量，
+/// `process_mouse`、`process_scroll` 直接修改相机状态，调用后用 `calc_matrix` 重新计算观察矩阵即可。
+/// 用于 tutorial6-uniforms 这类以 `eye`/`target` 描述相机的示例时，可由 [`OrbitCamera::from_eye_target`] 创建，
+/// 再用 `eye()` 与 `target` 替换原来的字段。
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrbitCamera {
+    pub target: Vec3,
+    /// 偏航角（弧度）
+    pub yaw: f32,
+    /// 俯仰角（弧度），始终在 [-`SAFE_FRAC_PI_2`, `SAFE_FRAC_PI_2`] 内
+    pub pitch: f32,
+    /// 相机到目标点的距离，不小于 `min_distance`
+    pub distance: f32,
+    /// 滚轮缩放时允许的最小距离，避免相机穿过目标点后画面翻转
+    pub min_distance: f32,
+    /// 鼠标每移动一个像素旋转的弧度
+    pub sensitivity: f32,
+}
+
+impl OrbitCamera {
+    /// 滚轮每滚动一行（格）距离缩放的比例
+    pub const ZOOM_PER_LINE: f32 = 0.9;
+
+    pub fn new(target: Vec3, yaw: f32, pitch: f32, distance: f32) -> Self {
+        let min_distance = 0.1;
+        Self {
+            target,
+            yaw,
+            pitch: pitch.clamp(-SAFE_FRAC_PI_2, SAFE_FRAC_PI_2),
+            distance: distance.max(min_distance),
+            min_distance,
+            sensitivity: 0.005,
+        }
+    }
+
+    /// 由相机位置与目标点反推偏航角、俯仰角与距离
+    pub fn from_eye_target(eye: Vec3, target: Vec3) -> Self {
+        let forward = target - eye;
+        let distance = forward.length();
+        let dir = forward.normalize_or(Vec3::NEG_Z);
+        Self::new(
+            target,
+            dir.z.atan2(dir.x),
+            dir.y.clamp(-1.0, 1.0).asin(),
+            distance,
+        )
+    }
+
+    /// 相机的世界空间位置
+    pub fn eye(&self) -> Vec3 {
+        orbit(self.target, self.yaw, self.pitch, self.distance).0
+    }
+
+    pub fn process_mouse(&mut self, mouse_dx: f64, mouse_dy: f64) {
+        self.yaw += mouse_dx as f32 * self.sensitivity;
+        self.pitch = (self.pitch - mouse_dy as f32 * self.sensitivity)
+            .clamp(-SAFE_FRAC_PI_2, SAFE_FRAC_PI_2);
+    }
+
+    /// 向上滚动拉近、向下滚动拉远，按比例缩放所以远近不同时的缩放手感一致
+    pub fn process_scroll(&mut self, delta: &MouseScrollDelta) {
+        let lines = crate::scroll_lines(delta);
+        self.distance = (self.distance * Self::ZOOM_PER_LINE.powf(lines)).max(self.min_distance);
+    }
+
+    /// 观察矩阵
+    pub fn calc_matrix(&self) -> Mat4 {
+        orbit(self.target, self.yaw, self.pitch, self.distance).1
+    }
+
+    /// 序列化为 JSON，需启用 `json` 特性
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("OrbitCamera is always serializable")
+    }
+
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn orbit_camera_controls() {
+        let mut camera = OrbitCamera::from_eye_target(Vec3::new(0.0, 1.0, 2.0), Vec3::ZERO);
+        assert!(camera.eye().abs_diff_eq(Vec3::new(0.0, 1.0, 2.0), 1e-5));
+        assert!((camera.distance - 5.0_f32.sqrt()).abs() < 1e-5);
+
+        let target_in_view = camera.calc_matrix().transform_point3(camera.target);
+        assert!(target_in_view.abs_diff_eq(Vec3::new(0.0, 0.0, -camera.distance), 1e-5));
+
+        // 俯仰角被限制，不会越过正上方
+        camera.process_mouse(0.0, -1.0e6);
+        assert_eq!(camera.pitch, SAFE_FRAC_PI_2);
+        assert!(camera.calc_matrix().is_finite());
+
+        camera.process_scroll(&MouseScrollDelta::LineDelta(0.0, 1.0));
+        assert!((camera.distance - 5.0_f32.sqrt() * 0.9).abs() < 1e-5);
+        // 拉近时不会穿过目标点
+        camera.process_scroll(&MouseScrollDelta::LineDelta(0.0, 1000.0));
+        assert_eq!(camera.distance, camera.min_distance);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn orbit_camera_json_round_trip() {
+        let camera = OrbitCamera::new(Vec3::ZERO, 0.5, 0.3, 4.0);
+        assert_eq!(OrbitCamera::from_json(&camera.to_json()).unwrap(), camera);
+        assert!(OrbitCamera::from_json("{}").is_err());
+    }
+}
@@ -0,0 +1,218 @@
+//! 帧捕获：把一帧渲染到离屏纹理上，再读回 CPU；以及按 F12 保存截图
+//!
+//! 仅支持 native 平台，因为读回时需要阻塞等待 GPU 完成。
+//! Web 平台的截图由框架直接把 canvas 导出为 PNG 并下载。
+
+/// 离屏渲染一帧并读回紧密排列的像素数据（每行 `width * 4` 字节）
+///
+/// `draw` 接收命令编码器与离屏纹理视图，负责录制渲染通道。
+/// 如果 `format` 是 sRGB 格式，着色器输出的线性颜色会在写入时被编码为 sRGB，读回的值与着色器输出不相等。
+pub fn capture_frame<F>(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    width: u32,
+    height: u32,
+    format: wgpu::TextureFormat,
+    draw: F,
+) -> Vec<u8>
+where
+    F: FnOnce(&mut wgpu::CommandEncoder, &wgpu::TextureView),
+{
+    let size = wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("capture texture"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("capture encoder"),
+    });
+    draw(&mut encoder, &view);
+    read_back(device, queue, encoder, &texture)
+}
+
+/// 与 [`capture_frame`] 相同，但读回为 `image::RgbaImage`，`format` 的要求与 [`read_texture`] 相同
+///
+/// 框架按 F12 截图与 `run_headless` 都以 surface 的格式调用它，BGRA 的通道会被交换为 RGBA。
+pub fn capture_image<F>(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    width: u32,
+    height: u32,
+    format: wgpu::TextureFormat,
+    draw: F,
+) -> image::RgbaImage
+where
+    F: FnOnce(&mut wgpu::CommandEncoder, &wgpu::TextureView),
+{
+    let is_bgra = is_bgra(format);
+    let pixels = capture_frame(device, queue, width, height, format, draw);
+    to_rgba_image(width, height, pixels, is_bgra)
+}
+
+/// 把已有的纹理读回为 `image::RgbaImage`，需要纹理带有 `TextureUsages::COPY_SRC`
+///
+/// 支持 `Rgba8Unorm`、`Bgra8Unorm` 及其 sRGB 格式，BGRA 的通道会被交换为 RGBA；
+/// sRGB 格式读回的就是编码后的值，可以直接保存为 PNG。
+///
+/// # NOTE:
+/// 交换链的纹理需要在 surface 配置的 `usage` 中加入 `COPY_SRC`，并且在 `present` 之前读取，
+/// 否则可以像 [`capture_frame`] 一样把这一帧再渲染到离屏纹理上。
+pub fn read_texture(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+) -> image::RgbaImage {
+    let is_bgra = is_bgra(texture.format());
+    let encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("read texture encoder"),
+    });
+    let pixels = read_back(device, queue, encoder, texture);
+    to_rgba_image(texture.width(), texture.height(), pixels, is_bgra)
+}
+
+/// 是否为 BGRA 通道顺序，不支持读回为 `RgbaImage` 的格式直接 panic
+fn is_bgra(format: wgpu::TextureFormat) -> bool {
+    match format {
+        wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => false,
+        wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => true,
+        format => panic!("不支持读回为 RgbaImage 的纹理格式: {format:?}"),
+    }
+}
+
+fn to_rgba_image(width: u32, height: u32, mut pixels: Vec<u8>, is_bgra: bool) -> image::RgbaImage {
+    if is_bgra {
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+    }
+    image::RgbaImage::from_raw(width, height, pixels).expect("像素数据长度与纹理尺寸不一致")
+}
+
+/// 截图的文件名，`timestamp` 为 Unix 时间戳（秒）
+pub fn screenshot_file_name(timestamp: u64) -> String {
+    format!("screenshot-{timestamp}.png")
+}
+
+/// 把截图保存到 `application_root_dir()` 下的 `screenshot-<时间戳>.png`，返回保存的路径
+pub fn save_screenshot(image: &image::RgbaImage) -> image::ImageResult<std::path::PathBuf> {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let path = std::path::PathBuf::from(crate::application_root_dir())
+        .join(screenshot_file_name(timestamp));
+    image.save_with_format(&path, image::ImageFormat::Png)?;
+    Ok(path)
+}
+
+/// 在 `encoder` 之后追加把 `texture` 复制到缓冲区的命令，提交并阻塞等待读回紧密排列的像素数据
+fn read_back(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    mut encoder: wgpu::CommandEncoder,
+    texture: &wgpu::Texture,
+) -> Vec<u8> {
+    let size = texture.size();
+    let (width, height) = (size.width, size.height);
+    let unpadded_bytes_per_row = width * 4;
+    let padded_bytes_per_row = padded_bytes_per_row(unpadded_bytes_per_row);
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("capture buffer"),
+        size: (padded_bytes_per_row * height) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::TexelCopyBufferInfo {
+            buffer: &buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_bytes_per_row),
+                rows_per_image: Some(height),
+            },
+        },
+        size,
+    );
+    queue.submit(Some(encoder.finish()));
+
+    let slice = buffer.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |_| {});
+    crate::poll::poll_wait(device);
+
+    let data = slice.get_mapped_range();
+    let pixels = unpad_rows(&data, unpadded_bytes_per_row, padded_bytes_per_row);
+    drop(data);
+    buffer.unmap();
+
+    pixels
+}
+
+/// 渲染到线性（非 sRGB）的 `Rgba8Unorm` 离屏纹理并读回
+///
+/// 与 [`capture_frame`] 使用 sRGB 格式时不同，这里读回的值就是着色器输出的颜色乘以 255，
+/// 没有色彩空间转换带来的歧义，适合做像素级的对比测试。
+pub fn capture_linear_frame<F>(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    width: u32,
+    height: u32,
+    draw: F,
+) -> Vec<u8>
+where
+    F: FnOnce(&mut wgpu::CommandEncoder, &wgpu::TextureView),
+{
+    capture_frame(
+        device,
+        queue,
+        width,
+        height,
+        wgpu::TextureFormat::Rgba8Unorm,
+        draw,
+    )
+}
+
+/// `copy_texture_to_buffer` 要求每行字节数按 256 字节对齐
+pub fn padded_bytes_per_row(unpadded_bytes_per_row: u32) -> u32 {
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    unpadded_bytes_per_row.div_ceil(align) * align
+}
+
+/// 去掉每行末尾的对齐填充
+pub fn unpad_rows(data: &[u8], unpadded_bytes_per_row: u32, padded_bytes_per_row: u32) -> Vec<u8> {
+    data.chunks(padded_bytes_per_row as usize)
+        .flat_map(|row| &row[..unpadded_bytes_per_row as usize])
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn row_padding() {
+        assert_eq!(padded_bytes_per_row(4), 256);
+        assert_eq!(padded_bytes_per_row(256), 256);
+        assert_eq!(padded_bytes_per_row(100 * 4), 512);
+
+        let data: Vec<u8> = (0..8).chain(0..8).map(|i| i as u8).collect();
+        assert_eq!(unpad_rows(&data, 3, 8), vec![0, 1, 2, 0, 1, 2]);
+        assert_eq!(
+            screenshot_file_name(1700000000),
+            "screenshot-1700000000.png"
+        );
+    }
+}
@@ -30,3 +30,321 @@ pub fn unpack_u32_to_color(value: u32) -> wgpu::Color {
         a: rgba8[3] as f64 / 255.0,
     }
 }
+
+/// 十六进制颜色字符串解析失败的原因
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColorParseError {
+    /// 缺少开头的 `#`
+    MissingHash,
+    /// `#` 之后的位数不是 3、6 或 8
+    InvalidLength(usize),
+    /// 含有非十六进制字符
+    InvalidDigit(char),
+}
+
+impl core::fmt::Display for ColorParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::MissingHash => write!(f, "hex color must start with '#'"),
+            Self::InvalidLength(len) => write!(
+                f,
+                "hex color must have 3, 6 or 8 digits after '#', found {len}"
+            ),
+            Self::InvalidDigit(c) => write!(f, "invalid hex digit {c:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ColorParseError {}
+
+/// 解析 `#RGB`、`#RRGGBB` 或 `#RRGGBBAA` 形式的颜色，不区分大小写，省略 alpha 时为不透明
+///
+/// 与 [`unpack_u32_to_color`] 一致，各通道按 sRGB 编码值原样归一化，不做线性转换：
+/// `color_from_hex("#f2eadd")` 等于 `unpack_u32_to_color(0xf2eaddff)`。
+pub fn color_from_hex(hex: &str) -> Result<wgpu::Color, ColorParseError> {
+    let digits = hex.strip_prefix('#').ok_or(ColorParseError::MissingHash)?;
+    if let Some(c) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(ColorParseError::InvalidDigit(c));
+    }
+    // 此时 digits 只含 ASCII 字符，可以按字节切分
+    let value = |range: core::ops::Range<usize>| u32::from_str_radix(&digits[range], 16).unwrap();
+    let packed = match digits.len() {
+        3 => {
+            // #RGB 的每一位重复一次，即 0xR 扩展为 0xRR
+            let [r, g, b] = [0, 1, 2].map(|i| value(i..i + 1) * 0x11);
+            (r << 24) | (g << 16) | (b << 8) | 0xff
+        }
+        6 => (value(0..6) << 8) | 0xff,
+        8 => value(0..8),
+        len => return Err(ColorParseError::InvalidLength(len)),
+    };
+    Ok(unpack_u32_to_color(packed))
+}
+
+/// 在颜色 `a` 与 `b` 之间按 `t` 逐通道（含 alpha）线性插值，`t` 会被限制在 [0, 1]
+///
+/// # NOTE:
+/// 直接对 sRGB 编码值插值，中间色会比在线性空间中插值略暗；需要物理正确的渐变时，
+/// 先用 [`srgb_to_linear`] 转换，插值后再用 [`linear_to_srgb`] 转回。
+pub fn lerp_color(a: wgpu::Color, b: wgpu::Color, t: f64) -> wgpu::Color {
+    let t = t.clamp(0.0, 1.0);
+    wgpu::Color {
+        r: a.r + (b.r - a.r) * t,
+        g: a.g + (b.g - a.g) * t,
+        b: a.b + (b.b - a.b) * t,
+        a: a.a + (b.a - a.a) * t,
+    }
+}
+
+/// sRGB 编码值转为线性值，各分量取值范围 [0, 1]
+///
+/// # NOTE:
+/// 以 `*Srgb` 格式（`add_srgb_suffix`）的纹理或渲染目标，GPU 在采样与写入时会自动做这一转换，着色器中使用线性值即可；
+/// 只有在非 sRGB 格式（`remove_srgb_suffix`）上需要得到与 sRGB 格式相同的显示结果，
+/// 或把设计稿中的 sRGB 颜色（如 `unpack_u32_to_rgba_f32` 的结果）作为 uniform 参与光照计算时，才需要手动转换。
+pub fn srgb_to_linear(c: [f32; 3]) -> [f32; 3] {
+    c.map(|v| {
+        if v <= 0.04045 {
+            v / 12.92
+        } else {
+            ((v + 0.055) / 1.055).powf(2.4)
+        }
+    })
+}
+
+/// 线性值转为 sRGB 编码值，是 [`srgb_to_linear`] 的逆运算
+pub fn linear_to_srgb(c: [f32; 3]) -> [f32; 3] {
+    c.map(|v| {
+        if v <= 0.003_130_8 {
+            v * 12.92
+        } else {
+            1.055 * v.powf(1.0 / 2.4) - 0.055
+        }
+    })
+}
+
+/// RGB 转为 HSV，返回 `[h, s, v]`，色相 `h` 归一化到 [0, 1)
+///
+/// 灰色（饱和度为 0）的色相为 0。
+pub fn rgb_to_hsv(c: [f32; 3]) -> [f32; 3] {
+    let [r, g, b] = c;
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    let h = if delta <= 0.0 {
+        0.0
+    } else if max == r {
+        ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / delta + 2.0
+    } else {
+        (r - g) / delta + 4.0
+    };
+    let s = if max <= 0.0 { 0.0 } else { delta / max };
+    [h / 6.0, s, max]
+}
+
+/// HSV 转为 RGB，色相 `h` 以 1 为周期，超出 [0, 1) 的值会被折回
+pub fn hsv_to_rgb(c: [f32; 3]) -> [f32; 3] {
+    let [h, s, v] = c;
+    let h = h.rem_euclid(1.0) * 6.0;
+    let chroma = v * s;
+    let x = chroma * (1.0 - (h.rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = v - chroma;
+    [r + m, g + m, b + m]
+}
+
+/// 色觉障碍友好的感知均匀色图
+///
+/// 在参数 `t ∈ [0, 1]` 上采样，超出范围的值会被截断；返回 sRGB 编码的 `[r, g, b, 1.0]`。
+/// 写入 sRGB 格式的渲染目标时需先转为线性值。
+///
+/// - `viridis`：紫 → 蓝绿 → 黄，亮度单调递增，红绿色盲下仍可区分，适合大多数数据可视化；
+/// - `cividis`：深蓝 → 灰 → 黄，专为红绿色盲设计，两种视觉下的观感几乎一致；
+/// - `turbo`：改进的彩虹色图，对比度更高，适合需要分辨细微差异的场景，但亮度不单调，不适合打印为灰度。
+pub mod palette {
+    /// 多项式拟合（mattz），与 matplotlib 的 viridis 最大误差约 0.01
+    pub fn viridis(t: f32) -> [f32; 4] {
+        const C: [[f32; 3]; 7] = [
+            [0.277_727_33, 0.005_407_345, 0.334_099_8],
+            [0.105_093_04, 1.404_613_5, 1.384_590_2],
+            [-0.330_861_83, 0.214_847_56, 0.095_095_16],
+            [-4.634_230_5, -5.799_101, -19.332_441],
+            [6.228_27, 14.179_933, 56.690_553],
+            [4.776_385, -13.745_145, -65.353_03],
+            [-5.435_456, 4.645_852_6, 26.312_435],
+        ];
+        let t = t.clamp(0.0, 1.0);
+        let mut rgb = [0.0; 3];
+        for c in C.iter().rev() {
+            for i in 0..3 {
+                rgb[i] = rgb[i] * t + c[i];
+            }
+        }
+        [rgb[0], rgb[1], rgb[2], 1.0]
+    }
+
+    /// Google 提供的多项式近似
+    pub fn turbo(t: f32) -> [f32; 4] {
+        const R: [f32; 6] = [
+            0.135_721_38,
+            4.615_392_6,
+            -42.660_324,
+            132.131_08,
+            -152.942_4,
+            59.286_38,
+        ];
+        const G: [f32; 6] = [
+            0.091_402_61,
+            2.194_188_4,
+            4.842_966_6,
+            -14.185_033,
+            4.277_298_6,
+            2.829_566,
+        ];
+        const B: [f32; 6] = [
+            0.106_673_3,
+            12.641_946,
+            -60.582_05,
+            110.362_77,
+            -89.903_11,
+            27.348_25,
+        ];
+        let t = t.clamp(0.0, 1.0);
+        let eval = |c: &[f32; 6]| c.iter().rev().fold(0.0, |acc, k| acc * t + k);
+        [eval(&R), eval(&G), eval(&B), 1.0]
+    }
+
+    /// 在 matplotlib 的 cividis 上取 5 个关键色做线性插值的近似
+    pub fn cividis(t: f32) -> [f32; 4] {
+        const STOPS: [[f32; 3]; 5] = [
+            [0.0, 0.135_1, 0.304_8],
+            [0.230_5, 0.312_9, 0.431_8],
+            [0.486_7, 0.482_3, 0.469_5],
+            [0.751_5, 0.671_9, 0.402_4],
+            [0.995_7, 0.909_3, 0.217_8],
+        ];
+        let x = t.clamp(0.0, 1.0) * (STOPS.len() - 1) as f32;
+        let i = (x as usize).min(STOPS.len() - 2);
+        let f = x - i as f32;
+        let (a, b) = (STOPS[i], STOPS[i + 1]);
+        [
+            a[0] + (b[0] - a[0]) * f,
+            a[1] + (b[1] - a[1]) * f,
+            a[2] + (b[2] - a[2]) * f,
+            1.0,
+        ]
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        fn assert_close(color: [f32; 4], expected: [f32; 3]) {
+            for i in 0..3 {
+                assert!(
+                    (color[i] - expected[i]).abs() < 0.02,
+                    "{color:?} != {expected:?}"
+                );
+            }
+        }
+
+        #[test]
+        fn viridis_endpoints() {
+            // 0 为深紫色，1 为黄色
+            assert_close(viridis(0.0), [0.267, 0.005, 0.329]);
+            assert_close(viridis(1.0), [0.993, 0.906, 0.144]);
+            // 超出范围时截断
+            assert_eq!(viridis(-1.0), viridis(0.0));
+
+            assert_close(turbo(0.0), [0.136, 0.091, 0.107]);
+            assert_close(cividis(1.0), [0.996, 0.909, 0.218]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_close(a: [f32; 3], b: [f32; 3]) {
+        for i in 0..3 {
+            assert!((a[i] - b[i]).abs() < 1e-5, "{a:?} != {b:?}");
+        }
+    }
+
+    #[test]
+    fn srgb_round_trip() {
+        // sRGB 的 0.5 约为线性的 0.214
+        assert!((srgb_to_linear([0.5; 3])[0] - 0.214_041).abs() < 1e-5);
+        assert_eq!(srgb_to_linear([0.0, 1.0, 0.02]), [0.0, 1.0, 0.02 / 12.92]);
+        for i in 0..=100 {
+            let v = i as f32 / 100.0;
+            assert_close(linear_to_srgb(srgb_to_linear([v; 3])), [v; 3]);
+            assert_close(srgb_to_linear(linear_to_srgb([v; 3])), [v; 3]);
+        }
+    }
+
+    #[test]
+    fn parse_hex_color() {
+        assert_eq!(
+            color_from_hex("#f2eadd"),
+            Ok(unpack_u32_to_color(0xf2eaddff))
+        );
+        assert_eq!(
+            color_from_hex("#F2EADD80"),
+            Ok(unpack_u32_to_color(0xf2eadd80))
+        );
+        assert_eq!(color_from_hex("#f0a"), color_from_hex("#ff00aa"));
+
+        assert_eq!(color_from_hex("f2eadd"), Err(ColorParseError::MissingHash));
+        assert_eq!(
+            color_from_hex("#f2ead"),
+            Err(ColorParseError::InvalidLength(5))
+        );
+        assert_eq!(
+            color_from_hex("#f2eadg"),
+            Err(ColorParseError::InvalidDigit('g'))
+        );
+        // 非 ASCII 字符不会在按字节切分时 panic
+        assert_eq!(
+            color_from_hex("#ffé"),
+            Err(ColorParseError::InvalidDigit('é'))
+        );
+
+        let (black, white) = (wgpu::Color::BLACK, wgpu::Color::WHITE);
+        assert_eq!(lerp_color(black, white, 0.25).r, 0.25);
+        assert_eq!(lerp_color(black, white, 2.0), white);
+        assert_eq!(lerp_color(wgpu::Color::TRANSPARENT, black, 0.5).a, 0.5);
+    }
+
+    #[test]
+    fn hsv_round_trip() {
+        assert_close(rgb_to_hsv([1.0, 0.0, 0.0]), [0.0, 1.0, 1.0]);
+        assert_close(rgb_to_hsv([0.0, 0.5, 0.0]), [1.0 / 3.0, 1.0, 0.5]);
+        assert_close(rgb_to_hsv([0.4, 0.4, 0.4]), [0.0, 0.0, 0.4]);
+        assert_close(hsv_to_rgb([2.0 / 3.0, 1.0, 1.0]), [0.0, 0.0, 1.0]);
+        // 色相超出 [0, 1) 时折回
+        assert_close(hsv_to_rgb([1.5, 0.5, 0.8]), hsv_to_rgb([0.5, 0.5, 0.8]));
+
+        let rgb = unpack_u32_to_rgba_f32(0xf2eaddff);
+        let rgb = [rgb[0], rgb[1], rgb[2]];
+        assert_close(hsv_to_rgb(rgb_to_hsv(rgb)), rgb);
+        for r in 0..=8 {
+            for g in 0..=8 {
+                for b in 0..=8 {
+                    let rgb = [r as f32 / 8.0, g as f32 / 8.0, b as f32 / 8.0];
+                    assert_close(hsv_to_rgb(rgb_to_hsv(rgb)), rgb);
+                }
+            }
+        }
+    }
+}
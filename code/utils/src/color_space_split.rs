@@ -0,0 +1,195 @@
+//! 把同一个场景分别以 sRGB 与线性格式输出到 surface 的左右两半，直观对比 gamma 的差异
+//!
+//! 着色器输出的总是线性空间的颜色值：
+//! - 左半边通过 sRGB 格式的 view 渲染，写入时硬件自动做 gamma 编码，显示的亮度是正确的；
+//! - 右半边通过线性（非 sRGB）格式的 view 渲染，线性值被原样写入，显示器再按 sRGB 解码，
+//!   所以中间调明显偏暗（比如线性的 0.5 显示出来约为 0.21 的亮度）。
+//!
+//! # NOTE:
+//! 两个 view 都来自同一个 surface 纹理，需要 surface 配置的 `view_formats` 同时包含两种格式，
+//! `app.ctx.update_config_format` 会把当前格式的 sRGB 与非 sRGB 版本都加入 `view_formats`。
+//! 渲染管线的 `ColorTargetState::format` 必须与 view 格式一致，所以场景的管线要以两种格式各创建一次。
+
+use crate::Viewport;
+use winit::dpi::PhysicalSize;
+
+/// 以 sRGB 与线性两种颜色目标格式各创建一份的场景管线
+///
+/// 用法见 tutorial4-buffer：按 F3 在普通渲染与左右分屏对比之间切换。
+pub struct ColorSpaceSplit<P> {
+    /// 渲染到左半边的管线，颜色目标为 `srgb_format`
+    pub srgb: P,
+    /// 渲染到右半边的管线，颜色目标为 `linear_format`
+    pub linear: P,
+    pub srgb_format: wgpu::TextureFormat,
+    pub linear_format: wgpu::TextureFormat,
+}
+
+impl<P> ColorSpaceSplit<P> {
+    /// `build` 以给定的颜色目标格式创建场景管线（或 `ViewNode`），会被调用两次
+    pub fn new(
+        surface_format: wgpu::TextureFormat,
+        mut build: impl FnMut(wgpu::TextureFormat) -> P,
+    ) -> Self {
+        let srgb_format = surface_format.add_srgb_suffix();
+        let linear_format = surface_format.remove_srgb_suffix();
+        Self {
+            srgb: build(srgb_format),
+            linear: build(linear_format),
+            srgb_format,
+            linear_format,
+        }
+    }
+
+    /// 左右两半的视口：(sRGB, 线性)
+    pub fn viewports(size: PhysicalSize<u32>) -> (Viewport, Viewport) {
+        let (w, h) = (size.width as f32, size.height as f32);
+        let half = (size.width / 2) as f32;
+        (
+            Viewport::new(0.0, 0.0, half, h),
+            Viewport::new(half, 0.0, w - half, h),
+        )
+    }
+
+    /// 分两个渲染通道把场景绘制到 surface 纹理的左右两半
+    ///
+    /// `draw` 在已设置好视口与裁剪矩形的渲染通道中以对应的管线绘制场景；
+    /// `LoadOp::Clear` 不受视口影响，所以只有第一个通道使用 `load_op`，第二个通道保留第一个通道的结果。
+    pub fn render(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        surface_texture: &wgpu::Texture,
+        load_op: wgpu::LoadOp<wgpu::Color>,
+        mut draw: impl FnMut(&mut wgpu::RenderPass<'_>, &P),
+    ) {
+        let size = PhysicalSize::new(surface_texture.width(), surface_texture.height());
+        let (srgb_vp, linear_vp) = Self::viewports(size);
+        let halves = [
+            (self.srgb_format, srgb_vp, &self.srgb, load_op),
+            (
+                self.linear_format,
+                linear_vp,
+                &self.linear,
+                wgpu::LoadOp::Load,
+            ),
+        ];
+        for (format, viewport, pipeline, load) in halves {
+            let view = surface_texture.create_view(&wgpu::TextureViewDescriptor {
+                format: Some(format),
+                ..Default::default()
+            });
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("color space split rpass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                ..Default::default()
+            });
+            if viewport.apply(&mut rpass, size) {
+                draw(&mut rpass, pipeline);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn split_viewports() {
+        let (left, right) = ColorSpaceSplit::<()>::viewports(PhysicalSize::new(801, 600));
+        assert_eq!(left, Viewport::new(0.0, 0.0, 400.0, 600.0));
+        assert_eq!(right, Viewport::new(400.0, 0.0, 401.0, 600.0));
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod gpu_test {
+    use super::*;
+
+    #[test]
+    fn build_both_pipelines() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(
+                "
+                @vertex
+                fn vs_main(@builtin(vertex_index) i: u32) -> @builtin(position) vec4f {
+                    let uv = vec2f(f32((i << 1u) & 2u), f32(i & 2u));
+                    return vec4f(uv * 2.0 - 1.0, 0.0, 1.0);
+                }
+                @fragment
+                fn fs_main() -> @location(0) vec4f {
+                    return vec4f(0.5, 0.5, 0.5, 1.0);
+                }
+                "
+                .into(),
+            ),
+        });
+        let split = ColorSpaceSplit::new(wgpu::TextureFormat::Rgba8Unorm, |format| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: None,
+                layout: None,
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some("vs_main"),
+                    compilation_options: Default::default(),
+                    buffers: &[],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some("fs_main"),
+                    compilation_options: Default::default(),
+                    targets: &[Some(format.into())],
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+                cache: None,
+            })
+        });
+        assert_eq!(split.srgb_format, wgpu::TextureFormat::Rgba8UnormSrgb);
+        assert_eq!(split.linear_format, wgpu::TextureFormat::Rgba8Unorm);
+
+        // 模拟 surface 纹理：同时允许两种 view 格式
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: 8,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[wgpu::TextureFormat::Rgba8UnormSrgb],
+        });
+        let mut encoder = device.create_command_encoder(&Default::default());
+        split.render(
+            &mut encoder,
+            &texture,
+            wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+            |rpass, pipeline| {
+                rpass.set_pipeline(pipeline);
+                rpass.draw(0..3, 0..1);
+            },
+        );
+        queue.submit(Some(encoder.finish()));
+        let error = pollster::block_on(device.pop_error_scope());
+        assert!(error.is_none(), "{error:?}");
+    }
+}
@@ -0,0 +1,264 @@
+use crate::AnyTexture;
+use wgpu::util::DeviceExt;
+
+const WORKGROUP_SIZE: u32 = 8;
+// 水平模糊的结果，保留更高的精度给垂直模糊使用
+const INTERMEDIATE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+/// 可分离的高斯模糊：先水平、再垂直两次计算通道，可作为 bloom 等后处理的基础
+///
+/// # NOTE:
+/// - `input` 只需 `TextureUsages::TEXTURE_BINDING`，通过 `textureLoad` 读取，不使用采样器；
+/// - `output` 作为只写存储纹理绑定，需要 `TextureUsages::STORAGE_BINDING`，
+///   且格式必须支持存储（如 `Rgba8Unorm`、`Rgba16Float`，`*Srgb` 格式不行），并与 `new_with_format` 传入的格式一致；
+/// - 中间结果保存在内部的 `Rgba16Float` 纹理中，输入尺寸变化时重新创建。
+pub struct GaussianBlur {
+    device: wgpu::Device,
+    radius: u32,
+    output_format: wgpu::TextureFormat,
+    weights_buf: wgpu::Buffer,
+    horizontal: (wgpu::BindGroupLayout, wgpu::ComputePipeline),
+    vertical: (wgpu::BindGroupLayout, wgpu::ComputePipeline),
+    intermediate: Option<AnyTexture>,
+}
+
+#[allow(dead_code)]
+impl GaussianBlur {
+    /// 输出格式为 `Rgba8Unorm`
+    pub fn new(device: &wgpu::Device, radius: u32) -> Self {
+        Self::new_with_format(device, radius, wgpu::TextureFormat::Rgba8Unorm)
+    }
+
+    pub fn new_with_format(
+        device: &wgpu::Device,
+        radius: u32,
+        output_format: wgpu::TextureFormat,
+    ) -> Self {
+        let weights = gaussian_weights(radius);
+        let weights_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("gaussian weights"),
+            contents: bytemuck::cast_slice(&weights),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+        Self {
+            device: device.clone(),
+            radius,
+            output_format,
+            weights_buf,
+            horizontal: create_pass(device, [1, 0], INTERMEDIATE_FORMAT),
+            vertical: create_pass(device, [0, 1], output_format),
+            intermediate: None,
+        }
+    }
+
+    pub fn radius(&self) -> u32 {
+        self.radius
+    }
+
+    /// 录制模糊 `input` 并写入 `output` 的两个计算通道，两者尺寸需相同
+    pub fn run(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        input: &AnyTexture,
+        output: &AnyTexture,
+    ) {
+        assert_eq!(input.size, output.size, "输入输出纹理尺寸需相同");
+        assert_eq!(
+            output.format, self.output_format,
+            "输出纹理格式与创建时的不一致"
+        );
+
+        let size = input.size;
+        if self
+            .intermediate
+            .as_ref()
+            .is_none_or(|tex| tex.size != size)
+        {
+            self.intermediate = Some(crate::load_texture::empty(
+                &self.device,
+                INTERMEDIATE_FORMAT,
+                size,
+                None,
+                wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::STORAGE_BINDING,
+                Some("gaussian blur intermediate"),
+            ));
+        }
+        let intermediate = self.intermediate.as_ref().unwrap();
+
+        let workgroup_count = (
+            size.width.div_ceil(WORKGROUP_SIZE),
+            size.height.div_ceil(WORKGROUP_SIZE),
+        );
+        let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("gaussian blur"),
+            timestamp_writes: None,
+        });
+        for ((layout, pipeline), src, dst) in [
+            (&self.horizontal, input, intermediate),
+            (&self.vertical, intermediate, output),
+        ] {
+            let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: None,
+                layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: self.weights_buf.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::TextureView(&src.tex_view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: wgpu::BindingResource::TextureView(&dst.tex_view),
+                    },
+                ],
+            });
+            cpass.set_pipeline(pipeline);
+            cpass.set_bind_group(0, &bind_group, &[]);
+            cpass.dispatch_workgroups(workgroup_count.0, workgroup_count.1, 1);
+        }
+    }
+}
+
+fn create_pass(
+    device: &wgpu::Device,
+    direction: [i32; 2],
+    output_format: wgpu::TextureFormat,
+) -> (wgpu::BindGroupLayout, wgpu::ComputePipeline) {
+    let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("gaussian blur"),
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only: true },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 2,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::StorageTexture {
+                    access: wgpu::StorageTextureAccess::WriteOnly,
+                    format: output_format,
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                },
+                count: None,
+            },
+        ],
+    });
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: None,
+        bind_group_layouts: &[&layout],
+        push_constant_ranges: &[],
+    });
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("gaussian blur"),
+        source: wgpu::ShaderSource::Wgsl(blur_shader_source(direction, output_format).into()),
+    });
+    let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some("gaussian blur"),
+        layout: Some(&pipeline_layout),
+        module: &shader,
+        entry_point: Some("cs_main"),
+        compilation_options: Default::default(),
+        cache: None,
+    });
+    (layout, pipeline)
+}
+
+fn blur_shader_source(direction: [i32; 2], output_format: wgpu::TextureFormat) -> String {
+    let format = match output_format {
+        wgpu::TextureFormat::Rgba8Unorm => "rgba8unorm",
+        wgpu::TextureFormat::Rgba16Float => "rgba16float",
+        wgpu::TextureFormat::Rgba32Float => "rgba32float",
+        _ => panic!("不支持作为模糊输出的存储纹理格式: {output_format:?}"),
+    };
+    format!(
+        "const DIRECTION: vec2i = vec2i({}, {});\n",
+        direction[0], direction[1]
+    ) + &include_str!("gaussian_blur.wgsl").replace("OUTPUT_FORMAT", format)
+}
+
+/// 半径为 `radius` 的归一化高斯权重：`[w0, w1, .., w_radius]`，满足 `w0 + 2 * (w1 + .. + w_radius) = 1`
+pub fn gaussian_weights(radius: u32) -> Vec<f32> {
+    // 半径处约为 2 个标准差
+    let sigma = (radius as f32 / 2.0).max(0.5);
+    let mut weights: Vec<f32> = (0..=radius)
+        .map(|i| (-((i * i) as f32) / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let sum = weights[0] + 2.0 * weights[1..].iter().sum::<f32>();
+    for w in weights.iter_mut() {
+        *w /= sum;
+    }
+    weights
+}
+
+/// 与 `gaussian_blur.wgsl` 相同算法的单通道 CPU 实现，用于验证
+pub fn blur_cpu(data: &[f32], width: usize, height: usize, radius: u32) -> Vec<f32> {
+    let weights = gaussian_weights(radius);
+    let blur_1d = |src: &[f32], (dx, dy): (i64, i64)| -> Vec<f32> {
+        let mut dst = vec![0.0; src.len()];
+        let at = |x: i64, y: i64| {
+            let x = x.clamp(0, width as i64 - 1) as usize;
+            let y = y.clamp(0, height as i64 - 1) as usize;
+            src[y * width + x]
+        };
+        for y in 0..height as i64 {
+            for x in 0..width as i64 {
+                let mut color = at(x, y) * weights[0];
+                for (i, w) in weights.iter().enumerate().skip(1) {
+                    let i = i as i64;
+                    color += (at(x + dx * i, y + dy * i) + at(x - dx * i, y - dy * i)) * w;
+                }
+                dst[y as usize * width + x as usize] = color;
+            }
+        }
+        dst
+    };
+    blur_1d(&blur_1d(data, (1, 0)), (0, 1))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn weights_are_normalized() {
+        assert_eq!(gaussian_weights(0), vec![1.0]);
+        for radius in [1, 4, 9] {
+            let weights = gaussian_weights(radius);
+            let sum = weights[0] + 2.0 * weights[1..].iter().sum::<f32>();
+            assert!((sum - 1.0).abs() < 1e-5);
+            assert!(weights.windows(2).all(|w| w[0] > w[1]));
+        }
+    }
+
+    #[test]
+    fn single_texel_spread() {
+        let (width, height) = (11, 11);
+        let mut data = vec![0.0; width * height];
+        data[5 * width + 5] = 1.0;
+        let blurred = blur_cpu(&data, width, height, 3);
+        let total: f32 = blurred.iter().sum();
+        assert!((total - 1.0).abs() < 1e-5);
+        assert!(blurred[5 * width + 5] < 1.0);
+        assert!(blurred[5 * width + 8] > 0.0);
+        assert_eq!(blurred[5 * width + 9], 0.0);
+    }
+}
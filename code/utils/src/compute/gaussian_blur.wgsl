@@ -0,0 +1,25 @@
+// 可分离高斯模糊的一个方向
+// DIRECTION 由 Rust 端在源码前插入：水平 (1, 0)，垂直 (0, 1)
+
+@group(0) @binding(0) var<storage, read> weights: array<f32>;
+@group(0) @binding(1) var input_tex: texture_2d<f32>;
+@group(0) @binding(2) var output_tex: texture_storage_2d<OUTPUT_FORMAT, write>;
+
+@compute @workgroup_size(8, 8)
+fn cs_main(@builtin(global_invocation_id) gid: vec3u) {
+    let size = vec2i(textureDimensions(input_tex));
+    let coord = vec2i(gid.xy);
+    // ceil-div dispatch 会多出一部分线程
+    if (coord.x >= size.x || coord.y >= size.y) {
+        return;
+    }
+    let radius = i32(arrayLength(&weights)) - 1;
+    var color = textureLoad(input_tex, coord, 0) * weights[0];
+    for (var i = 1; i <= radius; i++) {
+        // 超出边缘时取边缘像素
+        let a = clamp(coord + DIRECTION * i, vec2i(0), size - 1);
+        let b = clamp(coord - DIRECTION * i, vec2i(0), size - 1);
+        color += (textureLoad(input_tex, a, 0) + textureLoad(input_tex, b, 0)) * weights[i];
+    }
+    textureStore(output_tex, coord, color);
+}
@@ -0,0 +1,5 @@
+mod gaussian_blur;
+pub use gaussian_blur::{GaussianBlur, blur_cpu, gaussian_weights};
+
+mod vertex_displacer;
+pub use vertex_displacer::{VertexDisplacer, WaveParams};
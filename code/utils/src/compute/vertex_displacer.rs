@@ -0,0 +1,209 @@
+use crate::BufferObj;
+use bytemuck::{Pod, Zeroable};
+
+const WORKGROUP_SIZE: u32 = 64;
+
+/// 波浪高度函数的参数：`z = amplitude * sin(frequency * x + time * speed) * cos(frequency * y + time * speed)`
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct WaveParams {
+    pub amplitude: f32,
+    pub frequency: f32,
+    pub speed: f32,
+}
+
+impl Default for WaveParams {
+    fn default() -> Self {
+        Self {
+            amplitude: 0.1,
+            frequency: 6.0,
+            speed: 1.0,
+        }
+    }
+}
+
+impl WaveParams {
+    /// 与着色器一致的高度函数，用于 CPU 端的拾取/碰撞等
+    pub fn height(&self, x: f32, y: f32, time: f32) -> f32 {
+        let phase = time * self.speed;
+        self.amplitude * (self.frequency * x + phase).sin() * (self.frequency * y + phase).cos()
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+struct DisplaceParams {
+    time: f32,
+    amplitude: f32,
+    frequency: f32,
+    speed: f32,
+    vertex_count: u32,
+    stride: u32,
+    padding: [u32; 2],
+}
+
+/// 用计算着色器每帧按时间位移细分平面（`Plane`）的顶点，实现水面/波浪效果
+///
+/// 顶点的 z 坐标由其 x、y 坐标直接算出，不依赖上一帧的结果，所以无需保存初始位置。
+///
+/// # NOTE:
+/// 同一个缓冲区既作为存储缓冲区被计算着色器写入，又作为渲染通道的顶点缓冲区，
+/// 创建时需同时带有 `BufferUsages::VERTEX | BufferUsages::STORAGE`（见 [`VertexDisplacer::create_vertex_buffer`]）。
+/// 位置需为每个顶点的前 3 个 f32（如 `PosTex`），步长需为 4 字节的整数倍。
+/// 在同一个 encoder 中先录制 `dispatch` 再录制渲染通道，wgpu 会自动插入所需的同步。
+pub struct VertexDisplacer {
+    params: DisplaceParams,
+    params_buf: BufferObj,
+    bind_group: wgpu::BindGroup,
+    pipeline: wgpu::ComputePipeline,
+}
+
+#[allow(dead_code)]
+impl VertexDisplacer {
+    /// 创建可同时用作顶点缓冲区与存储缓冲区的顶点缓冲区
+    ///
+    /// 渲染时可把它赋给 `ViewNode::vertex_buf`，替换节点自己创建的顶点缓冲区。
+    pub fn create_vertex_buffer<T: 'static + Pod + Copy>(
+        device: &wgpu::Device,
+        vertices: &[T],
+    ) -> BufferObj {
+        BufferObj::create_buffer(
+            device,
+            Some(vertices),
+            None,
+            wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::STORAGE,
+            Some("displaced vertices"),
+        )
+    }
+
+    /// `vertex_stride` 为顶点的字节步长，如 `size_of::<PosTex>()`
+    pub fn new(
+        device: &wgpu::Device,
+        vertex_buf: &BufferObj,
+        vertex_stride: u32,
+        wave: WaveParams,
+    ) -> Self {
+        assert!(
+            vertex_stride >= 12 && vertex_stride % 4 == 0,
+            "顶点步长需至少包含 3 个 f32 且为 4 字节的整数倍"
+        );
+        let params = DisplaceParams {
+            time: 0.0,
+            amplitude: wave.amplitude,
+            frequency: wave.frequency,
+            speed: wave.speed,
+            vertex_count: (vertex_buf.size / vertex_stride as u64) as u32,
+            stride: vertex_stride / 4,
+            padding: [0; 2],
+        };
+        let params_buf =
+            BufferObj::create_uniform_buffer(device, &params, Some("vertex displacer params"));
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("vertex displacer"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("vertex_displacer.wgsl").into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("vertex displacer"),
+            layout: None,
+            module: &shader,
+            entry_point: Some("cs_main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("vertex displacer"),
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: params_buf.buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: vertex_buf.buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        Self {
+            params,
+            params_buf,
+            bind_group,
+            pipeline,
+        }
+    }
+
+    pub fn set_wave(&mut self, wave: WaveParams) {
+        self.params.amplitude = wave.amplitude;
+        self.params.frequency = wave.frequency;
+        self.params.speed = wave.speed;
+    }
+
+    /// 录制以 `time`（秒）位移所有顶点的计算通道
+    pub fn dispatch(&mut self, queue: &wgpu::Queue, encoder: &mut wgpu::CommandEncoder, time: f32) {
+        self.params.time = time;
+        queue.write_buffer(&self.params_buf.buffer, 0, bytemuck::bytes_of(&self.params));
+
+        let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("vertex displacer"),
+            timestamp_writes: None,
+        });
+        cpass.set_pipeline(&self.pipeline);
+        cpass.set_bind_group(0, &self.bind_group, &[]);
+        cpass.dispatch_workgroups(self.params.vertex_count.div_ceil(WORKGROUP_SIZE), 1, 1);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+    use crate::vertex::PosTex;
+
+    #[test]
+    fn displace_plane_vertex() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        let (vertices, _) = crate::Plane::new(4, 4).generate_vertices();
+        // 测试需读回结果，额外加上 COPY_SRC
+        let vertex_buf = BufferObj::create_buffer(
+            &device,
+            Some(&vertices),
+            None,
+            wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            None,
+        );
+        let wave = WaveParams {
+            amplitude: 0.5,
+            frequency: 2.0,
+            speed: 1.5,
+        };
+        let stride = std::mem::size_of::<PosTex>() as u32;
+        let mut displacer = VertexDisplacer::new(&device, &vertex_buf, stride, wave);
+
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: vertex_buf.size,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let time = 0.75;
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        displacer.dispatch(&queue, &mut encoder, time);
+        encoder.copy_buffer_to_buffer(&vertex_buf.buffer, 0, &readback, 0, vertex_buf.size);
+        queue.submit(Some(encoder.finish()));
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        crate::poll::poll_wait(&device);
+        let displaced: Vec<PosTex> = bytemuck::cast_slice(&slice.get_mapped_range()).to_vec();
+
+        let index = 7;
+        let [x, y, z] = displaced[index].pos;
+        assert_eq!([x, y], [vertices[index].pos[0], vertices[index].pos[1]]);
+        assert!((z - wave.height(x, y, time)).abs() < 1e-4);
+        assert_eq!(displaced[index].tex_coord, vertices[index].tex_coord);
+    }
+}
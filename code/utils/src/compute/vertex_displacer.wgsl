@@ -0,0 +1,28 @@
+struct DisplaceParams {
+    time: f32,
+    amplitude: f32,
+    frequency: f32,
+    speed: f32,
+    vertex_count: u32,
+    // 顶点步长（f32 个数）
+    stride: u32,
+};
+
+@group(0) @binding(0) var<uniform> params: DisplaceParams;
+// 以 f32 数组访问顶点缓冲区：顶点结构体中的 vec3f 在存储缓冲区中按 16 字节对齐，与紧凑排列的顶点布局不一致
+@group(0) @binding(1) var<storage, read_write> vertices: array<f32>;
+
+fn height(x: f32, y: f32) -> f32 {
+    let phase = params.time * params.speed;
+    return params.amplitude * sin(params.frequency * x + phase) * cos(params.frequency * y + phase);
+}
+
+@compute @workgroup_size(64)
+fn cs_main(@builtin(global_invocation_id) gid: vec3u) {
+    if gid.x >= params.vertex_count {
+        return;
+    }
+    let base = gid.x * params.stride;
+    // 平面位于 xy 平面，沿 z 方向位移
+    vertices[base + 2u] = height(vertices[base], vertices[base + 1u]);
+}
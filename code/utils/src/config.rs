@@ -0,0 +1,162 @@
+//! 示例程序的可调参数
+//!
+//! 原生平台从资源目录（`application_root_dir()`）下的 `config.toml` 加载，并可在运行时检测文件变化热重载；
+//! Web 平台没有文件系统，使用默认值并允许通过 URL 查询字符串覆盖单个参数，如 `?particle_count=4096`。
+//!
+//! 示例程序按需在 `new` 中调用 [`DemoConfig::load`] 读取配置，不读取则不受影响；需启用 utils 的 `serde` 特性。
+//! tutorial3-pipeline 从中读取清屏颜色，并在 `update` 中通过 [`ConfigWatcher`] 热重载。
+
+use serde::{Deserialize, Serialize};
+
+/// 配置文件名
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// 示例程序的通用参数
+///
+/// 文件中缺失的字段使用默认值，所以 `config.toml` 只需写出要修改的参数。
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DemoConfig {
+    /// 相机移动速度
+    pub camera_speed: f32,
+    /// 粒子数量
+    pub particle_count: u32,
+    /// 动画帧数
+    pub frame_count: u32,
+    /// 清屏颜色
+    pub clear_color: [f32; 4],
+}
+
+impl Default for DemoConfig {
+    fn default() -> Self {
+        Self {
+            camera_speed: 1.0,
+            particle_count: 1024,
+            frame_count: 60,
+            clear_color: [0.1, 0.2, 0.3, 1.0],
+        }
+    }
+}
+
+impl DemoConfig {
+    /// 解析 TOML 文本，解析失败时打印警告并返回默认值
+    pub fn from_toml_str(source: &str) -> Self {
+        toml::from_str(source).unwrap_or_else(|e| {
+            log::warn!("invalid {CONFIG_FILE_NAME}, fall back to defaults: {e}");
+            Self::default()
+        })
+    }
+
+    pub fn to_toml_string(&self) -> String {
+        toml::to_string(self).expect("DemoConfig serialize failed")
+    }
+
+    /// 加载配置
+    ///
+    /// 文件不存在或内容无效时打印警告并返回默认值，不会让示例程序启动失败。
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load() -> Self {
+        let path = config_path();
+        match std::fs::read_to_string(&path) {
+            Ok(source) => Self::from_toml_str(&source),
+            Err(e) => {
+                log::warn!("cannot read {}, use defaults: {e}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    /// 加载配置
+    ///
+    /// Web 平台以默认值为基础，用 URL 查询字符串中同名的参数覆盖。
+    #[cfg(target_arch = "wasm32")]
+    pub fn load() -> Self {
+        let query = web_sys::window().unwrap().location().search().unwrap();
+        let mut config = Self::default();
+        let param = |key| crate::parse_url_query_string(&query, key).and_then(|v| v.parse().ok());
+        if let Some(v) = param("camera_speed") {
+            config.camera_speed = v;
+        }
+        if let Some(v) = param("particle_count") {
+            config.particle_count = v as u32;
+        }
+        if let Some(v) = param("frame_count") {
+            config.frame_count = v as u32;
+        }
+        config
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn config_path() -> std::path::PathBuf {
+    crate::get_texture_file_path(CONFIG_FILE_NAME)
+}
+
+/// 检测 `config.toml` 的修改时间，实现热重载
+///
+/// 在 `update` 中每帧调用 [`ConfigWatcher::check`] 即可，开销只有一次文件元数据查询。
+#[cfg(not(target_arch = "wasm32"))]
+pub struct ConfigWatcher {
+    path: std::path::PathBuf,
+    modified: Option<std::time::SystemTime>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ConfigWatcher {
+    pub fn new() -> Self {
+        let path = config_path();
+        let modified = Self::modified_time(&path);
+        Self { path, modified }
+    }
+
+    /// 文件自上次检查后被修改过时，返回重新加载的配置
+    pub fn check(&mut self) -> Option<DemoConfig> {
+        let modified = Self::modified_time(&self.path);
+        if modified.is_none() || modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        log::info!("{} changed, reloading", self.path.display());
+        Some(DemoConfig::load())
+    }
+
+    fn modified_time(path: &std::path::Path) -> Option<std::time::SystemTime> {
+        std::fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for ConfigWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn toml_round_trip() {
+        let config = DemoConfig {
+            camera_speed: 2.5,
+            particle_count: 4096,
+            frame_count: 120,
+            clear_color: [0.0, 0.5, 1.0, 1.0],
+        };
+        let source = config.to_toml_string();
+        assert_eq!(DemoConfig::from_toml_str(&source), config);
+    }
+
+    #[test]
+    fn missing_fields_and_invalid_source() {
+        let config = DemoConfig::from_toml_str("particle_count = 8");
+        assert_eq!(config.particle_count, 8);
+        assert_eq!(config.camera_speed, DemoConfig::default().camera_speed);
+
+        assert_eq!(
+            DemoConfig::from_toml_str("particle_count = \"many\""),
+            DemoConfig::default()
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use glam::{Mat4, Vec3, Vec4};
+use winit::{
+    event::{ElementState, KeyEvent},
+    keyboard::{KeyCode, PhysicalKey},
+};
+
+/// 由视图投影矩阵提取的视锥体，用于 CPU 端的视锥体剔除
+///
+/// 6 个平面的法线都指向视锥体内部，平面方程为 `dot(normal, p) + d = 0`（存于 `Vec4(normal, d)`）。
+///
+/// # NOTE:
+/// 按 wgpu 的 NDC 约定提取：深度范围为 [0, 1]，所以近平面即矩阵的第 3 行，而不是 OpenGL 中的第 4 行 + 第 3 行。
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Frustum {
+    pub planes: [Vec4; 6],
+}
+
+impl Frustum {
+    pub fn from_view_proj(view_proj: Mat4) -> Self {
+        let (r0, r1, r2, r3) = (
+            view_proj.row(0),
+            view_proj.row(1),
+            view_proj.row(2),
+            view_proj.row(3),
+        );
+        let planes = [r3 + r0, r3 - r0, r3 + r1, r3 - r1, r2, r3 - r2]
+            .map(|plane| plane / plane.truncate().length());
+        Self { planes }
+    }
+
+    /// 球体是否与视锥体相交（或在其内部）
+    pub fn intersects_sphere(&self, center: Vec3, radius: f32) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.truncate().dot(center) + plane.w >= -radius)
+    }
+}
+
+/// 分别保存渲染用与剔除用的视图投影矩阵，支持冻结剔除矩阵以调试视锥体剔除
+///
+/// 冻结后剔除测试继续使用按键那一刻的矩阵，而渲染矩阵仍随相机实时更新：
+/// 把相机移开后，就能从“外部”看到原视锥体之外的物体已被剔除，以此检验剔除是否正确。
+///
+/// 每帧以最新的视图投影矩阵调用 [`CullingCamera::update`]，
+/// 渲染使用 [`CullingCamera::render_matrix`]，剔除使用 [`CullingCamera::frustum`]。
+#[derive(Clone, Copy, Debug)]
+pub struct CullingCamera {
+    render: Mat4,
+    frozen: Option<Mat4>,
+}
+
+#[allow(dead_code)]
+impl CullingCamera {
+    /// 切换冻结状态的按键
+    pub const FREEZE_KEY: KeyCode = KeyCode::KeyF;
+
+    pub fn new(view_proj: Mat4) -> Self {
+        Self {
+            render: view_proj,
+            frozen: None,
+        }
+    }
+
+    pub fn update(&mut self, view_proj: Mat4) {
+        self.render = view_proj;
+    }
+
+    pub fn render_matrix(&self) -> Mat4 {
+        self.render
+    }
+
+    /// 剔除用的矩阵：冻结时为冻结那一刻的渲染矩阵，否则与渲染矩阵相同
+    pub fn cull_matrix(&self) -> Mat4 {
+        self.frozen.unwrap_or(self.render)
+    }
+
+    pub fn frustum(&self) -> Frustum {
+        Frustum::from_view_proj(self.cull_matrix())
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen.is_some()
+    }
+
+    /// 冻结（以当前的渲染矩阵作为剔除矩阵）或解冻
+    pub fn toggle_freeze(&mut self) {
+        self.frozen = match self.frozen {
+            Some(_) => None,
+            None => Some(self.render),
+        };
+        log::info!(
+            "Culling matrix {}",
+            if self.is_frozen() { "frozen" } else { "live" }
+        );
+    }
+
+    /// 处理键盘事件，按下 `FREEZE_KEY` 时切换冻结状态并返回 true
+    pub fn keyboard_input(&mut self, event: &KeyEvent) -> bool {
+        if event.state == ElementState::Pressed
+            && !event.repeat
+            && event.physical_key == PhysicalKey::Code(Self::FREEZE_KEY)
+        {
+            self.toggle_freeze();
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn view_proj(eye: Vec3) -> Mat4 {
+        Mat4::perspective_rh(45.0_f32.to_radians(), 1.0, 0.1, 100.0)
+            * Mat4::look_at_rh(eye, eye + Vec3::NEG_Z, Vec3::Y)
+    }
+
+    #[test]
+    fn frozen_cull_matrix_stays_constant() {
+        let start = view_proj(Vec3::ZERO);
+        let mut camera = CullingCamera::new(start);
+        camera.toggle_freeze();
+
+        let moved = view_proj(Vec3::new(5.0, 0.0, 0.0));
+        camera.update(moved);
+        assert_eq!(camera.cull_matrix(), start);
+        assert_eq!(camera.render_matrix(), moved);
+
+        camera.toggle_freeze();
+        assert_eq!(camera.cull_matrix(), moved);
+    }
+
+    #[test]
+    fn frustum_sphere_test() {
+        let frustum = Frustum::from_view_proj(view_proj(Vec3::ZERO));
+        assert!(frustum.intersects_sphere(Vec3::new(0.0, 0.0, -10.0), 1.0));
+        // 相机背后、远平面之外、视野侧面之外
+        assert!(!frustum.intersects_sphere(Vec3::new(0.0, 0.0, 10.0), 1.0));
+        assert!(!frustum.intersects_sphere(Vec3::new(0.0, 0.0, -200.0), 1.0));
+        assert!(!frustum.intersects_sphere(Vec3::new(50.0, 0.0, -10.0), 1.0));
+    }
+}
@@ -0,0 +1,226 @@
+//! 显示每个渲染通道 GPU 耗时的调试叠加层
+//!
+//! # NOTE:
+//! 耗时来自时间戳查询，需要设备开启 `wgpu::Features::TIMESTAMP_QUERY`。
+//! 适配器不支持或没有开启该特性时，app 传入 `None`，叠加层只显示一行提示，不会报错。
+
+use crate::text::SdfText;
+use winit::event::{ElementState, KeyEvent};
+use winit::keyboard::{KeyCode, PhysicalKey};
+
+/// 一个渲染通道的名称与 GPU 耗时
+#[derive(Clone, Debug, PartialEq)]
+pub struct PassTiming {
+    pub name: String,
+    pub milliseconds: f64,
+}
+
+/// 把各通道的耗时格式化为叠加层显示的文字，每个通道一行，最后一行为总计
+///
+/// `timings` 为 `None` 表示没有时间戳查询可用。
+pub fn format_pass_timings(timings: Option<&[PassTiming]>) -> String {
+    let Some(timings) = timings else {
+        return "GPU timings unavailable (no TIMESTAMP_QUERY)".to_string();
+    };
+    // 总计行也参与对齐
+    let name_width = timings
+        .iter()
+        .map(|t| t.name.chars().count())
+        .chain(Some("total".len()))
+        .max()
+        .unwrap_or(0);
+    let mut text = String::new();
+    for timing in timings {
+        text += &format!(
+            "{:<name_width$} {:>7.3} ms\n",
+            timing.name, timing.milliseconds
+        );
+    }
+    let total: f64 = timings.iter().map(|t| t.milliseconds).sum();
+    text + &format!("{:<name_width$} {total:>7.3} ms", "total")
+}
+
+/// 在窗口左上角以文字列出各渲染通道及其 GPU 耗时，按 `toggle_key`（默认 F4）显示或隐藏
+///
+/// 用法：在 `keyboard_input` 中调用 `keyboard_input`，每帧用解析后的耗时调用 `draw`，
+/// 再在最后一个渲染通道中调用 `flush`。
+pub struct FrameGraphOverlay {
+    text: SdfText,
+    visible: bool,
+    pub toggle_key: KeyCode,
+    /// 文字块左上角的像素坐标
+    pub position: [f32; 2],
+    /// 字号（像素）
+    pub size: f32,
+    pub color: [f32; 4],
+}
+
+impl FrameGraphOverlay {
+    pub fn new(text: SdfText) -> Self {
+        Self {
+            text,
+            visible: false,
+            toggle_key: KeyCode::F4,
+            position: [8.0, 8.0],
+            size: 16.0,
+            color: [1.0, 1.0, 0.0, 1.0],
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    /// 窗口大小变化时调用
+    pub fn resize(&self, viewport_size: (u32, u32)) {
+        self.text.resize(viewport_size);
+    }
+
+    /// 按下 `toggle_key` 时切换显示，返回事件是否已被处理
+    pub fn keyboard_input(&mut self, event: &KeyEvent) -> bool {
+        if event.state == ElementState::Pressed
+            && !event.repeat
+            && event.physical_key == PhysicalKey::Code(self.toggle_key)
+        {
+            self.visible = !self.visible;
+            return true;
+        }
+        false
+    }
+
+    /// 添加本帧要显示的耗时，隐藏时什么也不做
+    pub fn draw(&mut self, timings: Option<&[PassTiming]>) {
+        if !self.visible {
+            return;
+        }
+        let text = format_pass_timings(timings);
+        self.text
+            .draw_text(&text, self.position, self.size, self.color);
+    }
+
+    pub fn flush(&mut self, rpass: &mut wgpu::RenderPass<'_>) {
+        self.text.flush(rpass);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::text::{SdfFont, layout_text};
+
+    #[test]
+    fn text_for_synthetic_timings() {
+        let timings = [
+            PassTiming {
+                name: "shadow".to_string(),
+                milliseconds: 0.25,
+            },
+            PassTiming {
+                name: "main".to_string(),
+                milliseconds: 1.5,
+            },
+        ];
+        let text = format_pass_timings(Some(&timings));
+        assert_eq!(
+            text,
+            "shadow   0.250 ms\nmain     1.500 ms\ntotal    1.750 ms"
+        );
+        assert!(format_pass_timings(None).contains("TIMESTAMP_QUERY"));
+        // 通道名都比 "total" 短时按 "total" 对齐
+        let ui = [PassTiming {
+            name: "ui".to_string(),
+            milliseconds: 0.5,
+        }];
+        assert_eq!(
+            format_pass_timings(Some(&ui)),
+            "ui      0.500 ms\ntotal   0.500 ms"
+        );
+
+        let chars = "abcdefghijklmnopqrstuvwxyz0123456789.";
+        let font = SdfFont::monospace_grid((16 * 10, 16 * 4), (16, 16), 10, chars);
+        let quads = layout_text(&font, &text, [8.0, 8.0], 16.0, [1.0; 4]);
+        let visible_chars = text.chars().filter(|c| chars.contains(*c)).count();
+        assert_eq!(quads.len(), visible_chars);
+        // 三行文字
+        let rows: std::collections::BTreeSet<u32> =
+            quads.iter().map(|q| q.rect[1] as u32).collect();
+        assert_eq!(rows.into_iter().collect::<Vec<_>>(), vec![8, 24, 40]);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod gpu_test {
+    use super::*;
+    use crate::text::SdfFont;
+
+    #[test]
+    fn draw_overlay() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        let chars = "abcdefghijklmnopqrstuvwxyz0123456789.";
+        let font = SdfFont::monospace_grid((16 * 10, 16 * 4), (16, 16), 10, chars);
+        let atlas = crate::load_texture::empty(
+            &device,
+            wgpu::TextureFormat::R8Unorm,
+            wgpu::Extent3d {
+                width: 16 * 10,
+                height: 16 * 4,
+                depth_or_array_layers: 1,
+            },
+            None,
+            wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            None,
+        );
+        let format = wgpu::TextureFormat::Rgba8Unorm;
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let text = SdfText::new(&device, &queue, format, &atlas, font, (64, 64));
+        let mut overlay = FrameGraphOverlay::new(text);
+        overlay.set_visible(true);
+        overlay.resize((64, 64));
+        overlay.draw(Some(&[PassTiming {
+            name: "main".to_string(),
+            milliseconds: 1.5,
+        }]));
+
+        let target = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: 64,
+                height: 64,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = target.create_view(&Default::default());
+        let mut encoder = device.create_command_encoder(&Default::default());
+        {
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                ..Default::default()
+            });
+            overlay.flush(&mut rpass);
+        }
+        queue.submit(Some(encoder.finish()));
+        let error = pollster::block_on(device.pop_error_scope());
+        assert!(error.is_none(), "{error:?}");
+    }
+}
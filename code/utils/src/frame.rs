@@ -0,0 +1,186 @@
+//! 一帧内多个渲染/计算通道的依赖排序
+//!
+//! 场景中的离屏目标（HDR、阴影、泛光等）多了之后，很容易把通道的录制顺序写错，读到还没写入的纹理。
+//! `RenderGraph` 让每个通道声明自己读写了哪些纹理，再按依赖关系拓扑排序后录制，
+//! 它只是现有节点（`ViewNode`、`ComputeNode` 等）之上的一层顺序检查，不创建也不管理任何 GPU 资源。
+//!
+//! # 句柄模型
+//! - [`TextureHandle`] 只是图内的一个编号，用来表达依赖关系，并不持有纹理；通道的录制闭包自己捕获真正的纹理视图；
+//! - `create_texture` 声明本帧内由某个通道生成的临时纹理，必须先有通道写入，才能被读取；
+//! - `import_texture` 声明图外已有内容的纹理（加载的贴图、上一帧的结果等），可以直接读取；
+//! - 读取某个纹理的通道排在所有写入它的通道之后；同一个通道可以读写同一个纹理（读-改-写）。
+//!
+//! 依赖成环，或读取了没有任何通道写入的临时纹理时，排序会 panic，在开发阶段就暴露顺序错误。
+
+/// 图内的纹理句柄
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TextureHandle(usize);
+
+struct GraphTexture {
+    name: &'static str,
+    // 图外已有内容，不需要通道写入即可读取
+    imported: bool,
+}
+
+type RecordFn<'a> = Box<dyn FnOnce(&mut wgpu::CommandEncoder) + 'a>;
+
+struct GraphPass<'a> {
+    name: &'static str,
+    reads: Vec<TextureHandle>,
+    writes: Vec<TextureHandle>,
+    record: RecordFn<'a>,
+}
+
+/// 按读写依赖排序并录制通道的渲染图
+#[derive(Default)]
+pub struct RenderGraph<'a> {
+    textures: Vec<GraphTexture>,
+    passes: Vec<GraphPass<'a>>,
+}
+
+#[allow(dead_code)]
+impl<'a> RenderGraph<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 声明本帧内由通道生成的临时纹理
+    pub fn create_texture(&mut self, name: &'static str) -> TextureHandle {
+        self.add_texture(name, false)
+    }
+
+    /// 声明图外已有内容的纹理
+    pub fn import_texture(&mut self, name: &'static str) -> TextureHandle {
+        self.add_texture(name, true)
+    }
+
+    fn add_texture(&mut self, name: &'static str, imported: bool) -> TextureHandle {
+        self.textures.push(GraphTexture { name, imported });
+        TextureHandle(self.textures.len() - 1)
+    }
+
+    /// 添加一个通道，`record` 在排序后按顺序被调用，在其中开启渲染/计算通道
+    pub fn add_pass(
+        &mut self,
+        name: &'static str,
+        reads: &[TextureHandle],
+        writes: &[TextureHandle],
+        record: impl FnOnce(&mut wgpu::CommandEncoder) + 'a,
+    ) {
+        self.passes.push(GraphPass {
+            name,
+            reads: reads.to_vec(),
+            writes: writes.to_vec(),
+            record: Box::new(record),
+        });
+    }
+
+    /// 拓扑排序后的通道下标
+    ///
+    /// 没有依赖关系的通道保持添加的顺序。依赖成环或读取了未被写入的临时纹理时 panic。
+    pub fn sorted_order(&self) -> Vec<usize> {
+        let count = self.passes.len();
+        // 依赖边：写入者 -> 读取者
+        let mut dependents: Vec<Vec<usize>> = vec![vec![]; count];
+        let mut in_degree = vec![0_usize; count];
+        for (reader, pass) in self.passes.iter().enumerate() {
+            for handle in pass.reads.iter() {
+                let texture = &self.textures[handle.0];
+                let writers: Vec<usize> = (0..count)
+                    .filter(|w| *w != reader && self.passes[*w].writes.contains(handle))
+                    .collect();
+                if writers.is_empty() && !texture.imported && !pass.writes.contains(handle) {
+                    panic!(
+                        "render graph: pass `{}` reads `{}` before any pass writes it",
+                        pass.name, texture.name
+                    );
+                }
+                for writer in writers {
+                    dependents[writer].push(reader);
+                    in_degree[reader] += 1;
+                }
+            }
+        }
+
+        let mut order = Vec::with_capacity(count);
+        let mut done = vec![false; count];
+        while order.len() < count {
+            // 每次取下标最小的就绪通道，使结果稳定
+            let Some(next) = (0..count).find(|i| !done[*i] && in_degree[*i] == 0) else {
+                let names: Vec<&str> = (0..count)
+                    .filter(|i| !done[*i])
+                    .map(|i| self.passes[i].name)
+                    .collect();
+                panic!("render graph: dependency cycle among passes {names:?}");
+            };
+            done[next] = true;
+            order.push(next);
+            for dependent in dependents[next].iter() {
+                in_degree[*dependent] -= 1;
+            }
+        }
+        order
+    }
+
+    /// 按依赖顺序把所有通道录制到 `encoder`
+    pub fn record(self, encoder: &mut wgpu::CommandEncoder) {
+        let order = self.sorted_order();
+        let mut passes: Vec<Option<GraphPass<'a>>> = self.passes.into_iter().map(Some).collect();
+        for index in order {
+            if let Some(pass) = passes[index].take() {
+                (pass.record)(encoder);
+            }
+        }
+    }
+
+    /// 按依赖顺序录制所有通道并提交
+    pub fn submit(self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("render graph encoder"),
+        });
+        self.record(&mut encoder);
+        queue.submit(Some(encoder.finish()));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn linear_chain_sorted() {
+        let mut graph = RenderGraph::new();
+        let albedo = graph.import_texture("albedo");
+        let shadow = graph.create_texture("shadow map");
+        let hdr = graph.create_texture("hdr");
+        let bloom = graph.create_texture("bloom");
+        let surface = graph.create_texture("surface");
+        // 故意打乱添加顺序
+        graph.add_pass("composite", &[hdr, bloom], &[surface], |_| {});
+        graph.add_pass("bloom", &[hdr], &[bloom], |_| {});
+        graph.add_pass("scene", &[albedo, shadow], &[hdr], |_| {});
+        graph.add_pass("shadow", &[], &[shadow], |_| {});
+        // shadow -> scene -> bloom -> composite
+        assert_eq!(graph.sorted_order(), vec![3, 2, 1, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "dependency cycle")]
+    fn cycle_detected() {
+        let mut graph = RenderGraph::new();
+        let a = graph.create_texture("a");
+        let b = graph.create_texture("b");
+        graph.add_pass("ping", &[b], &[a], |_| {});
+        graph.add_pass("pong", &[a], &[b], |_| {});
+        graph.sorted_order();
+    }
+
+    #[test]
+    #[should_panic(expected = "before any pass writes it")]
+    fn read_before_write_detected() {
+        let mut graph = RenderGraph::new();
+        let hdr = graph.create_texture("hdr");
+        graph.add_pass("tonemap", &[hdr], &[], |_| {});
+        graph.sorted_order();
+    }
+}
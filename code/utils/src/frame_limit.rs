@@ -0,0 +1,110 @@
+use instant::Duration;
+
+/// 命令行参数名，如 `cargo run --bin xxx -- --frames 300`
+pub const FRAMES_ARG: &str = "--frames";
+/// Web 端的 URL 查询参数名，如 `?frames=300`
+pub const FRAMES_QUERY_KEY: &str = "frames";
+
+/// 渲染指定帧数后退出，用于性能分析与 CI 中可重复的运行
+///
+/// 统计每帧的间隔，退出时打印平均帧时间。
+///
+/// # NOTE:
+/// 只作用于 `framework::run` 的窗口事件循环：帧数按 `RedrawRequested` 中实际执行的渲染计数，
+/// 窗口最小化期间跳过的帧不计入。离屏渲染（如 `capture::capture_frame`）不经过事件循环，不受其影响。
+#[derive(Clone, Copy, Debug)]
+pub struct FrameLimit {
+    max_frames: u32,
+    rendered: u32,
+    total_time: Duration,
+}
+
+#[allow(dead_code)]
+impl FrameLimit {
+    pub fn new(max_frames: u32) -> Self {
+        Self {
+            max_frames: max_frames.max(1),
+            rendered: 0,
+            total_time: Duration::ZERO,
+        }
+    }
+
+    /// 从命令行参数（原生）或 URL 查询字符串（web）读取帧数，未设置或无效时返回 `None`
+    pub fn from_env() -> Option<Self> {
+        cfg_if::cfg_if! {
+            if #[cfg(target_arch = "wasm32")] {
+                let query = web_sys::window()?.location().search().ok()?;
+                let frames = crate::parse_url_query_string(&query, FRAMES_QUERY_KEY)?.parse().ok()?;
+                Some(Self::new(frames))
+            } else {
+                frames_from_args(std::env::args()).map(Self::new)
+            }
+        }
+    }
+
+    /// 记录渲染完成的一帧及其帧间隔，达到指定帧数时返回 true
+    pub fn on_frame(&mut self, dt: Duration) -> bool {
+        self.rendered += 1;
+        self.total_time += dt;
+        self.rendered >= self.max_frames
+    }
+
+    pub fn rendered_frames(&self) -> u32 {
+        self.rendered
+    }
+
+    /// 平均帧时间
+    pub fn average_frame_time(&self) -> Duration {
+        if self.rendered == 0 {
+            Duration::ZERO
+        } else {
+            self.total_time / self.rendered
+        }
+    }
+}
+
+/// 解析 `--frames N` 或 `--frames=N`
+#[allow(dead_code)]
+fn frames_from_args(args: impl IntoIterator<Item = String>) -> Option<u32> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == FRAMES_ARG {
+            return args.next()?.parse().ok();
+        }
+        if let Some(value) = arg
+            .strip_prefix(FRAMES_ARG)
+            .and_then(|v| v.strip_prefix('='))
+        {
+            return value.parse().ok();
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_frames_arg() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            frames_from_args(args(&["app", "--frames", "300"])),
+            Some(300)
+        );
+        assert_eq!(frames_from_args(args(&["app", "--frames=60"])), Some(60));
+        assert_eq!(frames_from_args(args(&["app", "--frames"])), None);
+        assert_eq!(frames_from_args(args(&["app"])), None);
+    }
+
+    #[test]
+    fn exit_after_configured_count() {
+        let mut limit = FrameLimit::new(3);
+        let dt = Duration::from_millis(16);
+        assert!(!limit.on_frame(dt));
+        assert!(!limit.on_frame(dt));
+        assert!(limit.on_frame(dt));
+        assert_eq!(limit.rendered_frames(), 3);
+        assert_eq!(limit.average_frame_time(), dt);
+    }
+}
@@ -0,0 +1,68 @@
+use instant::{Duration, Instant};
+
+/// 把重绘限制在目标帧率以内，避免 `Immediate` / `Mailbox` 呈现模式下 GPU 空转
+///
+/// 每帧开始时调用 `schedule` 得到下一帧的最早开始时间：
+/// - 原生平台上事件循环以 `ControlFlow::WaitUntil` 休眠到该时间再请求重绘；
+/// - Web 平台上重绘由 `requestAnimationFrame` 驱动，`is_due` 为 false 的帧直接跳过，
+///   所以实际帧率是显示器刷新率的整数分之一（如 60Hz 下目标 40 帧时约为 30 帧）。
+///
+/// # NOTE:
+/// 下一帧的时间从上一帧的预定时间累加，不会因为每帧的调度误差而越跑越慢；
+/// 落后超过一帧时从当前时间重新开始计时，不会为了追赶而连续渲染。
+#[derive(Clone, Copy, Debug)]
+pub struct FramePacer {
+    interval: Duration,
+    deadline: Option<Instant>,
+}
+
+#[allow(dead_code)]
+impl FramePacer {
+    pub fn new(target_fps: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / target_fps.max(1),
+            deadline: None,
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// 是否已到下一帧的开始时间
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.deadline.is_none_or(|deadline| now >= deadline)
+    }
+
+    /// 记录一帧在 `now` 开始，返回下一帧的最早开始时间
+    pub fn schedule(&mut self, now: Instant) -> Instant {
+        let next = match self.deadline {
+            Some(deadline) if deadline + self.interval > now => deadline + self.interval,
+            _ => now + self.interval,
+        };
+        self.deadline = Some(next);
+        next
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn paces_to_target_fps() {
+        let mut pacer = FramePacer::new(50);
+        assert_eq!(pacer.interval(), Duration::from_millis(20));
+
+        let start = Instant::now();
+        let ms = |n| start + Duration::from_millis(n);
+        assert!(pacer.is_due(start));
+        assert_eq!(pacer.schedule(start), ms(20));
+        assert!(!pacer.is_due(ms(16)));
+        assert!(pacer.is_due(ms(21)));
+        // 晚了 1ms 开始的帧不会推迟之后的帧
+        assert_eq!(pacer.schedule(ms(21)), ms(40));
+        // 落后超过一帧时从当前时间重新计时
+        assert_eq!(pacer.schedule(ms(100)), ms(120));
+    }
+}
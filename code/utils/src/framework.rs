@@ -1,3 +1,4 @@
+use crate::touch::MouseEmulation;
 use parking_lot::Mutex;
 use std::sync::Arc;
 use wgpu::WasmNotSend;
@@ -5,13 +6,16 @@ use winit::{
     application::ApplicationHandler,
     dpi::{PhysicalPosition, PhysicalSize},
     event::{
-        DeviceEvent, DeviceId, ElementState, KeyEvent, MouseButton, MouseScrollDelta, TouchPhase,
-        WindowEvent,
+        DeviceEvent, DeviceId, ElementState, KeyEvent, MouseButton, MouseScrollDelta, StartCause,
+        TouchPhase, WindowEvent,
     },
-    event_loop::{ActiveEventLoop, EventLoop},
+    event_loop::{ActiveEventLoop, ControlFlow, EventLoop},
+    keyboard::{KeyCode, ModifiersState, PhysicalKey},
     window::{Window, WindowId},
 };
 
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::{JsCast, closure::Closure};
 #[cfg(target_arch = "wasm32")]
 use winit::platform::web::WindowExtWebSys;
 
@@ -19,6 +23,19 @@ pub trait WgpuAppAction {
     #[allow(opaque_hidden_inferred_bound)]
     fn new(window: Arc<Window>) -> impl core::future::Future<Output = Self> + WasmNotSend;
 
+    /// 以运行配置创建 app，框架总是通过它创建 app；默认忽略配置，直接调用 `new`
+    ///
+    /// 需要 `RunConfig::present_mode` 的 app 实现此方法，并用 [`create_app_surface`] 代替 `AppSurface::new`，
+    /// 这样在创建其它资源之前 surface 就已按请求的呈现模式配置好。
+    #[allow(opaque_hidden_inferred_bound)]
+    fn new_with_config(
+        window: Arc<Window>,
+        config: &RunConfig,
+    ) -> impl core::future::Future<Output = Self> + WasmNotSend {
+        let _ = config;
+        Self::new(window)
+    }
+
     /// 记录窗口大小已发生变化
     ///
     /// # NOTE:
@@ -37,6 +54,17 @@ pub trait WgpuAppAction {
         false
     }
 
+    /// 鼠标按键事件，默认转发给 `mouse_click`，已实现 `mouse_click` 的 app 无需改动
+    fn mouse_input(&mut self, button: MouseButton, state: ElementState) -> bool {
+        self.mouse_click(state, button)
+    }
+
+    /// 鼠标滚轮/触控板滚动事件，`delta` 原样来自 `WindowEvent::MouseWheel`
+    ///
+    /// # NOTE:
+    /// 收到哪种变体取决于平台与设备：普通鼠标滚轮在 Windows、X11 上为 `LineDelta`（每格 1 行）；
+    /// macOS 触控板、Wayland 的平滑滚动以及大多数浏览器为 `PixelDelta`。
+    /// 两者的量级相差约百倍，需要统一处理时用 [`scroll_lines`] 换算为行数。
     fn mouse_wheel(&mut self, _delta: MouseScrollDelta, _phase: TouchPhase) -> bool {
         false
     }
@@ -45,14 +73,179 @@ pub trait WgpuAppAction {
         false
     }
 
+    /// 触摸事件，`id` 在同一个触点按下到抬起之间保持不变
+    ///
+    /// 返回 false（默认）时，框架把单指触摸转换为鼠标事件：按下、移动、抬起依次对应
+    /// `cursor_move` + 左键 `mouse_input` 按下、`cursor_move`、左键抬起，所以只处理鼠标的 app 在手机上也能点击与拖动。
+    fn touch(&mut self, _phase: TouchPhase, _id: u64, _location: PhysicalPosition<f64>) -> bool {
+        false
+    }
+
+    /// 每次触摸事件后调用，`touches` 为当前所有按下的触点，可用于还原双指缩放等多点手势
+    fn touches_changed(&mut self, _touches: &crate::ActiveTouches) {}
+
     /// 鼠标移动/触摸事件
     fn device_input(&mut self, _event: &DeviceEvent) -> bool {
         false
     }
 
+    /// 鼠标的原始移动量，来自 `DeviceEvent::MouseMotion`，不受光标位置与窗口边缘的限制，适合控制相机
+    ///
+    /// # NOTE:
+    /// 在 web 端，移动量来自浏览器指针事件的 `movementX/Y`：未锁定指针时光标离开网页后就不再有移动量，
+    /// 且画布之外的移动也会产生该事件，所以框架只在画布获得焦点时转发。
+    /// 同一个事件仍会先交给 `device_input`。
+    fn mouse_motion(&mut self, _dx: f64, _dy: f64) {}
+
+    /// 渲染通道颜色附件的加载操作，默认每帧清屏
+    ///
+    /// 框架本身不开启渲染通道，app 在 `render` 中创建主渲染通道时以它作为颜色附件的 `load`，
+    /// 便于在清屏与增量渲染之间切换（见 hilbert-curve 示例的拖尾开关）。
+    ///
+    /// # NOTE:
+    /// 返回 `LoadOp::Load` 可以实现累积/拖尾类的增量渲染，但交换链的纹理在帧与帧之间并不保证保留上一帧的内容
+    /// （多个 surface 纹理轮流使用，且平台可能直接丢弃其内容），
+    /// 所以 `Load` 应用于一个持久的中间纹理，再把它复制/绘制到 surface（如 `wgpu::util::TextureBlitter`）。
+    fn color_load_op(&self) -> wgpu::LoadOp<wgpu::Color> {
+        wgpu::LoadOp::Clear(wgpu::Color::BLACK)
+    }
+
+    /// 多渲染目标（MRT）时每个颜色附件各自的加载操作，按附件的 location 排列
+    ///
+    /// 默认只有一个附件，即 `color_load_op()`；G-buffer 可使用 `mrt::gbuffer_load_ops()`，
+    /// 再通过 `mrt::color_attachments` 构造渲染通道的颜色附件。
+    fn color_load_ops(&self) -> Vec<wgpu::LoadOp<wgpu::Color>> {
+        vec![self.color_load_op()]
+    }
+
+    /// 以固定的宽高比渲染场景，`None`（默认）时填满整个窗口
+    ///
+    /// 返回 `Some(aspect)` 时，app 应在 `render` 中以黑色清屏，并对渲染通道应用 `render_viewport()`，
+    /// 视口之外的区域即为黑边；鼠标坐标需通过 `Viewport::to_ndc` 换算，以扣除视口的偏移。
+    fn fixed_aspect(&self) -> Option<f32> {
+        None
+    }
+
+    /// 场景的渲染视口：根据 `fixed_aspect` 计算居中的信箱视口，或覆盖整个窗口
+    fn render_viewport(&self) -> crate::Viewport {
+        let size = self.get_size();
+        match self.fixed_aspect() {
+            Some(aspect) => crate::Viewport::letterboxed(size, aspect),
+            None => crate::Viewport::full(size),
+        }
+    }
+
+    /// 当前的抗锯齿方式
+    ///
+    /// 返回 `Some`（默认为 `None`）表示 app 支持运行时切换，框架会在按下 F2 键时
+    /// 以 `AntiAliasing::next()` 调用 `set_anti_aliasing`。
+    fn anti_aliasing(&self) -> Option<crate::post::AntiAliasing> {
+        None
+    }
+
+    /// 切换抗锯齿方式
+    ///
+    /// app 通常在这里调用 `AntiAliasingTargets::set_mode` 重建渲染目标，其返回 true（采样数变化）时
+    /// 还需以新的采样数重建场景的渲染管线。
+    fn set_anti_aliasing(&mut self, _mode: crate::post::AntiAliasing) {}
+
+    /// 是否处于 sRGB / 线性输出的左右分屏对比模式
+    ///
+    /// 返回 `Some`（默认为 `None`）表示 app 支持该模式，框架会在按下 F3 键时切换，
+    /// app 在 `render` 中通过 `ColorSpaceSplit::render` 绘制两半。
+    fn color_space_split(&self) -> Option<bool> {
+        None
+    }
+
+    /// 开启或关闭分屏对比模式
+    fn set_color_space_split(&mut self, _enabled: bool) {}
+
+    /// 窗口获得或失去焦点时调用，默认不做任何事
+    ///
+    /// 如可以在失去焦点时冻结相机等随时间变化的逻辑。
+    fn focused(&mut self, _is_focused: bool) {}
+
+    /// 失去焦点时是否暂停，默认为 false
+    ///
+    /// 返回 true 时，窗口失去焦点后框架不再调用 `update`/`render`，也不再请求重绘，避免后台窗口持续占用 GPU；
+    /// 重新获得焦点后恢复，暂停的时长不计入 `dt`。
+    fn pause_when_unfocused(&self) -> bool {
+        false
+    }
+
+    /// 在 `render` 之前调用，告知剩余累积时间占一个固定步长的比例，用于插值渲染
+    fn set_interpolation_alpha(&mut self, _alpha: f32) {}
+
+    /// 提交渲染命令所用的队列
+    ///
+    /// 返回 `Some` 时，框架在每帧 `render` 之后通过 `on_submitted_work_done` 记录 GPU 执行完毕的时间，
+    /// 延迟统计中才会包含 `update_to_gpu_done`。
+    fn latency_queue(&self) -> Option<&wgpu::Queue> {
+        None
+    }
+
+    /// 在 `render` 之后调用，告知最近若干帧的平均延迟（见 `FrameLatency`）
+    fn set_frame_latency(&mut self, _latency: crate::LatencyEstimate) {}
+
+    /// `render` 返回 `SurfaceError` 时各类错误的处理方式，见 [`crate::SurfaceErrorPolicy`]
+    fn surface_error_policy(&self) -> crate::SurfaceErrorPolicy {
+        crate::SurfaceErrorPolicy::default()
+    }
+
+    /// 离屏渲染所需的 device、queue 与渲染目标的格式、尺寸，`None`（默认）表示不支持离屏渲染
+    ///
+    /// 返回 `Some` 时，按 F12 截图与 [`run_headless`] 都通过 `render_to` 把一帧渲染到这个格式与尺寸的离屏纹理上再读回；
+    /// 持有 `AppSurface` 的 app 通常实现为 `Some(RenderContext::new(&self.app, format))`，`format` 与场景管线的目标格式一致。
+    ///
+    /// # NOTE:
+    /// 截图只支持 `Rgba8Unorm`、`Bgra8Unorm` 及其 sRGB 格式。Web 端不调用此方法：GPU 数据无法阻塞读回，
+    /// 框架直接把 canvas 导出为 PNG 并触发浏览器下载。
+    fn render_context(&self) -> Option<RenderContext<'_>> {
+        None
+    }
+
+    /// 把一帧录制到 `encoder`，渲染到格式与尺寸由 `render_context` 给出的 `view`
+    ///
+    /// 默认不录制任何命令。实现了它的 app 通常让 `render` 只负责获取交换链纹理、调用它，然后提交并呈现，
+    /// 这样窗口、截图与离屏渲染绘制的都是同一个画面。
+    fn render_to(&mut self, _encoder: &mut wgpu::CommandEncoder, _view: &wgpu::TextureView) {}
+
+    /// 以当前配置重新配置 surface，处理方式为 `SurfaceErrorAction::Reconfigure` 时调用
+    ///
+    /// 默认不做任何事；持有 `AppSurface` 的 app 通常实现为 `self.app.surface.configure(&self.app.device, &self.app.config)`。
+    /// 不能用 `set_window_resized` 代替：尺寸没有变化时 app 一般会忽略它。
+    fn reconfigure_surface(&mut self) {}
+
+    /// 窗口的缩放因子（物理像素 / 逻辑像素）变化时调用，如窗口被拖到另一个 DPI 不同的显示器上
+    ///
+    /// 默认不做任何事。按 `scale_factor` 换算尺寸的 app（如粒子的像素尺寸）可在这里更新缩放因子并重算。
+    ///
+    /// # NOTE:
+    /// 紧接着还会收到一次 `set_window_resized`，新的物理尺寸约为原逻辑尺寸乘以新的缩放因子，
+    /// surface 的重新配置应留给它处理；这里只需处理与缩放因子相关、而与窗口尺寸无关的数据。
+    fn scale_factor_changed(&mut self, _scale_factor: f64) {}
+
+    /// 退出前调用，把需要保留到下次运行的设置写入 `settings`（见 [`crate::settings`]）
+    ///
+    /// 窗口位置、尺寸、抗锯齿方式与呈现模式由框架写入；默认不做任何事，app 可在这里写入相机。
+    fn save_settings(&self, _settings: &mut crate::settings::AppSettings) {}
+
+    /// app 创建完成后调用，传入上次运行保存的设置
+    ///
+    /// 抗锯齿方式已由框架通过 `set_anti_aliasing` 恢复，呈现模式已作为 `RunConfig::present_mode` 传给 `new_with_config`；
+    /// 默认不做任何事。
+    fn restore_settings(&mut self, _settings: &crate::settings::AppSettings) {}
+
     /// 更新渲染数据
     fn update(&mut self, _dt: instant::Duration) {}
 
+    /// 是否请求退出，在每帧的 `update` 之后调用，默认返回 false
+    ///
+    /// 返回 true 时（如动画播放完一个完整的周期）框架结束事件循环，不再渲染这一帧。
+    fn should_exit(&self) -> bool {
+        false
+    }
+
     /// 提交渲染
     fn render(&mut self) -> Result<(), wgpu::SurfaceError>;
 }
@@ -71,16 +264,128 @@ struct WgpuAppHandler<A: WgpuAppAction> {
 
     /// 上次执行渲染的时间
     last_render_time: instant::Instant,
+    /// 固定步长累加器，由 `RunConfig::fixed_timestep` 创建
+    fixed_timestep: Option<crate::FixedTimestep>,
+    /// 渲染指定帧数后退出，由 `--frames N` 参数（web 端为 `?frames=N`）开启
+    frame_limit: Option<crate::FrameLimit>,
+    /// 从 `update` 开始到提交/GPU 执行完毕的延迟统计
+    latency: crate::FrameLatency,
+
+    /// 监听 canvas 容器尺寸变化的 ResizeObserver 及其 JS 回调
+    ///
+    /// 回调闭包需要与 observer 一样长寿，否则 JS 侧调用时闭包已被释放
+    #[cfg(target_arch = "wasm32")]
+    resize_observer: Option<(web_sys::ResizeObserver, Closure<dyn FnMut(js_sys::Array)>)>,
+    /// 当前按下的触点
+    touches: crate::ActiveTouches,
+    /// 窗口（web 端为画布）是否拥有焦点
+    ///
+    /// web 端只在拥有焦点时转发 `mouse_motion`；`WgpuAppAction::pause_when_unfocused` 返回 true 时，失去焦点期间暂停渲染
+    focused: bool,
+
+    /// 当前按下的修饰键，用于识别 Alt+Enter
+    modifiers: ModifiersState,
+    /// 进入全屏前的窗口大小，退出全屏时恢复
+    windowed_size: Option<PhysicalSize<u32>>,
+    /// 窗口是否已最小化
+    minimize_guard: MinimizeGuard,
+    /// 上次运行保存的设置，退出时更新并保存
+    settings: crate::settings::AppSettings,
+    /// `run_with_config` 传入的运行配置
+    config: RunConfig,
+    /// 按 `RunConfig::target_fps` 限制帧率
+    frame_pacer: Option<crate::FramePacer>,
+}
+
+/// 窗口最小化状态
+///
+/// # NOTE:
+/// 窗口最小化时（部分平台上）会收到宽或高为 0 的 Resized 事件，而 surface 不能配置为 0 尺寸，
+/// 所以最小化期间既不把尺寸转发给 app，也不调用 `update`/`render`；恢复后再转发新尺寸并继续渲染。
+#[derive(Clone, Copy, Debug, Default)]
+struct MinimizeGuard {
+    is_minimized: bool,
+}
+
+impl MinimizeGuard {
+    /// 处理 Resized 事件，返回需要转发给 app 的尺寸
+    fn on_resized(&mut self, size: PhysicalSize<u32>) -> Option<PhysicalSize<u32>> {
+        self.is_minimized = size.width == 0 || size.height == 0;
+        if self.is_minimized { None } else { Some(size) }
+    }
+
+    /// 是否可以渲染
+    fn can_render(&self) -> bool {
+        !self.is_minimized
+    }
+}
+
+/// `PixelDelta` 换算为行数时，一行对应的像素数（与浏览器滚轮每格约 100 像素一致）
+pub const PIXELS_PER_SCROLL_LINE: f64 = 100.0;
+
+/// 把滚动量统一换算为行数（滚轮的格数），向上滚动为正
+pub fn scroll_lines(delta: &MouseScrollDelta) -> f32 {
+    match delta {
+        MouseScrollDelta::LineDelta(_, y) => *y,
+        MouseScrollDelta::PixelDelta(position) => (position.y / PIXELS_PER_SCROLL_LINE) as f32,
+    }
+}
+
+/// 是否为切换全屏的按键：F11 或 Alt+Enter（忽略按住不放时的重复事件）
+fn is_fullscreen_toggle(
+    key: PhysicalKey,
+    state: ElementState,
+    repeat: bool,
+    modifiers: ModifiersState,
+) -> bool {
+    if state != ElementState::Pressed || repeat {
+        return false;
+    }
+    match key {
+        PhysicalKey::Code(KeyCode::F11) => true,
+        PhysicalKey::Code(KeyCode::Enter | KeyCode::NumpadEnter) => modifiers.alt_key(),
+        _ => false,
+    }
+}
+
+/// 是否为切换抗锯齿方式的按键：F2（忽略按住不放时的重复事件）
+fn is_anti_aliasing_toggle(key: PhysicalKey, state: ElementState, repeat: bool) -> bool {
+    state == ElementState::Pressed && !repeat && key == PhysicalKey::Code(KeyCode::F2)
+}
+
+/// 是否为切换颜色空间分屏对比的按键：F3（忽略按住不放时的重复事件）
+fn is_color_space_split_toggle(key: PhysicalKey, state: ElementState, repeat: bool) -> bool {
+    state == ElementState::Pressed && !repeat && key == PhysicalKey::Code(KeyCode::F3)
+}
+
+/// 是否为保存截图的按键：F12（忽略按住不放时的重复事件）
+fn is_screenshot_key(key: PhysicalKey, state: ElementState, repeat: bool) -> bool {
+    state == ElementState::Pressed && !repeat && key == PhysicalKey::Code(KeyCode::F12)
 }
 
 impl<A: WgpuAppAction> WgpuAppHandler<A> {
-    fn new(title: &'static str) -> Self {
+    fn new(title: &'static str, config: RunConfig) -> Self {
+        let settings = crate::settings::AppSettings::load(title);
+        let config = config_with_settings(config, &settings);
         Self {
             title,
             window: None,
             app: Arc::new(Mutex::new(None)),
             missed_resize: Arc::new(Mutex::new(None)),
             last_render_time: instant::Instant::now(),
+            fixed_timestep: fixed_timestep_from_config(&config),
+            frame_limit: crate::FrameLimit::from_env(),
+            latency: crate::FrameLatency::default(),
+            #[cfg(target_arch = "wasm32")]
+            resize_observer: None,
+            touches: crate::ActiveTouches::default(),
+            focused: true,
+            modifiers: ModifiersState::empty(),
+            windowed_size: None,
+            minimize_guard: MinimizeGuard::default(),
+            settings,
+            frame_pacer: config.target_fps.map(crate::FramePacer::new),
+            config,
         }
     }
     /// 配置窗口
@@ -88,10 +393,18 @@ impl<A: WgpuAppAction> WgpuAppHandler<A> {
         let window = self.window.as_mut().unwrap();
         window.set_title(self.title);
         if cfg!(not(target_arch = "wasm32")) {
-            // 计算一个默认显示高度
-            let height = 600 * window.scale_factor() as u32;
-            let width = height;
-            let _ = window.request_inner_size(PhysicalSize::new(width, height));
+            // 优先恢复上次运行时的窗口尺寸，否则计算一个默认显示高度
+            let size = self.settings.window_size.map_or_else(
+                || {
+                    let height = 600 * window.scale_factor() as u32;
+                    PhysicalSize::new(height, height)
+                },
+                |[width, height]| PhysicalSize::new(width, height),
+            );
+            let _ = window.request_inner_size(size);
+            if let Some([x, y]) = self.settings.window_position {
+                window.set_outer_position(PhysicalPosition::new(x, y));
+            }
         }
 
         #[cfg(target_arch = "wasm32")]
@@ -129,6 +442,99 @@ impl<A: WgpuAppAction> WgpuAppHandler<A> {
         }
     }
 
+    /// 监听 canvas 容器的 CSS 尺寸变化
+    ///
+    /// # NOTE:
+    /// 在 web 端，容器的 CSS 尺寸变化（比如布局变化而浏览器窗口未缩放）时，winit 并不总会发出 Resized 事件。
+    /// 这里通过 JS 的 `ResizeObserver` 监听容器，回调中把 CSS 像素尺寸乘以 `devicePixelRatio` 得到物理像素尺寸，
+    /// 再调用 `set_window_resized`；app 尚未初始化完成时则记录到 `missed_resize` 中。
+    #[cfg(target_arch = "wasm32")]
+    fn observe_container_resize(&mut self)
+    where
+        A: 'static,
+    {
+        let Some(container) = web_sys::window()
+            .and_then(|win| win.document())
+            .and_then(|doc| doc.get_element_by_id("wgpu-app-container"))
+        else {
+            return;
+        };
+        let Some(window) = self.window.clone() else {
+            return;
+        };
+        let app = self.app.clone();
+        let missed_resize = self.missed_resize.clone();
+
+        let callback = Closure::<dyn FnMut(js_sys::Array)>::new(move |entries: js_sys::Array| {
+            let Ok(entry) = entries.get(0).dyn_into::<web_sys::ResizeObserverEntry>() else {
+                return;
+            };
+            let rect = entry.content_rect();
+            let dpr = web_sys::window().map_or(1.0, |win| win.device_pixel_ratio());
+            let physical_size = PhysicalSize::new(
+                (rect.width() * dpr).round() as u32,
+                (rect.height() * dpr).round() as u32,
+            );
+            if physical_size.width == 0 || physical_size.height == 0 {
+                return;
+            }
+            match app.lock().as_mut() {
+                Some(app) => app.set_window_resized(physical_size),
+                None => *missed_resize.lock() = Some(physical_size),
+            }
+            window.request_redraw();
+        });
+        if let Ok(observer) = web_sys::ResizeObserver::new(callback.as_ref().unchecked_ref()) {
+            observer.observe(&container);
+            self.resize_observer = Some((observer, callback));
+        }
+    }
+
+    /// 在无边框全屏与窗口模式之间切换
+    ///
+    /// # NOTE:
+    /// `Fullscreen::Borderless(None)` 表示使用窗口当前所在的显示器；
+    /// 需要指定显示器时，可从 `window.available_monitors()` 中选择一个传入 `Borderless(Some(monitor))`。
+    /// 全屏切换后窗口系统会发出 Resized 事件，surface 随之经由 `set_window_resized`（去抖动后）重新配置。
+    /// 在 web 端使用 canvas 的 Fullscreen API，浏览器要求此调用发生在用户手势（如按键）的处理过程中。
+    fn toggle_fullscreen(
+        window: &Window,
+        windowed_size: &mut Option<PhysicalSize<u32>>,
+        app: &mut A,
+    ) {
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = (windowed_size, app);
+            let Some(document) = web_sys::window().and_then(|win| win.document()) else {
+                return;
+            };
+            if document.fullscreen_element().is_some() {
+                document.exit_fullscreen();
+            } else if let Some(canvas) = window.canvas() {
+                if let Err(e) = canvas.request_fullscreen() {
+                    log::warn!("无法进入全屏: {e:?}");
+                }
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if window.fullscreen().is_some() {
+                window.set_fullscreen(None);
+                if let Some(size) = windowed_size.take() {
+                    // 窗口系统立即应用了新尺寸时不会再发出 Resized 事件，需手动通知 app
+                    if let Some(size) = window.request_inner_size(size) {
+                        app.set_window_resized(size);
+                    }
+                }
+            } else {
+                *windowed_size = Some(window.inner_size());
+                window.set_fullscreen(Some(winit::window::Fullscreen::Borderless(None)));
+            }
+        }
+        window.request_redraw();
+    }
+
     /// 在提交渲染之前通知窗口系统。
     fn pre_present_notify(&self) {
         if let Some(window) = self.window.as_ref() {
@@ -158,16 +564,21 @@ impl<A: WgpuAppAction + 'static> ApplicationHandler for WgpuAppHandler<A> {
 
         self.window = Some(window.clone());
         self.config_window();
+        #[cfg(target_arch = "wasm32")]
+        self.observe_container_resize();
 
         cfg_if::cfg_if! {
             if #[cfg(target_arch = "wasm32")] {
                 let app = self.app.clone();
                 let missed_resize = self.missed_resize.clone();
+                let settings = self.settings.clone();
+                let config = self.config.clone();
 
                 wasm_bindgen_futures::spawn_local(async move {
                      let window_cloned = window.clone();
 
-                    let wgpu_app = A::new(window).await;
+                    let mut wgpu_app = A::new_with_config(window, &config).await;
+                    restore_settings(&mut wgpu_app, &settings);
                     let mut app = app.lock();
                     *app = Some(wgpu_app);
 
@@ -177,12 +588,21 @@ impl<A: WgpuAppAction + 'static> ApplicationHandler for WgpuAppHandler<A> {
                     }
                 });
             } else {
-                let wgpu_app = pollster::block_on(A::new(window));
+                let mut wgpu_app = pollster::block_on(A::new_with_config(window, &self.config));
+                restore_settings(&mut wgpu_app, &self.settings);
                 self.app.lock().replace(wgpu_app);
             }
         }
     }
 
+    fn new_events(&mut self, event_loop: &ActiveEventLoop, cause: StartCause) {
+        // `FramePacer` 设置的休眠时间已到
+        if let StartCause::ResumeTimeReached { .. } = cause {
+            event_loop.set_control_flow(ControlFlow::Wait);
+            self.request_redraw();
+        }
+    }
+
     fn suspended(&mut self, _event_loop: &ActiveEventLoop) {
         // 暂停事件
     }
@@ -209,19 +629,97 @@ impl<A: WgpuAppAction + 'static> ApplicationHandler for WgpuAppHandler<A> {
 
         // 窗口事件
         match event {
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers.state();
+            }
+            WindowEvent::KeyboardInput { event, .. }
+                if is_fullscreen_toggle(
+                    event.physical_key,
+                    event.state,
+                    event.repeat,
+                    self.modifiers,
+                ) =>
+            {
+                if let Some(window) = self.window.as_deref() {
+                    Self::toggle_fullscreen(window, &mut self.windowed_size, app);
+                }
+            }
+            WindowEvent::KeyboardInput { event, .. }
+                if is_anti_aliasing_toggle(event.physical_key, event.state, event.repeat) =>
+            {
+                if let Some(mode) = app.anti_aliasing() {
+                    let next = mode.next();
+                    app.set_anti_aliasing(next);
+                    log::info!("Anti-aliasing: {next:?}");
+                    // 网页关闭时不会触发 exiting，切换后立即保存
+                    #[cfg(target_arch = "wasm32")]
+                    {
+                        collect_settings(
+                            self.window.as_deref(),
+                            app,
+                            &self.config,
+                            &mut self.settings,
+                        );
+                        self.settings.save(self.title);
+                    }
+                } else {
+                    let _ = app.keyboard_input(&event);
+                }
+            }
+            WindowEvent::KeyboardInput { event, .. }
+                if is_color_space_split_toggle(event.physical_key, event.state, event.repeat) =>
+            {
+                if let Some(enabled) = app.color_space_split() {
+                    app.set_color_space_split(!enabled);
+                    log::info!("Color space split: {}", !enabled);
+                } else {
+                    let _ = app.keyboard_input(&event);
+                }
+            }
+            WindowEvent::KeyboardInput { event, .. }
+                if is_screenshot_key(event.physical_key, event.state, event.repeat) =>
+            {
+                cfg_if::cfg_if! {
+                    if #[cfg(target_arch = "wasm32")] {
+                        if let Some(window) = self.window.as_ref() {
+                            download_canvas_png(window);
+                        }
+                    } else {
+                        match capture_app_frame(app).map(|image| crate::capture::save_screenshot(&image)) {
+                            Some(Ok(path)) => log::info!("Screenshot saved to {}", path.display()),
+                            Some(Err(e)) => log::warn!("cannot save screenshot: {e}"),
+                            None => {
+                                let _ = app.keyboard_input(&event);
+                            }
+                        }
+                    }
+                }
+            }
             WindowEvent::CloseRequested => {
                 event_loop.exit();
             }
             WindowEvent::Resized(physical_size) => {
-                if physical_size.width == 0 || physical_size.height == 0 {
+                let was_minimized = !self.minimize_guard.can_render();
+                match self.minimize_guard.on_resized(physical_size) {
                     // 处理最小化窗口的事件
-                    log::info!("Window minimized!");
-                } else {
-                    log::info!("Window resized: {:?}", physical_size);
+                    None => log::info!("Window minimized!"),
+                    Some(size) => {
+                        log::info!("Window resized: {:?}", size);
 
-                    app.set_window_resized(physical_size);
+                        app.set_window_resized(size);
+                        if was_minimized {
+                            // 最小化期间停止了重绘循环，恢复后需重新启动；
+                            // 同时避免把最小化的时长计入下一帧的 dt
+                            self.last_render_time = instant::Instant::now();
+                            self.request_redraw();
+                        }
+                    }
                 }
             }
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                log::info!("Scale factor changed: {scale_factor}");
+                app.scale_factor_changed(scale_factor);
+            }
             WindowEvent::KeyboardInput { event, .. } => {
                 // 键盘事件
                 let _ = app.keyboard_input(&event);
@@ -232,37 +730,146 @@ impl<A: WgpuAppAction + 'static> ApplicationHandler for WgpuAppHandler<A> {
             }
             WindowEvent::MouseInput { button, state, .. } => {
                 // 鼠标点击事件
-                let _ = app.mouse_click(state, button);
+                let _ = app.mouse_input(button, state);
+            }
+            WindowEvent::Touch(touch) => {
+                let emulation = self.touches.update(touch.phase, touch.id, touch.location);
+                if !app.touch(touch.phase, touch.id, touch.location) {
+                    match emulation {
+                        Some(MouseEmulation::Press(location)) => {
+                            let _ = app.cursor_move(location);
+                            let _ = app.mouse_input(MouseButton::Left, ElementState::Pressed);
+                        }
+                        Some(MouseEmulation::Move(location)) => {
+                            let _ = app.cursor_move(location);
+                        }
+                        Some(MouseEmulation::Release) => {
+                            let _ = app.mouse_input(MouseButton::Left, ElementState::Released);
+                        }
+                        None => {}
+                    }
+                }
+                app.touches_changed(&self.touches);
+            }
+            WindowEvent::Focused(focused) => {
+                let was_paused = !self.focused && app.pause_when_unfocused();
+                self.focused = focused;
+                app.focused(focused);
+                if focused && was_paused {
+                    // 与最小化后恢复相同：重新启动重绘循环，且不把暂停的时长计入下一帧的 dt
+                    self.last_render_time = instant::Instant::now();
+                    self.request_redraw();
+                }
             }
             WindowEvent::CursorMoved { position, .. } => {
                 // 鼠标移动事件
                 let _ = app.cursor_move(position);
             }
             WindowEvent::RedrawRequested => {
+                // 最小化时跳过渲染，也不再请求重绘，直到窗口恢复
+                if !self.minimize_guard.can_render() {
+                    return;
+                }
+                // app 选择在失去焦点时暂停，重新获得焦点后再请求重绘
+                if !self.focused && app.pause_when_unfocused() {
+                    return;
+                }
                 // surface 重绘事件
                 let now = instant::Instant::now();
+                if let Some(pacer) = self.frame_pacer.as_mut() {
+                    // web 端由 requestAnimationFrame 驱动重绘，未到时间的帧直接跳过；
+                    // 原生平台上提前到来的重绘（如系统触发的）也跳过，到时间后 `new_events` 会再次请求
+                    if !pacer.is_due(now) {
+                        #[cfg(target_arch = "wasm32")]
+                        self.request_redraw();
+                        return;
+                    }
+                    let next_frame = pacer.schedule(now);
+                    // 原生平台上休眠到下一帧的时间，由 `new_events` 请求重绘
+                    #[cfg(not(target_arch = "wasm32"))]
+                    event_loop.set_control_flow(ControlFlow::WaitUntil(next_frame));
+                    #[cfg(target_arch = "wasm32")]
+                    let _ = next_frame;
+                }
                 let dt = now - self.last_render_time;
                 self.last_render_time = now;
 
-                app.update(dt);
+                let update_start = now;
+                if let Some(timestep) = self.fixed_timestep.as_mut() {
+                    let steps = timestep.advance(dt);
+                    for _ in 0..steps {
+                        app.update(timestep.step());
+                    }
+                    app.set_interpolation_alpha(timestep.alpha());
+                } else {
+                    app.update(dt);
+                }
+                if app.should_exit() {
+                    log::info!("Exit requested by app");
+                    event_loop.exit();
+                    return;
+                }
 
                 self.pre_present_notify();
 
-                match app.render() {
-                    Ok(_) => {}
-                    // 当展示平面的上下文丢失，就需重新配置
-                    Err(wgpu::SurfaceError::Lost) => eprintln!("Surface is lost"),
-                    // 所有其他错误（过期、超时等）应在下一帧解决
-                    Err(e) => eprintln!("{e:?}"),
+                if let Err(e) = app.render() {
+                    match app.surface_error_policy().action(&e) {
+                        crate::SurfaceErrorAction::SkipFrame => {
+                            log::warn!("{e:?}, skip this frame");
+                        }
+                        crate::SurfaceErrorAction::Reconfigure => {
+                            log::warn!("{e:?}, reconfigure the surface");
+                            app.reconfigure_surface();
+                        }
+                        crate::SurfaceErrorAction::Exit => {
+                            log::error!("{e:?}, exit");
+                            event_loop.exit();
+                            return;
+                        }
+                    }
+                }
+
+                self.latency
+                    .record_submit(update_start, instant::Instant::now());
+                if let Some(queue) = app.latency_queue() {
+                    self.latency.track_gpu_done(queue, update_start);
+                }
+                app.set_frame_latency(self.latency.estimate());
+
+                if let Some(limit) = self.frame_limit.as_mut() {
+                    if limit.on_frame(dt) {
+                        log::info!(
+                            "Rendered {} frames, average frame time: {:?}",
+                            limit.rendered_frames(),
+                            limit.average_frame_time()
+                        );
+                        event_loop.exit();
+                        return;
+                    }
                 }
 
                 // 除非我们手动请求，RedrawRequested 将只会触发一次。
-                self.request_redraw();
+                // 原生平台限制帧率时，由 `new_events` 在下一帧的时间到达后请求
+                if cfg!(target_arch = "wasm32") || self.frame_pacer.is_none() {
+                    self.request_redraw();
+                }
             }
             _ => (),
         }
     }
 
+    fn exiting(&mut self, _event_loop: &ActiveEventLoop) {
+        if let Some(app) = self.app.lock().as_ref() {
+            collect_settings(
+                self.window.as_deref(),
+                app,
+                &self.config,
+                &mut self.settings,
+            );
+            self.settings.save(self.title);
+        }
+    }
+
     fn device_event(
         &mut self,
         _event_loop: &ActiveEventLoop,
@@ -271,14 +878,498 @@ impl<A: WgpuAppAction + 'static> ApplicationHandler for WgpuAppHandler<A> {
     ) {
         if let Some(app) = self.app.lock().as_mut() {
             app.device_input(&event);
+            if let DeviceEvent::MouseMotion { delta: (dx, dy) } = event {
+                #[cfg(target_arch = "wasm32")]
+                if !self.focused {
+                    return;
+                }
+                app.mouse_motion(dx, dy);
+            }
+        }
+    }
+}
+
+/// 把 canvas 的内容导出为 PNG，并以 `screenshot-<时间戳>.png` 触发浏览器下载
+///
+/// # NOTE:
+/// WebGPU 的 canvas 在呈现后内容可能已被丢弃，导出的图像可能是空白的；WebGL 后端需要 `preserveDrawingBuffer`。
+#[cfg(target_arch = "wasm32")]
+fn download_canvas_png(window: &Window) {
+    let Some(canvas) = window.canvas() else {
+        return;
+    };
+    let download = || -> Option<()> {
+        let url = canvas.to_data_url_with_type("image/png").ok()?;
+        let document = web_sys::window()?.document()?;
+        let anchor: web_sys::HtmlAnchorElement =
+            document.create_element("a").ok()?.dyn_into().ok()?;
+        anchor.set_href(&url);
+        let timestamp = (js_sys::Date::now() / 1000.0) as u64;
+        anchor.set_download(&format!("screenshot-{timestamp}.png"));
+        anchor.click();
+        Some(())
+    };
+    if download().is_none() {
+        log::warn!("cannot download the screenshot");
+    }
+}
+
+/// 把保存的设置应用到刚创建的 app
+fn restore_settings<A: WgpuAppAction>(app: &mut A, settings: &crate::settings::AppSettings) {
+    if let Some(mode) = settings.anti_aliasing {
+        if app.anti_aliasing().is_some() {
+            app.set_anti_aliasing(mode);
         }
     }
+    app.restore_settings(settings);
 }
 
+/// 收集退出时需要保存的设置
+fn collect_settings<A: WgpuAppAction>(
+    window: Option<&Window>,
+    app: &A,
+    config: &RunConfig,
+    settings: &mut crate::settings::AppSettings,
+) {
+    // 网页上的 canvas 尺寸由容器决定，不保存窗口位置与尺寸
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(window) = window {
+        let size = window.inner_size();
+        if size.width > 0 && size.height > 0 && window.fullscreen().is_none() {
+            settings.window_size = Some([size.width, size.height]);
+        }
+        if let Ok(position) = window.outer_position() {
+            settings.window_position = Some([position.x, position.y]);
+        }
+    }
+    #[cfg(target_arch = "wasm32")]
+    let _ = window;
+    if let Some(mode) = app.anti_aliasing() {
+        settings.anti_aliasing = Some(mode);
+    }
+    if let Some(mode) = config.present_mode {
+        settings.set_present_mode(mode);
+    }
+    app.save_settings(settings);
+}
+
+/// 用保存的设置补全运行配置中未指定的呈现模式，显式的配置优先于保存的设置
+fn config_with_settings(config: RunConfig, settings: &crate::settings::AppSettings) -> RunConfig {
+    RunConfig {
+        present_mode: config.present_mode.or_else(|| settings.present_mode()),
+        ..config
+    }
+}
+
+/// `run_with_config` 的运行配置，`Default` 即 `run` 使用的配置
+#[derive(Clone, Debug, Default)]
+pub struct RunConfig {
+    /// 请求的呈现模式，如低延迟测试时的 `Immediate`、`Mailbox`
+    ///
+    /// surface 不支持时回退到 `Fifo`；`None` 时使用 `AppSurface` 的默认值。
+    /// 需要 app 实现 `WgpuAppAction::new_with_config` 并用 [`create_app_surface`] 创建 surface 才会生效。
+    pub present_mode: Option<wgpu::PresentMode>,
+    /// 帧率上限，`None`（默认）表示不限制，见 [`crate::FramePacer`]
+    ///
+    /// `update` 收到的 `dt` 仍是两次渲染之间实际经过的时间，相机等随时间变化的逻辑不受影响。
+    pub target_fps: Option<u32>,
+    /// 固定的 update 步长
+    ///
+    /// 为 `Some` 时框架累加真实经过的时间，每帧以该步长调用 `update` 0 次或多次，
+    /// 动画推进的速度与 `render` 的调用频率无关；`None`（默认）时每帧以实际帧间隔调用一次 `update`。
+    /// 步长为 0 时忽略并打印警告。
+    pub fixed_timestep: Option<instant::Duration>,
+}
+
+/// 由运行配置创建固定步长累加器，步长为 0 时视为未设置
+fn fixed_timestep_from_config(config: &RunConfig) -> Option<crate::FixedTimestep> {
+    match config.fixed_timestep {
+        Some(step) if step.is_zero() => {
+            log::warn!("RunConfig::fixed_timestep is zero, fall back to variable timestep");
+            None
+        }
+        step => step.map(crate::FixedTimestep::new),
+    }
+}
+
+/// 按运行配置创建 `AppSurface`：请求了呈现模式时，在返回之前就以该模式（不支持时为 `Fifo`）配置好 surface
+pub async fn create_app_surface(
+    window: Arc<Window>,
+    config: &RunConfig,
+) -> app_surface::AppSurface {
+    let mut app = app_surface::AppSurface::new(window).await;
+    if let Some(mode) = config.present_mode {
+        crate::present_mode::configure_present_mode(&mut app, mode);
+    }
+    app
+}
+
+/// 运行 app
+///
+/// 带有 `--frames N` 参数（web 端为 `?frames=N`）时，渲染 N 帧后退出并打印平均帧时间，见 [`crate::FrameLimit`]
 pub fn run<A: WgpuAppAction + 'static>(title: &'static str) -> Result<(), impl std::error::Error> {
+    run_with_config::<A>(title, RunConfig::default())
+}
+
+/// 与 `run` 相同，但使用指定的运行配置
+pub fn run_with_config<A: WgpuAppAction + 'static>(
+    title: &'static str,
+    config: RunConfig,
+) -> Result<(), impl std::error::Error> {
     crate::init_logger();
 
     let events_loop = EventLoop::new().unwrap();
-    let mut app = WgpuAppHandler::<A>::new(title);
+    let mut app = WgpuAppHandler::<A>::new(title, config);
     events_loop.run_app(&mut app)
 }
+
+/// 离屏渲染所需的 GPU 上下文，见 `WgpuAppAction::render_context`
+pub struct RenderContext<'a> {
+    pub device: &'a wgpu::Device,
+    pub queue: &'a wgpu::Queue,
+    /// 渲染目标的格式，即 `render_to` 中 `view` 的格式
+    pub format: wgpu::TextureFormat,
+    pub size: PhysicalSize<u32>,
+}
+
+impl<'a> RenderContext<'a> {
+    /// 使用 `app` 的 device、queue 与 surface 尺寸，渲染目标的格式为 `format`
+    pub fn new(app: &'a app_surface::AppSurface, format: wgpu::TextureFormat) -> Self {
+        Self {
+            device: &app.device,
+            queue: &app.queue,
+            format,
+            size: PhysicalSize::new(app.config.width, app.config.height),
+        }
+    }
+}
+
+/// 复制出 `render_context` 中的 device、queue 等，以便之后以 `&mut app` 调用 `render_to`
+#[cfg(not(target_arch = "wasm32"))]
+fn owned_render_context<A: WgpuAppAction>(
+    app: &A,
+) -> Option<(
+    wgpu::Device,
+    wgpu::Queue,
+    wgpu::TextureFormat,
+    wgpu::Extent3d,
+)> {
+    let ctx = app.render_context()?;
+    let size = wgpu::Extent3d {
+        width: ctx.size.width.max(1),
+        height: ctx.size.height.max(1),
+        depth_or_array_layers: 1,
+    };
+    Some((ctx.device.clone(), ctx.queue.clone(), ctx.format, size))
+}
+
+/// 用 `render_to` 把当前画面重新渲染到离屏纹理并读回，app 不支持离屏渲染时返回 `None`
+///
+/// 交换链的纹理在 `render` 中已经呈现，无法再读取，所以按 F12 时由这里再渲染一次。
+#[cfg(not(target_arch = "wasm32"))]
+fn capture_app_frame<A: WgpuAppAction>(app: &mut A) -> Option<image::RgbaImage> {
+    let (device, queue, format, size) = owned_render_context(app)?;
+    Some(crate::capture::capture_image(
+        &device,
+        &queue,
+        size.width,
+        size.height,
+        format,
+        |encoder, view| app.render_to(encoder, view),
+    ))
+}
+
+/// 每帧以 1/60 秒调用一次 `update` 与 `render_to`，共 `frames` 帧（至少 1 帧），返回最后一帧的图像；
+/// `should_exit` 返回 true 时提前结束。app 不支持离屏渲染时返回 `None`
+#[cfg(not(target_arch = "wasm32"))]
+fn render_offscreen<A: WgpuAppAction>(app: &mut A, frames: u32) -> Option<image::RgbaImage> {
+    let (device, queue, format, size) = owned_render_context(app)?;
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("headless target"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+    let dt = instant::Duration::from_secs(1) / 60;
+    for _ in 0..frames.max(1) {
+        app.update(dt);
+        if app.should_exit() {
+            break;
+        }
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("headless frame"),
+        });
+        app.render_to(&mut encoder, &view);
+        queue.submit(Some(encoder.finish()));
+    }
+
+    Some(crate::capture::read_texture(&device, &queue, &texture))
+}
+
+/// 不显示窗口运行 app：每帧以 1/60 秒调用一次 `update` 与 `render_to`，共 `frames` 帧（至少 1 帧），
+/// 返回最后一帧紧密排列的 RGBA 像素（每行 `width * 4` 字节）；`should_exit` 返回 true 时提前结束
+///
+/// 需要 app 实现 `render_context` 与 `render_to`，否则 panic。
+///
+/// # NOTE:
+/// `WgpuAppAction::new` 需要窗口，所以这里创建一个不可见的 `width * height` 窗口，app 照常从它创建 surface，
+/// 但每一帧都渲染到离屏纹理上，不获取也不呈现交换链纹理；读回的尺寸是 `render_context` 给出的 surface 尺寸。
+/// winit 要求在主线程上创建事件循环且每个进程只能创建一次，所以应在 CI 的独立程序（而非 `cargo test` 的测试线程）中调用；
+/// 没有显示服务的环境可以配合 xvfb 运行。
+#[cfg(not(target_arch = "wasm32"))]
+pub fn run_headless<A: WgpuAppAction>(width: u32, height: u32, frames: u32) -> Vec<u8> {
+    crate::init_logger();
+
+    let event_loop = EventLoop::new().unwrap();
+    let mut handler = HeadlessHandler::<A> {
+        size: PhysicalSize::new(width.max(1), height.max(1)),
+        frames,
+        pixels: None,
+        _app: std::marker::PhantomData,
+    };
+    event_loop.run_app(&mut handler).unwrap();
+    handler
+        .pixels
+        .expect("the event loop exited before the window was created")
+}
+
+/// `run_headless` 的事件循环：窗口创建后立即渲染全部帧并退出
+#[cfg(not(target_arch = "wasm32"))]
+struct HeadlessHandler<A> {
+    size: PhysicalSize<u32>,
+    frames: u32,
+    pixels: Option<Vec<u8>>,
+    _app: std::marker::PhantomData<A>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<A: WgpuAppAction> ApplicationHandler for HeadlessHandler<A> {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        if self.pixels.is_some() {
+            return;
+        }
+        let window_attributes = Window::default_attributes()
+            .with_visible(false)
+            .with_inner_size(self.size);
+        let window = Arc::new(event_loop.create_window(window_attributes).unwrap());
+        let mut app = pollster::block_on(A::new_with_config(window, &RunConfig::default()));
+        app.set_window_resized(self.size);
+        let image = render_offscreen(&mut app, self.frames)
+            .expect("run_headless requires WgpuAppAction::render_context");
+        self.pixels = Some(image.into_raw());
+        event_loop.exit();
+    }
+
+    fn window_event(&mut self, _event_loop: &ActiveEventLoop, _id: WindowId, _event: WindowEvent) {}
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn saved_present_mode_fills_run_config() {
+        let mut settings = crate::settings::AppSettings::default();
+        settings.set_present_mode(wgpu::PresentMode::Mailbox);
+        let config = config_with_settings(RunConfig::default(), &settings);
+        assert_eq!(config.present_mode, Some(wgpu::PresentMode::Mailbox));
+
+        // 显式的配置优先
+        let config = config_with_settings(
+            RunConfig {
+                present_mode: Some(wgpu::PresentMode::Immediate),
+                ..Default::default()
+            },
+            &settings,
+        );
+        assert_eq!(config.present_mode, Some(wgpu::PresentMode::Immediate));
+    }
+
+    #[test]
+    fn zero_fixed_timestep_is_ignored() {
+        let config = RunConfig {
+            fixed_timestep: Some(instant::Duration::ZERO),
+            ..Default::default()
+        };
+        assert!(fixed_timestep_from_config(&config).is_none());
+        let config = RunConfig {
+            fixed_timestep: Some(instant::Duration::from_millis(10)),
+            ..Default::default()
+        };
+        assert_eq!(
+            fixed_timestep_from_config(&config).map(|t| t.step()),
+            Some(instant::Duration::from_millis(10))
+        );
+    }
+
+    #[test]
+    fn scroll_deltas_in_lines() {
+        assert_eq!(scroll_lines(&MouseScrollDelta::LineDelta(0.0, 1.0)), 1.0);
+        assert_eq!(
+            scroll_lines(&MouseScrollDelta::PixelDelta(PhysicalPosition::new(
+                0.0, -250.0
+            ))),
+            -2.5
+        );
+    }
+
+    #[test]
+    fn fullscreen_toggle_keys() {
+        let f11 = PhysicalKey::Code(KeyCode::F11);
+        let enter = PhysicalKey::Code(KeyCode::Enter);
+        let none = ModifiersState::empty();
+        assert!(is_fullscreen_toggle(
+            f11,
+            ElementState::Pressed,
+            false,
+            none
+        ));
+        assert!(!is_fullscreen_toggle(
+            f11,
+            ElementState::Released,
+            false,
+            none
+        ));
+        assert!(!is_fullscreen_toggle(
+            f11,
+            ElementState::Pressed,
+            true,
+            none
+        ));
+        assert!(!is_fullscreen_toggle(
+            enter,
+            ElementState::Pressed,
+            false,
+            none
+        ));
+        assert!(is_fullscreen_toggle(
+            enter,
+            ElementState::Pressed,
+            false,
+            ModifiersState::ALT
+        ));
+    }
+
+    #[test]
+    fn minimize_then_restore() {
+        let mut guard = MinimizeGuard::default();
+        assert!(guard.can_render());
+        assert_eq!(guard.on_resized(PhysicalSize::new(0, 0)), None);
+        assert!(!guard.can_render());
+        assert_eq!(guard.on_resized(PhysicalSize::new(800, 0)), None);
+        assert!(!guard.can_render());
+        assert_eq!(
+            guard.on_resized(PhysicalSize::new(800, 600)),
+            Some(PhysicalSize::new(800, 600))
+        );
+        assert!(guard.can_render());
+    }
+
+    /// 以 `update` 的累计次数决定清屏颜色的 app，`gpu` 为 `None` 时不支持离屏渲染
+    struct ClearApp {
+        frames: u32,
+        gpu: Option<(wgpu::Device, wgpu::Queue)>,
+    }
+
+    impl WgpuAppAction for ClearApp {
+        async fn new(_window: Arc<Window>) -> Self {
+            Self {
+                frames: 0,
+                gpu: None,
+            }
+        }
+
+        fn set_window_resized(&mut self, _new_size: PhysicalSize<u32>) {}
+
+        fn get_size(&self) -> PhysicalSize<u32> {
+            PhysicalSize::new(8, 4)
+        }
+
+        fn update(&mut self, _dt: instant::Duration) {
+            self.frames += 1;
+        }
+
+        fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
+            Ok(())
+        }
+
+        fn render_context(&self) -> Option<RenderContext<'_>> {
+            let (device, queue) = self.gpu.as_ref()?;
+            // 与常见的 surface 格式一致，读回时需交换 BGRA 通道
+            Some(RenderContext {
+                device,
+                queue,
+                format: wgpu::TextureFormat::Bgra8UnormSrgb,
+                size: self.get_size(),
+            })
+        }
+
+        fn render_to(&mut self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView) {
+            let red = if self.frames == 3 { 1.0 } else { 0.0 };
+            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color {
+                            r: red,
+                            g: 0.0,
+                            b: 0.0,
+                            a: 1.0,
+                        }),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                ..Default::default()
+            });
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn offscreen_frames() {
+        let Some(gpu) = crate::test_device() else {
+            return;
+        };
+        let mut app = ClearApp {
+            frames: 0,
+            gpu: Some(gpu),
+        };
+        let image = render_offscreen(&mut app, 3).unwrap();
+        assert_eq!(image.dimensions(), (8, 4));
+        assert!(image.pixels().all(|p| p.0 == [255, 0, 0, 255]));
+
+        let mut app = ClearApp {
+            frames: 0,
+            gpu: None,
+        };
+        assert!(render_offscreen(&mut app, 3).is_none());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn screenshot_renders_current_frame() {
+        let Some(gpu) = crate::test_device() else {
+            return;
+        };
+        // F12 截图时画面不再 update，重新渲染的就是第 3 帧
+        let mut app = ClearApp {
+            frames: 3,
+            gpu: Some(gpu),
+        };
+        let image = capture_app_frame(&mut app).unwrap();
+        assert_eq!(image.dimensions(), (8, 4));
+        assert!(image.pixels().all(|p| p.0 == [255, 0, 0, 255]));
+        assert_eq!(app.frames, 3);
+
+        app.gpu = None;
+        assert!(capture_app_frame(&mut app).is_none());
+    }
+}
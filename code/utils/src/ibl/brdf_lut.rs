@@ -0,0 +1,155 @@
+use crate::AnyTexture;
+
+/// BRDF 查找表的纹理格式
+pub const BRDF_LUT_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rg16Float;
+
+/// 生成 split-sum 近似所需的 BRDF 积分查找表（`size x size`，`Rg16Float`）
+///
+/// 纹理坐标 u 为 `n·v`，v 为粗糙度（纹理第 0 行对应粗糙度 0），texel 的两个通道是菲涅尔项 F0 的缩放与偏移：
+/// 镜面反射的 IBL 项为 `prefiltered_color * (F0 * lut.r + lut.g)`。查找表与场景无关，程序启动时生成一次即可。
+///
+/// 每个 texel 用 1024 个样本积分：样本点取自 Hammersley 低差异序列，再按 GGX 法线分布做重要性采样得到半程向量，
+/// 几何遮蔽项使用 IBL 版本的 Schlick-GGX（k = roughness² / 2）。
+///
+/// # NOTE:
+/// `Rg16Float` 在 WebGPU 中不能作为存储纹理，所以这里用全屏的渲染通道而非计算通道生成。
+pub fn generate_brdf_lut(device: &wgpu::Device, queue: &wgpu::Queue, size: u32) -> AnyTexture {
+    let lut = crate::load_texture::empty(
+        device,
+        BRDF_LUT_FORMAT,
+        wgpu::Extent3d {
+            width: size.max(1),
+            height: size.max(1),
+            depth_or_array_layers: 1,
+        },
+        None,
+        wgpu::TextureUsages::RENDER_ATTACHMENT
+            | wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::COPY_SRC,
+        Some("brdf lut"),
+    );
+
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("brdf lut"),
+        source: wgpu::ShaderSource::Wgsl(include_str!("brdf_lut.wgsl").into()),
+    });
+    let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("brdf lut pipeline"),
+        layout: None,
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: Some("vs_main"),
+            compilation_options: Default::default(),
+            buffers: &[],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: Some("fs_main"),
+            compilation_options: Default::default(),
+            targets: &[Some(BRDF_LUT_FORMAT.into())],
+        }),
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+        cache: None,
+    });
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("brdf lut encoder"),
+    });
+    {
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("brdf lut rpass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &lut.tex_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            ..Default::default()
+        });
+        rpass.set_pipeline(&pipeline);
+        rpass.draw(0..3, 0..1);
+    }
+    queue.submit(Some(encoder.finish()));
+
+    lut
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+
+    fn f16_to_f32(bits: u16) -> f32 {
+        let sign = if bits & 0x8000 == 0 { 1.0 } else { -1.0 };
+        let exponent = ((bits >> 10) & 0x1f) as i32;
+        let mantissa = (bits & 0x3ff) as f32;
+        match exponent {
+            0 => sign * mantissa * 2f32.powi(-24),
+            _ => sign * (1.0 + mantissa / 1024.0) * 2f32.powi(exponent - 15),
+        }
+    }
+
+    #[test]
+    fn lut_texels_match_reference() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        let size = 32;
+        let lut = generate_brdf_lut(&device, &queue, size);
+
+        // 每个 texel 4 字节，一行 128 字节，需按 256 字节对齐
+        let bytes_per_row = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: (bytes_per_row * size) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        encoder.copy_texture_to_buffer(
+            lut.tex.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(bytes_per_row),
+                    rows_per_image: Some(size),
+                },
+            },
+            lut.size,
+        );
+        queue.submit(Some(encoder.finish()));
+        let slice = buffer.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        crate::poll::poll_wait(&device);
+        let data = slice.get_mapped_range();
+        let texel = |x: u32, y: u32| {
+            let offset = (y * bytes_per_row + x * 4) as usize;
+            let channel =
+                |i: usize| f16_to_f32(u16::from_le_bytes([data[offset + i], data[offset + i + 1]]));
+            (channel(0), channel(2))
+        };
+
+        // 粗糙度接近 0 时半程向量即法线，G_vis = 1：scale = 1 - (1 - n·v)^5，bias = (1 - n·v)^5
+        for x in [4, 15, size - 1] {
+            let n_dot_v = (x as f32 + 0.5) / size as f32;
+            let fc = (1.0 - n_dot_v).powi(5);
+            let (scale, bias) = texel(x, 0);
+            assert!(
+                (scale - (1.0 - fc)).abs() < 0.02,
+                "x = {x}: scale = {scale}"
+            );
+            assert!((bias - fc).abs() < 0.02, "x = {x}: bias = {bias}");
+        }
+        // 能量守恒：scale + bias 不超过 1
+        for (x, y) in [(0, size - 1), (size - 1, size - 1), (15, 15)] {
+            let (scale, bias) = texel(x, y);
+            assert!(scale >= 0.0 && bias >= 0.0 && scale + bias <= 1.01);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+// split-sum 近似中的 BRDF 积分查找表
+// 横坐标（u）为 n·v，纵坐标（v，自上而下）为粗糙度；输出 (scale, bias)，镜面反射 = F0 * scale + bias
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) uv: vec2f,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let uv = vec2f(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    var out: VertexOutput;
+    out.position = vec4f(uv * 2.0 - 1.0, 0.0, 1.0);
+    out.uv = vec2f(uv.x, 1.0 - uv.y);
+    return out;
+}
+
+const PI: f32 = 3.14159265359;
+const SAMPLE_COUNT: u32 = 1024u;
+
+// Van der Corput 序列：把 i 的二进制位以小数点为轴镜像
+fn radical_inverse_vdc(bits: u32) -> f32 {
+    return f32(reverseBits(bits)) * 2.3283064365386963e-10;
+}
+
+// Hammersley 低差异序列，比随机采样收敛得更快
+fn hammersley(i: u32, n: u32) -> vec2f {
+    return vec2f(f32(i) / f32(n), radical_inverse_vdc(i));
+}
+
+// 按 GGX 法线分布做重要性采样，返回切线空间（法线为 +z）中的半程向量
+fn importance_sample_ggx(xi: vec2f, roughness: f32) -> vec3f {
+    let a = roughness * roughness;
+    let phi = 2.0 * PI * xi.x;
+    let cos_theta = sqrt((1.0 - xi.y) / (1.0 + (a * a - 1.0) * xi.y));
+    let sin_theta = sqrt(1.0 - cos_theta * cos_theta);
+    return vec3f(cos(phi) * sin_theta, sin(phi) * sin_theta, cos_theta);
+}
+
+// IBL 使用的 k = roughness^2 / 2
+fn geometry_schlick_ggx(n_dot_v: f32, roughness: f32) -> f32 {
+    let k = roughness * roughness / 2.0;
+    return n_dot_v / (n_dot_v * (1.0 - k) + k);
+}
+
+fn geometry_smith(n_dot_v: f32, n_dot_l: f32, roughness: f32) -> f32 {
+    return geometry_schlick_ggx(n_dot_v, roughness) * geometry_schlick_ggx(n_dot_l, roughness);
+}
+
+fn integrate_brdf(n_dot_v: f32, roughness: f32) -> vec2f {
+    let v = vec3f(sqrt(1.0 - n_dot_v * n_dot_v), 0.0, n_dot_v);
+    var scale = 0.0;
+    var bias = 0.0;
+    for (var i = 0u; i < SAMPLE_COUNT; i++) {
+        let h = importance_sample_ggx(hammersley(i, SAMPLE_COUNT), roughness);
+        let l = normalize(2.0 * dot(v, h) * h - v);
+        let n_dot_l = max(l.z, 0.0);
+        let n_dot_h = max(h.z, 0.0);
+        let v_dot_h = max(dot(v, h), 0.0);
+        if n_dot_l > 0.0 {
+            let g = geometry_smith(n_dot_v, n_dot_l, roughness);
+            let g_vis = g * v_dot_h / (n_dot_h * n_dot_v);
+            let fc = pow(1.0 - v_dot_h, 5.0);
+            scale += (1.0 - fc) * g_vis;
+            bias += fc * g_vis;
+        }
+    }
+    return vec2f(scale, bias) / f32(SAMPLE_COUNT);
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec2f {
+    return integrate_brdf(in.uv.x, in.uv.y);
+}
@@ -0,0 +1,303 @@
+use crate::{AnyTexture, BufferObj};
+use bytemuck::{Pod, Zeroable};
+
+const WORKGROUP_SIZE: u32 = 8;
+/// 辐照度图与预过滤镜面反射图的纹理格式
+pub const IBL_CUBE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+/// 辐照度图的边长：辐照度在方向上变化缓慢，低分辨率即可
+pub const IRRADIANCE_SIZE: u32 = 32;
+/// 预过滤时每个 texel 的 GGX 重要性采样数
+pub const PREFILTER_SAMPLE_COUNT: u32 = 512;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+struct ConvolutionParams {
+    roughness: f32,
+    sample_count: u32,
+    padding: [f32; 2],
+}
+
+/// 由环境立方体贴图生成漫反射辐照度图（`IRRADIANCE_SIZE` 边长的 `Rgba16Float` 立方体贴图）
+///
+/// 每个 texel 在其法线方向的半球上以 0.05 弧度的步长均匀积分（约 4000 个样本）。
+///
+/// # NOTE:
+/// `env_cube` 需为 `TextureViewDimension::Cube` 视图且带有 `TEXTURE_BINDING`；采样时使用最近邻过滤，
+/// 所以 `Rgba32Float` 这类不可过滤的格式也能直接使用。
+pub fn convolve_irradiance(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    env_cube: &AnyTexture,
+) -> AnyTexture {
+    let convolution = CubeConvolution::new(device);
+    let irradiance = create_cube(device, IRRADIANCE_SIZE, 1, "irradiance cube");
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("irradiance encoder"),
+    });
+    convolution.dispatch(
+        device,
+        &mut encoder,
+        &convolution.irradiance_pipeline,
+        env_cube,
+        &irradiance,
+        0,
+        0.0,
+    );
+    queue.submit(Some(encoder.finish()));
+    irradiance
+}
+
+/// 由环境立方体贴图生成按粗糙度预过滤的镜面反射图，共 `mips` 级 mipmap
+///
+/// 第 0 级与 `env_cube` 同尺寸，之后每级边长减半；第 m 级对应的粗糙度为 `m / (mips - 1)`，
+/// 着色时以 `roughness * (mips - 1)` 作为 LOD 采样。
+/// 每个 texel 取 `PREFILTER_SAMPLE_COUNT` 个 Hammersley 序列样本，按 GGX 分布做重要性采样（假设 n = v = r）。
+///
+/// # NOTE:
+/// 逐级（mip）录制一次计算通道，每次以调度的 z 维度（0..6）遍历立方体的 6 个面。
+/// 对 `env_cube` 的要求同 [`convolve_irradiance`]。
+pub fn prefilter_specular(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    env_cube: &AnyTexture,
+    mips: u32,
+) -> AnyTexture {
+    let size = env_cube.size.width;
+    let mips = mips.clamp(1, u32::BITS - size.leading_zeros());
+    let convolution = CubeConvolution::new(device);
+    let prefiltered = create_cube(device, size, mips, "prefiltered specular cube");
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("prefilter encoder"),
+    });
+    for mip in 0..mips {
+        let roughness = if mips > 1 {
+            mip as f32 / (mips - 1) as f32
+        } else {
+            0.0
+        };
+        convolution.dispatch(
+            device,
+            &mut encoder,
+            &convolution.prefilter_pipeline,
+            env_cube,
+            &prefiltered,
+            mip,
+            roughness,
+        );
+    }
+    queue.submit(Some(encoder.finish()));
+    prefiltered
+}
+
+struct CubeConvolution {
+    layout: wgpu::BindGroupLayout,
+    irradiance_pipeline: wgpu::ComputePipeline,
+    prefilter_pipeline: wgpu::ComputePipeline,
+    sampler: wgpu::Sampler,
+}
+
+impl CubeConvolution {
+    fn new(device: &wgpu::Device) -> Self {
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("cube convolution"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::Cube,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::NonFiltering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::StorageTexture {
+                        access: wgpu::StorageTextureAccess::WriteOnly,
+                        format: IBL_CUBE_FORMAT,
+                        view_dimension: wgpu::TextureViewDimension::D2Array,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("cube convolution"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("cube_convolution.wgsl").into()),
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("cube convolution"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let create_pipeline = |entry_point| {
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(entry_point),
+                layout: Some(&pipeline_layout),
+                module: &shader,
+                entry_point: Some(entry_point),
+                compilation_options: Default::default(),
+                cache: None,
+            })
+        };
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("env cube sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            ..Default::default()
+        });
+        Self {
+            irradiance_pipeline: create_pipeline("irradiance"),
+            prefilter_pipeline: create_pipeline("prefilter"),
+            layout,
+            sampler,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn dispatch(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        pipeline: &wgpu::ComputePipeline,
+        env_cube: &AnyTexture,
+        dst: &AnyTexture,
+        mip: u32,
+        roughness: f32,
+    ) {
+        let params = ConvolutionParams {
+            roughness,
+            sample_count: PREFILTER_SAMPLE_COUNT,
+            padding: [0.0; 2],
+        };
+        let params_buf =
+            BufferObj::create_uniform_buffer(device, &params, Some("cube convolution params"));
+        let dst_view = dst.tex.create_view(&wgpu::TextureViewDescriptor {
+            label: Some("cube convolution dst"),
+            dimension: Some(wgpu::TextureViewDimension::D2Array),
+            base_mip_level: mip,
+            mip_level_count: Some(1),
+            ..Default::default()
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("cube convolution"),
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&env_cube.tex_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(&dst_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: params_buf.buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let size = (dst.size.width >> mip).max(1);
+        let groups = size.div_ceil(WORKGROUP_SIZE);
+        let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("cube convolution"),
+            timestamp_writes: None,
+        });
+        cpass.set_pipeline(pipeline);
+        cpass.set_bind_group(0, &bind_group, &[]);
+        // z 维度遍历立方体的 6 个面
+        cpass.dispatch_workgroups(groups, groups, 6);
+    }
+}
+
+fn create_cube(device: &wgpu::Device, size: u32, mips: u32, label: &str) -> AnyTexture {
+    let extent = wgpu::Extent3d {
+        width: size,
+        height: size,
+        depth_or_array_layers: 6,
+    };
+    let tex = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(label),
+        size: extent,
+        mip_level_count: mips,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: IBL_CUBE_FORMAT,
+        usage: wgpu::TextureUsages::STORAGE_BINDING
+            | wgpu::TextureUsages::TEXTURE_BINDING
+            | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+    let tex_view = tex.create_view(&wgpu::TextureViewDescriptor {
+        label: Some(label),
+        dimension: Some(wgpu::TextureViewDimension::Cube),
+        ..Default::default()
+    });
+    AnyTexture {
+        size: extent,
+        tex,
+        tex_view,
+        format: IBL_CUBE_FORMAT,
+        view_dimension: wgpu::TextureViewDimension::Cube,
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn convolve_constant_env() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let env_cube = create_cube(&device, 16, 1, "env cube");
+        // 各方向辐射度都为 1 的环境（0x3c00 为半精度浮点数的 1.0）
+        let half_texels = vec![0x3c00_u16; 16 * 16 * 6 * 4];
+        queue.write_texture(
+            env_cube.tex.as_image_copy(),
+            bytemuck::cast_slice(&half_texels),
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(16 * 8),
+                rows_per_image: Some(16),
+            },
+            env_cube.size,
+        );
+
+        let irradiance = convolve_irradiance(&device, &queue, &env_cube);
+        let prefiltered = prefilter_specular(&device, &queue, &env_cube, 5);
+        crate::poll::poll_wait(&device);
+        assert!(pollster::block_on(device.pop_error_scope()).is_none());
+
+        assert_eq!(irradiance.size.width, IRRADIANCE_SIZE);
+        assert_eq!(irradiance.view_dimension, wgpu::TextureViewDimension::Cube);
+        assert_eq!(prefiltered.tex.mip_level_count(), 5);
+    }
+}
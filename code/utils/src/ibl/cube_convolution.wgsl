@@ -0,0 +1,102 @@
+// 环境立方体贴图的卷积：漫反射辐照度图与按粗糙度预过滤的镜面反射图
+
+struct ConvolutionParams {
+    roughness: f32,
+    // 每个 texel 的采样数（预过滤）
+    sample_count: u32,
+};
+
+@group(0) @binding(0) var env_cube: texture_cube<f32>;
+@group(0) @binding(1) var env_sampler: sampler;
+@group(0) @binding(2) var dst: texture_storage_2d_array<rgba16float, write>;
+@group(0) @binding(3) var<uniform> params: ConvolutionParams;
+
+const PI: f32 = 3.14159265359;
+// 辐照度卷积在半球上的角度步长（弧度）
+const IRRADIANCE_DELTA: f32 = 0.05;
+
+// 由立方体面的索引及该面上的纹理坐标（[-1, 1]，v 向下）求方向，与 wgpu 立方体贴图的面朝向约定一致
+fn cube_direction(face: u32, uv: vec2f) -> vec3f {
+    switch face {
+        case 0u: { return normalize(vec3f(1.0, -uv.y, -uv.x)); }
+        case 1u: { return normalize(vec3f(-1.0, -uv.y, uv.x)); }
+        case 2u: { return normalize(vec3f(uv.x, 1.0, uv.y)); }
+        case 3u: { return normalize(vec3f(uv.x, -1.0, -uv.y)); }
+        case 4u: { return normalize(vec3f(uv.x, -uv.y, 1.0)); }
+        default: { return normalize(vec3f(-uv.x, -uv.y, -1.0)); }
+    }
+}
+
+// 以 n 为 z 轴的切线空间基
+fn tangent_basis(n: vec3f) -> mat3x3f {
+    let up = select(vec3f(1.0, 0.0, 0.0), vec3f(0.0, 0.0, 1.0), abs(n.z) < 0.999);
+    let tangent = normalize(cross(up, n));
+    let bitangent = cross(n, tangent);
+    return mat3x3f(tangent, bitangent, n);
+}
+
+fn texel_direction(gid: vec3u) -> vec3f {
+    let size = vec2f(textureDimensions(dst));
+    let uv = (vec2f(gid.xy) + 0.5) / size * 2.0 - 1.0;
+    return cube_direction(gid.z, uv);
+}
+
+@compute @workgroup_size(8, 8, 1)
+fn irradiance(@builtin(global_invocation_id) gid: vec3u) {
+    if any(gid.xy >= textureDimensions(dst)) {
+        return;
+    }
+    let basis = tangent_basis(texel_direction(gid));
+
+    // 在半球上按 (phi, theta) 均匀步进，cos(theta) 为朗伯余弦项，sin(theta) 补偿高纬度处变小的立体角
+    var irradiance = vec3f(0.0);
+    var count = 0.0;
+    for (var phi = 0.0; phi < 2.0 * PI; phi += IRRADIANCE_DELTA) {
+        for (var theta = 0.0; theta < 0.5 * PI; theta += IRRADIANCE_DELTA) {
+            let local = vec3f(sin(theta) * cos(phi), sin(theta) * sin(phi), cos(theta));
+            let color = textureSampleLevel(env_cube, env_sampler, basis * local, 0.0).rgb;
+            irradiance += color * cos(theta) * sin(theta);
+            count += 1.0;
+        }
+    }
+    textureStore(dst, gid.xy, gid.z, vec4f(PI * irradiance / count, 1.0));
+}
+
+fn radical_inverse_vdc(bits: u32) -> f32 {
+    return f32(reverseBits(bits)) * 2.3283064365386963e-10;
+}
+
+fn hammersley(i: u32, n: u32) -> vec2f {
+    return vec2f(f32(i) / f32(n), radical_inverse_vdc(i));
+}
+
+fn importance_sample_ggx(xi: vec2f, roughness: f32) -> vec3f {
+    let a = roughness * roughness;
+    let phi = 2.0 * PI * xi.x;
+    let cos_theta = sqrt((1.0 - xi.y) / (1.0 + (a * a - 1.0) * xi.y));
+    let sin_theta = sqrt(1.0 - cos_theta * cos_theta);
+    return vec3f(cos(phi) * sin_theta, sin(phi) * sin_theta, cos_theta);
+}
+
+@compute @workgroup_size(8, 8, 1)
+fn prefilter(@builtin(global_invocation_id) gid: vec3u) {
+    if any(gid.xy >= textureDimensions(dst)) {
+        return;
+    }
+    // 预过滤时假设 n = v = r
+    let n = texel_direction(gid);
+    let basis = tangent_basis(n);
+
+    var color = vec3f(0.0);
+    var total_weight = 0.0;
+    for (var i = 0u; i < params.sample_count; i++) {
+        let h = basis * importance_sample_ggx(hammersley(i, params.sample_count), params.roughness);
+        let l = normalize(2.0 * dot(n, h) * h - n);
+        let n_dot_l = dot(n, l);
+        if n_dot_l > 0.0 {
+            color += textureSampleLevel(env_cube, env_sampler, l, 0.0).rgb * n_dot_l;
+            total_weight += n_dot_l;
+        }
+    }
+    textureStore(dst, gid.xy, gid.z, vec4f(color / max(total_weight, 1e-4), 1.0));
+}
@@ -0,0 +1,10 @@
+//! 基于图像的光照（IBL）所需的预计算资源
+
+mod brdf_lut;
+pub use brdf_lut::{BRDF_LUT_FORMAT, generate_brdf_lut};
+
+mod cube_convolution;
+pub use cube_convolution::{
+    IBL_CUBE_FORMAT, IRRADIANCE_SIZE, PREFILTER_SAMPLE_COUNT, convolve_irradiance,
+    prefilter_specular,
+};
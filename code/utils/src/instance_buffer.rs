@@ -0,0 +1,131 @@
+use bytemuck::Pod;
+use core::marker::PhantomData;
+
+/// 每帧更新的实例缓冲区，可选双缓冲
+///
+/// 单缓冲时每帧都改写同一个缓冲区：本帧数据的拷贝必须等上一帧读取该缓冲区的绘制命令执行完，
+/// CPU 提交得越快，GPU 上的这种串行等待越明显。双缓冲模式下 `update_next` 在两个缓冲区之间轮换，
+/// 写入的总是上一帧没有使用的那个，两帧之间不再争用同一块内存。
+///
+/// # NOTE:
+/// 双缓冲只在实例数据每帧都变化、且数据量较大时才有收益，代价是两倍的显存；
+/// 数据很少变化时使用单缓冲并只在变化时调用 `update` 即可。
+/// 绑定时必须使用每次更新之后的 `current_slice()`，不能缓存上一帧的缓冲区。
+pub struct InstanceBuffer<T> {
+    buffers: Vec<wgpu::Buffer>,
+    current: usize,
+    capacity: usize,
+    len: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Pod> InstanceBuffer<T> {
+    /// 创建可容纳 `capacity` 个实例的缓冲区，`double_buffered` 为 true 时创建两个
+    pub fn new(device: &wgpu::Device, capacity: usize, double_buffered: bool) -> Self {
+        let capacity = capacity.max(1);
+        let count = if double_buffered { 2 } else { 1 };
+        let buffers = (0..count)
+            .map(|_| {
+                device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("instance buffer"),
+                    size: (capacity * core::mem::size_of::<T>()) as wgpu::BufferAddress,
+                    usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                })
+            })
+            .collect();
+        Self {
+            buffers,
+            current: 0,
+            capacity,
+            len: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn is_double_buffered(&self) -> bool {
+        self.buffers.len() > 1
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// 当前的实例数
+    pub fn len(&self) -> u32 {
+        self.len as u32
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// 当前用于绑定的缓冲区的序号，单缓冲时始终为 0
+    pub fn current_index(&self) -> usize {
+        self.current
+    }
+
+    pub fn current_buffer(&self) -> &wgpu::Buffer {
+        &self.buffers[self.current]
+    }
+
+    /// 当前缓冲区中有效实例数据的范围，用于 `set_vertex_buffer`
+    pub fn current_slice(&self) -> wgpu::BufferSlice<'_> {
+        let size = (self.len.max(1) * core::mem::size_of::<T>()) as wgpu::BufferAddress;
+        self.current_buffer().slice(..size)
+    }
+
+    /// 改写当前缓冲区
+    pub fn update(&mut self, queue: &wgpu::Queue, data: &[T]) {
+        self.write(queue, data);
+    }
+
+    /// 切换到下一个缓冲区再写入，返回本帧需要绑定的缓冲区；单缓冲时与 `update` 相同
+    pub fn update_next(&mut self, queue: &wgpu::Queue, data: &[T]) -> &wgpu::Buffer {
+        self.current = (self.current + 1) % self.buffers.len();
+        self.write(queue, data);
+        self.current_buffer()
+    }
+
+    fn write(&mut self, queue: &wgpu::Queue, data: &[T]) {
+        assert!(
+            data.len() <= self.capacity,
+            "instance count {} exceeds capacity {}",
+            data.len(),
+            self.capacity
+        );
+        self.len = data.len();
+        if !data.is_empty() {
+            queue.write_buffer(&self.buffers[self.current], 0, bytemuck::cast_slice(data));
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn update_next_alternates_buffers() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        let mut instances = InstanceBuffer::<[f32; 4]>::new(&device, 16, true);
+        assert!(instances.is_double_buffered());
+        let first = instances.update_next(&queue, &[[1.0; 4]; 4]).clone();
+        assert_eq!(instances.current_index(), 1);
+        let second = instances.update_next(&queue, &[[2.0; 4]; 8]).clone();
+        assert_eq!(instances.current_index(), 0);
+        assert_ne!(first, second);
+        assert_eq!(instances.update_next(&queue, &[[3.0; 4]; 2]), &first);
+        assert_eq!(instances.len(), 2);
+        assert_eq!(instances.current_slice().size().get(), 2 * 16);
+
+        // 单缓冲时始终写入同一个缓冲区
+        let mut instances = InstanceBuffer::<[f32; 4]>::new(&device, 16, false);
+        instances.update_next(&queue, &[[1.0; 4]; 4]);
+        instances.update_next(&queue, &[[1.0; 4]; 4]);
+        assert_eq!(instances.current_index(), 0);
+    }
+}
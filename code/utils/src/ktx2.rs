@@ -0,0 +1,280 @@
+//! KTX2 压缩纹理加载
+//!
+//! 直接把文件中已压缩好的各级 mip（BC7/BC5/ETC2 等）上传到 GPU，不做重新编码，显存占用只有 PNG 解码后的几分之一。
+//!
+//! # NOTE:
+//! 只支持单层、非立方体、无超压缩（supercompression 为 0）的 2D 纹理；
+//! Basis Universal 等需要转码的文件请先用 `ktx` 命令行工具转换为目标格式。
+
+use crate::texture_budget::{mip_level_bytes, mip_size};
+use crate::{AnyTexture, TextureBudget};
+use wgpu::TextureFormat;
+
+/// KTX2 文件的标识符
+const IDENTIFIER: [u8; 12] = [
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+/// 标识符 + 9 个 u32 的文件头 + 4 个 u32 与 2 个 u64 的数据索引
+const LEVEL_INDEX_OFFSET: usize = 12 + 9 * 4 + 4 * 4 + 2 * 8;
+
+/// KTX2 加载失败的原因
+#[derive(Debug, Clone, PartialEq)]
+pub enum Ktx2Error {
+    /// 不是 KTX2 文件，或文件被截断
+    InvalidContainer,
+    /// 暂不支持的 `VkFormat`
+    UnsupportedVkFormat(u32),
+    /// 文件使用了超压缩（如 Basis Universal、Zstandard）
+    Supercompressed(u32),
+    /// 纹理数组、立方体贴图或 3D 纹理
+    UnsupportedLayout,
+    /// 设备未开启该压缩格式所需的特性，如 `TEXTURE_COMPRESSION_BC`
+    MissingFeatures {
+        format: TextureFormat,
+        features: wgpu::Features,
+    },
+    /// 第 `level` 级 mip 的数据长度与格式、尺寸不符
+    InvalidLevel { level: u32 },
+}
+
+impl core::fmt::Display for Ktx2Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidContainer => write!(f, "not a valid KTX2 container"),
+            Self::UnsupportedVkFormat(vk_format) => {
+                write!(f, "unsupported VkFormat {vk_format}")
+            }
+            Self::Supercompressed(scheme) => {
+                write!(f, "supercompression scheme {scheme} is not supported")
+            }
+            Self::UnsupportedLayout => {
+                write!(f, "only single-layer 2D textures are supported")
+            }
+            Self::MissingFeatures { format, features } => write!(
+                f,
+                "{format:?} requires device features {features:?}, which are not enabled by this backend"
+            ),
+            Self::InvalidLevel { level } => write!(f, "mip level {level} has an invalid length"),
+        }
+    }
+}
+
+impl std::error::Error for Ktx2Error {}
+
+/// 解析后的 KTX2 纹理，`levels[i]` 为第 i 级 mip 的数据
+#[derive(Debug)]
+pub struct Ktx2Texture<'a> {
+    pub format: TextureFormat,
+    pub size: (u32, u32),
+    pub levels: Vec<&'a [u8]>,
+}
+
+/// 解析 KTX2 文件并把全部 mip 层级上传为纹理
+///
+/// 纹理格式由文件的 `VkFormat` 决定，需要的设备特性（如 BC 需要 `TEXTURE_COMPRESSION_BC`，
+/// ETC2 需要 `TEXTURE_COMPRESSION_ETC2`）未开启时返回 [`Ktx2Error::MissingFeatures`]，
+/// 调用方可以据此改为加载另一种格式的文件或 PNG。
+///
+/// 提供 `budget` 时与 [`AnyTexture::from_mip_levels`] 相同，按剩余预算跳过最大的若干层级。
+pub fn load_ktx2(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    bytes: &[u8],
+    budget: Option<&mut TextureBudget>,
+    label: Option<&'static str>,
+) -> Result<AnyTexture, Ktx2Error> {
+    let ktx2 = parse_ktx2(bytes)?;
+    let features = ktx2.format.required_features();
+    if !device.features().contains(features) {
+        return Err(Ktx2Error::MissingFeatures {
+            format: ktx2.format,
+            features: features - device.features(),
+        });
+    }
+    Ok(AnyTexture::from_mip_levels(
+        device,
+        queue,
+        ktx2.format,
+        ktx2.size,
+        &ktx2.levels,
+        budget,
+        label,
+    ))
+}
+
+/// 解析 KTX2 文件头与 mip 层级索引，不访问 GPU
+pub fn parse_ktx2(bytes: &[u8]) -> Result<Ktx2Texture<'_>, Ktx2Error> {
+    if bytes.len() < LEVEL_INDEX_OFFSET || bytes[..12] != IDENTIFIER {
+        return Err(Ktx2Error::InvalidContainer);
+    }
+    let header = |i: usize| read_u32(bytes, 12 + i * 4).unwrap_or(0);
+    let (vk_format, width, height, depth) = (header(0), header(2), header(3), header(4));
+    let (layer_count, face_count, level_count) = (header(5), header(6), header(7));
+    let supercompression = header(8);
+
+    if supercompression != 0 {
+        return Err(Ktx2Error::Supercompressed(supercompression));
+    }
+    if depth > 1 || layer_count > 1 || face_count != 1 || width == 0 || height == 0 {
+        return Err(Ktx2Error::UnsupportedLayout);
+    }
+    let format = texture_format(vk_format).ok_or(Ktx2Error::UnsupportedVkFormat(vk_format))?;
+    // levelCount 为 0 表示需要运行时生成 mip，文件中只有第 0 级
+    let level_count = level_count.max(1);
+
+    let levels = (0..level_count)
+        .map(|level| {
+            let entry = LEVEL_INDEX_OFFSET + level as usize * 3 * 8;
+            let offset = read_u64(bytes, entry).ok_or(Ktx2Error::InvalidContainer)?;
+            let length = read_u64(bytes, entry + 8).ok_or(Ktx2Error::InvalidContainer)?;
+            let expected = mip_level_bytes(format, mip_size((width, height), level)) as u64;
+            if length != expected {
+                return Err(Ktx2Error::InvalidLevel { level });
+            }
+            // 损坏的文件中 offset + length 可能溢出
+            let start = usize::try_from(offset).ok();
+            let end = start.and_then(|start| start.checked_add(usize::try_from(length).ok()?));
+            start
+                .zip(end)
+                .and_then(|(start, end)| bytes.get(start..end))
+                .ok_or(Ktx2Error::InvalidContainer)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Ktx2Texture {
+        format,
+        size: (width, height),
+        levels,
+    })
+}
+
+/// `VkFormat` 对应的 wgpu 纹理格式
+pub fn texture_format(vk_format: u32) -> Option<TextureFormat> {
+    use wgpu::{AstcBlock, AstcChannel};
+    let format = match vk_format {
+        37 => TextureFormat::Rgba8Unorm,
+        43 => TextureFormat::Rgba8UnormSrgb,
+        133 => TextureFormat::Bc1RgbaUnorm,
+        134 => TextureFormat::Bc1RgbaUnormSrgb,
+        137 => TextureFormat::Bc3RgbaUnorm,
+        138 => TextureFormat::Bc3RgbaUnormSrgb,
+        139 => TextureFormat::Bc4RUnorm,
+        141 => TextureFormat::Bc5RgUnorm,
+        142 => TextureFormat::Bc5RgSnorm,
+        145 => TextureFormat::Bc7RgbaUnorm,
+        146 => TextureFormat::Bc7RgbaUnormSrgb,
+        147 => TextureFormat::Etc2Rgb8Unorm,
+        148 => TextureFormat::Etc2Rgb8UnormSrgb,
+        151 => TextureFormat::Etc2Rgba8Unorm,
+        152 => TextureFormat::Etc2Rgba8UnormSrgb,
+        157 => TextureFormat::Astc {
+            block: AstcBlock::B4x4,
+            channel: AstcChannel::Unorm,
+        },
+        158 => TextureFormat::Astc {
+            block: AstcBlock::B4x4,
+            channel: AstcChannel::UnormSrgb,
+        },
+        _ => return None,
+    };
+    Some(format)
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let data = bytes.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes(data.try_into().ok()?))
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> Option<u64> {
+    let data = bytes.get(offset..offset + 8)?;
+    Some(u64::from_le_bytes(data.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// 构造只含文件头与层级索引的 KTX2 文件，`levels` 紧跟在索引之后
+    fn ktx2_file(vk_format: u32, size: (u32, u32), levels: &[Vec<u8>]) -> Vec<u8> {
+        let mut bytes = IDENTIFIER.to_vec();
+        let header = [
+            vk_format,
+            1,
+            size.0,
+            size.1,
+            0,
+            0,
+            1,
+            levels.len() as u32,
+            0,
+        ];
+        header
+            .iter()
+            .for_each(|v| bytes.extend_from_slice(&v.to_le_bytes()));
+        // dfd、kvd、sgd 的偏移与长度都为 0
+        bytes.extend_from_slice(&[0; 4 * 4 + 2 * 8]);
+        let mut offset = (LEVEL_INDEX_OFFSET + levels.len() * 3 * 8) as u64;
+        for level in levels {
+            let length = level.len() as u64;
+            for v in [offset, length, length] {
+                bytes.extend_from_slice(&v.to_le_bytes());
+            }
+            offset += length;
+        }
+        levels.iter().for_each(|l| bytes.extend_from_slice(l));
+        bytes
+    }
+
+    #[test]
+    fn parse_mip_levels() {
+        let levels: Vec<Vec<u8>> = vec![vec![1; 4 * 4 * 4], vec![2; 2 * 2 * 4], vec![3; 4]];
+        let bytes = ktx2_file(43, (4, 4), &levels);
+        let ktx2 = parse_ktx2(&bytes).unwrap();
+        assert_eq!(ktx2.format, TextureFormat::Rgba8UnormSrgb);
+        assert_eq!(ktx2.size, (4, 4));
+        assert_eq!(
+            ktx2.levels,
+            vec![&levels[0][..], &levels[1][..], &levels[2][..]]
+        );
+
+        // BC7 的 8x8 纹理：4 个 16 字节的块
+        let bytes = ktx2_file(145, (8, 8), &[vec![0; 64]]);
+        let ktx2 = parse_ktx2(&bytes).unwrap();
+        assert_eq!(ktx2.format, TextureFormat::Bc7RgbaUnorm);
+        assert!(
+            ktx2.format
+                .required_features()
+                .contains(wgpu::Features::TEXTURE_COMPRESSION_BC)
+        );
+    }
+
+    #[test]
+    fn reject_invalid_files() {
+        assert_eq!(
+            parse_ktx2(b"not ktx2").unwrap_err(),
+            Ktx2Error::InvalidContainer
+        );
+        assert_eq!(
+            parse_ktx2(&ktx2_file(9999, (4, 4), &[vec![0; 64]])).unwrap_err(),
+            Ktx2Error::UnsupportedVkFormat(9999)
+        );
+        assert_eq!(
+            parse_ktx2(&ktx2_file(43, (4, 4), &[vec![0; 60]])).unwrap_err(),
+            Ktx2Error::InvalidLevel { level: 0 }
+        );
+        let mut truncated = ktx2_file(43, (4, 4), &[vec![0; 64]]);
+        truncated.truncate(truncated.len() - 1);
+        assert_eq!(
+            parse_ktx2(&truncated).unwrap_err(),
+            Ktx2Error::InvalidContainer
+        );
+        // 第 0 级的偏移量接近 u64::MAX，加上长度后溢出
+        let mut overflowing = ktx2_file(43, (4, 4), &[vec![0; 64]]);
+        overflowing[LEVEL_INDEX_OFFSET..LEVEL_INDEX_OFFSET + 8]
+            .copy_from_slice(&(u64::MAX - 8).to_le_bytes());
+        assert_eq!(
+            parse_ktx2(&overflowing).unwrap_err(),
+            Ktx2Error::InvalidContainer
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use instant::{Duration, Instant};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// 最近若干帧的平均延迟
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LatencyEstimate {
+    /// 从 `update` 开始到 `render` 返回（命令已提交）
+    pub update_to_submit: Duration,
+    /// 从 `update` 开始到 GPU 执行完该帧的命令，app 未提供队列时为 `None`
+    pub update_to_gpu_done: Option<Duration>,
+    /// 参与平均的帧数
+    pub samples: usize,
+}
+
+/// 帧延迟的滚动统计
+///
+/// 时间戳使用 `instant::Instant`：原生平台上即 `std::time::Instant`，Web 平台上基于 `performance.now()`。
+///
+/// # NOTE:
+/// 这里只能测到“GPU 执行完毕”为止，而不是画面真正显示到屏幕上（scanout）的时刻：
+/// 之后还有交换链排队、合成器（及浏览器）合成、垂直同步等待与显示器本身的延迟，这些都无法从应用内观测。
+/// `on_submitted_work_done` 的回调只在设备被轮询时触发（原生平台上提交/获取下一帧纹理时会隐式轮询），
+/// 所以 GPU 完成时间会偏大最多约一帧；把结果当作延迟的下限参考即可。
+pub struct FrameLatency {
+    capacity: usize,
+    cpu: VecDeque<Duration>,
+    gpu: VecDeque<Duration>,
+    // GPU 完成回调在 wgpu 内部调用，先暂存在这里，下次统计时再取出
+    gpu_pending: Arc<Mutex<Vec<Duration>>>,
+}
+
+impl FrameLatency {
+    /// `capacity` 为参与滚动平均的帧数
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            cpu: VecDeque::new(),
+            gpu: VecDeque::new(),
+            gpu_pending: Arc::new(Mutex::new(vec![])),
+        }
+    }
+
+    /// 记录一帧从 `update` 开始到提交完成的时间
+    pub fn record_submit(&mut self, update_start: Instant, submitted: Instant) {
+        let latency = submitted.saturating_duration_since(update_start);
+        Self::push(&mut self.cpu, self.capacity, latency);
+    }
+
+    /// 记录一帧从 `update` 开始到 GPU 执行完毕的时间
+    pub fn record_gpu_done(&mut self, update_start: Instant, done: Instant) {
+        let latency = done.saturating_duration_since(update_start);
+        Self::push(&mut self.gpu, self.capacity, latency);
+    }
+
+    /// 在本帧的命令提交之后调用，GPU 执行完这些命令时记录完成时间
+    pub fn track_gpu_done(&self, queue: &wgpu::Queue, update_start: Instant) {
+        let pending = self.gpu_pending.clone();
+        queue.on_submitted_work_done(move || {
+            pending
+                .lock()
+                .push(Instant::now().saturating_duration_since(update_start));
+        });
+    }
+
+    /// 当前的滚动平均延迟
+    pub fn estimate(&mut self) -> LatencyEstimate {
+        let pending: Vec<Duration> = self.gpu_pending.lock().drain(..).collect();
+        for latency in pending {
+            Self::push(&mut self.gpu, self.capacity, latency);
+        }
+        LatencyEstimate {
+            update_to_submit: Self::average(&self.cpu).unwrap_or_default(),
+            update_to_gpu_done: Self::average(&self.gpu),
+            samples: self.cpu.len(),
+        }
+    }
+
+    fn push(samples: &mut VecDeque<Duration>, capacity: usize, latency: Duration) {
+        if samples.len() == capacity {
+            samples.pop_front();
+        }
+        samples.push_back(latency);
+    }
+
+    fn average(samples: &VecDeque<Duration>) -> Option<Duration> {
+        if samples.is_empty() {
+            None
+        } else {
+            Some(samples.iter().sum::<Duration>() / samples.len() as u32)
+        }
+    }
+}
+
+impl Default for FrameLatency {
+    fn default() -> Self {
+        Self::new(60)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rolling_average() {
+        let mut latency = FrameLatency::new(2);
+        let start = Instant::now();
+        let ms = Duration::from_millis;
+
+        assert_eq!(latency.estimate(), LatencyEstimate::default());
+
+        latency.record_submit(start, start + ms(4));
+        latency.record_submit(start, start + ms(8));
+        latency.record_gpu_done(start, start + ms(20));
+        let estimate = latency.estimate();
+        assert_eq!(estimate.update_to_submit, ms(6));
+        assert_eq!(estimate.update_to_gpu_done, Some(ms(20)));
+        assert_eq!(estimate.samples, 2);
+
+        // 超出容量时丢弃最早的一帧
+        latency.record_submit(start, start + ms(12));
+        assert_eq!(latency.estimate().update_to_submit, ms(10));
+        // 早于 update 开始的时间戳按 0 计算
+        latency.record_gpu_done(start + ms(5), start);
+        assert_eq!(latency.estimate().update_to_gpu_done, Some(ms(10)));
+    }
+}
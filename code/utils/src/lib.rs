@@ -1,26 +1,108 @@
 pub mod framework;
-pub use framework::{WgpuAppAction, run};
+#[cfg(not(target_arch = "wasm32"))]
+pub use framework::run_headless;
+pub use framework::{
+    RenderContext, RunConfig, WgpuAppAction, create_app_surface, run, run_with_config, scroll_lines,
+};
 
+pub mod alpha_mode;
+pub mod assets;
+pub mod backend;
+pub mod compute;
+#[cfg(feature = "serde")]
+pub mod config;
+pub mod debug_overlay;
+pub mod frame;
+pub mod ibl;
+pub mod ktx2;
 pub mod load_texture;
 pub use load_texture::{
-    AnyTexture, bilinear_sampler, default_sampler, mirror_repeate_sampler, repeate_sampler,
+    AnyTexture, anisotropic_sampler, bilinear_sampler, default_cube_sampler, default_sampler,
+    full_mip_level_count, generate_mipmaps, lod_clamped_sampler, mirror_repeate_sampler,
+    repeate_sampler,
 };
 pub mod node;
+pub mod picking;
+pub mod poll;
+pub mod post;
+pub mod present_mode;
+pub mod reflect;
+pub mod settings;
+pub mod sprite;
+pub mod surface_format;
+pub mod terrain;
+pub mod text;
 
 mod plane;
 pub use plane::Plane;
 
 mod buffer;
-pub use buffer::BufferObj;
+pub use buffer::{BufferObj, DynamicUniformRing, ReadBackError, TypedBufferObj};
+
+mod viewport;
+pub use viewport::Viewport;
+
+mod resize_debouncer;
+pub use resize_debouncer::ResizeDebouncer;
+
+mod touch;
+pub use touch::ActiveTouches;
+
+mod surface_error;
+pub use surface_error::{SurfaceErrorAction, SurfaceErrorPolicy};
+
+mod texture_budget;
+pub use texture_budget::{TextureBudget, mip_chain_bytes, mip_size};
+
+mod transform;
+pub use transform::Transform;
+
+mod culling;
+pub use culling::{CullingCamera, Frustum};
+
+mod timestep;
+pub use timestep::FixedTimestep;
+
+mod frame_limit;
+pub use frame_limit::FrameLimit;
+
+mod frame_pacer;
+pub use frame_pacer::FramePacer;
 
+mod latency;
+pub use latency::{FrameLatency, LatencyEstimate};
+
+mod instance_buffer;
+pub use instance_buffer::InstanceBuffer;
+
+mod light;
+pub use light::{LIGHT_STORE_WGSL, LightStore, PointLight};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod capture;
+
+pub mod camera;
 pub mod matrix_helper;
+pub mod mrt;
 pub mod vertex;
 
-mod color;
+pub mod color;
 pub use color::*;
 
+mod color_space_split;
+pub use color_space_split::ColorSpaceSplit;
+
+mod log_capture;
+pub use log_capture::LogCapture;
+
 use bytemuck::{Pod, Zeroable};
 
+/// 深度纹理格式
+///
+/// # NOTE:
+/// 默认搭配 `perspective_rh` 与 `CompareFunction::Less`、清除值 1.0；
+/// 大场景可改用 `matrix_helper::perspective_reversed_z`，此时深度比较为 `Greater`、清除值为 0.0，
+/// 见 `matrix_helper::reversed_z_depth_stencil_state`。
 pub static DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
 
 #[repr(C)]
@@ -131,6 +213,20 @@ pub fn init_logger() {
     }
 }
 
+/// 使用自定义的 `log::Log` 实现作为日志输出，如测试中用 `LogCapture` 收集日志
+///
+/// # NOTE:
+/// 全局日志器在进程内只能设置一次，之后再调用此函数或 `init_logger` 都会失败；
+/// `init_logger` 的默认行为不受影响。
+pub fn init_logger_with_sink(
+    sink: Box<dyn log::Log>,
+    level: log::LevelFilter,
+) -> Result<(), log::SetLoggerError> {
+    log::set_boxed_logger(sink)?;
+    log::set_max_level(level);
+    Ok(())
+}
+
 #[cfg(target_arch = "wasm32")]
 fn parse_url_query_string<'a>(query: &'a str, search_key: &str) -> Option<&'a str> {
     let query_string = query.strip_prefix('?')?;
@@ -147,3 +243,27 @@ fn parse_url_query_string<'a>(query: &'a str, search_key: &str) -> Option<&'a st
 
     None
 }
+
+/// 测试用的 device/queue
+///
+/// 没有可用的 GPU 适配器（如 CI 环境）时打印跳过信息并返回 `None`，调用方直接 `return` 即可；
+/// 用 `cargo test -- --nocapture` 可以看到哪些 GPU 测试被跳过。
+/// 其它 crate 的测试需在 dev-dependencies 中为 utils 启用 `test-util` 特性才能使用。
+#[cfg(all(any(test, feature = "test-util"), not(target_arch = "wasm32")))]
+#[track_caller]
+pub fn test_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+    let instance = wgpu::Instance::default();
+    let adapter =
+        match pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+        {
+            Ok(adapter) => adapter,
+            Err(e) => {
+                eprintln!(
+                    "skipping GPU test at {}: no adapter ({e})",
+                    std::panic::Location::caller()
+                );
+                return None;
+            }
+        };
+    Some(pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default())).unwrap())
+}
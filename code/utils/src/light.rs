@@ -0,0 +1,192 @@
+use crate::BufferObj;
+use bytemuck::{Pod, Zeroable};
+
+/// 点光源
+///
+/// # NOTE:
+/// 存储缓冲区按 std430 规则布局：`vec3f` 按 16 字节对齐但只占 12 字节，其后的 `f32` 正好填满剩余的 4 字节，
+/// 所以每个光源为紧凑的 32 字节，数组步长也是 32 字节，Rust 端无需额外的填充字段。
+/// （uniform 缓冲区的 std140 规则下，数组元素需按 16 字节对齐，同样的结构体也恰好满足。）
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct PointLight {
+    pub position: [f32; 3],
+    /// 光照的影响范围，超出此距离不再计算
+    pub range: f32,
+    pub color: [f32; 3],
+    pub intensity: f32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+struct LightCount {
+    count: u32,
+    padding: [u32; 3],
+}
+
+/// 与 `PointLight`、`LightStore` 对应的 WGSL 声明，可拼接到着色器源码之前
+///
+/// 绑定序号需按实际的 `BindGroupData` 调整：uniform 在前，存储缓冲区在后。
+pub const LIGHT_STORE_WGSL: &str = r#"
+struct PointLight {
+    position: vec3f,
+    range: f32,
+    color: vec3f,
+    intensity: f32,
+};
+"#;
+
+/// 以存储缓冲区保存任意数量点光源的光源列表，用于多光源的前向渲染
+///
+/// 着色器中以只读存储缓冲区绑定光源数组，并通过一个 uniform 获取光源数，循环到该数量为止：
+///
+/// ```wgsl
+/// @group(1) @binding(0) var<uniform> light_count: vec4u;
+/// @group(1) @binding(1) var<storage, read> lights: array<PointLight>;
+///
+/// for (var i = 0u; i < light_count.x; i++) { let light = lights[i]; ... }
+/// ```
+///
+/// 使用 `BindGroupData` 时，把 `count_buffer()` 放入 `uniforms`，`light_buffer()` 放入 `storage_buffers`。
+///
+/// # NOTE:
+/// 光源数超出缓冲区容量时 `update` 会以两倍容量重新创建存储缓冲区，此时引用旧缓冲区的绑定组需要重建。
+pub struct LightStore {
+    lights: Vec<PointLight>,
+    capacity: usize,
+    light_buf: BufferObj,
+    count_buf: BufferObj,
+}
+
+#[allow(dead_code)]
+impl LightStore {
+    pub fn new(device: &wgpu::Device, capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            lights: vec![],
+            capacity,
+            light_buf: create_light_buffer(device, capacity),
+            count_buf: BufferObj::create_uniform_buffer(
+                device,
+                &LightCount {
+                    count: 0,
+                    padding: [0; 3],
+                },
+                Some("light count"),
+            ),
+        }
+    }
+
+    /// 添加光源，返回其索引
+    pub fn push(&mut self, light: PointLight) -> usize {
+        self.lights.push(light);
+        self.lights.len() - 1
+    }
+
+    pub fn lights(&self) -> &[PointLight] {
+        &self.lights
+    }
+
+    pub fn lights_mut(&mut self) -> &mut [PointLight] {
+        &mut self.lights
+    }
+
+    pub fn clear(&mut self) {
+        self.lights.clear();
+    }
+
+    pub fn count(&self) -> u32 {
+        self.lights.len() as u32
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn light_buffer(&self) -> &BufferObj {
+        &self.light_buf
+    }
+
+    pub fn count_buffer(&self) -> &BufferObj {
+        &self.count_buf
+    }
+
+    /// 写入存储缓冲区中的光源数据（std430 布局），长度为光源数 * 32 字节
+    pub fn to_bytes(&self) -> &[u8] {
+        bytemuck::cast_slice(&self.lights)
+    }
+
+    /// 把光源列表上传到 GPU，返回存储缓冲区是否因扩容而被重新创建（需要重建绑定组）
+    pub fn update(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) -> bool {
+        let reallocated = self.lights.len() > self.capacity;
+        if reallocated {
+            self.capacity = self.lights.len().next_power_of_two();
+            self.light_buf = create_light_buffer(device, self.capacity);
+        }
+        if !self.lights.is_empty() {
+            queue.write_buffer(&self.light_buf.buffer, 0, self.to_bytes());
+        }
+        let count = LightCount {
+            count: self.count(),
+            padding: [0; 3],
+        };
+        queue.write_buffer(&self.count_buf.buffer, 0, bytemuck::bytes_of(&count));
+        reallocated
+    }
+}
+
+fn create_light_buffer(device: &wgpu::Device, capacity: usize) -> BufferObj {
+    let mut buf = BufferObj::create_storage_buffer(
+        device,
+        &vec![PointLight::default(); capacity],
+        Some("point lights"),
+    );
+    buf.read_only = true;
+    buf
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn std430_layout() {
+        assert_eq!(std::mem::size_of::<PointLight>(), 32);
+        assert_eq!(std::mem::offset_of!(PointLight, range), 12);
+        assert_eq!(std::mem::offset_of!(PointLight, color), 16);
+        assert_eq!(std::mem::offset_of!(PointLight, intensity), 28);
+
+        let light = PointLight {
+            position: [1.0, 2.0, 3.0],
+            range: 10.0,
+            color: [0.5, 0.25, 1.0],
+            intensity: 4.0,
+        };
+        let floats: &[f32] = bytemuck::cast_slice(bytemuck::bytes_of(&light));
+        assert_eq!(floats, &[1.0, 2.0, 3.0, 10.0, 0.5, 0.25, 1.0, 4.0]);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn push_and_serialize() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        let mut store = LightStore::new(&device, 2);
+        for i in 0..3 {
+            let index = store.push(PointLight {
+                position: [i as f32, 0.0, 0.0],
+                ..Default::default()
+            });
+            assert_eq!(index, i);
+        }
+        assert_eq!(store.count(), 3);
+        assert_eq!(store.to_bytes().len(), 3 * 32);
+        // 第 3 个光源超出了容量
+        assert!(store.update(&device, &queue));
+        assert_eq!(store.capacity(), 4);
+        assert_eq!(store.light_buffer().size, 4 * 32);
+        assert!(!store.update(&device, &queue));
+    }
+}
@@ -1,6 +1,8 @@
 #[cfg(not(target_arch = "wasm32"))]
 use std::path::PathBuf;
 
+use crate::TextureBudget;
+use crate::texture_budget::{block_layout, mip_size};
 use image::{DynamicImage, GenericImageView};
 use wgpu::{Extent3d, Sampler, Texture, TextureFormat, TextureView};
 
@@ -12,6 +14,588 @@ pub struct AnyTexture {
     pub view_dimension: wgpu::TextureViewDimension,
 }
 
+/// 纹理区域超出范围的错误
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RegionOutOfBounds {
+    pub origin: wgpu::Origin3d,
+    pub size: Extent3d,
+    pub bounds: Extent3d,
+}
+
+impl core::fmt::Display for RegionOutOfBounds {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "region {:?} + {:?} is out of bounds {:?}",
+            self.origin, self.size, self.bounds
+        )
+    }
+}
+
+impl std::error::Error for RegionOutOfBounds {}
+
+/// [`AnyTexture::write_region`] 失败的原因
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WriteRegionError {
+    /// 区域超出图片或纹理的范围
+    OutOfBounds(RegionOutOfBounds),
+    /// 纹理格式不是 `R8Unorm`、`Rgba8Unorm` 或 `Rgba8UnormSrgb`，无法由图片转换得到
+    UnsupportedFormat(TextureFormat),
+}
+
+impl core::fmt::Display for WriteRegionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::OutOfBounds(e) => write!(f, "{e}"),
+            Self::UnsupportedFormat(format) => {
+                write!(f, "cannot write an image region into a {format:?} texture")
+            }
+        }
+    }
+}
+
+impl std::error::Error for WriteRegionError {}
+
+impl From<RegionOutOfBounds> for WriteRegionError {
+    fn from(e: RegionOutOfBounds) -> Self {
+        Self::OutOfBounds(e)
+    }
+}
+
+/// mip 层级范围超出纹理 mip 数的错误
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InvalidMipRange {
+    pub base_mip: u32,
+    pub mip_count: u32,
+    pub texture_mip_count: u32,
+}
+
+impl core::fmt::Display for InvalidMipRange {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "mip range {}..{} is invalid for a texture with {} mip levels",
+            self.base_mip,
+            self.base_mip + self.mip_count,
+            self.texture_mip_count
+        )
+    }
+}
+
+impl std::error::Error for InvalidMipRange {}
+
+/// 纹理数组中的图片尺寸不一致的错误
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MismatchedImageSize {
+    pub index: usize,
+    pub expected: (u32, u32),
+    pub found: (u32, u32),
+}
+
+impl core::fmt::Display for MismatchedImageSize {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "image {} has size {:?}, expected {:?}",
+            self.index, self.found, self.expected
+        )
+    }
+}
+
+impl std::error::Error for MismatchedImageSize {}
+
+impl AnyTexture {
+    /// 把图片中 `origin` 处大小为 `size` 的区域上传到纹理的同一位置
+    ///
+    /// 用于纹理图集或大图的局部/流式更新。区域需同时位于图片与纹理的范围内，否则返回错误。
+    ///
+    /// # NOTE:
+    /// 图片只会被转换为 luma8 或 rgba8，因此纹理格式需为 `R8Unorm`、`Rgba8Unorm` 或 `Rgba8UnormSrgb`，
+    /// 其它格式（包括块压缩格式）返回 [`WriteRegionError::UnsupportedFormat`]。
+    pub fn write_region(
+        &self,
+        queue: &wgpu::Queue,
+        img: &DynamicImage,
+        origin: wgpu::Origin3d,
+        size: Extent3d,
+    ) -> Result<(), WriteRegionError> {
+        let pixel_bytes = region_pixel_bytes(self.format)?;
+        check_region(origin, size, self.size)?;
+        let texels = region_texels(img, origin, size, self.format)?;
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &self.tex,
+                mip_level: 0,
+                origin,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &texels,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(pixel_bytes * size.width),
+                rows_per_image: Some(size.height),
+            },
+            size,
+        );
+        Ok(())
+    }
+
+    /// 创建只包含 `base_mip..base_mip + mip_count` 层级的纹理视图
+    ///
+    /// 着色器中 mip 0 对应视图的 `base_mip`，例如跳过 mip 0 可得到更模糊的采样结果，
+    /// 或在降采样链中每次只绑定其中一级。
+    pub fn view_mip_range(
+        &self,
+        base_mip: u32,
+        mip_count: u32,
+    ) -> Result<TextureView, InvalidMipRange> {
+        check_mip_range(base_mip, mip_count, self.tex.mip_level_count())?;
+        Ok(self.tex.create_view(&wgpu::TextureViewDescriptor {
+            label: Some("mip range view"),
+            dimension: Some(self.view_dimension),
+            base_mip_level: base_mip,
+            mip_level_count: Some(mip_count),
+            ..Default::default()
+        }))
+    }
+
+    /// 把多张尺寸相同的图片加载为一个 2D 纹理数组，每张图片占一层
+    ///
+    /// 着色器中以 `texture_2d_array<f32>` 声明，并通过层索引采样：
+    /// `textureSample(tex_array, tex_sampler, uv, layer_index)`
+    pub fn array_from_images(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        images: &[DynamicImage],
+        label: Option<&'static str>,
+    ) -> Result<Self, MismatchedImageSize> {
+        let size = array_extent(images)?;
+        let format = TextureFormat::Rgba8UnormSrgb;
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            label,
+            view_formats: &[format.remove_srgb_suffix()],
+        });
+        for (layer, img) in images.iter().enumerate() {
+            queue.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    texture: &texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d {
+                        x: 0,
+                        y: 0,
+                        z: layer as u32,
+                    },
+                    aspect: wgpu::TextureAspect::All,
+                },
+                &img.to_rgba8(),
+                wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(4 * size.width),
+                    rows_per_image: Some(size.height),
+                },
+                Extent3d {
+                    depth_or_array_layers: 1,
+                    ..size
+                },
+            );
+        }
+        let tex_view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label,
+            format: Some(format.remove_srgb_suffix()),
+            dimension: Some(wgpu::TextureViewDimension::D2Array),
+            ..Default::default()
+        });
+
+        Ok(AnyTexture {
+            size,
+            tex: texture,
+            tex_view,
+            format,
+            view_dimension: wgpu::TextureViewDimension::D2Array,
+        })
+    }
+
+    /// 由已生成好的 mip 链创建纹理，`levels[i]` 为第 i 级（紧密排列）的数据，如 KTX2 文件解码后的各层级
+    ///
+    /// 提供 `budget` 时按剩余预算跳过最大的若干层级，从较小的基础层级开始加载，见 [`TextureBudget`]；
+    /// 返回的纹理尺寸即实际加载的基础层级尺寸。
+    pub fn from_mip_levels(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        format: TextureFormat,
+        size: (u32, u32),
+        levels: &[&[u8]],
+        budget: Option<&mut TextureBudget>,
+        label: Option<&'static str>,
+    ) -> Self {
+        let mip_count = levels.len().max(1) as u32;
+        let skip = budget.map_or(0, |b| b.reserve(format, size, mip_count));
+        let (width, height) = mip_size(size, skip);
+        let size = Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            size,
+            mip_level_count: mip_count - skip,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            label,
+            view_formats: &[],
+        });
+        for (mip_level, data) in levels.iter().skip(skip as usize).enumerate() {
+            let mip_level = mip_level as u32;
+            let (rows, bytes_per_row) = block_layout(format, mip_size((width, height), mip_level));
+            queue.write_texture(
+                wgpu::TexelCopyTextureInfo {
+                    texture: &texture,
+                    mip_level,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                data,
+                wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(bytes_per_row),
+                    rows_per_image: Some(rows),
+                },
+                // 压缩格式的拷贝尺寸需按块对齐
+                size.mip_level_size(mip_level, wgpu::TextureDimension::D2)
+                    .physical_size(format),
+            );
+        }
+        let tex_view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label,
+            ..Default::default()
+        });
+
+        AnyTexture {
+            size,
+            tex: texture,
+            tex_view,
+            format,
+            view_dimension: wgpu::TextureViewDimension::D2,
+        }
+    }
+
+    /// 生成棋盘格纹理，用于调试 uv 与纹理过滤
+    ///
+    /// `cell` 为每个格子的像素边长，左上角的格子为 `color_a`。
+    /// `format` 只支持 `Rgba8Unorm` 与 `Rgba8UnormSrgb`：颜色值按原样写入，
+    /// 即 sRGB 格式下它们被当作 sRGB 编码的颜色，采样时会转换到线性空间。
+    pub fn checkerboard(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        size: (u32, u32),
+        cell: u32,
+        color_a: [u8; 4],
+        color_b: [u8; 4],
+        format: TextureFormat,
+    ) -> Self {
+        let texels = checkerboard_texels(size, cell, color_a, color_b);
+        Self::from_rgba8_texels(device, queue, size, &texels, format, Some("checkerboard"))
+    }
+
+    /// 生成 uv 网格纹理：红色通道为 u，绿色通道为 v，每 1/8 处绘制一条白色网格线
+    pub fn uv_grid(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        size: (u32, u32),
+        format: TextureFormat,
+    ) -> Self {
+        let texels = uv_grid_texels(size);
+        Self::from_rgba8_texels(device, queue, size, &texels, format, Some("uv grid"))
+    }
+
+    fn from_rgba8_texels(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        (width, height): (u32, u32),
+        texels: &[u8],
+        format: TextureFormat,
+        label: Option<&'static str>,
+    ) -> Self {
+        assert!(
+            matches!(
+                format,
+                TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb
+            ),
+            "procedural textures only support Rgba8Unorm / Rgba8UnormSrgb"
+        );
+        let size = Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let tex = empty(
+            device,
+            format,
+            size,
+            None,
+            wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            label,
+        );
+        queue.write_texture(
+            tex.tex.as_image_copy(),
+            texels,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * width),
+                rows_per_image: Some(height),
+            },
+            size,
+        );
+        tex
+    }
+}
+
+/// 立方体贴图加载失败的原因
+#[derive(Debug)]
+pub enum CubeTextureError {
+    /// 第 `face` 个面的图片解码失败
+    Decode {
+        face: usize,
+        error: image::ImageError,
+    },
+    /// 第 `face` 个面不是正方形
+    NotSquare { face: usize, size: (u32, u32) },
+    /// 六个面的尺寸不一致
+    MismatchedSize(MismatchedImageSize),
+}
+
+impl core::fmt::Display for CubeTextureError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Decode { face, error } => write!(f, "failed to decode cube face {face}: {error}"),
+            Self::NotSquare { face, size } => {
+                write!(f, "cube face {face} has size {size:?}, which is not square")
+            }
+            Self::MismatchedSize(e) => write!(f, "cube faces differ in size: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for CubeTextureError {}
+
+/// 由六张编码后的图片（PNG/JPEG 等）加载立方体贴图
+///
+/// `faces` 依次为 +X、-X、+Y、-Y、+Z、-Z 面，分别上传到纹理的第 0~5 层。
+/// 返回的纹理视图维度为 `Cube`，着色器中以 `texture_cube<f32>` 声明，
+/// 以方向向量采样：`textureSample(sky_texture, sky_sampler, direction)`，采样器可用 [`default_cube_sampler`]。
+pub fn load_cube_texture(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    faces: [&[u8]; 6],
+    label: Option<&'static str>,
+) -> Result<AnyTexture, CubeTextureError> {
+    let images = faces
+        .iter()
+        .enumerate()
+        .map(|(face, bytes)| {
+            image::load_from_memory(bytes).map_err(|error| CubeTextureError::Decode { face, error })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let size = cube_extent(&images)?;
+    let format = TextureFormat::Rgba8UnormSrgb;
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        label,
+        view_formats: &[],
+    });
+    for (layer, img) in images.iter().enumerate() {
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: 0,
+                    y: 0,
+                    z: layer as u32,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            &img.to_rgba8(),
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * size.width),
+                rows_per_image: Some(size.height),
+            },
+            Extent3d {
+                depth_or_array_layers: 1,
+                ..size
+            },
+        );
+    }
+    let tex_view = texture.create_view(&wgpu::TextureViewDescriptor {
+        label,
+        dimension: Some(wgpu::TextureViewDimension::Cube),
+        ..Default::default()
+    });
+
+    Ok(AnyTexture {
+        size,
+        tex: texture,
+        tex_view,
+        format,
+        view_dimension: wgpu::TextureViewDimension::Cube,
+    })
+}
+
+/// 立方体贴图的尺寸：六个面需为尺寸相同的正方形
+fn cube_extent(faces: &[DynamicImage]) -> Result<Extent3d, CubeTextureError> {
+    if let Some((face, img)) = faces
+        .iter()
+        .enumerate()
+        .find(|(_, img)| img.width() != img.height())
+    {
+        return Err(CubeTextureError::NotSquare {
+            face,
+            size: img.dimensions(),
+        });
+    }
+    array_extent(faces).map_err(CubeTextureError::MismatchedSize)
+}
+
+/// 纹理数组的尺寸，层数为图片数
+fn array_extent(images: &[DynamicImage]) -> Result<Extent3d, MismatchedImageSize> {
+    let expected = images.first().map_or((1, 1), |img| img.dimensions());
+    for (index, img) in images.iter().enumerate() {
+        if img.dimensions() != expected {
+            return Err(MismatchedImageSize {
+                index,
+                expected,
+                found: img.dimensions(),
+            });
+        }
+    }
+    Ok(Extent3d {
+        width: expected.0,
+        height: expected.1,
+        depth_or_array_layers: images.len().max(1) as u32,
+    })
+}
+
+/// 棋盘格的 RGBA8 像素数据
+fn checkerboard_texels(
+    (width, height): (u32, u32),
+    cell: u32,
+    color_a: [u8; 4],
+    color_b: [u8; 4],
+) -> Vec<u8> {
+    let cell = cell.max(1);
+    let mut texels = Vec::with_capacity((width * height * 4) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let is_a = (x / cell + y / cell) % 2 == 0;
+            texels.extend_from_slice(if is_a { &color_a } else { &color_b });
+        }
+    }
+    texels
+}
+
+/// uv 网格的 RGBA8 像素数据
+fn uv_grid_texels((width, height): (u32, u32)) -> Vec<u8> {
+    let to_u8 = |i: u32, n: u32| (i * 255 / (n - 1).max(1)) as u8;
+    let is_line = |i: u32, n: u32| i % (n / 8).max(1) == 0;
+    let mut texels = Vec::with_capacity((width * height * 4) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            if is_line(x, width) || is_line(y, height) {
+                texels.extend_from_slice(&[255, 255, 255, 255]);
+            } else {
+                texels.extend_from_slice(&[to_u8(x, width), to_u8(y, height), 0, 255]);
+            }
+        }
+    }
+    texels
+}
+
+fn check_region(
+    origin: wgpu::Origin3d,
+    size: Extent3d,
+    bounds: Extent3d,
+) -> Result<(), RegionOutOfBounds> {
+    if origin.x + size.width > bounds.width
+        || origin.y + size.height > bounds.height
+        || origin.z + size.depth_or_array_layers > bounds.depth_or_array_layers
+    {
+        return Err(RegionOutOfBounds {
+            origin,
+            size,
+            bounds,
+        });
+    }
+    Ok(())
+}
+
+fn check_mip_range(
+    base_mip: u32,
+    mip_count: u32,
+    texture_mip_count: u32,
+) -> Result<(), InvalidMipRange> {
+    if mip_count == 0 || base_mip.saturating_add(mip_count) > texture_mip_count {
+        return Err(InvalidMipRange {
+            base_mip,
+            mip_count,
+            texture_mip_count,
+        });
+    }
+    Ok(())
+}
+
+/// 图片区域能直接上传的纹理格式及其单个像素的字节数
+fn region_pixel_bytes(format: TextureFormat) -> Result<u32, WriteRegionError> {
+    match format {
+        TextureFormat::R8Unorm => Ok(1),
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => Ok(4),
+        _ => Err(WriteRegionError::UnsupportedFormat(format)),
+    }
+}
+
+/// 裁剪出图片中的区域，并按纹理格式排列像素
+fn region_texels(
+    img: &DynamicImage,
+    origin: wgpu::Origin3d,
+    size: Extent3d,
+    format: TextureFormat,
+) -> Result<Vec<u8>, WriteRegionError> {
+    let (width, height) = img.dimensions();
+    let img_extent = Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+    check_region(
+        wgpu::Origin3d { z: 0, ..origin },
+        Extent3d {
+            depth_or_array_layers: 1,
+            ..size
+        },
+        img_extent,
+    )?;
+    let cropped = img.crop_imm(origin.x, origin.y, size.width, size.height);
+    let texels = if region_pixel_bytes(format)? == 1 {
+        cropped.into_luma8().into_raw()
+    } else {
+        cropped.into_rgba8().into_raw()
+    };
+    Ok(texels)
+}
+
 #[cfg(target_arch = "wasm32")]
 pub async fn get_web_img(img_name: &str) -> Result<Vec<u8>, reqwest::Error> {
     let url =
@@ -28,41 +612,74 @@ pub async fn from_path(
     usage: wgpu::TextureUsages,
     set_to_grayscale: bool,
 ) -> (AnyTexture, Sampler) {
-    #[cfg(target_arch = "wasm32")]
-    let img = {
-        let bytes = get_web_img(image_path).await;
-        let format = if image_path.to_lowercase().ends_with(".png") {
-            image::ImageFormat::Png
-        } else if image_path.to_lowercase().ends_with(".jpg")
-            || image_path.to_lowercase().ends_with(".jpeg")
-        {
-            image::ImageFormat::Jpeg
-        } else {
-            panic!("不支持的图片格式，仅支持 PNG 和 JPEG/JPG")
-        };
-        image::load_from_memory_with_format(&bytes.unwrap(), format).unwrap()
-    };
-    #[cfg(not(target_arch = "wasm32"))]
-    let img = {
-        let path = if image_path.split('/').count() > 1 {
-            // is already a full path
-            PathBuf::from(image_path)
+    let img = open_img(image_path).await;
+    texture_from_img(img, app, usage, set_to_grayscale, false)
+}
+
+/// 与 [`from_path`] 相同，但会创建完整的 mip 链并由 [`generate_mipmaps`] 生成各层级，
+/// 返回的采样器在 mip 层级之间线性插值，缩小显示时不会出现明显的走样
+#[allow(dead_code)]
+pub async fn from_path_with_mipmaps(
+    image_path: &str,
+    app: &app_surface::AppSurface,
+    usage: wgpu::TextureUsages,
+    set_to_grayscale: bool,
+) -> (AnyTexture, Sampler) {
+    let img = open_img(image_path).await;
+    texture_from_img(img, app, usage, set_to_grayscale, true)
+}
+
+async fn open_img(image_path: &str) -> DynamicImage {
+    cfg_if::cfg_if! {
+        if #[cfg(target_arch = "wasm32")] {
+            let bytes = get_web_img(image_path).await;
+            let format = if image_path.to_lowercase().ends_with(".png") {
+                image::ImageFormat::Png
+            } else if image_path.to_lowercase().ends_with(".jpg")
+                || image_path.to_lowercase().ends_with(".jpeg")
+            {
+                image::ImageFormat::Jpeg
+            } else {
+                panic!("不支持的图片格式，仅支持 PNG 和 JPEG/JPG")
+            };
+            image::load_from_memory_with_format(&bytes.unwrap(), format).unwrap()
         } else {
-            super::get_texture_file_path(image_path)
-        };
-        log::info!("path: {:?}", path);
-        image::open(path.as_path()).unwrap()
-    };
+            let path = if image_path.split('/').count() > 1 {
+                // is already a full path
+                PathBuf::from(image_path)
+            } else {
+                super::get_texture_file_path(image_path)
+            };
+            log::info!("path: {:?}", path);
+            image::open(path.as_path()).unwrap()
+        }
+    }
+}
 
+fn texture_from_img(
+    img: DynamicImage,
+    app: &app_surface::AppSurface,
+    usage: wgpu::TextureUsages,
+    set_to_grayscale: bool,
+    generate_mips: bool,
+) -> (AnyTexture, Sampler) {
     let (texels, texture_extent, format) = load_from_img(img, set_to_grayscale);
     let pixel_bytes = single_pixel_bytes(format);
+    let (mip_level_count, mip_usage) = if generate_mips {
+        (
+            full_mip_level_count((texture_extent.width, texture_extent.height)),
+            wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT,
+        )
+    } else {
+        (1, wgpu::TextureUsages::empty())
+    };
     let texture = app.device.create_texture(&wgpu::TextureDescriptor {
         size: texture_extent,
-        mip_level_count: 1,
+        mip_level_count,
         sample_count: 1,
         dimension: wgpu::TextureDimension::D2,
         format,
-        usage: usage | wgpu::TextureUsages::COPY_DST,
+        usage: usage | mip_usage | wgpu::TextureUsages::COPY_DST,
         label: None,
         view_formats: &[format.remove_srgb_suffix()],
     });
@@ -85,6 +702,12 @@ pub async fn from_path(
         },
         texture_extent,
     );
+    let sampler = if generate_mips {
+        generate_mipmaps(&app.device, &app.queue, &texture, None);
+        bilinear_sampler(&app.device)
+    } else {
+        default_sampler(&app.device)
+    };
     let any_tex = AnyTexture {
         size: texture_extent,
         tex: texture,
@@ -93,7 +716,117 @@ pub async fn from_path(
         format,
     };
 
-    (any_tex, default_sampler(&app.device))
+    (any_tex, sampler)
+}
+
+/// 由纹理尺寸计算完整 mip 链的层级数（直到 1x1）
+pub fn full_mip_level_count((width, height): (u32, u32)) -> u32 {
+    u32::BITS - width.max(height).max(1).leading_zeros()
+}
+
+/// 由第 0 级依次渲染出 `1..mip_level_count` 各级 mip：每一级都以线性采样器采样上一级
+///
+/// `mip_level_count` 为 `None` 时生成纹理的全部层级；纹理需以 [`full_mip_level_count`]
+/// 等层级数创建，并带有 `TEXTURE_BINDING | RENDER_ATTACHMENT`。
+///
+/// # NOTE:
+/// 纹理格式需可渲染且可线性过滤，如 `Rgba8UnormSrgb`、`Rgba8Unorm`、`Rgba16Float`。
+/// sRGB 格式在采样时解码、写入时编码，所以是在线性空间中求平均。
+pub fn generate_mipmaps(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+    mip_level_count: Option<u32>,
+) {
+    let mip_level_count = mip_level_count.unwrap_or(texture.mip_level_count());
+    assert!(
+        mip_level_count <= texture.mip_level_count(),
+        "mip 层级数 {mip_level_count} 超出纹理的层级数 {}",
+        texture.mip_level_count()
+    );
+    if mip_level_count < 2 {
+        return;
+    }
+    let format = texture.format();
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("mipmap blit"),
+        source: wgpu::ShaderSource::Wgsl(include_str!("mipmap.wgsl").into()),
+    });
+    let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("mipmap blit pipeline"),
+        layout: None,
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: Some("vs_main"),
+            compilation_options: Default::default(),
+            buffers: &[],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: Some("fs_main"),
+            compilation_options: Default::default(),
+            targets: &[Some(format.into())],
+        }),
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        multiview: None,
+        cache: None,
+    });
+    let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some("mipmap sampler"),
+        mag_filter: wgpu::FilterMode::Linear,
+        min_filter: wgpu::FilterMode::Linear,
+        ..Default::default()
+    });
+    let level_view = |mip_level| {
+        texture.create_view(&wgpu::TextureViewDescriptor {
+            label: Some("mip level view"),
+            format: Some(format),
+            dimension: Some(wgpu::TextureViewDimension::D2),
+            base_mip_level: mip_level,
+            mip_level_count: Some(1),
+            ..Default::default()
+        })
+    };
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("mipmap encoder"),
+    });
+    for target_mip in 1..mip_level_count {
+        let src_view = level_view(target_mip - 1);
+        let dst_view = level_view(target_mip);
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&src_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("mipmap pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &dst_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            ..Default::default()
+        });
+        rpass.set_pipeline(&pipeline);
+        rpass.set_bind_group(0, &bind_group, &[]);
+        rpass.draw(0..3, 0..1);
+    }
+    queue.submit(Some(encoder.finish()));
 }
 
 fn load_from_img(
@@ -184,6 +917,21 @@ pub fn default_sampler(device: &wgpu::Device) -> Sampler {
     })
 }
 
+/// 立方体贴图（天空盒等）的采样器：三个方向都 `ClampToEdge`，避免面与面的接缝处采样到另一侧
+#[allow(dead_code)]
+pub fn default_cube_sampler(device: &wgpu::Device) -> Sampler {
+    device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some("cube sampler"),
+        address_mode_u: wgpu::AddressMode::ClampToEdge,
+        address_mode_v: wgpu::AddressMode::ClampToEdge,
+        address_mode_w: wgpu::AddressMode::ClampToEdge,
+        mag_filter: wgpu::FilterMode::Linear,
+        min_filter: wgpu::FilterMode::Linear,
+        mipmap_filter: wgpu::FilterMode::Linear,
+        ..Default::default()
+    })
+}
+
 #[allow(dead_code)]
 pub fn repeate_sampler(device: &wgpu::Device) -> Sampler {
     device.create_sampler(&wgpu::SamplerDescriptor {
@@ -246,6 +994,66 @@ pub fn bilinear_sampler(device: &wgpu::Device) -> Sampler {
     })
 }
 
+/// 限制 mip 层级范围的三线性采样器
+///
+/// 采样时选中的 mip 层级被限制在 `[min_lod, max_lod]` 之内：提高 `min_lod` 可得到更模糊的效果，
+/// 降低 `max_lod` 则让远处保持更清晰。
+///
+/// # NOTE:
+/// wgpu 的 `SamplerDescriptor` 没有 mip 偏移（LOD bias）参数，需要偏移时在着色器中使用
+/// `textureSampleBias(t, s, uv, bias)`：正值偏向更模糊的 mip，负值偏向更清晰的 mip。
+#[allow(dead_code)]
+pub fn lod_clamped_sampler(device: &wgpu::Device, min_lod: f32, max_lod: f32) -> Sampler {
+    device.create_sampler(&lod_clamped_sampler_descriptor(min_lod, max_lod))
+}
+
+fn lod_clamped_sampler_descriptor(min_lod: f32, max_lod: f32) -> wgpu::SamplerDescriptor<'static> {
+    assert!(
+        0.0 <= min_lod && min_lod <= max_lod,
+        "lod clamp requires 0 <= min_lod <= max_lod"
+    );
+    wgpu::SamplerDescriptor {
+        label: Some("lod clamped sampler"),
+        address_mode_u: wgpu::AddressMode::ClampToEdge,
+        address_mode_v: wgpu::AddressMode::ClampToEdge,
+        address_mode_w: wgpu::AddressMode::ClampToEdge,
+        mag_filter: wgpu::FilterMode::Linear,
+        min_filter: wgpu::FilterMode::Linear,
+        mipmap_filter: wgpu::FilterMode::Linear,
+        lod_min_clamp: min_lod,
+        lod_max_clamp: max_lod,
+        ..Default::default()
+    }
+}
+
+/// 各向异性过滤采样器，倾斜视角（如贴近地面看远处）下的纹理比三线性过滤更清晰
+///
+/// `max_anisotropy` 会被限制在 wgpu 允许的 `1..=16` 之内，为 1 时等同于三线性过滤。
+///
+/// # NOTE:
+/// - 各向异性过滤要求 min/mag/mipmap 过滤都为 `Linear`，并且纹理需有 mip 链（见 [`generate_mipmaps`]），
+///   否则远处仍会走样；
+/// - 不支持 `DownlevelFlags::ANISOTROPIC_FILTERING` 的后端（如部分 WebGL 实现）会忽略 `anisotropy_clamp`，
+///   效果退化为三线性过滤。
+#[allow(dead_code)]
+pub fn anisotropic_sampler(device: &wgpu::Device, max_anisotropy: u16) -> Sampler {
+    device.create_sampler(&anisotropic_sampler_descriptor(max_anisotropy))
+}
+
+fn anisotropic_sampler_descriptor(max_anisotropy: u16) -> wgpu::SamplerDescriptor<'static> {
+    wgpu::SamplerDescriptor {
+        label: Some("anisotropic sampler"),
+        address_mode_u: wgpu::AddressMode::Repeat,
+        address_mode_v: wgpu::AddressMode::Repeat,
+        address_mode_w: wgpu::AddressMode::Repeat,
+        mag_filter: wgpu::FilterMode::Linear,
+        min_filter: wgpu::FilterMode::Linear,
+        mipmap_filter: wgpu::FilterMode::Linear,
+        anisotropy_clamp: max_anisotropy.clamp(1, 16),
+        ..Default::default()
+    }
+}
+
 fn single_pixel_bytes(format: TextureFormat) -> u32 {
     match format {
         TextureFormat::R8Sint
@@ -277,3 +1085,268 @@ fn single_pixel_bytes(format: TextureFormat) -> u32 {
         _ => 0,
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn write_2x2_region_of_4x4() {
+        let img = image::RgbaImage::from_fn(4, 4, |x, y| image::Rgba([x as u8, y as u8, 0, 255]));
+        let img = DynamicImage::ImageRgba8(img);
+        let origin = wgpu::Origin3d { x: 1, y: 2, z: 0 };
+        let size = Extent3d {
+            width: 2,
+            height: 2,
+            depth_or_array_layers: 1,
+        };
+        let bounds = Extent3d {
+            width: 4,
+            height: 4,
+            depth_or_array_layers: 1,
+        };
+        assert!(check_region(origin, size, bounds).is_ok());
+        let texels = region_texels(&img, origin, size, TextureFormat::Rgba8UnormSrgb).unwrap();
+        assert_eq!(
+            texels,
+            vec![1, 2, 0, 255, 2, 2, 0, 255, 1, 3, 0, 255, 2, 3, 0, 255]
+        );
+
+        let origin = wgpu::Origin3d { x: 3, y: 0, z: 0 };
+        assert!(check_region(origin, size, bounds).is_err());
+        assert!(region_texels(&img, origin, size, TextureFormat::Rgba8Unorm).is_err());
+    }
+
+    #[test]
+    fn reject_unsupported_region_formats() {
+        let img = DynamicImage::ImageRgba8(image::RgbaImage::new(4, 4));
+        let size = Extent3d {
+            width: 4,
+            height: 4,
+            depth_or_array_layers: 1,
+        };
+        for format in [
+            TextureFormat::Bc1RgbaUnormSrgb,
+            TextureFormat::Rgba16Float,
+            TextureFormat::Bgra8Unorm,
+        ] {
+            assert_eq!(
+                region_texels(&img, wgpu::Origin3d::ZERO, size, format),
+                Err(WriteRegionError::UnsupportedFormat(format))
+            );
+        }
+        assert_eq!(region_pixel_bytes(TextureFormat::R8Unorm), Ok(1));
+    }
+
+    #[test]
+    fn checkerboard_corners() {
+        let (a, b) = ([255, 0, 0, 255], [0, 0, 255, 255]);
+        let (w, h) = (16, 12);
+        let texels = checkerboard_texels((w, h), 4, a, b);
+        let texel = |x: u32, y: u32| {
+            let i = ((y * w + x) * 4) as usize;
+            [texels[i], texels[i + 1], texels[i + 2], texels[i + 3]]
+        };
+        assert_eq!(texels.len(), (w * h * 4) as usize);
+        assert_eq!(texel(0, 0), a);
+        assert_eq!(texel(4, 0), b);
+        assert_eq!(texel(4, 4), a);
+        // 右上角：第 3 列格子；右下角：第 3 列、第 2 行格子
+        assert_eq!(texel(w - 1, 0), b);
+        assert_eq!(texel(w - 1, h - 1), b);
+    }
+
+    #[test]
+    fn mip_range_of_4_mips() {
+        // mips 1..3
+        assert!(check_mip_range(1, 2, 4).is_ok());
+        assert!(check_mip_range(0, 4, 4).is_ok());
+        assert!(check_mip_range(3, 2, 4).is_err());
+        assert!(check_mip_range(1, 0, 4).is_err());
+        assert!(check_mip_range(u32::MAX, 2, 4).is_err());
+    }
+
+    #[test]
+    fn lod_clamp_descriptor() {
+        let desc = lod_clamped_sampler_descriptor(1.0, 3.5);
+        assert_eq!((desc.lod_min_clamp, desc.lod_max_clamp), (1.0, 3.5));
+        assert_eq!(desc.mipmap_filter, wgpu::FilterMode::Linear);
+        let default = wgpu::SamplerDescriptor::default();
+        assert_ne!(desc.lod_min_clamp, default.lod_min_clamp);
+    }
+
+    #[test]
+    fn anisotropy_is_clamped() {
+        assert_eq!(anisotropic_sampler_descriptor(0).anisotropy_clamp, 1);
+        assert_eq!(anisotropic_sampler_descriptor(8).anisotropy_clamp, 8);
+        assert_eq!(anisotropic_sampler_descriptor(64).anisotropy_clamp, 16);
+        assert_eq!(
+            anisotropic_sampler_descriptor(16).mipmap_filter,
+            wgpu::FilterMode::Linear
+        );
+    }
+
+    #[test]
+    fn cube_faces_must_be_equal_squares() {
+        let face = |w, h| DynamicImage::ImageRgba8(image::RgbaImage::new(w, h));
+        let mut faces: Vec<DynamicImage> = (0..6).map(|_| face(16, 16)).collect();
+        let extent = cube_extent(&faces).unwrap();
+        assert_eq!((extent.width, extent.height), (16, 16));
+        assert_eq!(extent.depth_or_array_layers, 6);
+
+        faces[4] = face(8, 8);
+        assert!(matches!(
+            cube_extent(&faces),
+            Err(CubeTextureError::MismatchedSize(e)) if e.index == 4
+        ));
+        faces[2] = face(16, 8);
+        assert!(matches!(
+            cube_extent(&faces),
+            Err(CubeTextureError::NotSquare { face: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn full_mip_chain_length() {
+        assert_eq!(full_mip_level_count((1, 1)), 1);
+        assert_eq!(full_mip_level_count((256, 256)), 9);
+        assert_eq!(full_mip_level_count((300, 20)), 9);
+        assert_eq!(full_mip_level_count((0, 0)), 1);
+    }
+
+    #[test]
+    fn texture_array_layer_count() {
+        let images: Vec<DynamicImage> = (0..3)
+            .map(|_| DynamicImage::ImageRgba8(image::RgbaImage::new(8, 4)))
+            .collect();
+        let extent = array_extent(&images).unwrap();
+        assert_eq!(extent.depth_or_array_layers, 3);
+        assert_eq!((extent.width, extent.height), (8, 4));
+
+        let mut images = images;
+        images.push(DynamicImage::ImageRgba8(image::RgbaImage::new(4, 4)));
+        assert_eq!(array_extent(&images).unwrap_err().index, 3);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod gpu_test {
+    use super::*;
+    use crate::mip_chain_bytes;
+
+    #[test]
+    fn tiny_budget_loads_reduced_base_level() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        let format = TextureFormat::Rgba8Unorm;
+        let levels: Vec<Vec<u8>> = (0..9)
+            .map(|level| {
+                let (w, h) = mip_size((256, 256), level);
+                vec![255; (w * h * 4) as usize]
+            })
+            .collect();
+        let levels: Vec<&[u8]> = levels.iter().map(|l| l.as_slice()).collect();
+        // 64x64 起的 mip 链约 21 KB
+        let mut budget = TextureBudget::new(32 * 1024);
+
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let tex = AnyTexture::from_mip_levels(
+            &device,
+            &queue,
+            format,
+            (256, 256),
+            &levels,
+            Some(&mut budget),
+            None,
+        );
+        assert!(pollster::block_on(device.pop_error_scope()).is_none());
+        assert_eq!((tex.size.width, tex.size.height), (64, 64));
+        assert_eq!(tex.tex.mip_level_count(), 7);
+        assert_eq!(
+            budget.used_bytes(),
+            mip_chain_bytes(format, (256, 256), 9, 2)
+        );
+    }
+
+    #[test]
+    fn mipmaps_average_checkerboard() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        // 1 像素大小的黑白棋盘格，每一级 mip 都应是灰色
+        let size = (4, 4);
+        let format = TextureFormat::Rgba8Unorm;
+        let texels = checkerboard_texels(size, 1, [255; 4], [0, 0, 0, 255]);
+        let extent = Extent3d {
+            width: size.0,
+            height: size.1,
+            depth_or_array_layers: 1,
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: extent,
+            mip_level_count: full_mip_level_count(size),
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::COPY_DST
+                | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        queue.write_texture(
+            texture.as_image_copy(),
+            &texels,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(size.0 * 4),
+                rows_per_image: None,
+            },
+            extent,
+        );
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        generate_mipmaps(&device, &queue, &texture, None);
+        assert!(pollster::block_on(device.pop_error_scope()).is_none());
+
+        // 读回最后一级（1x1）
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 2,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &readback,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+                    rows_per_image: None,
+                },
+            },
+            Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+        );
+        queue.submit(Some(encoder.finish()));
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        crate::poll::poll_wait(&device);
+        let pixel = slice.get_mapped_range()[..4].to_vec();
+        assert!((120..=135).contains(&pixel[0]), "{pixel:?}");
+        assert_eq!(pixel[3], 255);
+    }
+}
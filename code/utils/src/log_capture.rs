@@ -0,0 +1,78 @@
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+/// 把日志记录保存到内存中的 `log::Log` 实现，配合 `init_logger_with_sink` 在测试中断言输出的日志
+///
+/// 克隆出的实例共享同一个缓冲区：一份交给 `init_logger_with_sink`，另一份留在测试中读取。
+#[derive(Clone, Default)]
+pub struct LogCapture {
+    level: Option<log::Level>,
+    records: Arc<Mutex<Vec<(log::Level, String)>>>,
+}
+
+#[allow(dead_code)]
+impl LogCapture {
+    /// 记录所有级别的日志
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 只记录不低于 `level` 的日志（如 `Level::Warn` 只保留警告与错误）
+    pub fn with_level(level: log::Level) -> Self {
+        Self {
+            level: Some(level),
+            ..Default::default()
+        }
+    }
+
+    /// 已记录的日志（级别，消息）
+    pub fn records(&self) -> Vec<(log::Level, String)> {
+        self.records.lock().clone()
+    }
+
+    /// 是否有指定级别且消息中包含 `pattern` 的日志
+    pub fn contains(&self, level: log::Level, pattern: &str) -> bool {
+        self.records
+            .lock()
+            .iter()
+            .any(|(l, message)| *l == level && message.contains(pattern))
+    }
+
+    pub fn clear(&self) {
+        self.records.lock().clear();
+    }
+}
+
+impl log::Log for LogCapture {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.level.is_none_or(|level| metadata.level() <= level)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            self.records
+                .lock()
+                .push((record.level(), record.args().to_string()));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn captures_warning() {
+        let capture = LogCapture::with_level(log::Level::Warn);
+        crate::init_logger_with_sink(Box::new(capture.clone()), log::LevelFilter::Trace)
+            .expect("logger already initialized");
+
+        log::info!("not captured");
+        log::warn!("feature unavailable, falling back");
+
+        assert!(capture.contains(log::Level::Warn, "falling back"));
+        assert!(!capture.contains(log::Level::Info, "not captured"));
+    }
+}
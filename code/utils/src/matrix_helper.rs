@@ -1,4 +1,5 @@
-#[derive(Clone, Copy)]
+/// 把 [-1, 1] 的矩形缩放到刚好铺满视口所需的参数
+#[derive(Clone, Copy, Debug)]
 pub struct FullscreenFactor {
     pub sx: f32,
     pub sy: f32,
@@ -14,6 +15,11 @@ pub fn perspective_fullscreen_mvp(viewport: glam::Vec2, fovy: f32) -> (glam::Mat
     (p_matrix, vm_matrix * scale_matrix)
 }
 
+/// 透视投影矩阵与默认的观察矩阵
+///
+/// 返回 `(p_matrix, mv_matrix, fullscreen_factor)`：
+/// - `mv_matrix` 把场景沿 -z 平移 `fullscreen_factor.translate_z`，使 z = 0 平面上的 [-1, 1] 矩形刚好贴合视口的短边；
+/// - `fullscreen_factor` 即上述计算所用的缩放与平移参数，调用方可用其 `sx`/`sy` 把矩形拉伸到铺满整个视口。
 pub fn perspective_mvp(
     viewport: glam::Vec2,
     fovy: f32,
@@ -26,16 +32,74 @@ pub fn perspective_mvp(
     (p_matrix, vm_matrix, factor)
 }
 
+/// 俯仰角的上限，略小于 π/2：俯仰角达到 ±π/2 时视线与 up 向量平行，观察矩阵会突然翻转
+pub const SAFE_FRAC_PI_2: f32 = core::f32::consts::FRAC_PI_2 - 0.0001;
+
+/// 右手坐标系的观察矩阵，相机位于 `eye`，看向 `target`
+pub fn look_at(eye: glam::Vec3, target: glam::Vec3, up: glam::Vec3) -> glam::Mat4 {
+    glam::Mat4::look_at_rh(eye, target, up)
+}
+
+/// 环绕 `target` 的轨道相机，返回 `(eye, view_matrix)`
+///
+/// `yaw`、`pitch` 为相机视线方向的偏航角与俯仰角（弧度），与 tutorial12-camera 中 `Camera` 的约定一致：
+/// 视线方向为 `(cos(pitch) * cos(yaw), sin(pitch), cos(pitch) * sin(yaw))`，相机位于 `target` 沿视线反方向 `distance` 处。
+///
+/// # NOTE:
+/// `pitch` 会被限制在 [-`SAFE_FRAC_PI_2`, `SAFE_FRAC_PI_2`] 内，避免越过正上方/正下方时画面翻转。
+pub fn orbit(target: glam::Vec3, yaw: f32, pitch: f32, distance: f32) -> (glam::Vec3, glam::Mat4) {
+    let pitch = pitch.clamp(-SAFE_FRAC_PI_2, SAFE_FRAC_PI_2);
+    let (sin_pitch, cos_pitch) = pitch.sin_cos();
+    let (sin_yaw, cos_yaw) = yaw.sin_cos();
+    let forward = glam::Vec3::new(cos_pitch * cos_yaw, sin_pitch, cos_pitch * sin_yaw);
+    let eye = target - forward * distance;
+
+    (eye, look_at(eye, target, glam::Vec3::Y))
+}
+
+/// 使用自定义观察点的透视投影
+///
+/// 返回 `(p_matrix, mv_matrix)`，投影矩阵与 [`perspective_mvp`] 相同，`mv_matrix` 由 [`look_at`] 构造。
+///
+/// # NOTE:
+/// 不返回 [`FullscreenFactor`]：其 `translate_z` 假定相机位于 z 轴上、看向原点，对任意的 `eye` 不成立，
+/// 需要铺满视口时请使用 [`perspective_mvp`]。
+pub fn perspective_mvp_with_eye(
+    viewport: glam::Vec2,
+    fovy: f32,
+    eye: glam::Vec3,
+    target: glam::Vec3,
+    up: glam::Vec3,
+) -> (glam::Mat4, glam::Mat4) {
+    let p_matrix = glam::Mat4::perspective_rh(fovy, viewport.x / viewport.y, 0.1, 100.0);
+    let vm_matrix = look_at(eye, target, up);
+
+    (p_matrix, vm_matrix)
+}
+
+/// 把 z = 0 平面上的 [-1, 1] 矩形铺满视口所需的缩放与平移参数
+///
+/// 等价于 `fullscreen_factor_for_aspect(viewport.x / viewport.y, fovy)`
 pub fn fullscreen_factor(viewport: glam::Vec2, fovy: f32) -> FullscreenFactor {
+    fullscreen_factor_for_aspect(viewport.x / viewport.y, fovy)
+}
+
+/// 只依赖宽高比（宽 / 高）的 [`fullscreen_factor`]，用于没有具体像素尺寸的场景
+///
+/// - 横屏（aspect >= 1）：`sy = 1`，`sx = aspect`，矩形的高刚好贴合视口；
+/// - 竖屏（aspect < 1）：`sx = 1`，`sy = 1 / aspect`，矩形的宽刚好贴合视口，相机需后退 `1 / aspect` 倍；
+///
+/// 两种情况都满足 `sx / sy == aspect`，粒子等按 `sx`/`sy` 铺满屏幕的布局依赖这一点。
+pub fn fullscreen_factor_for_aspect(aspect: f32, fovy: f32) -> FullscreenFactor {
     // 缩放到贴合屏幕
     let mut sx: f32 = 1.0;
     let mut sy = 1.0;
-    let ratio = if viewport.y > viewport.x {
-        let ratio = viewport.y / viewport.x;
+    let ratio = if aspect < 1.0 {
+        let ratio = 1.0 / aspect;
         sy = ratio;
         ratio
     } else {
-        sx = viewport.x / viewport.y;
+        sx = aspect;
         1.0
     };
     // 右手坐标系，z 轴朝屏幕外，所以是负数
@@ -48,6 +112,34 @@ pub fn fullscreen_factor(viewport: glam::Vec2, fovy: f32) -> FullscreenFactor {
     }
 }
 
+/// 反向 Z（reversed-Z）时的深度比较函数：离相机越近深度值越大
+pub const REVERSED_Z_DEPTH_COMPARE: wgpu::CompareFunction = wgpu::CompareFunction::Greater;
+/// 反向 Z 时深度附件的清除值，对应无穷远处
+pub const REVERSED_Z_CLEAR_DEPTH: f32 = 0.0;
+
+/// 远平面在无穷远处的反向 Z 透视投影矩阵
+///
+/// 近平面 `znear` 映射到深度 1.0，无穷远映射到 0.0。
+/// 浮点数在 0 附近的精度最高，与之互补的透视除法使远处的深度分布更均匀，大场景中不易出现 z-fighting。
+///
+/// # NOTE:
+/// 需配合 `DEPTH_FORMAT`（Depth32Float）使用：深度比较改为 [`REVERSED_Z_DEPTH_COMPARE`]，
+/// 深度附件以 [`REVERSED_Z_CLEAR_DEPTH`] 清除，管线的深度状态可直接使用 [`reversed_z_depth_stencil_state`]。
+pub fn perspective_reversed_z(viewport: glam::Vec2, fovy: f32, znear: f32) -> glam::Mat4 {
+    glam::Mat4::perspective_infinite_reverse_rh(fovy, viewport.x / viewport.y, znear)
+}
+
+/// 与 [`perspective_reversed_z`] 搭配的深度模板状态：写入深度，比较函数为 `Greater`
+pub fn reversed_z_depth_stencil_state() -> wgpu::DepthStencilState {
+    wgpu::DepthStencilState {
+        format: crate::DEPTH_FORMAT,
+        depth_write_enabled: true,
+        depth_compare: REVERSED_Z_DEPTH_COMPARE,
+        stencil: wgpu::StencilState::default(),
+        bias: wgpu::DepthBiasState::default(),
+    }
+}
+
 pub fn ortho_mvp(viewport_size: glam::Vec2) -> (glam::Mat4, glam::Mat4) {
     let fovy: f32 = 45.0f32.to_radians();
     let factor = fullscreen_factor(viewport_size, fovy);
@@ -61,3 +153,75 @@ pub fn ortho_mvp(viewport_size: glam::Vec2) -> (glam::Mat4, glam::Mat4) {
     );
     (p_matrix, glam::Mat4::IDENTITY)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn midpoint_projects_to_ndc_center() {
+        let viewport = glam::Vec2::new(800.0, 600.0);
+        let eye = glam::Vec3::new(1.0, 2.0, 5.0);
+        let target = glam::Vec3::new(-1.0, 0.0, -3.0);
+        let (p_matrix, mv_matrix) =
+            perspective_mvp_with_eye(viewport, 45.0_f32.to_radians(), eye, target, glam::Vec3::Y);
+        let ndc = (p_matrix * mv_matrix).project_point3((eye + target) * 0.5);
+        assert!(ndc.x.abs() < 1e-5 && ndc.y.abs() < 1e-5);
+        assert!((0.0..1.0).contains(&ndc.z));
+    }
+
+    #[test]
+    fn orbit_looks_at_target() {
+        let target = glam::Vec3::new(1.0, 2.0, 3.0);
+        // yaw = -π/2、pitch = 0 时视线朝 -z，相机位于目标的 +z 方向
+        let (eye, view) = orbit(target, -core::f32::consts::FRAC_PI_2, 0.0, 5.0);
+        assert!(eye.abs_diff_eq(target + glam::Vec3::Z * 5.0, 1e-5));
+        assert!(
+            view.transform_point3(target)
+                .abs_diff_eq(glam::Vec3::new(0.0, 0.0, -5.0), 1e-5)
+        );
+
+        // 俯仰角超出范围时被限制，相机不会越过正上方
+        let (eye, view) = orbit(target, 0.0, -3.0, 5.0);
+        assert!(((eye - target).length() - 5.0).abs() < 1e-4);
+        assert!(eye.y > target.y + 4.99);
+        assert!(view.is_finite());
+    }
+
+    #[test]
+    fn reversed_z_depth_range() {
+        let viewport = glam::Vec2::new(800.0, 600.0);
+        let p_matrix = perspective_reversed_z(viewport, 45.0_f32.to_radians(), 0.1);
+        let depth = |z: f32| p_matrix.project_point3(glam::Vec3::new(0.0, 0.0, z)).z;
+        assert!((depth(-0.1) - 1.0).abs() < 1e-6);
+        // 越远深度越小，但始终大于清除值
+        assert!(depth(-1.0) > depth(-1000.0));
+        assert!(depth(-1.0e6) > REVERSED_Z_CLEAR_DEPTH);
+        assert_eq!(
+            reversed_z_depth_stencil_state().depth_compare,
+            wgpu::CompareFunction::Greater
+        );
+    }
+
+    #[test]
+    fn factor_matches_aspect() {
+        let fovy = 45.0_f32.to_radians();
+        for aspect in [16.0 / 9.0, 4.0 / 3.0, 1.0, 9.0 / 16.0, 3.0 / 4.0] {
+            let factor = fullscreen_factor_for_aspect(aspect, fovy);
+            assert!((factor.sx / factor.sy - aspect).abs() < 1e-5);
+            assert!(factor.sx.min(factor.sy) == 1.0);
+        }
+        let a = fullscreen_factor(glam::Vec2::new(1920.0, 1080.0), fovy);
+        let b = fullscreen_factor_for_aspect(16.0 / 9.0, fovy);
+        assert_eq!((a.sx, a.sy, a.translate_z), (b.sx, b.sy, b.translate_z));
+    }
+
+    #[test]
+    fn default_view_fits_unit_rect() {
+        let viewport = glam::Vec2::new(600.0, 600.0);
+        let (p_matrix, mv_matrix, factor) = perspective_mvp(viewport, 45.0_f32.to_radians());
+        assert_eq!((factor.sx, factor.sy), (1.0, 1.0));
+        let corner = (p_matrix * mv_matrix).project_point3(glam::Vec3::new(1.0, 1.0, 0.0));
+        assert!((corner.x - 1.0).abs() < 1e-5 && (corner.y - 1.0).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,95 @@
+//! 多渲染目标（MRT）的颜色附件
+
+/// G-buffer 各附件常用的清屏值：
+/// - 反照率（albedo）：黑色，未绘制的像素不贡献颜色；
+/// - 法线：`(0.5, 0.5, 1.0)`，即编码到 [0, 1] 后朝向 +z 的法线，未绘制的像素不会被误当成零向量；
+/// - 位置/深度等数据：0，alpha 为 0 可用于标记未绘制的像素。
+pub const GBUFFER_CLEAR_COLORS: [wgpu::Color; 3] = [
+    wgpu::Color::BLACK,
+    wgpu::Color {
+        r: 0.5,
+        g: 0.5,
+        b: 1.0,
+        a: 1.0,
+    },
+    wgpu::Color::TRANSPARENT,
+];
+
+/// 以 `GBUFFER_CLEAR_COLORS` 清屏的加载操作
+pub fn gbuffer_load_ops() -> Vec<wgpu::LoadOp<wgpu::Color>> {
+    GBUFFER_CLEAR_COLORS
+        .iter()
+        .map(|color| wgpu::LoadOp::Clear(*color))
+        .collect()
+}
+
+/// 为每个颜色附件分别指定加载操作（清屏颜色），`views` 与 `load_ops` 按附件的 location 一一对应
+///
+/// # NOTE:
+/// 两者长度不一致时 panic：少于附件数的加载操作通常意味着渲染管线的 targets 与渲染通道不匹配。
+pub fn color_attachments<'a>(
+    views: &[&'a wgpu::TextureView],
+    load_ops: &[wgpu::LoadOp<wgpu::Color>],
+) -> Vec<Option<wgpu::RenderPassColorAttachment<'a>>> {
+    assert_eq!(views.len(), load_ops.len(), "颜色附件数与加载操作数不一致");
+    views
+        .iter()
+        .zip(load_ops)
+        .map(|(view, load)| {
+            Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: *load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })
+        })
+        .collect()
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn clear_two_attachments() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let create_view = || {
+            device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: None,
+                    size: wgpu::Extent3d {
+                        width: 4,
+                        height: 4,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: wgpu::TextureFormat::Rgba8Unorm,
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                    view_formats: &[],
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        };
+        let albedo = create_view();
+        let normal = create_view();
+        let load_ops = &gbuffer_load_ops()[..2];
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        {
+            let _rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("mrt clear"),
+                color_attachments: &color_attachments(&[&albedo, &normal], load_ops),
+                ..Default::default()
+            });
+        }
+        queue.submit(Some(encoder.finish()));
+        assert!(pollster::block_on(device.pop_error_scope()).is_none());
+    }
+}
@@ -69,9 +69,7 @@ impl BindGroupSetting {
                     }
                 } else {
                     wgpu::BindingType::Texture {
-                        sample_type: wgpu::TextureSampleType::Float {
-                            filterable: texture_sample_filterable(any_tex.format),
-                        },
+                        sample_type: texture_sample_type(any_tex.format),
                         view_dimension,
                         multisampled: false,
                     }
@@ -121,6 +119,17 @@ impl BindGroupSetting {
     }
 }
 
+// 深度格式的纹理以 `Depth` 绑定（着色器中为 `texture_depth_2d`），只能搭配 textureLoad 或非过滤采样器读取
+fn texture_sample_type(format: TextureFormat) -> wgpu::TextureSampleType {
+    if format.is_depth_stencil_format() {
+        wgpu::TextureSampleType::Depth
+    } else {
+        wgpu::TextureSampleType::Float {
+            filterable: texture_sample_filterable(format),
+        }
+    }
+}
+
 fn texture_sample_filterable(format: TextureFormat) -> bool {
     match format {
         // on iOS: texture binding 1 expects sample type = Float { filterable: true }, but given a view with format = R32Float
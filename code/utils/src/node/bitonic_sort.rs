@@ -0,0 +1,216 @@
+use super::{BindGroupData, ComputeNode};
+use crate::{BufferObj, DynamicUniformRing};
+use bytemuck::{Pod, Zeroable};
+
+/// 排序键：按 `key` 排序，`index` 指向被排序的原始元素
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable, PartialEq)]
+pub struct SortKey {
+    pub key: f32,
+    pub index: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+struct SortParams {
+    k: u32,
+    j: u32,
+    count: u32,
+    descending: u32,
+}
+
+/// 对 `SortKey` 存储缓冲区做原地双调排序（bitonic sort）的计算节点
+///
+/// # NOTE:
+/// - 元素数必须为 2 的幂，不足时由调用方填充，且填充元素的 key 需保证排在末尾
+///   （升序时用 `f32::MAX`，降序时用 `f32::MIN`）；
+/// - 每次排序需 log2(n) * (log2(n) + 1) / 2 次 dispatch，总比较次数为 O(n log²n)，
+///   几十万个粒子时每帧约 200 次 dispatch，需留意其开销。
+pub struct BitonicSortNode {
+    node: ComputeNode,
+    // 每个排序阶段对应一个动态偏移
+    offsets: Vec<Vec<wgpu::DynamicOffset>>,
+}
+
+#[allow(dead_code)]
+impl BitonicSortNode {
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        keys: &BufferObj,
+        count: u32,
+        descending: bool,
+    ) -> Self {
+        assert!(count.is_power_of_two(), "双调排序的元素数必须为 2 的幂");
+
+        let stages = bitonic_stages(count);
+        // 每个阶段占用一个按设备偏移对齐的槽位
+        let params_buf = DynamicUniformRing::<SortParams>::new(
+            device,
+            stages.len().max(1) as u32,
+            Some("bitonic sort params"),
+        );
+        let params: Vec<SortParams> = stages
+            .iter()
+            .map(|&(k, j)| SortParams {
+                k,
+                j,
+                count,
+                descending: descending as u32,
+            })
+            .collect();
+        params_buf.write_frames(queue, &params);
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("bitonic sort"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("bitonic_sort.wgsl").into()),
+        });
+        let bg_data = BindGroupData {
+            storage_buffers: vec![keys],
+            dynamic_uniforms: vec![&*params_buf],
+            workgroup_count: (count.div_ceil(256), 1, 1),
+            ..Default::default()
+        };
+        let node = ComputeNode::new_with_dynamic_uniforms(device, &bg_data, &shader);
+        let offsets = (0..stages.len())
+            .map(|i| vec![params_buf.dynamic_offset(i as u32)])
+            .collect();
+
+        Self { node, offsets }
+    }
+
+    /// 填充后的元素数
+    pub fn padded_len(count: u32) -> u32 {
+        count.max(1).next_power_of_two()
+    }
+
+    pub fn sort_by_pass<'a, 'b: 'a>(&'b self, cpass: &mut wgpu::ComputePass<'a>) {
+        self.node.dispatch_by_offsets(cpass, Some(&self.offsets));
+    }
+}
+
+/// 排序网络的全部 (k, j) 阶段
+pub fn bitonic_stages(count: u32) -> Vec<(u32, u32)> {
+    let mut stages = vec![];
+    let mut k = 2;
+    while k <= count {
+        let mut j = k / 2;
+        while j > 0 {
+            stages.push((k, j));
+            j /= 2;
+        }
+        k *= 2;
+    }
+    stages
+}
+
+/// 与 `bitonic_sort.wgsl` 相同排序网络的 CPU 实现，用于验证
+#[cfg(test)]
+fn bitonic_sort_cpu(keys: &mut [SortKey], descending: bool) {
+    let count = keys.len() as u32;
+    assert!(count.is_power_of_two());
+    for (k, j) in bitonic_stages(count) {
+        for i in 0..count {
+            let l = i ^ j;
+            if l <= i || l >= count {
+                continue;
+            }
+            let (a, b) = (keys[i as usize], keys[l as usize]);
+            let ascending = ((i & k) == 0) != descending;
+            if (ascending && a.key > b.key) || (!ascending && a.key < b.key) {
+                keys.swap(i as usize, l as usize);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn keys_from(values: &[f32]) -> Vec<SortKey> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(index, key)| SortKey {
+                key: *key,
+                index: index as u32,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn matches_cpu_sort() {
+        let values = [
+            0.3, -1.0, 7.5, 2.0, 2.0, -4.25, 9.0, 0.0, 1.5, -0.5, 3.0, 6.0, -2.0, 8.0, 5.0, 4.0,
+        ];
+        for descending in [false, true] {
+            let mut keys = keys_from(&values);
+            bitonic_sort_cpu(&mut keys, descending);
+
+            let mut expected = values.to_vec();
+            expected.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            if descending {
+                expected.reverse();
+            }
+            let sorted: Vec<f32> = keys.iter().map(|k| k.key).collect();
+            assert_eq!(sorted, expected);
+            // index 仍指向原始元素
+            assert!(keys.iter().all(|k| values[k.index as usize] == k.key));
+        }
+    }
+
+    #[test]
+    fn stage_count() {
+        assert_eq!(bitonic_stages(1).len(), 0);
+        assert_eq!(bitonic_stages(16).len(), 10);
+        assert_eq!(BitonicSortNode::padded_len(1000), 1024);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod gpu_test {
+    use super::*;
+
+    #[test]
+    fn gpu_sort_matches_cpu() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        // 64 个元素，含重复的 key
+        let keys: Vec<SortKey> = (0..64_u32)
+            .map(|index| SortKey {
+                key: ((index * 37) % 23) as f32 - 11.5,
+                index,
+            })
+            .collect();
+        for descending in [false, true] {
+            let keys_buf = BufferObj::create_buffer(
+                &device,
+                Some(&keys),
+                None,
+                wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+                None,
+            );
+            let node = BitonicSortNode::new(&device, &queue, &keys_buf, 64, descending);
+            let mut encoder =
+                device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+            {
+                let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+                node.sort_by_pass(&mut cpass);
+            }
+            queue.submit(Some(encoder.finish()));
+
+            let sorted = keys_buf.read_back::<SortKey>(&device, &queue).unwrap();
+            let mut expected = keys.clone();
+            bitonic_sort_cpu(&mut expected, descending);
+            assert_eq!(sorted, expected);
+            assert!(sorted.windows(2).all(|w| if descending {
+                w[0].key >= w[1].key
+            } else {
+                w[0].key <= w[1].key
+            }));
+        }
+    }
+}
@@ -0,0 +1,37 @@
+// 双调排序的一个比较-交换步骤
+// 每次 dispatch 对应排序网络中的一个 (k, j) 阶段
+
+struct SortParams {
+    // 当前双调序列的长度
+    k: u32,
+    // 比较距离
+    j: u32,
+    // 参与排序的元素数，必须为 2 的幂
+    count: u32,
+    // 1 表示降序
+    descending: u32,
+};
+
+struct SortKey {
+    key: f32,
+    index: u32,
+};
+
+@group(0) @binding(0) var<storage, read_write> keys: array<SortKey>;
+@group(1) @binding(0) var<uniform> params: SortParams;
+
+@compute @workgroup_size(256)
+fn cs_main(@builtin(global_invocation_id) gid: vec3u) {
+    let i = gid.x;
+    let l = i ^ params.j;
+    if (l <= i || l >= params.count) {
+        return;
+    }
+    let a = keys[i];
+    let b = keys[l];
+    let ascending = ((i & params.k) == 0u) != (params.descending == 1u);
+    if ((ascending && a.key > b.key) || (!ascending && a.key < b.key)) {
+        keys[i] = b;
+        keys[l] = a;
+    }
+}
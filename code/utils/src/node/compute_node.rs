@@ -1,6 +1,6 @@
 use wgpu::{PushConstantRange, ShaderModule};
 
-use super::{BindGroupSetting, DynamicUniformBindGroup};
+use super::{BindGroupSetting, DynamicUniformBindGroup, PushConstantError};
 use crate::BufferObj;
 
 use core::ops::Range;
@@ -73,22 +73,27 @@ impl ComputeNode {
         }
     }
 
+    /// 创建带推送常量的计算节点，设备不支持推送常量时 panic，见 [`ComputeNode::try_new_with_push_constants`]
     pub fn new_with_push_constants(
         device: &wgpu::Device,
         bg_data: &super::BindGroupData,
         shader_module: &ShaderModule,
         push_constants: Option<Vec<(wgpu::ShaderStages, Range<u32>)>>,
     ) -> Self {
-        let mut visibilitys: Vec<wgpu::ShaderStages> = vec![];
-        for _ in
-            0..(bg_data.uniforms.len() + bg_data.storage_buffers.len() + bg_data.inout_tv.len())
-        {
-            visibilitys.push(wgpu::ShaderStages::COMPUTE);
-        }
-        let mut bg_data = bg_data.clone();
-        bg_data.visibilitys = visibilitys;
-        let bg_setting = BindGroupSetting::new(device, &bg_data);
+        Self::try_new_with_push_constants(device, bg_data, shader_module, push_constants)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
 
+    /// 创建带推送常量的计算节点
+    ///
+    /// 设备未开启 `Features::PUSH_CONSTANTS`（如 WebGPU/WebGL）或范围超出 `max_push_constant_size` 时返回错误，
+    /// 调用方可以据此回退为 uniform 缓冲区。
+    pub fn try_new_with_push_constants(
+        device: &wgpu::Device,
+        bg_data: &super::BindGroupData,
+        shader_module: &ShaderModule,
+        push_constants: Option<Vec<(wgpu::ShaderStages, Range<u32>)>>,
+    ) -> Result<Self, PushConstantError> {
         let mut ranges: Vec<PushConstantRange> = vec![];
         if let Some(constants) = push_constants {
             for (stage, range) in constants.iter() {
@@ -98,6 +103,17 @@ impl ComputeNode {
                 })
             }
         }
+        super::check_push_constant_ranges(device, &ranges)?;
+
+        let mut visibilitys: Vec<wgpu::ShaderStages> = vec![];
+        for _ in
+            0..(bg_data.uniforms.len() + bg_data.storage_buffers.len() + bg_data.inout_tv.len())
+        {
+            visibilitys.push(wgpu::ShaderStages::COMPUTE);
+        }
+        let mut bg_data = bg_data.clone();
+        bg_data.visibilitys = visibilitys;
+        let bg_setting = BindGroupSetting::new(device, &bg_data);
 
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: None,
@@ -113,13 +129,19 @@ impl ComputeNode {
             cache: None,
         });
 
-        ComputeNode {
+        Ok(ComputeNode {
             bg_setting,
             dy_uniform_bg: None,
             pipeline_layout,
             pipeline,
             workgroup_count: bg_data.workgroup_count,
-        }
+        })
+    }
+
+    /// 设置推送常量，需在 `dispatch_*` 之前调用；`offset` 与 `data` 的长度都需是 4 的整数倍
+    pub fn set_push_constants(&self, cpass: &mut wgpu::ComputePass, offset: u32, data: &[u8]) {
+        cpass.set_pipeline(&self.pipeline);
+        cpass.set_push_constants(offset, data);
     }
 
     pub fn compute(&self, encoder: &mut wgpu::CommandEncoder) {
@@ -133,7 +155,7 @@ impl ComputeNode {
     pub fn compute_by_offsets(
         &self,
         encoder: &mut wgpu::CommandEncoder,
-        offsets: Option<Vec<Vec<wgpu::DynamicOffset>>>,
+        offsets: Option<&[Vec<wgpu::DynamicOffset>]>,
     ) {
         let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
         self.dispatch_by_offsets(&mut cpass, offsets);
@@ -142,13 +164,13 @@ impl ComputeNode {
     pub fn dispatch_by_offsets<'a, 'b: 'a>(
         &'b self,
         cpass: &mut wgpu::ComputePass<'a>,
-        offsets: Option<Vec<Vec<wgpu::DynamicOffset>>>,
+        offsets: Option<&[Vec<wgpu::DynamicOffset>]>,
     ) {
         cpass.set_pipeline(&self.pipeline);
         cpass.set_bind_group(0, &self.bg_setting.bind_group, &[]);
         if let Some(offsets) = offsets {
             for os in offsets {
-                cpass.set_bind_group(1, &self.dy_uniform_bg.as_ref().unwrap().bind_group, &os);
+                cpass.set_bind_group(1, &self.dy_uniform_bg.as_ref().unwrap().bind_group, os);
                 cpass.dispatch_workgroups(
                     self.workgroup_count.0,
                     self.workgroup_count.1,
@@ -163,4 +185,139 @@ impl ComputeNode {
             );
         }
     }
+
+    /// 从 `indirect_buf` 的 `offset` 处读取工作组数量并派发，忽略 `workgroup_count`
+    ///
+    /// 用于工作组数量由 GPU 计算得出的场景，如 GPU 驱动的粒子生成：前一个计算通道写入本帧要生成的粒子数，
+    /// 本通道直接按它派发，不需要回读到 CPU。
+    ///
+    /// 参数的布局是连续的 3 个 `u32`，共 12 字节：`[x, y, z]`，与 `wgpu::util::DispatchIndirectArgs` 一致。
+    /// `dy_offsets` 为第 1 组（动态 uniform）的动态偏移，与 `dispatch_by_offsets` 中的一组偏移相同。
+    ///
+    /// # NOTE:
+    /// - `indirect_buf` 需带有 `BufferUsages::INDIRECT`（可由 [`BufferObj::create_indirect_buffer`] 创建），
+    ///   `offset` 需是 4 的整数倍，且 `offset + 12` 不能超出缓冲区的大小；
+    /// - 由 `new_with_dynamic_uniforms` 创建的节点必须传入 `dy_offsets`，其它节点必须传入 `None`。
+    pub fn dispatch_indirect<'a, 'b: 'a>(
+        &'b self,
+        cpass: &mut wgpu::ComputePass<'a>,
+        indirect_buf: &wgpu::Buffer,
+        offset: wgpu::BufferAddress,
+        dy_offsets: Option<&[wgpu::DynamicOffset]>,
+    ) {
+        assert!(
+            indirect_buf.usage().contains(wgpu::BufferUsages::INDIRECT),
+            "间接派发的缓冲区需带有 BufferUsages::INDIRECT"
+        );
+        assert!(offset % 4 == 0, "间接派发的偏移量需是 4 的整数倍");
+        cpass.set_pipeline(&self.pipeline);
+        cpass.set_bind_group(0, &self.bg_setting.bind_group, &[]);
+        match (&self.dy_uniform_bg, dy_offsets) {
+            (Some(dy_uniform_bg), Some(dy_offsets)) => {
+                cpass.set_bind_group(1, &dy_uniform_bg.bind_group, dy_offsets);
+            }
+            (None, None) => {}
+            (Some(_), None) => panic!("带有动态 uniform 的节点需传入 dy_offsets"),
+            (None, Some(_)) => panic!("节点没有动态 uniform，dy_offsets 需为 None"),
+        }
+        cpass.dispatch_workgroups_indirect(indirect_buf, offset);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dispatch_from_indirect_buffer() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        let indirect = BufferObj::create_indirect_buffer(&device, (4, 1, 1), None);
+        assert_eq!(indirect.size, 12);
+        let data = BufferObj::create_storage_buffer(&device, &[0_u32; 4], None);
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(
+                "@group(0) @binding(0) var<storage, read_write> data: array<u32>;
+                @compute @workgroup_size(1)
+                fn cs_main(@builtin(global_invocation_id) id: vec3<u32>) {
+                    data[id.x] = id.x + 1u;
+                }"
+                .into(),
+            ),
+        });
+        let bg_data = crate::node::BindGroupData {
+            storage_buffers: vec![&data],
+            ..Default::default()
+        };
+
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let node = ComputeNode::new(&device, &bg_data, &shader);
+        let mut encoder = device.create_command_encoder(&Default::default());
+        {
+            let mut cpass = encoder.begin_compute_pass(&Default::default());
+            node.dispatch_indirect(&mut cpass, &indirect.buffer, 0, None);
+        }
+        queue.submit(Some(encoder.finish()));
+        let error = pollster::block_on(device.pop_error_scope());
+        assert!(error.is_none(), "{error:?}");
+    }
+
+    #[test]
+    fn dispatch_indirect_with_dynamic_uniform() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        let indirect = BufferObj::create_indirect_buffer(&device, (4, 1, 1), None);
+        let data = BufferObj::create_buffer(
+            &device,
+            Some(&[0_u32; 4]),
+            None,
+            wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            None,
+        );
+        // 槽位 0 加 10、槽位 1 加 20
+        let params = crate::DynamicUniformRing::<u32>::new(&device, 2, None);
+        params.write_frames(&queue, &[10, 20]);
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(
+                "@group(0) @binding(0) var<storage, read_write> data: array<u32>;
+                @group(1) @binding(0) var<uniform> add: u32;
+                @compute @workgroup_size(1)
+                fn cs_main(@builtin(global_invocation_id) id: vec3<u32>) {
+                    data[id.x] = id.x + add;
+                }"
+                .into(),
+            ),
+        });
+        let bg_data = crate::node::BindGroupData {
+            storage_buffers: vec![&data],
+            dynamic_uniforms: vec![&*params],
+            ..Default::default()
+        };
+
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let node = ComputeNode::new_with_dynamic_uniforms(&device, &bg_data, &shader);
+        let mut encoder = device.create_command_encoder(&Default::default());
+        {
+            let mut cpass = encoder.begin_compute_pass(&Default::default());
+            node.dispatch_indirect(
+                &mut cpass,
+                &indirect.buffer,
+                0,
+                Some(&[params.dynamic_offset(1)]),
+            );
+        }
+        queue.submit(Some(encoder.finish()));
+        let error = pollster::block_on(device.pop_error_scope());
+        assert!(error.is_none(), "{error:?}");
+        assert_eq!(
+            data.read_back::<u32>(&device, &queue).unwrap(),
+            vec![20, 21, 22, 23]
+        );
+    }
 }
@@ -0,0 +1,292 @@
+use crate::DEPTH_FORMAT;
+use bytemuck::{Pod, Zeroable};
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct DepthRange {
+    near: f32,
+    far: f32,
+    padding: [f32; 2],
+}
+
+/// 多个采样点的深度合并为一个值的方式
+///
+/// - `Min`：取离相机最近的深度，物体边缘向外扩张，适合软粒子，避免粒子在几何体边缘处漏出；
+/// - `Max`：取最远的深度，物体边缘向内收缩，适合需要保守判断遮挡的效果；
+/// - `Average`：取平均值，边缘处得到介于前后景之间、实际并不存在的深度，SSAO 等效果可能在边缘出现光晕。
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DepthResolveMode {
+    #[default]
+    Min,
+    Max,
+    Average,
+}
+
+impl DepthResolveMode {
+    fn shader_constants(&self) -> String {
+        let mode = match self {
+            DepthResolveMode::Min => 0,
+            DepthResolveMode::Max => 1,
+            DepthResolveMode::Average => 2,
+        };
+        format!("const RESOLVE_MODE: u32 = {mode}u;\n")
+    }
+}
+
+/// 解析结果的格式
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DepthResolveTarget {
+    /// 单采样的深度纹理（`DEPTH_FORMAT`），可以继续作为深度附件或以 `texture_depth_2d` 采样
+    Depth,
+    /// `R32Float` 格式的视图空间线性深度，即到相机的距离
+    LinearDepth,
+}
+
+impl DepthResolveTarget {
+    pub fn format(&self) -> wgpu::TextureFormat {
+        match self {
+            DepthResolveTarget::Depth => DEPTH_FORMAT,
+            DepthResolveTarget::LinearDepth => wgpu::TextureFormat::R32Float,
+        }
+    }
+}
+
+/// 把多重采样的深度纹理解析为单采样的深度（或线性深度）纹理
+///
+/// wgpu 只会自动解析颜色附件（`resolve_target`），开启 MSAA 后软粒子、SSAO 等需要读取深度的效果，
+/// 要先用这个通道在着色器中以 `textureLoad` 逐个读取采样点，合并为单采样的纹理。
+///
+/// # NOTE:
+/// 多重采样的深度纹理创建时需包含 `TextureUsages::TEXTURE_BINDING`，
+/// 输出纹理与之尺寸一致、采样数为 1，格式为 `target.format()`。
+/// `Average` 模式合并的是透视投影后的非线性深度，之后再线性化。
+pub struct DepthResolve {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    target: DepthResolveTarget,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+    range_buf: wgpu::Buffer,
+}
+
+#[allow(dead_code)]
+impl DepthResolve {
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        mode: DepthResolveMode,
+        target: DepthResolveTarget,
+    ) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("depth resolve"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Depth,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: true,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let source = mode.shader_constants() + include_str!("depth_resolve.wgsl");
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("depth resolve"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+        let color_targets = [Some(wgpu::ColorTargetState {
+            format: target.format(),
+            blend: None,
+            write_mask: wgpu::ColorWrites::ALL,
+        })];
+        let (entry_point, targets, depth_stencil) = match target {
+            DepthResolveTarget::Depth => (
+                "fs_depth",
+                &[][..],
+                Some(wgpu::DepthStencilState {
+                    format: DEPTH_FORMAT,
+                    depth_write_enabled: true,
+                    depth_compare: wgpu::CompareFunction::Always,
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+            ),
+            DepthResolveTarget::LinearDepth => ("fs_linear", &color_targets[..], None),
+        };
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("depth resolve pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some(entry_point),
+                compilation_options: Default::default(),
+                targets,
+            }),
+            primitive: wgpu::PrimitiveState {
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+        let range_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("depth resolve range"),
+            size: core::mem::size_of::<DepthRange>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            device: device.clone(),
+            queue: queue.clone(),
+            target,
+            bind_group_layout,
+            pipeline,
+            range_buf,
+        }
+    }
+
+    pub fn target(&self) -> DepthResolveTarget {
+        self.target
+    }
+
+    /// 把 `msaa_depth_view` 解析到 `output_view`
+    ///
+    /// `near`/`far` 需与生成深度时的投影矩阵一致，只在输出线性深度时使用。
+    pub fn resolve(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        msaa_depth_view: &wgpu::TextureView,
+        output_view: &wgpu::TextureView,
+        near: f32,
+        far: f32,
+    ) {
+        self.queue.write_buffer(
+            &self.range_buf,
+            0,
+            bytemuck::bytes_of(&DepthRange {
+                near,
+                far,
+                padding: [0.0; 2],
+            }),
+        );
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("depth resolve"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: self.range_buf.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(msaa_depth_view),
+                },
+            ],
+        });
+        let color_attachments = [Some(wgpu::RenderPassColorAttachment {
+            view: output_view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                store: wgpu::StoreOp::Store,
+            },
+        })];
+        let (color_attachments, depth_stencil_attachment) = match self.target {
+            DepthResolveTarget::Depth => (
+                &[][..],
+                Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: output_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+            ),
+            DepthResolveTarget::LinearDepth => (&color_attachments[..], None),
+        };
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("depth resolve"),
+            color_attachments,
+            depth_stencil_attachment,
+            ..Default::default()
+        });
+        rpass.set_pipeline(&self.pipeline);
+        rpass.set_bind_group(0, &bind_group, &[]);
+        rpass.draw(0..3, 0..1);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn resolve_4x_msaa_depth() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        let create_view = |format, sample_count, usage| {
+            device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: None,
+                    size: wgpu::Extent3d {
+                        width: 4,
+                        height: 4,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count,
+                    dimension: wgpu::TextureDimension::D2,
+                    format,
+                    usage,
+                    view_formats: &[],
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        };
+        let usage = wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING;
+        let msaa_depth = create_view(DEPTH_FORMAT, 4, usage);
+
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let mut encoder = device.create_command_encoder(&Default::default());
+        for (mode, target) in [
+            (DepthResolveMode::Min, DepthResolveTarget::Depth),
+            (DepthResolveMode::Average, DepthResolveTarget::LinearDepth),
+        ] {
+            let resolve = DepthResolve::new(&device, &queue, mode, target);
+            let output = create_view(target.format(), 1, usage);
+            resolve.resolve(&mut encoder, &msaa_depth, &output, 0.1, 100.0);
+        }
+        queue.submit(Some(encoder.finish()));
+        let error = pollster::block_on(device.pop_error_scope());
+        assert!(error.is_none(), "{error:?}");
+    }
+}
@@ -0,0 +1,57 @@
+// RESOLVE_MODE 常量由 DepthResolveMode 生成：0 取最小值，1 取最大值，2 取平均值
+
+// 覆盖整个剪辑空间的大三角形
+@vertex
+fn vs_main(@builtin(vertex_index) vertexIndex: u32) -> @builtin(position) vec4f {
+    let uv = vec2f(f32((vertexIndex << 1u) & 2u), f32(vertexIndex & 2u));
+    return vec4f(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+struct DepthRange {
+    near: f32,
+    far: f32,
+    padding: vec2f,
+};
+
+@group(0) @binding(0) var<uniform> depth_range: DepthRange;
+@group(0) @binding(1) var depth_ms: texture_depth_multisampled_2d;
+
+// 逐个读取像素内所有采样点的深度，按 RESOLVE_MODE 合并
+fn resolve_depth(coord: vec2i) -> f32 {
+    let count = i32(textureNumSamples(depth_ms));
+    var depth = textureLoad(depth_ms, coord, 0);
+    for (var i = 1; i < count; i++) {
+        let d = textureLoad(depth_ms, coord, i);
+        switch RESOLVE_MODE {
+            case 0u: {
+                depth = min(depth, d);
+            }
+            case 1u: {
+                depth = max(depth, d);
+            }
+            default: {
+                depth += d;
+            }
+        }
+    }
+    if (RESOLVE_MODE == 2u) {
+        depth /= f32(count);
+    }
+    return depth;
+}
+
+// 输出单采样的深度纹理
+@fragment
+fn fs_depth(@builtin(position) position: vec4f) -> @builtin(frag_depth) f32 {
+    return resolve_depth(vec2i(position.xy));
+}
+
+// 输出视图空间的线性深度（到相机的距离）
+@fragment
+fn fs_linear(@builtin(position) position: vec4f) -> @location(0) vec4f {
+    let depth = resolve_depth(vec2i(position.xy));
+    let near = depth_range.near;
+    let far = depth_range.far;
+    let linear_depth = near * far / (far - depth * (far - near));
+    return vec4f(linear_depth, 0.0, 0.0, 1.0);
+}
@@ -0,0 +1,174 @@
+use bytemuck::{Pod, Zeroable};
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct DepthRange {
+    near: f32,
+    far: f32,
+    padding: [f32; 2],
+}
+
+/// 把透视投影写入的 [0, 1] 深度值还原为 [0, 1] 的线性灰度，与 `depth_visualizer.wgsl` 中的计算一致
+pub fn linearize_depth(depth: f32, near: f32, far: f32) -> f32 {
+    let linear_depth = near * far / (far - depth * (far - near));
+    ((linear_depth - near) / (far - near)).clamp(0.0, 1.0)
+}
+
+/// 把深度缓冲区以灰度图显示出来的全屏绘制节点，用于调试深度问题
+///
+/// # NOTE:
+/// 深度纹理需以 `TextureSampleType::Depth` 绑定（着色器中为 `texture_depth_2d`），
+/// 且深度格式不可过滤，所以搭配的是 `SamplerBindingType::NonFiltering` 的最近邻采样器；
+/// 深度纹理创建时需包含 `TextureUsages::TEXTURE_BINDING`。
+pub struct DepthVisualizer {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+    range_buf: wgpu::Buffer,
+    sampler: wgpu::Sampler,
+}
+
+#[allow(dead_code)]
+impl DepthVisualizer {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, format: wgpu::TextureFormat) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("depth visualizer"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Depth,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::NonFiltering),
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("depth visualizer"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("depth_visualizer.wgsl").into()),
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("depth visualizer pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+        let range_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("depth range"),
+            size: core::mem::size_of::<DepthRange>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let sampler = crate::default_sampler(device);
+
+        Self {
+            device: device.clone(),
+            queue: queue.clone(),
+            bind_group_layout,
+            pipeline,
+            range_buf,
+            sampler,
+        }
+    }
+
+    /// 以灰度绘制 `depth_view`，`near`/`far` 需与生成深度时的投影矩阵一致
+    pub fn draw(
+        &self,
+        rpass: &mut wgpu::RenderPass<'_>,
+        depth_view: &wgpu::TextureView,
+        near: f32,
+        far: f32,
+    ) {
+        self.queue.write_buffer(
+            &self.range_buf,
+            0,
+            bytemuck::bytes_of(&DepthRange {
+                near,
+                far,
+                padding: [0.0; 2],
+            }),
+        );
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("depth visualizer"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: self.range_buf.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(depth_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+        });
+        rpass.set_pipeline(&self.pipeline);
+        rpass.set_bind_group(0, &bind_group, &[]);
+        rpass.draw(0..3, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn linearize_depth_range() {
+        let (near, far) = (0.1, 100.0);
+        assert_eq!(linearize_depth(0.0, near, far), 0.0);
+        assert!((linearize_depth(1.0, near, far) - 1.0).abs() < 1e-5);
+        // 透视深度在近处变化剧烈，线性化后中间值应远小于原始深度
+        assert!(linearize_depth(0.99, near, far) < 0.1);
+    }
+}
@@ -0,0 +1,36 @@
+struct VertexOutput {
+    @location(0) uv: vec2f,
+    @builtin(position) position: vec4f,
+};
+
+// 覆盖整个剪辑空间的大三角形
+@vertex
+fn vs_main(@builtin(vertex_index) vertexIndex: u32) -> VertexOutput {
+    let uv = vec2f(f32((vertexIndex << 1u) & 2u), f32(vertexIndex & 2u));
+    var out: VertexOutput;
+    out.position = vec4f(uv * 2.0 - 1.0, 0.0, 1.0);
+    // invert uv.y
+    out.uv = vec2f(uv.x, 1.0 - uv.y);
+    return out;
+}
+
+struct DepthRange {
+    near: f32,
+    far: f32,
+    padding: vec2f,
+};
+
+@group(0) @binding(0) var<uniform> depth_range: DepthRange;
+@group(0) @binding(1) var depth_tex: texture_depth_2d;
+@group(0) @binding(2) var depth_sampler: sampler;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4f {
+    let depth = textureSample(depth_tex, depth_sampler, in.uv);
+    let near = depth_range.near;
+    let far = depth_range.far;
+    // 透视投影的 [0, 1] 深度还原为视图空间的线性深度
+    let linear_depth = near * far / (far - depth * (far - near));
+    let gray = clamp((linear_depth - near) / (far - near), 0.0, 1.0);
+    return vec4f(vec3f(gray), 1.0);
+}
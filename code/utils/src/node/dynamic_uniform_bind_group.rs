@@ -5,12 +5,15 @@ use std::vec::Vec;
 pub struct DynamicUniformBindGroup {
     pub bind_group_layout: wgpu::BindGroupLayout,
     pub bind_group: wgpu::BindGroup,
+    // 各绑定的缓冲区槽位间距，取自 `BufferObj::dynamic_offset_stride`
+    pub strides: Vec<wgpu::BufferAddress>,
 }
 
 impl DynamicUniformBindGroup {
     pub fn new(device: &wgpu::Device, uniforms: Vec<(&BufferObj, wgpu::ShaderStages)>) -> Self {
         let mut layouts: Vec<wgpu::BindGroupLayoutEntry> = vec![];
         let mut entries: Vec<wgpu::BindGroupEntry> = vec![];
+        let mut strides: Vec<wgpu::BufferAddress> = vec![];
 
         for (b_index, (buffer_obj, visibility)) in uniforms.iter().enumerate() {
             layouts.push(wgpu::BindGroupLayoutEntry {
@@ -24,6 +27,13 @@ impl DynamicUniformBindGroup {
                 },
                 count: None,
             });
+            // 未记录槽位间距的缓冲区沿用设备的偏移对齐作为间距
+            let stride = if buffer_obj.dynamic_offset_stride > 0 {
+                buffer_obj.dynamic_offset_stride
+            } else {
+                device.limits().min_uniform_buffer_offset_alignment as wgpu::BufferAddress
+            };
+            strides.push(stride);
             // 对于动态 uniform buffer, 必须指定 buffer slice 大小
             // make sure that in your BindingResource::Buffer, you're slicing with .slice(..size_of::<Whatever>() as BufferAddress)
             // and not .slice(..)
@@ -34,9 +44,7 @@ impl DynamicUniformBindGroup {
                     buffer: &buffer_obj.buffer,
                     offset: 0,
                     // size: buffer_obj.0.min_binding_size,
-                    size: wgpu::BufferSize::new(
-                        device.limits().min_uniform_buffer_offset_alignment as wgpu::BufferAddress,
-                    ),
+                    size: wgpu::BufferSize::new(stride),
                 }),
             });
         }
@@ -54,6 +62,12 @@ impl DynamicUniformBindGroup {
         DynamicUniformBindGroup {
             bind_group_layout,
             bind_group,
+            strides,
         }
     }
+
+    /// 第 `binding` 个缓冲区第 `index` 个槽位的动态偏移量
+    pub fn dynamic_offset(&self, binding: usize, index: u32) -> wgpu::DynamicOffset {
+        (self.strides[binding] * index as wgpu::BufferAddress) as wgpu::DynamicOffset
+    }
 }
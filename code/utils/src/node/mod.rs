@@ -7,8 +7,25 @@ pub use bind_group_setting::BindGroupSetting;
 mod dynamic_uniform_bind_group;
 pub use dynamic_uniform_bind_group::DynamicUniformBindGroup;
 
+mod push_constant;
+pub use push_constant::{PushConstantError, check_push_constant_ranges};
+
 mod view_node;
-pub use view_node::{ViewNode, ViewNodeBuilder};
+pub use view_node::{
+    ViewNode, ViewNodeBuilder, clamp_index_range, draw_with_depth_prepass, select_lod,
+};
+mod bitonic_sort;
+pub use bitonic_sort::{BitonicSortNode, SortKey, bitonic_stages};
+
+mod depth_resolve;
+pub use depth_resolve::{DepthResolve, DepthResolveMode, DepthResolveTarget};
+
+mod depth_visualizer;
+pub use depth_visualizer::{DepthVisualizer, linearize_depth};
+
+mod wireframe_overlay;
+pub use wireframe_overlay::{BarycentricVertex, WireframeOverlay, expand_with_barycentric};
+
 mod bufferless_fullscreen_node;
 pub use bufferless_fullscreen_node::BufferlessFullscreenNode;
 
@@ -0,0 +1,91 @@
+/// 推送常量不可用的原因
+#[derive(Debug, Clone, PartialEq)]
+pub enum PushConstantError {
+    /// 设备未开启 `Features::PUSH_CONSTANTS`（WebGPU 与 WebGL 后端都不支持）
+    Unsupported,
+    /// 推送常量的范围超出了设备的 `max_push_constant_size`
+    TooLarge { size: u32, max: u32 },
+}
+
+impl core::fmt::Display for PushConstantError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Unsupported => write!(f, "device does not enable Features::PUSH_CONSTANTS"),
+            Self::TooLarge { size, max } => write!(
+                f,
+                "push constant range of {size} bytes exceeds max_push_constant_size {max}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PushConstantError {}
+
+/// 检查设备能否使用 `ranges` 描述的推送常量，`ranges` 为空时总是可用
+///
+/// # NOTE:
+/// 推送常量需要在请求设备时开启 `Features::PUSH_CONSTANTS` 并把 `Limits::max_push_constant_size`
+/// 设为所需的大小（默认为 0）；不可用时应回退为 uniform 缓冲区。
+pub fn check_push_constant_ranges(
+    device: &wgpu::Device,
+    ranges: &[wgpu::PushConstantRange],
+) -> Result<(), PushConstantError> {
+    push_constant_ranges_supported(device.features(), &device.limits(), ranges)
+}
+
+fn push_constant_ranges_supported(
+    features: wgpu::Features,
+    limits: &wgpu::Limits,
+    ranges: &[wgpu::PushConstantRange],
+) -> Result<(), PushConstantError> {
+    let Some(size) = ranges.iter().map(|r| r.range.end).max() else {
+        return Ok(());
+    };
+    if !features.contains(wgpu::Features::PUSH_CONSTANTS) {
+        return Err(PushConstantError::Unsupported);
+    }
+    if size > limits.max_push_constant_size {
+        return Err(PushConstantError::TooLarge {
+            size,
+            max: limits.max_push_constant_size,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn push_constant_availability() {
+        let range = |size| wgpu::PushConstantRange {
+            stages: wgpu::ShaderStages::VERTEX,
+            range: 0..size,
+        };
+        let limits = wgpu::Limits {
+            max_push_constant_size: 128,
+            ..Default::default()
+        };
+        let features = wgpu::Features::PUSH_CONSTANTS;
+        assert_eq!(
+            push_constant_ranges_supported(wgpu::Features::empty(), &limits, &[]),
+            Ok(())
+        );
+        assert_eq!(
+            push_constant_ranges_supported(wgpu::Features::empty(), &limits, &[range(4)]),
+            Err(PushConstantError::Unsupported)
+        );
+        assert_eq!(
+            push_constant_ranges_supported(features, &limits, &[range(128)]),
+            Ok(())
+        );
+        assert_eq!(
+            push_constant_ranges_supported(features, &limits, &[range(4), range(256)]),
+            Err(PushConstantError::TooLarge {
+                size: 256,
+                max: 128
+            })
+        );
+    }
+}
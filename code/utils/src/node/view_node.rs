@@ -1,9 +1,9 @@
-use super::{BindGroupData, BindGroupSetting};
+use super::{BindGroupData, BindGroupSetting, PushConstantError};
 use crate::BufferObj;
 use crate::DEPTH_FORMAT;
 use crate::vertex::Vertex;
 use bytemuck::Pod;
-use core::ops::{Deref, DerefMut};
+use core::ops::{Deref, DerefMut, Range};
 use glam::{Vec2 as Size, Vec4 as Rect};
 use wgpu::util::DeviceExt;
 
@@ -16,12 +16,25 @@ pub struct NodeAttributes<'a, T: Vertex + Pod> {
 
     pub tex_rect: Option<Rect>,
     pub corlor_format: Option<wgpu::TextureFormat>,
+    // 多渲染目标（MRT）时各颜色附件的格式，非空时优先于 `corlor_format`
+    pub color_formats: Vec<wgpu::TextureFormat>,
     pub color_blend_state: Option<wgpu::BlendState>,
     pub primitive_topology: wgpu::PrimitiveTopology,
+    pub strip_index_format: Option<wgpu::IndexFormat>,
     pub polygon_mode: wgpu::PolygonMode,
     pub cull_mode: Option<wgpu::Face>,
     pub use_depth_stencil: bool,
+    // 是否额外创建只写深度的预通道管线，颜色管线改为 Equal 深度比较且不写深度
+    pub use_depth_prepass: bool,
+    // 多重采样数，需与渲染目标（及深度纹理）的采样数一致
+    pub sample_count: u32,
     pub shader_module: &'a wgpu::ShaderModule,
+    // 着色器的 WGSL 源码，用于在创建管线前检查片元输出数与颜色目标数是否一致
+    pub shader_source: Option<&'a str>,
+    // 各级 LOD 在索引缓冲区中的范围
+    pub lod_index_ranges: Vec<Range<u32>>,
+    // 管线布局中的推送常量范围
+    pub push_constant_ranges: Vec<wgpu::PushConstantRange>,
 }
 
 pub struct ViewNodeBuilder<'a, T: Vertex + Pod> {
@@ -52,21 +65,55 @@ impl<'a, T: Vertex + Pod> ViewNodeBuilder<'a, T> {
                 bg_data,
                 tex_rect: None,
                 corlor_format: None,
+                color_formats: vec![],
                 color_blend_state: Some(wgpu::BlendState::ALPHA_BLENDING),
                 primitive_topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
                 polygon_mode: wgpu::PolygonMode::Fill,
                 cull_mode: Some(wgpu::Face::Back),
                 use_depth_stencil: true,
+                use_depth_prepass: false,
+                sample_count: 1,
                 shader_module,
+                shader_source: None,
+                lod_index_ranges: vec![],
+                push_constant_ranges: vec![],
             },
         }
     }
 
+    /// 设置图元拓扑，默认为 `TriangleList`；strip 拓扑使用索引绘制时还需调用 [`ViewNodeBuilder::with_strip_index_format`]
     pub fn with_primitive_topology(mut self, primitive_topology: wgpu::PrimitiveTopology) -> Self {
         self.primitive_topology = primitive_topology;
         self
     }
 
+    /// 设置图元拓扑，可用于点云（`PointList`）、折线（`LineStrip`）与三角形带（`TriangleStrip`）
+    ///
+    /// # NOTE:
+    /// strip 拓扑使用索引绘制时管线必须设置 `strip_index_format`，这里会同时设为 `Uint32`
+    /// （ViewNode 的索引缓冲区格式）；非 strip 拓扑时清除该值。之后仍可用 [`ViewNodeBuilder::with_strip_index_format`] 覆盖。
+    pub fn with_topology(mut self, topology: wgpu::PrimitiveTopology) -> Self {
+        self.primitive_topology = topology;
+        self.strip_index_format = topology.is_strip().then_some(wgpu::IndexFormat::Uint32);
+        self
+    }
+
+    /// 设置 strip 拓扑的索引格式
+    ///
+    /// # NOTE:
+    /// `LineStrip` / `TriangleStrip` 使用索引绘制时必须设置，
+    /// 且需与索引缓冲区的格式一致（ViewNode 的索引缓冲区为 `Uint32`）；非 strip 拓扑时该值会被忽略。
+    pub fn with_strip_index_format(mut self, format: wgpu::IndexFormat) -> Self {
+        self.strip_index_format = Some(format);
+        self
+    }
+
+    /// 设置多边形的光栅化模式，默认为 `Fill`
+    ///
+    /// # NOTE:
+    /// `Line`（线框）与 `Point` 分别需要设备开启 `POLYGON_MODE_LINE`、`POLYGON_MODE_POINT` 特性（WebGL/WebGPU 上都不可用），
+    /// 不支持时创建管线会回退为 `Fill` 并输出警告；需要跨平台的线框可以使用 `WireframeOverlay`。
     pub fn with_polygon_mode(mut self, polygon_mode: wgpu::PolygonMode) -> Self {
         self.polygon_mode = polygon_mode;
         self
@@ -100,11 +147,26 @@ impl<'a, T: Vertex + Pod> ViewNodeBuilder<'a, T> {
         self
     }
 
+    /// 只有一个颜色目标时的简写，等同于 `with_color_formats(vec![format])`
     pub fn with_color_format(mut self, format: wgpu::TextureFormat) -> Self {
         self.corlor_format = Some(format);
         self
     }
 
+    /// 设置多个颜色目标（MRT）的格式，第 i 个格式对应片元着色器的 `@location(i)` 输出
+    ///
+    /// 所有颜色目标使用同一个混合状态；绘制时通过 [`ViewNode::draw_to_targets`] 传入同样数量的纹理视图。
+    pub fn with_color_formats(mut self, formats: Vec<wgpu::TextureFormat>) -> Self {
+        self.color_formats = formats;
+        self
+    }
+
+    /// 提供着色器的 WGSL 源码，debug 构建下会检查 `fs_main` 的输出数与颜色目标数是否一致
+    pub fn with_shader_source(mut self, source: &'a str) -> Self {
+        self.shader_source = Some(source);
+        self
+    }
+
     pub fn with_color_blend_state(mut self, blend_state: Option<wgpu::BlendState>) -> Self {
         self.color_blend_state = blend_state;
         self
@@ -115,7 +177,48 @@ impl<'a, T: Vertex + Pod> ViewNodeBuilder<'a, T> {
         self
     }
 
+    /// 开启深度预通道，见 [`draw_with_depth_prepass`]
+    pub fn with_depth_prepass(mut self, bl: bool) -> Self {
+        self.use_depth_prepass = bl;
+        self
+    }
+
+    /// 设置多重采样数，MSAA 渲染目标的采样数变化时需以新的采样数重建节点
+    pub fn with_sample_count(mut self, count: u32) -> Self {
+        self.sample_count = count;
+        self
+    }
+
+    /// 设置各级 LOD 的索引范围，第 0 级为最精细的一级
+    ///
+    /// # NOTE:
+    /// 所有 LOD 的索引需连续存放在同一个索引缓冲区里（即 `with_vertices_and_indices` 传入的索引），
+    /// 每级通过范围来区分。
+    pub fn with_lod_index_ranges(mut self, ranges: Vec<Range<u32>>) -> Self {
+        self.lod_index_ranges = ranges;
+        self
+    }
+
+    /// 为 `stages` 声明 `0..size` 字节的推送常量，用于每次绘制都会变化的少量数据（如动画帧索引）
+    ///
+    /// # NOTE:
+    /// - 每个着色阶段只能出现在一个范围中，`size` 需是 4 的整数倍；
+    /// - 需要设备开启 `Features::PUSH_CONSTANTS`，WebGPU/WebGL 上不可用，见 [`ViewNodeBuilder::try_build`]。
+    pub fn with_push_constant_range(mut self, stages: wgpu::ShaderStages, size: u32) -> Self {
+        self.push_constant_ranges.push(wgpu::PushConstantRange {
+            stages,
+            range: 0..size,
+        });
+        self
+    }
+
+    /// 创建节点，声明的推送常量不可用时 panic，见 [`ViewNodeBuilder::try_build`]
     pub fn build(self, device: &wgpu::Device) -> ViewNode {
+        self.try_build(device).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// 创建节点，设备不支持声明的推送常量时返回错误，调用方可以据此回退为 uniform 缓冲区
+    pub fn try_build(self, device: &wgpu::Device) -> Result<ViewNode, PushConstantError> {
         debug_assert!(
             self.bg_data.visibilitys.len()
                 >= self.bg_data.uniforms.len()
@@ -124,7 +227,33 @@ impl<'a, T: Vertex + Pod> ViewNodeBuilder<'a, T> {
                     + self.bg_data.inout_tv.len(),
             "visibilitys count less than binding resource count"
         );
-        ViewNode::frome_attributes::<T>(self.attributes, device)
+        debug_assert!(
+            !self.primitive_topology.is_strip()
+                || self
+                    .vertices_and_indices
+                    .as_ref()
+                    .is_none_or(|(_, indices)| indices.is_empty())
+                || self.strip_index_format == Some(wgpu::IndexFormat::Uint32),
+            "strip topology with indices requires strip_index_format Uint32"
+        );
+        debug_assert!(
+            !self.use_depth_prepass || self.use_depth_stencil,
+            "depth prepass requires use_depth_stencil"
+        );
+        if let (true, Some(source)) = (cfg!(debug_assertions), self.shader_source) {
+            if let Ok(outputs) = crate::reflect::fragment_output_count(source, "fs_main") {
+                assert_eq!(
+                    outputs as usize,
+                    color_target_formats(&self.attributes).len(),
+                    "fragment outputs count does not match color targets count"
+                );
+            }
+        }
+        super::check_push_constant_ranges(device, &self.push_constant_ranges)?;
+        let mut attributes = self.attributes;
+        attributes.polygon_mode =
+            supported_polygon_mode(device.features(), attributes.polygon_mode);
+        Ok(ViewNode::frome_attributes::<T>(attributes, device))
     }
 }
 
@@ -137,9 +266,14 @@ pub struct ViewNode {
     pub bg_setting: BindGroupSetting,
     pub dy_uniform_bg: Option<super::DynamicUniformBindGroup>,
     pub pipeline: wgpu::RenderPipeline,
+    // 深度预通道的管线，没有片元着色阶段
+    pub prepass_pipeline: Option<wgpu::RenderPipeline>,
     view_width: f32,
     view_height: f32,
     pub clear_color: wgpu::Color,
+    pub lod_index_ranges: Vec<Range<u32>>,
+    // 颜色目标（渲染通道颜色附件）的个数
+    pub color_target_count: usize,
 }
 
 #[allow(dead_code)]
@@ -148,11 +282,16 @@ impl ViewNode {
         attributes: NodeAttributes<T>,
         device: &wgpu::Device,
     ) -> Self {
-        let corlor_format = if let Some(format) = attributes.corlor_format {
-            format
-        } else {
-            wgpu::TextureFormat::Bgra8UnormSrgb
-        };
+        let color_targets: Vec<Option<wgpu::ColorTargetState>> = color_target_formats(&attributes)
+            .into_iter()
+            .map(|format| {
+                Some(wgpu::ColorTargetState {
+                    format,
+                    blend: attributes.color_blend_state,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })
+            })
+            .collect();
 
         let bg_setting = BindGroupSetting::new(device, &attributes.bg_data);
 
@@ -200,18 +339,60 @@ impl ViewNode {
             let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                 label: None,
                 bind_group_layouts: &[&bg_setting.bind_group_layout, &dy_bg.bind_group_layout],
-                push_constant_ranges: &[],
+                push_constant_ranges: &attributes.push_constant_ranges,
             });
             (Some(dy_bg), pipeline_layout)
         } else {
             let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                 label: None,
                 bind_group_layouts: &[&bg_setting.bind_group_layout],
-                push_constant_ranges: &[],
+                push_constant_ranges: &attributes.push_constant_ranges,
             });
             (None, pipeline_layout)
         };
 
+        let primitive = primitive_state(
+            attributes.primitive_topology,
+            attributes.strip_index_format,
+            attributes.cull_mode,
+            attributes.polygon_mode,
+        );
+        let multisample = wgpu::MultisampleState {
+            count: attributes.sample_count,
+            ..Default::default()
+        };
+        let use_depth_prepass = attributes.use_depth_stencil && attributes.use_depth_prepass;
+        let depth_state = |depth_write_enabled, depth_compare| wgpu::DepthStencilState {
+            format: DEPTH_FORMAT,
+            depth_write_enabled,
+            depth_compare,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        };
+        // 预通道与颜色通道共用同一个顶点着色器与顶点数据，保证两次光栅化得到的深度值完全相同
+        let prepass_pipeline = if use_depth_prepass {
+            Some(
+                device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some("depth prepass pipeline"),
+                    layout: Some(&pipeline_layout),
+                    vertex: wgpu::VertexState {
+                        module: attributes.shader_module,
+                        entry_point: Some("vs_main"),
+                        compilation_options: Default::default(),
+                        buffers: &vertex_buffer_layouts,
+                    },
+                    fragment: None,
+                    primitive,
+                    depth_stencil: Some(depth_state(true, wgpu::CompareFunction::Less)),
+                    multisample,
+                    multiview: None,
+                    cache: None,
+                }),
+            )
+        } else {
+            None
+        };
+
         // Create the render pipeline
         let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("view pipeline"),
@@ -226,31 +407,18 @@ impl ViewNode {
                 module: attributes.shader_module,
                 entry_point: Some("fs_main"),
                 compilation_options: Default::default(),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: corlor_format,
-                    blend: attributes.color_blend_state,
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
+                targets: &color_targets,
             }),
-            primitive: wgpu::PrimitiveState {
-                topology: attributes.primitive_topology,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: attributes.cull_mode,
-                polygon_mode: attributes.polygon_mode,
-                ..Default::default()
-            },
-            depth_stencil: if attributes.use_depth_stencil {
-                Some(wgpu::DepthStencilState {
-                    format: DEPTH_FORMAT,
-                    depth_write_enabled: true,
-                    depth_compare: wgpu::CompareFunction::Less,
-                    stencil: wgpu::StencilState::default(),
-                    bias: wgpu::DepthBiasState::default(),
-                })
+            primitive,
+            depth_stencil: if use_depth_prepass {
+                // 深度已由预通道写好，只绘制深度与之相等（即最前面）的片元
+                Some(depth_state(false, wgpu::CompareFunction::Equal))
+            } else if attributes.use_depth_stencil {
+                Some(depth_state(true, wgpu::CompareFunction::Less))
             } else {
                 None
             },
-            multisample: wgpu::MultisampleState::default(),
+            multisample,
             multiview: None,
             cache: None,
         });
@@ -265,7 +433,10 @@ impl ViewNode {
             bg_setting,
             dy_uniform_bg,
             pipeline,
+            prepass_pipeline,
             clear_color: wgpu::Color::BLACK,
+            lod_index_ranges: attributes.lod_index_ranges,
+            color_target_count: color_targets.len(),
         }
     }
 
@@ -290,6 +461,22 @@ impl ViewNode {
         self.draw_rpass_by_offset(rpass, 0, instance_count);
     }
 
+    /// 只绘制到渲染通道的 `viewport` 子区域内
+    ///
+    /// 绘制后恢复覆盖整个 `surface` 的视口与裁剪矩形，同一通道中后续的绘制不受影响；
+    /// `viewport` 完全位于 `surface` 之外时不绘制。
+    pub fn draw_by_pass_in_viewport<'a, 'b: 'a>(
+        &'b self,
+        rpass: &mut wgpu::RenderPass<'b>,
+        viewport: &crate::Viewport,
+        surface: winit::dpi::PhysicalSize<u32>,
+    ) {
+        if viewport.apply(rpass, surface) {
+            self.draw_rpass_by_offset(rpass, 0, 1);
+            crate::Viewport::full(surface).apply(rpass, surface);
+        }
+    }
+
     pub fn draw_by_offset(
         &self,
         frame_view: &wgpu::TextureView,
@@ -297,6 +484,10 @@ impl ViewNode {
         load_op: wgpu::LoadOp<wgpu::Color>,
         offset_index: u32,
     ) {
+        debug_assert_eq!(
+            self.color_target_count, 1,
+            "use draw_to_targets for multiple color targets"
+        );
         let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             label: None,
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
@@ -313,20 +504,66 @@ impl ViewNode {
         self.draw_rpass_by_offset(&mut rpass, offset_index, 1);
     }
 
+    /// 绘制到多个颜色目标（MRT），`views` 与 `load_ops` 按 `@location` 顺序对应 `with_color_formats` 的格式
+    pub fn draw_to_targets(
+        &self,
+        views: &[&wgpu::TextureView],
+        encoder: &mut wgpu::CommandEncoder,
+        load_ops: &[wgpu::LoadOp<wgpu::Color>],
+    ) {
+        assert_eq!(
+            views.len(),
+            self.color_target_count,
+            "颜色附件数与节点的颜色目标数不一致"
+        );
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("mrt view node"),
+            color_attachments: &crate::mrt::color_attachments(views, load_ops),
+            ..Default::default()
+        });
+        self.set_rpass(&mut rpass);
+        self.draw_rpass_by_offset(&mut rpass, 0, 1);
+    }
+
     pub fn draw_rpass_by_offset<'a, 'b: 'a>(
         &'b self,
         rpass: &mut wgpu::RenderPass<'b>,
         offset_index: u32,
         instance_count: u32,
     ) {
-        self.set_rpass(rpass);
-        if let Some(node) = &self.dy_uniform_bg {
-            rpass.set_bind_group(
-                1,
-                &node.bind_group,
-                &[256 * offset_index as wgpu::DynamicOffset],
-            );
+        self.draw_with_pipeline(rpass, &self.pipeline, offset_index, instance_count);
+    }
+
+    /// 设置推送常量，需在 `draw_*` 之前调用；`offset` 与 `data` 的长度都需是 4 的整数倍
+    ///
+    /// # NOTE:
+    /// 设置推送常量前需先设置管线，这里会设置颜色通道的管线；之后 `draw_*` 再次设置同一管线时推送常量不会被清除。
+    pub fn set_push_constants(
+        &self,
+        rpass: &mut wgpu::RenderPass,
+        stages: wgpu::ShaderStages,
+        offset: u32,
+        data: &[u8],
+    ) {
+        rpass.set_pipeline(&self.pipeline);
+        rpass.set_push_constants(stages, offset, data);
+    }
+
+    /// 以深度预通道的管线绘制，没有开启深度预通道时不做任何事
+    pub fn draw_depth_prepass<'a, 'b: 'a>(&'b self, rpass: &mut wgpu::RenderPass<'b>) {
+        if let Some(pipeline) = self.prepass_pipeline.as_ref() {
+            self.draw_with_pipeline(rpass, pipeline, 0, 1);
         }
+    }
+
+    fn draw_with_pipeline<'a, 'b: 'a>(
+        &'b self,
+        rpass: &mut wgpu::RenderPass<'b>,
+        pipeline: &'b wgpu::RenderPipeline,
+        offset_index: u32,
+        instance_count: u32,
+    ) {
+        self.bind_with_pipeline(rpass, pipeline, offset_index);
         if self.index_count > 0 {
             rpass.draw_indexed(0..self.index_count as u32, 0, 0..instance_count);
         } else {
@@ -334,8 +571,59 @@ impl ViewNode {
         }
     }
 
+    /// 设置管线与绑定组，有动态 uniform 时以第 `offset_index` 个槽位绑定到第 1 组
+    fn bind_with_pipeline<'a, 'b: 'a>(
+        &'b self,
+        rpass: &mut wgpu::RenderPass<'b>,
+        pipeline: &'b wgpu::RenderPipeline,
+        offset_index: u32,
+    ) {
+        rpass.set_pipeline(pipeline);
+        self.set_resources(rpass);
+        if let Some(node) = &self.dy_uniform_bg {
+            rpass.set_bind_group(1, &node.bind_group, &[node.dynamic_offset(0, offset_index)]);
+        }
+    }
+
+    /// 只绘制索引缓冲区中 `index_range` 范围内的图元，`offset_index` 为动态 uniform 的槽位索引
+    pub fn draw_range<'a, 'b: 'a>(
+        &'b self,
+        rpass: &mut wgpu::RenderPass<'b>,
+        index_range: Range<u32>,
+        offset_index: u32,
+        instance_count: u32,
+    ) {
+        self.bind_with_pipeline(rpass, &self.pipeline, offset_index);
+        rpass.draw_indexed(
+            clamp_index_range(index_range, self.index_count as u32),
+            0,
+            0..instance_count,
+        );
+    }
+
+    /// 绘制第 `lod` 级，超出范围时使用最粗糙的一级
+    pub fn draw_lod<'a, 'b: 'a>(
+        &'b self,
+        rpass: &mut wgpu::RenderPass<'b>,
+        lod: usize,
+        offset_index: u32,
+        instance_count: u32,
+    ) {
+        match self.lod_index_ranges.last() {
+            Some(last) => {
+                let range = self.lod_index_ranges.get(lod).unwrap_or(last).clone();
+                self.draw_range(rpass, range, offset_index, instance_count);
+            }
+            None => self.draw_rpass_by_offset(rpass, offset_index, instance_count),
+        }
+    }
+
     pub fn set_rpass<'a, 'b: 'a>(&'b self, rpass: &mut wgpu::RenderPass<'a>) {
         rpass.set_pipeline(&self.pipeline);
+        self.set_resources(rpass);
+    }
+
+    fn set_resources<'a, 'b: 'a>(&'b self, rpass: &mut wgpu::RenderPass<'a>) {
         rpass.set_bind_group(0, &self.bg_setting.bind_group, &[]);
         rpass.set_index_buffer(self.index_buf.slice(..), wgpu::IndexFormat::Uint32);
         if let Some(vertex_buf) = self.vertex_buf.as_ref() {
@@ -343,3 +631,425 @@ impl ViewNode {
         }
     }
 }
+
+/// 先以深度预通道绘制所有不透明的 `nodes`，再以 Equal 深度比较绘制颜色，减少重复着色（overdraw）
+///
+/// 第一个通道只写深度（以 1.0 清除 `depth_view`），第二个通道加载该深度、不再写入，
+/// 每个像素只有最前面的片元会执行片元着色器。`nodes` 需以 `with_depth_prepass(true)` 创建。
+///
+/// # NOTE:
+/// Equal 比较要求两个通道得到的深度值逐位相同：两个管线共用同一个顶点着色器与顶点数据，
+/// 所以不要在两个通道之间修改变换矩阵等顶点着色器的输入；若颜色通道使用了不同的顶点着色器（或不同的计算顺序），
+/// 浮点误差会让深度不相等，出现闪烁或缺失的像素，此时可在位置输出上使用 `@invariant`。
+pub fn draw_with_depth_prepass(
+    encoder: &mut wgpu::CommandEncoder,
+    frame_view: &wgpu::TextureView,
+    depth_view: &wgpu::TextureView,
+    load_op: wgpu::LoadOp<wgpu::Color>,
+    nodes: &[&ViewNode],
+) {
+    {
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("depth prepass"),
+            color_attachments: &[],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            ..Default::default()
+        });
+        for node in nodes {
+            node.draw_depth_prepass(&mut rpass);
+        }
+    }
+    let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("color pass after depth prepass"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: frame_view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: load_op,
+                store: wgpu::StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+            view: depth_view,
+            depth_ops: Some(wgpu::Operations {
+                load: wgpu::LoadOp::Load,
+                store: wgpu::StoreOp::Store,
+            }),
+            stencil_ops: None,
+        }),
+        ..Default::default()
+    });
+    for node in nodes {
+        node.draw_by_pass(&mut rpass);
+    }
+}
+
+/// 颜色目标的格式：优先使用 `color_formats`，否则为单个 `corlor_format`（默认 `Bgra8UnormSrgb`）
+fn color_target_formats<T: Vertex + Pod>(
+    attributes: &NodeAttributes<T>,
+) -> Vec<wgpu::TextureFormat> {
+    if !attributes.color_formats.is_empty() {
+        attributes.color_formats.clone()
+    } else {
+        vec![
+            attributes
+                .corlor_format
+                .unwrap_or(wgpu::TextureFormat::Bgra8UnormSrgb),
+        ]
+    }
+}
+
+fn primitive_state(
+    topology: wgpu::PrimitiveTopology,
+    strip_index_format: Option<wgpu::IndexFormat>,
+    cull_mode: Option<wgpu::Face>,
+    polygon_mode: wgpu::PolygonMode,
+) -> wgpu::PrimitiveState {
+    wgpu::PrimitiveState {
+        topology,
+        // 非 strip 拓扑设置 strip_index_format 会触发验证错误
+        strip_index_format: if topology.is_strip() {
+            strip_index_format
+        } else {
+            None
+        },
+        front_face: wgpu::FrontFace::Ccw,
+        cull_mode,
+        polygon_mode,
+        ..Default::default()
+    }
+}
+
+/// 设备不支持 `polygon_mode` 所需的特性时回退为 `Fill`
+fn supported_polygon_mode(
+    features: wgpu::Features,
+    polygon_mode: wgpu::PolygonMode,
+) -> wgpu::PolygonMode {
+    let required = match polygon_mode {
+        wgpu::PolygonMode::Fill => wgpu::Features::empty(),
+        wgpu::PolygonMode::Line => wgpu::Features::POLYGON_MODE_LINE,
+        wgpu::PolygonMode::Point => wgpu::Features::POLYGON_MODE_POINT,
+    };
+    if features.contains(required) {
+        polygon_mode
+    } else {
+        log::warn!("{polygon_mode:?} polygon mode requires {required:?}, falling back to Fill");
+        wgpu::PolygonMode::Fill
+    }
+}
+
+/// 把索引范围限制在索引缓冲区的有效范围内
+pub fn clamp_index_range(range: Range<u32>, index_count: u32) -> Range<u32> {
+    let end = range.end.min(index_count);
+    range.start.min(end)..end
+}
+
+/// 按相机距离选择 LOD 级别
+///
+/// `thresholds` 为升序排列的距离阈值：距离小于 `thresholds[0]` 时为第 0 级，
+/// 以此类推，超过全部阈值时为 `thresholds.len()` 级。
+pub fn select_lod(distance: f32, thresholds: &[f32]) -> usize {
+    thresholds
+        .iter()
+        .position(|t| distance < *t)
+        .unwrap_or(thresholds.len())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn partial_index_range() {
+        // 两级 LOD：0..600 与 600..750
+        let ranges = [0..600, 600..750];
+        let lod = select_lod(25.0, &[10.0, 50.0]);
+        assert_eq!(lod, 1);
+        let range = clamp_index_range(ranges[lod].clone(), 750);
+        assert_eq!(range.len(), 150);
+
+        assert_eq!(select_lod(5.0, &[10.0, 50.0]), 0);
+        assert_eq!(select_lod(100.0, &[10.0, 50.0]), 2);
+        assert_eq!(clamp_index_range(600..900, 750), 600..750);
+        assert_eq!(clamp_index_range(800..900, 750), 750..750);
+    }
+
+    #[test]
+    fn line_strip_primitive_state() {
+        let state = primitive_state(
+            wgpu::PrimitiveTopology::LineStrip,
+            Some(wgpu::IndexFormat::Uint32),
+            None,
+            wgpu::PolygonMode::Fill,
+        );
+        assert_eq!(state.topology, wgpu::PrimitiveTopology::LineStrip);
+        assert_eq!(state.strip_index_format, Some(wgpu::IndexFormat::Uint32));
+
+        let state = primitive_state(
+            wgpu::PrimitiveTopology::TriangleList,
+            Some(wgpu::IndexFormat::Uint32),
+            None,
+            wgpu::PolygonMode::Fill,
+        );
+        assert_eq!(state.strip_index_format, None);
+    }
+
+    #[test]
+    fn polygon_mode_fallback() {
+        use wgpu::PolygonMode;
+        let line = wgpu::Features::POLYGON_MODE_LINE;
+        assert_eq!(
+            supported_polygon_mode(wgpu::Features::empty(), PolygonMode::Fill),
+            PolygonMode::Fill
+        );
+        assert_eq!(
+            supported_polygon_mode(wgpu::Features::empty(), PolygonMode::Line),
+            PolygonMode::Fill
+        );
+        assert_eq!(
+            supported_polygon_mode(line, PolygonMode::Line),
+            PolygonMode::Line
+        );
+        assert_eq!(
+            supported_polygon_mode(line, PolygonMode::Point),
+            PolygonMode::Fill
+        );
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod gpu_test {
+    use super::*;
+    use crate::vertex::PosOnly;
+
+    const SHADER: &str = "
+        @group(0) @binding(0) var<uniform> color: vec4f;
+
+        @vertex
+        fn vs_main(@location(0) pos: vec3f) -> @builtin(position) vec4f {
+            return vec4f(pos, 1.0);
+        }
+
+        @fragment
+        fn fs_main() -> @location(0) vec4f {
+            return color;
+        }
+    ";
+
+    #[test]
+    fn equal_depth_after_prepass() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+        let color_buf = BufferObj::create_uniform_buffer(&device, &[0.0_f32, 1.0, 0.0, 1.0], None);
+        // 覆盖整个视口、深度为 0.5 的三角形
+        let vertices = vec![
+            PosOnly {
+                pos: [-1.0, -1.0, 0.5],
+            },
+            PosOnly {
+                pos: [3.0, -1.0, 0.5],
+            },
+            PosOnly {
+                pos: [-1.0, 3.0, 0.5],
+            },
+        ];
+        let node = ViewNodeBuilder::<PosOnly>::new(
+            BindGroupData {
+                uniforms: vec![&color_buf],
+                visibilitys: vec![wgpu::ShaderStages::FRAGMENT],
+                ..Default::default()
+            },
+            &shader,
+        )
+        .with_vertices_and_indices((vertices, vec![0, 1, 2]))
+        .with_color_format(wgpu::TextureFormat::Rgba8Unorm)
+        .with_depth_prepass(true)
+        .build(&device);
+        assert!(node.prepass_pipeline.is_some());
+
+        let depth_view = device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: None,
+                size: wgpu::Extent3d {
+                    width: 4,
+                    height: 4,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: DEPTH_FORMAT,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            })
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        let pixels =
+            crate::capture::capture_linear_frame(&device, &queue, 4, 4, |encoder, view| {
+                draw_with_depth_prepass(
+                    encoder,
+                    view,
+                    &depth_view,
+                    wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    &[&node],
+                );
+            });
+        assert!(pollster::block_on(device.pop_error_scope()).is_none());
+        // 预通道写入的深度与颜色通道相等，所以每个像素都通过了 Equal 深度测试
+        assert!(pixels.chunks(4).all(|p| p == [0, 255, 0, 255]));
+    }
+
+    #[test]
+    fn draw_lod_with_dynamic_uniform() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(
+                "
+                @group(0) @binding(0) var<uniform> tint: vec4f;
+                @group(1) @binding(0) var<uniform> color: vec4f;
+
+                @vertex
+                fn vs_main(@location(0) pos: vec3f) -> @builtin(position) vec4f {
+                    return vec4f(pos, 1.0);
+                }
+
+                @fragment
+                fn fs_main() -> @location(0) vec4f {
+                    return color * tint;
+                }
+                "
+                .into(),
+            ),
+        });
+        let tint_buf = BufferObj::create_uniform_buffer(&device, &[1.0_f32; 4], None);
+        // 槽位 0 为红色、槽位 1 为绿色
+        let colors = crate::DynamicUniformRing::<[f32; 4]>::new(&device, 2, None);
+        colors.write_frames(&queue, &[[1.0, 0.0, 0.0, 1.0], [0.0, 1.0, 0.0, 1.0]]);
+
+        // 左、右两半各一个四边形：第 0 级 LOD 为全部 12 个索引，第 1 级只有左半边的 6 个
+        let quad = |x0: f32, x1: f32| {
+            [[x0, -1.0], [x1, -1.0], [x1, 1.0], [x0, 1.0]]
+                .map(|[x, y]| PosOnly { pos: [x, y, 0.0] })
+        };
+        let vertices = [quad(-1.0, 0.0), quad(0.0, 1.0)].concat();
+        let indices = vec![0, 1, 2, 0, 2, 3, 4, 5, 6, 4, 6, 7];
+        let node = ViewNodeBuilder::<PosOnly>::new(
+            BindGroupData {
+                uniforms: vec![&tint_buf],
+                visibilitys: vec![wgpu::ShaderStages::FRAGMENT],
+                dynamic_uniforms: vec![&*colors],
+                dynamic_uniform_visibilitys: vec![wgpu::ShaderStages::FRAGMENT],
+                ..Default::default()
+            },
+            &shader,
+        )
+        .with_vertices_and_indices((vertices, indices))
+        .with_lod_index_ranges(vec![0..12, 0..6])
+        .with_color_format(wgpu::TextureFormat::Rgba8Unorm)
+        .with_use_depth_stencil(false)
+        .build(&device);
+
+        let pixels =
+            crate::capture::capture_linear_frame(&device, &queue, 4, 4, |encoder, view| {
+                let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: None,
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    ..Default::default()
+                });
+                node.draw_lod(&mut rpass, 1, 1, 1);
+            });
+        // 没有绑定第 1 组时绘制会产生验证错误
+        assert!(pollster::block_on(device.pop_error_scope()).is_none());
+        // 只绘制了第 1 级的 6 个索引（左半边），颜色取自槽位 1
+        for row in pixels.chunks(4 * 4) {
+            assert_eq!(row[..8], [0, 255, 0, 255, 0, 255, 0, 255]);
+            assert_eq!(row[8..], [0, 0, 0, 255, 0, 0, 0, 255]);
+        }
+    }
+
+    #[test]
+    fn draw_line_strip() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+        let color_buf = BufferObj::create_uniform_buffer(&device, &[0.0_f32, 1.0, 0.0, 1.0], None);
+        // 穿过第 1 行像素中心（NDC y = 0.25）的两段折线
+        let vertices = [-1.0, 0.0, 1.0]
+            .map(|x| PosOnly {
+                pos: [x, 0.25, 0.0],
+            })
+            .to_vec();
+        let builder = ViewNodeBuilder::<PosOnly>::new(
+            BindGroupData {
+                uniforms: vec![&color_buf],
+                visibilitys: vec![wgpu::ShaderStages::FRAGMENT],
+                ..Default::default()
+            },
+            &shader,
+        )
+        .with_vertices_and_indices((vertices, vec![0, 1, 2]))
+        .with_topology(wgpu::PrimitiveTopology::LineStrip)
+        .with_color_format(wgpu::TextureFormat::Rgba8Unorm)
+        .with_use_depth_stencil(false);
+        assert_eq!(builder.strip_index_format, Some(wgpu::IndexFormat::Uint32));
+        let node = builder.build(&device);
+
+        let pixels =
+            crate::capture::capture_linear_frame(&device, &queue, 4, 4, |encoder, view| {
+                let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: None,
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    ..Default::default()
+                });
+                node.draw_by_pass(&mut rpass);
+            });
+        assert!(pollster::block_on(device.pop_error_scope()).is_none());
+        // 只有第 1 行被线段覆盖
+        for (y, row) in pixels.chunks(4 * 4).enumerate() {
+            let expected: &[u8] = if y == 1 {
+                &[0, 255, 0, 255]
+            } else {
+                &[0, 0, 0, 255]
+            };
+            assert!(row.chunks(4).all(|p| p == expected), "row {y}: {row:?}");
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use super::{BindGroupData, ViewNode, ViewNodeBuilder};
+use crate::{BufferObj, vertex::Vertex};
+use bytemuck::{Pod, Zeroable};
+
+/// 带重心坐标的顶点
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable, PartialEq)]
+pub struct BarycentricVertex {
+    pub pos: [f32; 3],
+    pub barycentric: [f32; 3],
+}
+
+impl Vertex for BarycentricVertex {
+    fn vertex_attributes(offset: u32) -> Vec<wgpu::VertexAttribute> {
+        vec![
+            wgpu::VertexAttribute {
+                shader_location: offset,
+                format: wgpu::VertexFormat::Float32x3,
+                offset: 0,
+            },
+            wgpu::VertexAttribute {
+                shader_location: offset + 1,
+                format: wgpu::VertexFormat::Float32x3,
+                offset: 4 * 3,
+            },
+        ]
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+struct WireframeParams {
+    fill_color: [f32; 4],
+    line_color: [f32; 4],
+    line_width: f32,
+    padding: [f32; 3],
+}
+
+/// 基于重心坐标的线框叠加绘制
+///
+/// 不依赖 `PolygonMode::Line`（需要 `POLYGON_MODE_LINE` 特性，WebGL/WebGPU 上不可用），
+/// 片元着色器根据到三角形各边的距离，在填充色之上混合线框颜色。
+///
+/// # NOTE:
+/// 需要额外的顶点属性 `@location(1) barycentric: vec3f`。同一个顶点被多个三角形共用时，
+/// 它在不同三角形中的重心坐标不同，所以网格需先展开为不共享顶点的三角形列表（见 [`expand_with_barycentric`]），
+/// 顶点数为索引数。
+pub struct WireframeOverlay {
+    node: ViewNode,
+    params: WireframeParams,
+    params_buf: BufferObj,
+}
+
+#[allow(dead_code)]
+impl WireframeOverlay {
+    pub fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        mvp_buf: &BufferObj,
+        positions: &[[f32; 3]],
+        indices: &[u32],
+    ) -> Self {
+        let params = WireframeParams {
+            fill_color: [0.2, 0.2, 0.2, 1.0],
+            line_color: [1.0, 1.0, 1.0, 1.0],
+            line_width: 1.0,
+            padding: [0.0; 3],
+        };
+        let params_buf =
+            BufferObj::create_uniform_buffer(device, &params, Some("wireframe params"));
+        let vertices = expand_with_barycentric(positions, indices);
+        let indices = (0..vertices.len() as u32).collect();
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("wireframe overlay"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("wireframe_overlay.wgsl").into()),
+        });
+        let bind_group_data = BindGroupData {
+            uniforms: vec![mvp_buf, &params_buf],
+            visibilitys: vec![wgpu::ShaderStages::VERTEX, wgpu::ShaderStages::FRAGMENT],
+            ..Default::default()
+        };
+        let node = ViewNodeBuilder::<BarycentricVertex>::new(bind_group_data, &shader)
+            .with_vertices_and_indices((vertices, indices))
+            .with_use_depth_stencil(true)
+            .with_color_format(format)
+            .build(device);
+
+        Self {
+            node,
+            params,
+            params_buf,
+        }
+    }
+
+    /// 设置线框颜色，alpha 控制线框与填充色的混合程度
+    pub fn set_line_color(&mut self, queue: &wgpu::Queue, color: [f32; 4]) {
+        self.params.line_color = color;
+        self.write_params(queue);
+    }
+
+    /// 设置线宽，单位为像素
+    pub fn set_line_width(&mut self, queue: &wgpu::Queue, width: f32) {
+        self.params.line_width = width.max(0.0);
+        self.write_params(queue);
+    }
+
+    pub fn set_fill_color(&mut self, queue: &wgpu::Queue, color: [f32; 4]) {
+        self.params.fill_color = color;
+        self.write_params(queue);
+    }
+
+    fn write_params(&self, queue: &wgpu::Queue) {
+        queue.write_buffer(&self.params_buf.buffer, 0, bytemuck::bytes_of(&self.params));
+    }
+
+    pub fn draw_by_pass<'a, 'b: 'a>(&'b self, rpass: &mut wgpu::RenderPass<'b>) {
+        self.node.draw_by_pass(rpass);
+    }
+}
+
+/// 把索引网格展开为不共享顶点的三角形列表，并为每个三角形的三个顶点分配重心坐标
+pub fn expand_with_barycentric(positions: &[[f32; 3]], indices: &[u32]) -> Vec<BarycentricVertex> {
+    const CORNERS: [[f32; 3]; 3] = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    indices
+        .chunks_exact(3)
+        .flat_map(|triangle| {
+            triangle
+                .iter()
+                .zip(CORNERS)
+                .map(|(index, barycentric)| BarycentricVertex {
+                    pos: positions[*index as usize],
+                    barycentric,
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn expand_shared_vertices() {
+        // 共用对角线的两个三角形组成的四边形
+        let positions = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+        ];
+        let vertices = expand_with_barycentric(&positions, &[0, 1, 2, 0, 2, 3]);
+        assert_eq!(vertices.len(), 6);
+        assert_eq!(vertices[2].pos, vertices[4].pos);
+        // 同一个位置在两个三角形中的重心坐标不同
+        assert_ne!(vertices[2].barycentric, vertices[4].barycentric);
+        for triangle in vertices.chunks(3) {
+            let sum: [f32; 3] = triangle.iter().fold([0.0; 3], |acc, v| {
+                [
+                    acc[0] + v.barycentric[0],
+                    acc[1] + v.barycentric[1],
+                    acc[2] + v.barycentric[2],
+                ]
+            });
+            assert_eq!(sum, [1.0; 3]);
+        }
+    }
+}
@@ -0,0 +1,37 @@
+struct MVPMatUniform {
+    mvp: mat4x4f,
+};
+
+struct WireframeParams {
+    fill_color: vec4f,
+    line_color: vec4f,
+    // 线宽，单位为像素
+    line_width: f32,
+};
+
+@group(0) @binding(0) var<uniform> mat_uniform: MVPMatUniform;
+@group(0) @binding(1) var<uniform> params: WireframeParams;
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    // 重心坐标：三角形的三个顶点分别为 (1, 0, 0)、(0, 1, 0)、(0, 0, 1)，
+    // 插值后任一分量接近 0 即表示靠近对应的边
+    @location(0) barycentric: vec3f,
+};
+
+@vertex
+fn vs_main(@location(0) pos: vec3f, @location(1) barycentric: vec3f) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = mat_uniform.mvp * vec4f(pos, 1.0);
+    out.barycentric = barycentric;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4f {
+    // fwidth 为相邻像素间重心坐标的变化量，除以它即把到边的距离换算为像素，线宽因此不受三角形大小与远近影响
+    let d = fwidth(in.barycentric);
+    let a = smoothstep(vec3f(0.0), d * params.line_width, in.barycentric);
+    let edge = 1.0 - min(a.x, min(a.y, a.z));
+    return mix(params.fill_color, params.line_color, edge * params.line_color.a);
+}
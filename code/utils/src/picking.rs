@@ -0,0 +1,334 @@
+//! 基于 ID 渲染目标的物体拾取
+
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
+
+const ID_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Uint;
+
+/// 可拾取物体的实例数据
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct PickInstance {
+    pub model: [[f32; 4]; 4],
+    /// 物体 ID，0 保留给背景（未命中任何物体）
+    pub id: u32,
+    pub padding: [u32; 3],
+}
+
+impl PickInstance {
+    pub fn new(model: glam::Mat4, id: u32) -> Self {
+        Self {
+            model: model.to_cols_array_2d(),
+            id,
+            padding: [0; 3],
+        }
+    }
+}
+
+/// 把每个实例的 ID 渲染到 `R32Uint` 纹理上，再读回鼠标位置处的像素得到被选中的物体
+///
+/// # NOTE:
+/// - ID 纹理是整数格式，不支持混合，也不能开启 MSAA：混合或多重采样解析都会把相邻物体的 ID 混在一起，
+///   得到一个不存在的 ID。所以此通道的管线不设置混合，采样数为 1；
+/// - 通过深度测试保证读到的是离相机最近的物体；
+/// - 背景被清除为 0，所以物体 ID 应从 1 开始。
+pub struct IdPass {
+    id_texture: wgpu::Texture,
+    id_view: wgpu::TextureView,
+    depth_view: wgpu::TextureView,
+    size: (u32, u32),
+    view_proj_buf: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    pipeline: wgpu::RenderPipeline,
+}
+
+#[allow(dead_code)]
+impl IdPass {
+    pub fn new(device: &wgpu::Device, width: u32, height: u32) -> Self {
+        let view_proj_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("picking view proj"),
+            contents: bytemuck::bytes_of(&glam::Mat4::IDENTITY.to_cols_array_2d()),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("picking"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("picking"),
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: view_proj_buf.as_entire_binding(),
+            }],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("picking"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("picking.wgsl").into()),
+        });
+        let vertex_attributes = wgpu::vertex_attr_array![0 => Float32x3];
+        let instance_attributes = wgpu::vertex_attr_array![1 => Float32x4, 2 => Float32x4, 3 => Float32x4, 4 => Float32x4, 5 => Uint32];
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("picking pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[
+                    wgpu::VertexBufferLayout {
+                        array_stride: core::mem::size_of::<crate::vertex::PosOnly>()
+                            as wgpu::BufferAddress,
+                        step_mode: wgpu::VertexStepMode::Vertex,
+                        attributes: &vertex_attributes,
+                    },
+                    wgpu::VertexBufferLayout {
+                        array_stride: core::mem::size_of::<PickInstance>() as wgpu::BufferAddress,
+                        step_mode: wgpu::VertexStepMode::Instance,
+                        attributes: &instance_attributes,
+                    },
+                ],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: ID_FORMAT,
+                    // 整数格式不支持混合
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: crate::DEPTH_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        let (id_texture, id_view, depth_view) = create_targets(device, width, height);
+        Self {
+            id_texture,
+            id_view,
+            depth_view,
+            size: (width, height),
+            view_proj_buf,
+            bind_group,
+            pipeline,
+        }
+    }
+
+    /// 窗口大小变化时重建 ID 纹理与深度纹理
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        if self.size == (width, height) {
+            return;
+        }
+        (self.id_texture, self.id_view, self.depth_view) = create_targets(device, width, height);
+        self.size = (width, height);
+    }
+
+    /// 设置视图投影矩阵，需与可见渲染使用的一致
+    pub fn set_view_proj(&self, queue: &wgpu::Queue, view_proj: glam::Mat4) {
+        queue.write_buffer(
+            &self.view_proj_buf,
+            0,
+            bytemuck::bytes_of(&view_proj.to_cols_array_2d()),
+        );
+    }
+
+    /// 录制 ID 渲染通道
+    ///
+    /// `vertices` 为 `PosOnly` 三角形列表，`instances` 为 `PickInstance` 数组
+    pub fn render(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        vertices: &wgpu::Buffer,
+        vertex_count: u32,
+        instances: &wgpu::Buffer,
+        instance_count: u32,
+    ) {
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("picking id pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &self.id_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    // 整数纹理的清除值按 u32 处理
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &self.depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Discard,
+                }),
+                stencil_ops: None,
+            }),
+            ..Default::default()
+        });
+        rpass.set_pipeline(&self.pipeline);
+        rpass.set_bind_group(0, &self.bind_group, &[]);
+        rpass.set_vertex_buffer(0, vertices.slice(..));
+        rpass.set_vertex_buffer(1, instances.slice(..));
+        rpass.draw(0..vertex_count, 0..instance_count);
+    }
+
+    /// 读回像素 (x, y) 处的物体 ID，未命中或超出范围时返回 0
+    ///
+    /// 会阻塞等待 GPU 完成，调用前需已提交 `render` 所在的命令编码器。
+    /// 只复制一个像素，但 `copy_texture_to_buffer` 仍要求 `bytes_per_row` 按 256 字节对齐。
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn read_id_at(&self, device: &wgpu::Device, queue: &wgpu::Queue, x: u32, y: u32) -> u32 {
+        if x >= self.size.0 || y >= self.size.1 {
+            return 0;
+        }
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("picking readback"),
+            size: wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("picking readback"),
+        });
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture: &self.id_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d { x, y, z: 0 },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+                    rows_per_image: Some(1),
+                },
+            },
+            wgpu::Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+        );
+        queue.submit(Some(encoder.finish()));
+
+        let slice = buffer.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        crate::poll::poll_wait(device);
+        let id = bytemuck::pod_read_unaligned::<u32>(&slice.get_mapped_range()[..4]);
+        buffer.unmap();
+        id
+    }
+}
+
+fn create_targets(
+    device: &wgpu::Device,
+    width: u32,
+    height: u32,
+) -> (wgpu::Texture, wgpu::TextureView, wgpu::TextureView) {
+    let size = wgpu::Extent3d {
+        width: width.max(1),
+        height: height.max(1),
+        depth_or_array_layers: 1,
+    };
+    let create = |format, usage, label| {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size,
+            mip_level_count: 1,
+            // ID 纹理不能多重采样
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage,
+            view_formats: &[],
+        })
+    };
+    let id_texture = create(
+        ID_FORMAT,
+        wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        "picking id",
+    );
+    let depth_texture = create(
+        crate::DEPTH_FORMAT,
+        wgpu::TextureUsages::RENDER_ATTACHMENT,
+        "picking depth",
+    );
+    let id_view = id_texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
+    (id_texture, id_view, depth_view)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pick_two_quads() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        // 覆盖 NDC [-0.5, 0.5] 的四边形
+        let quad: Vec<[f32; 3]> = vec![
+            [-0.5, -0.5, 0.0],
+            [0.5, -0.5, 0.0],
+            [0.5, 0.5, 0.0],
+            [-0.5, -0.5, 0.0],
+            [0.5, 0.5, 0.0],
+            [-0.5, 0.5, 0.0],
+        ];
+        let instances = [
+            PickInstance::new(glam::Mat4::from_translation(glam::vec3(-0.5, 0.0, 0.0)), 7),
+            PickInstance::new(glam::Mat4::from_translation(glam::vec3(0.5, 0.0, 0.0)), 42),
+        ];
+        let vertex_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: bytemuck::cast_slice(&quad),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let instance_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: bytemuck::cast_slice(&instances),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        let pass = IdPass::new(&device, 64, 64);
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        pass.render(&mut encoder, &vertex_buf, 6, &instance_buf, 2);
+        queue.submit(Some(encoder.finish()));
+
+        assert_eq!(pass.read_id_at(&device, &queue, 16, 32), 7);
+        assert_eq!(pass.read_id_at(&device, &queue, 48, 32), 42);
+        assert_eq!(pass.read_id_at(&device, &queue, 32, 2), 0);
+        assert_eq!(pass.read_id_at(&device, &queue, 100, 100), 0);
+    }
+}
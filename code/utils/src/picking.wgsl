@@ -0,0 +1,32 @@
+struct ViewProj {
+    mat: mat4x4f,
+};
+
+@group(0) @binding(0) var<uniform> view_proj: ViewProj;
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    // 整数不能插值，需使用 flat
+    @location(0) @interpolate(flat) id: u32,
+};
+
+@vertex
+fn vs_main(
+    @location(0) pos: vec3f,
+    @location(1) model_0: vec4f,
+    @location(2) model_1: vec4f,
+    @location(3) model_2: vec4f,
+    @location(4) model_3: vec4f,
+    @location(5) id: u32,
+) -> VertexOutput {
+    let model = mat4x4f(model_0, model_1, model_2, model_3);
+    var out: VertexOutput;
+    out.position = view_proj.mat * model * vec4f(pos, 1.0);
+    out.id = id;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) u32 {
+    return in.id;
+}
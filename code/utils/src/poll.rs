@@ -0,0 +1,75 @@
+//! 设备轮询
+//!
+//! wgpu 中 `map_async`、`on_submitted_work_done` 等回调只会在轮询设备时被调用。
+//! 原生平台上需要应用主动调用 `device.poll()`；Web 平台由浏览器驱动，`poll()` 为空操作。
+//!
+//! # NOTE:
+//! 当前 wgpu 版本（25）中轮询方式由 `wgpu::PollType` 描述（旧版本中名为 `wgpu::Maintain`）：
+//! - `PollType::Poll`：检查已完成的工作并调用对应的回调后立即返回，不阻塞；
+//! - `PollType::Wait`：阻塞直到之前提交的所有工作完成；
+//! - `PollType::WaitForSubmissionIndex(index)`：阻塞直到指定的提交完成。
+//!
+//! `device.poll()` 返回 `Result<PollStatus, PollError>`，等待超时等错误也通过它返回。
+
+/// 非阻塞轮询：调用已完成工作的回调后立即返回
+///
+/// 适合在交互式应用的每帧调用，计算结果的回读不会卡住界面。
+/// 返回值表示队列中是否已没有未完成的工作。
+pub fn poll_once(device: &wgpu::Device) -> bool {
+    match device.poll(wgpu::PollType::Poll) {
+        Ok(status) => status.is_queue_empty(),
+        Err(e) => {
+            log::warn!("device poll failed: {e}");
+            false
+        }
+    }
+}
+
+/// 阻塞轮询：等待之前提交的所有工作完成，并调用它们的回调
+///
+/// 用于离线渲染、测试及需要立即拿到结果的回读。
+///
+/// # NOTE:
+/// Web 平台上不能阻塞主线程，此函数不会等待，回调要等控制权交还给浏览器后才会被调用。
+pub fn poll_wait(device: &wgpu::Device) {
+    if let Err(e) = device.poll(wgpu::PollType::Wait) {
+        log::warn!("device poll failed: {e}");
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    };
+
+    #[test]
+    fn map_callback_fires_after_poll_wait() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: 16,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        queue.write_buffer(&buffer, 0, &[1u8; 16]);
+        queue.submit(None);
+
+        let mapped = Arc::new(AtomicBool::new(false));
+        let flag = mapped.clone();
+        buffer.slice(..).map_async(wgpu::MapMode::Read, move |res| {
+            flag.store(res.is_ok(), Ordering::SeqCst);
+        });
+        poll_wait(&device);
+
+        assert!(mapped.load(Ordering::SeqCst));
+        assert_eq!(&buffer.slice(..).get_mapped_range()[..], &[1u8; 16]);
+        // 工作都已完成，非阻塞轮询应报告队列为空
+        assert!(poll_once(&device));
+    }
+}
@@ -0,0 +1,420 @@
+use crate::DEPTH_FORMAT;
+
+/// 抗锯齿方式
+///
+/// - `None`：直接渲染到 surface；
+/// - `Msaa(n)`：渲染到 n 倍多重采样的纹理，渲染通道结束时解析（resolve）到 surface；
+/// - `Fxaa`：渲染到与 surface 同尺寸的离屏纹理，再用 FXAA 全屏通道绘制到 surface。
+///
+/// # NOTE:
+/// WebGPU 只保证支持 1 与 4 倍采样，其它采样数需查询适配器的 `get_texture_format_features`。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AntiAliasing {
+    #[default]
+    None,
+    Msaa(u32),
+    Fxaa,
+}
+
+impl AntiAliasing {
+    /// 渲染管线与渲染目标需使用的采样数
+    pub fn sample_count(&self) -> u32 {
+        match self {
+            Self::Msaa(count) => (*count).max(1),
+            _ => 1,
+        }
+    }
+
+    /// 运行时切换的顺序：None -> Msaa(4) -> Fxaa -> None
+    pub fn next(&self) -> Self {
+        match self {
+            Self::None => Self::Msaa(4),
+            Self::Msaa(_) => Self::Fxaa,
+            Self::Fxaa => Self::None,
+        }
+    }
+}
+
+/// 抗锯齿所需的渲染目标
+///
+/// 用法：
+/// 1. 场景的渲染管线以 [`AntiAliasingTargets::sample_count`] 创建（如 `ViewNodeBuilder::with_sample_count`）；
+/// 2. 场景渲染通道的颜色附件使用 [`AntiAliasingTargets::color_attachment`]，深度附件使用 [`AntiAliasingTargets::depth_view`]；
+/// 3. 场景渲染通道结束后调用 [`AntiAliasingTargets::finish`]，FXAA 模式下在这里绘制到 surface。
+///
+/// # NOTE:
+/// 切换模式（[`AntiAliasingTargets::set_mode`]）会重建多重采样/离屏纹理及深度纹理；
+/// 采样数变化时已有的渲染管线也不再可用，app 需以新的采样数重建它们。
+pub struct AntiAliasingTargets {
+    mode: AntiAliasing,
+    format: wgpu::TextureFormat,
+    size: (u32, u32),
+    use_depth: bool,
+    msaa_view: Option<wgpu::TextureView>,
+    fxaa_target: Option<(wgpu::TextureView, wgpu::BindGroup)>,
+    depth_view: Option<wgpu::TextureView>,
+    fxaa_layout: wgpu::BindGroupLayout,
+    fxaa_pipeline: wgpu::RenderPipeline,
+    sampler: wgpu::Sampler,
+}
+
+#[allow(dead_code)]
+impl AntiAliasingTargets {
+    pub fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+        use_depth: bool,
+        mode: AntiAliasing,
+    ) -> Self {
+        let fxaa_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("fxaa"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("fxaa"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("fxaa.wgsl").into()),
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("fxaa"),
+            bind_group_layouts: &[&fxaa_layout],
+            push_constant_ranges: &[],
+        });
+        let fxaa_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("fxaa pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(format.into())],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        let mut targets = Self {
+            mode,
+            format,
+            size: (width.max(1), height.max(1)),
+            use_depth,
+            msaa_view: None,
+            fxaa_target: None,
+            depth_view: None,
+            fxaa_layout,
+            fxaa_pipeline,
+            sampler: crate::bilinear_sampler(device),
+        };
+        targets.create_targets(device);
+        targets
+    }
+
+    pub fn mode(&self) -> AntiAliasing {
+        self.mode
+    }
+
+    pub fn sample_count(&self) -> u32 {
+        self.mode.sample_count()
+    }
+
+    /// 切换抗锯齿方式，返回采样数是否发生了变化（即渲染管线是否需要重建）
+    pub fn set_mode(&mut self, device: &wgpu::Device, mode: AntiAliasing) -> bool {
+        if mode == self.mode {
+            return false;
+        }
+        let old_count = self.sample_count();
+        self.mode = mode;
+        self.create_targets(device);
+        old_count != self.sample_count()
+    }
+
+    /// surface 尺寸变化时重建渲染目标
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        let size = (width.max(1), height.max(1));
+        if size != self.size {
+            self.size = size;
+            self.create_targets(device);
+        }
+    }
+
+    /// 场景渲染通道的颜色附件
+    ///
+    /// MSAA 模式下多重采样纹理的内容在解析到 `frame_view` 之后即被丢弃，无需保存。
+    pub fn color_attachment<'a>(
+        &'a self,
+        frame_view: &'a wgpu::TextureView,
+        load: wgpu::LoadOp<wgpu::Color>,
+    ) -> wgpu::RenderPassColorAttachment<'a> {
+        let (view, resolve_target, store) = match (&self.msaa_view, &self.fxaa_target) {
+            (Some(msaa_view), _) => (msaa_view, Some(frame_view), wgpu::StoreOp::Discard),
+            (_, Some((offscreen, _))) => (offscreen, None, wgpu::StoreOp::Store),
+            _ => (frame_view, None, wgpu::StoreOp::Store),
+        };
+        wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target,
+            ops: wgpu::Operations { load, store },
+        }
+    }
+
+    /// 与颜色附件采样数一致的深度纹理视图，创建时 `use_depth` 为 false 则返回 `None`
+    pub fn depth_view(&self) -> Option<&wgpu::TextureView> {
+        self.depth_view.as_ref()
+    }
+
+    /// 场景渲染通道结束之后调用：FXAA 模式下把离屏纹理经 FXAA 绘制到 `frame_view`，其它模式无操作
+    pub fn finish(&self, encoder: &mut wgpu::CommandEncoder, frame_view: &wgpu::TextureView) {
+        let Some((_, bind_group)) = self.fxaa_target.as_ref() else {
+            return;
+        };
+        let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("fxaa rpass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: frame_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            ..Default::default()
+        });
+        rpass.set_pipeline(&self.fxaa_pipeline);
+        rpass.set_bind_group(0, bind_group, &[]);
+        rpass.draw(0..3, 0..1);
+    }
+
+    fn create_targets(&mut self, device: &wgpu::Device) {
+        let sample_count = self.sample_count();
+        let (width, height) = self.size;
+        let create = |format, usage, sample_count, label| {
+            device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: Some(label),
+                    size: wgpu::Extent3d {
+                        width,
+                        height,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count,
+                    dimension: wgpu::TextureDimension::D2,
+                    format,
+                    usage,
+                    view_formats: &[],
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        };
+
+        self.msaa_view = match self.mode {
+            AntiAliasing::Msaa(_) => Some(create(
+                self.format,
+                wgpu::TextureUsages::RENDER_ATTACHMENT,
+                sample_count,
+                "msaa color",
+            )),
+            _ => None,
+        };
+        self.fxaa_target = match self.mode {
+            AntiAliasing::Fxaa => {
+                let view = create(
+                    self.format,
+                    wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+                    1,
+                    "fxaa offscreen",
+                );
+                let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("fxaa"),
+                    layout: &self.fxaa_layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: wgpu::BindingResource::TextureView(&view),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: wgpu::BindingResource::Sampler(&self.sampler),
+                        },
+                    ],
+                });
+                Some((view, bind_group))
+            }
+            _ => None,
+        };
+        self.depth_view = if self.use_depth {
+            Some(create(
+                DEPTH_FORMAT,
+                wgpu::TextureUsages::RENDER_ATTACHMENT,
+                sample_count,
+                "aa depth",
+            ))
+        } else {
+            None
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cycle_modes() {
+        let mut mode = AntiAliasing::default();
+        assert_eq!(mode.sample_count(), 1);
+        mode = mode.next();
+        assert_eq!(mode, AntiAliasing::Msaa(4));
+        assert_eq!(mode.sample_count(), 4);
+        mode = mode.next();
+        assert_eq!(mode, AntiAliasing::Fxaa);
+        assert_eq!(mode.sample_count(), 1);
+        assert_eq!(mode.next(), AntiAliasing::None);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn toggling_changes_resolve_path() {
+        let Some((device, _queue)) = crate::test_device() else {
+            return;
+        };
+
+        let format = wgpu::TextureFormat::Rgba8UnormSrgb;
+        let frame = device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: None,
+                size: wgpu::Extent3d {
+                    width: 16,
+                    height: 16,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            })
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        let mut targets =
+            AntiAliasingTargets::new(&device, format, 16, 16, false, AntiAliasing::None);
+        let load = wgpu::LoadOp::Clear(wgpu::Color::BLACK);
+
+        // 按 F2 的切换顺序依次检查颜色附件
+        for _ in 0..3 {
+            let mode = targets.mode().next();
+            targets.set_mode(&device, mode);
+            let attachment = targets.color_attachment(&frame, load);
+            match mode {
+                // 多重采样纹理解析到 surface，自身内容丢弃
+                AntiAliasing::Msaa(_) => {
+                    assert!(attachment.resolve_target.is_some());
+                    assert_eq!(attachment.ops.store, wgpu::StoreOp::Discard);
+                }
+                // 先渲染到离屏纹理，由 `finish` 中的 FXAA 通道绘制到 surface
+                AntiAliasing::Fxaa => {
+                    assert!(attachment.resolve_target.is_none());
+                    assert!(targets.fxaa_target.is_some());
+                    assert_eq!(attachment.ops.store, wgpu::StoreOp::Store);
+                }
+                // 直接渲染到 surface
+                AntiAliasing::None => {
+                    assert!(attachment.resolve_target.is_none());
+                    assert!(targets.msaa_view.is_none() && targets.fxaa_target.is_none());
+                }
+            }
+        }
+        assert_eq!(targets.mode(), AntiAliasing::None);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn switch_msaa_then_none() {
+        let Some((device, _queue)) = crate::test_device() else {
+            return;
+        };
+
+        let format = wgpu::TextureFormat::Rgba8Unorm;
+        let mut targets =
+            AntiAliasingTargets::new(&device, format, 64, 64, true, AntiAliasing::None);
+        assert!(targets.msaa_view.is_none() && targets.fxaa_target.is_none());
+
+        assert!(targets.set_mode(&device, AntiAliasing::Msaa(4)));
+        assert_eq!(targets.sample_count(), 4);
+        assert!(targets.msaa_view.is_some());
+        // 再次设置相同的模式不会重建
+        assert!(!targets.set_mode(&device, AntiAliasing::Msaa(4)));
+
+        assert!(targets.set_mode(&device, AntiAliasing::None));
+        assert_eq!(targets.sample_count(), 1);
+        assert!(targets.msaa_view.is_none() && targets.fxaa_target.is_none());
+        assert!(targets.depth_view().is_some());
+
+        // 新的目标需能与采样数为 1 的渲染通道一起使用
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let frame = device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: None,
+                size: wgpu::Extent3d {
+                    width: 64,
+                    height: 64,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            })
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        {
+            let _rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(
+                    targets.color_attachment(&frame, wgpu::LoadOp::Clear(wgpu::Color::BLACK)),
+                )],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: targets.depth_view().unwrap(),
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Discard,
+                    }),
+                    stencil_ops: None,
+                }),
+                ..Default::default()
+            });
+        }
+        targets.finish(&mut encoder, &frame);
+        let _ = encoder.finish();
+        assert!(pollster::block_on(device.pop_error_scope()).is_none());
+    }
+}
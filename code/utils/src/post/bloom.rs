@@ -0,0 +1,365 @@
+use crate::{AnyTexture, BufferObj, compute::GaussianBlur};
+use bytemuck::{Pod, Zeroable};
+
+const WORKGROUP_SIZE: u32 = 8;
+/// 降采样的级数，需与 `bloom_composite.wgsl` 中绑定的纹理数一致
+pub const BLOOM_LEVELS: usize = 3;
+const LEVEL_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct BloomParams {
+    /// 亮度阈值，亮度超出此值的部分才会产生泛光
+    pub threshold: f32,
+    /// 泛光叠加回原图时的强度
+    pub intensity: f32,
+    pub padding: [f32; 2],
+}
+
+impl Default for BloomParams {
+    fn default() -> Self {
+        Self {
+            threshold: 1.0,
+            intensity: 0.5,
+            padding: [0.0; 2],
+        }
+    }
+}
+
+// 一级降采样：降采样结果及其模糊结果
+struct BloomLevel {
+    downsampled: AnyTexture,
+    blurred: AnyTexture,
+}
+
+/// 泛光（bloom）后处理
+///
+/// 降采样链：
+/// 1. `extract`：把 HDR 输入按 2x2 平均降采样到半分辨率，同时只保留亮度超出 `threshold` 的部分；
+/// 2. `downsample`：把上一级的模糊结果再降采样一半，共 `BLOOM_LEVELS` 级（1/2、1/4、1/8 分辨率）；
+/// 3. 每一级都用 `GaussianBlur` 模糊，分辨率越低，同样的模糊半径覆盖的屏幕范围越大；
+/// 4. `composite`：把各级模糊结果双线性放大后乘以 `intensity` 叠加到 HDR 输入上。
+///
+/// # NOTE:
+/// 输出仍然是 HDR 颜色，需在之后再做色调映射（tone mapping）。
+/// `hdr_input` 需要 `TEXTURE_BINDING`；`output` 作为存储纹理写入，需要 `STORAGE_BINDING` 且格式为 `Rgba16Float`。
+pub struct Bloom {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    params: BloomParams,
+    params_buf: BufferObj,
+    sampler: wgpu::Sampler,
+    blur: GaussianBlur,
+    downsample_layout: wgpu::BindGroupLayout,
+    extract_pipeline: wgpu::ComputePipeline,
+    downsample_pipeline: wgpu::ComputePipeline,
+    composite_layout: wgpu::BindGroupLayout,
+    composite_pipeline: wgpu::ComputePipeline,
+    levels: Vec<BloomLevel>,
+}
+
+#[allow(dead_code)]
+impl Bloom {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, params: BloomParams) -> Self {
+        let params_buf = BufferObj::create_uniform_buffer(device, &params, Some("bloom params"));
+
+        let uniform_entry = wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let texture_entry = |binding, filterable| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let storage_entry = wgpu::BindGroupLayoutEntry {
+            binding: 2,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::StorageTexture {
+                access: wgpu::StorageTextureAccess::WriteOnly,
+                format: LEVEL_FORMAT,
+                view_dimension: wgpu::TextureViewDimension::D2,
+            },
+            count: None,
+        };
+
+        let downsample_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("bloom downsample"),
+            entries: &[uniform_entry, texture_entry(1, false), storage_entry],
+        });
+        let mut composite_entries = vec![
+            uniform_entry,
+            texture_entry(1, false),
+            storage_entry,
+            wgpu::BindGroupLayoutEntry {
+                binding: 3,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+        ];
+        for i in 0..BLOOM_LEVELS as u32 {
+            composite_entries.push(texture_entry(4 + i, true));
+        }
+        let composite_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("bloom composite"),
+            entries: &composite_entries,
+        });
+
+        let create_pipeline = |layout: &wgpu::BindGroupLayout,
+                               wgsl: &'static str,
+                               entry_point: &str| {
+            let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: None,
+                bind_group_layouts: &[layout],
+                push_constant_ranges: &[],
+            });
+            let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("bloom"),
+                source: wgpu::ShaderSource::Wgsl(wgsl.into()),
+            });
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(entry_point),
+                layout: Some(&pipeline_layout),
+                module: &shader,
+                entry_point: Some(entry_point),
+                compilation_options: Default::default(),
+                cache: None,
+            })
+        };
+        let downsample_wgsl = include_str!("bloom_downsample.wgsl");
+        let extract_pipeline = create_pipeline(&downsample_layout, downsample_wgsl, "extract");
+        let downsample_pipeline =
+            create_pipeline(&downsample_layout, downsample_wgsl, "downsample");
+        let composite_pipeline = create_pipeline(
+            &composite_layout,
+            include_str!("bloom_composite.wgsl"),
+            "cs_main",
+        );
+
+        Self {
+            device: device.clone(),
+            queue: queue.clone(),
+            params,
+            params_buf,
+            sampler: crate::bilinear_sampler(device),
+            blur: GaussianBlur::new_with_format(device, 4, LEVEL_FORMAT),
+            downsample_layout,
+            extract_pipeline,
+            downsample_pipeline,
+            composite_layout,
+            composite_pipeline,
+            levels: vec![],
+        }
+    }
+
+    pub fn params(&self) -> BloomParams {
+        self.params
+    }
+
+    pub fn set_threshold(&mut self, threshold: f32) {
+        self.params.threshold = threshold.max(0.0);
+        self.write_params();
+    }
+
+    pub fn set_intensity(&mut self, intensity: f32) {
+        self.params.intensity = intensity.max(0.0);
+        self.write_params();
+    }
+
+    fn write_params(&self) {
+        self.queue
+            .write_buffer(&self.params_buf.buffer, 0, bytemuck::bytes_of(&self.params));
+    }
+
+    /// 输入尺寸变化时重新创建各级纹理
+    fn ensure_levels(&mut self, size: wgpu::Extent3d) {
+        let sizes = bloom_level_sizes(size, BLOOM_LEVELS);
+        if self.levels.len() == sizes.len()
+            && self
+                .levels
+                .iter()
+                .zip(sizes.iter())
+                .all(|(level, size)| level.downsampled.size == *size)
+        {
+            return;
+        }
+        let usage = wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::STORAGE_BINDING;
+        let create = |size, label| {
+            crate::load_texture::empty(&self.device, LEVEL_FORMAT, size, None, usage, Some(label))
+        };
+        self.levels = sizes
+            .into_iter()
+            .map(|size| BloomLevel {
+                downsampled: create(size, "bloom downsampled"),
+                blurred: create(size, "bloom blurred"),
+            })
+            .collect();
+    }
+
+    fn dispatch(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        pipeline: &wgpu::ComputePipeline,
+        bind_group: &wgpu::BindGroup,
+        size: wgpu::Extent3d,
+    ) {
+        let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("bloom"),
+            timestamp_writes: None,
+        });
+        cpass.set_pipeline(pipeline);
+        cpass.set_bind_group(0, bind_group, &[]);
+        cpass.dispatch_workgroups(
+            size.width.div_ceil(WORKGROUP_SIZE),
+            size.height.div_ceil(WORKGROUP_SIZE),
+            1,
+        );
+    }
+
+    fn downsample_bind_group(&self, src: &AnyTexture, dst: &AnyTexture) -> wgpu::BindGroup {
+        self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("bloom downsample"),
+            layout: &self.downsample_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: self.params_buf.buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&src.tex_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(&dst.tex_view),
+                },
+            ],
+        })
+    }
+
+    /// 录制泛光的全部计算通道：`output = hdr_input + intensity * bloom`
+    pub fn run(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        hdr_input: &AnyTexture,
+        output: &AnyTexture,
+    ) {
+        assert_eq!(hdr_input.size, output.size, "输入输出纹理尺寸需相同");
+        assert_eq!(output.format, LEVEL_FORMAT, "输出纹理格式需为 Rgba16Float");
+        self.ensure_levels(hdr_input.size);
+
+        let levels = std::mem::take(&mut self.levels);
+        for (i, level) in levels.iter().enumerate() {
+            let (pipeline, src) = if i == 0 {
+                (&self.extract_pipeline, hdr_input)
+            } else {
+                (&self.downsample_pipeline, &levels[i - 1].blurred)
+            };
+            let bind_group = self.downsample_bind_group(src, &level.downsampled);
+            self.dispatch(encoder, pipeline, &bind_group, level.downsampled.size);
+            self.blur.run(encoder, &level.downsampled, &level.blurred);
+        }
+
+        let mut entries = vec![
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: self.params_buf.buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::TextureView(&hdr_input.tex_view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: wgpu::BindingResource::TextureView(&output.tex_view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 3,
+                resource: wgpu::BindingResource::Sampler(&self.sampler),
+            },
+        ];
+        for (i, level) in levels.iter().enumerate() {
+            entries.push(wgpu::BindGroupEntry {
+                binding: 4 + i as u32,
+                resource: wgpu::BindingResource::TextureView(&level.blurred.tex_view),
+            });
+        }
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("bloom composite"),
+            layout: &self.composite_layout,
+            entries: &entries,
+        });
+        self.dispatch(encoder, &self.composite_pipeline, &bind_group, output.size);
+        self.levels = levels;
+    }
+}
+
+/// 降采样链中每一级的尺寸：逐级减半（向上取整），最小为 1
+pub fn bloom_level_sizes(size: wgpu::Extent3d, levels: usize) -> Vec<wgpu::Extent3d> {
+    let mut sizes = Vec::with_capacity(levels);
+    let mut current = size;
+    for _ in 0..levels {
+        current = wgpu::Extent3d {
+            width: current.width.div_ceil(2).max(1),
+            height: current.height.div_ceil(2).max(1),
+            depth_or_array_layers: 1,
+        };
+        sizes.push(current);
+    }
+    sizes
+}
+
+/// 与 `bloom_downsample.wgsl` 中 `extract` 相同的亮度阈值计算
+pub fn bright_pass(color: [f32; 3], threshold: f32) -> [f32; 3] {
+    let luminance = 0.2126 * color[0] + 0.7152 * color[1] + 0.0722 * color[2];
+    let contribution = (luminance - threshold).max(0.0) / luminance.max(0.0001);
+    color.map(|c| c * contribution)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn downsample_chain() {
+        let size = wgpu::Extent3d {
+            width: 1280,
+            height: 721,
+            depth_or_array_layers: 1,
+        };
+        let sizes: Vec<(u32, u32)> = bloom_level_sizes(size, BLOOM_LEVELS)
+            .iter()
+            .map(|s| (s.width, s.height))
+            .collect();
+        assert_eq!(sizes, vec![(640, 361), (320, 181), (160, 91)]);
+        let tiny = wgpu::Extent3d {
+            width: 1,
+            height: 1,
+            depth_or_array_layers: 1,
+        };
+        assert!(
+            bloom_level_sizes(tiny, 2)
+                .iter()
+                .all(|s| s.width == 1 && s.height == 1)
+        );
+    }
+
+    #[test]
+    fn threshold() {
+        assert_eq!(bright_pass([0.5, 0.5, 0.5], 1.0), [0.0; 3]);
+        let bright = bright_pass([4.0, 4.0, 4.0], 1.0);
+        assert!((bright[0] - 3.0).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,31 @@
+// 把各级模糊结果叠加回 HDR 输入
+
+struct BloomParams {
+    threshold: f32,
+    intensity: f32,
+    padding: vec2f,
+};
+
+@group(0) @binding(0) var<uniform> params: BloomParams;
+@group(0) @binding(1) var hdr_tex: texture_2d<f32>;
+@group(0) @binding(2) var dst_tex: texture_storage_2d<rgba16float, write>;
+@group(0) @binding(3) var level_sampler: sampler;
+@group(0) @binding(4) var level0: texture_2d<f32>;
+@group(0) @binding(5) var level1: texture_2d<f32>;
+@group(0) @binding(6) var level2: texture_2d<f32>;
+
+@compute @workgroup_size(8, 8)
+fn cs_main(@builtin(global_invocation_id) gid: vec3u) {
+    let size = vec2i(textureDimensions(dst_tex));
+    let coord = vec2i(gid.xy);
+    if (coord.x >= size.x || coord.y >= size.y) {
+        return;
+    }
+    let uv = (vec2f(coord) + 0.5) / vec2f(size);
+    // 低分辨率的模糊结果通过双线性采样放大
+    let bloom = textureSampleLevel(level0, level_sampler, uv, 0.0)
+        + textureSampleLevel(level1, level_sampler, uv, 0.0)
+        + textureSampleLevel(level2, level_sampler, uv, 0.0);
+    let color = textureLoad(hdr_tex, coord, 0);
+    textureStore(dst_tex, coord, vec4f(color.rgb + bloom.rgb * params.intensity, color.a));
+}
@@ -0,0 +1,51 @@
+// 提取高亮像素 / 逐级降采样，输出尺寸为输入的一半（向上取整）
+
+struct BloomParams {
+    threshold: f32,
+    intensity: f32,
+    padding: vec2f,
+};
+
+@group(0) @binding(0) var<uniform> params: BloomParams;
+@group(0) @binding(1) var src_tex: texture_2d<f32>;
+@group(0) @binding(2) var dst_tex: texture_storage_2d<rgba16float, write>;
+
+// 2x2 像素的平均值
+fn box_average(dst_coord: vec2i) -> vec4f {
+    let max_coord = vec2i(textureDimensions(src_tex)) - 1;
+    let base = dst_coord * 2;
+    var sum = vec4f(0.0);
+    for (var y = 0; y < 2; y++) {
+        for (var x = 0; x < 2; x++) {
+            sum += textureLoad(src_tex, min(base + vec2i(x, y), max_coord), 0);
+        }
+    }
+    return sum * 0.25;
+}
+
+fn out_of_bounds(coord: vec2i) -> bool {
+    let size = vec2i(textureDimensions(dst_tex));
+    return coord.x >= size.x || coord.y >= size.y;
+}
+
+@compute @workgroup_size(8, 8)
+fn extract(@builtin(global_invocation_id) gid: vec3u) {
+    let coord = vec2i(gid.xy);
+    if (out_of_bounds(coord)) {
+        return;
+    }
+    let color = box_average(coord);
+    // 只保留亮度超出阈值的部分，按比例缩放颜色以保持色相
+    let luminance = dot(color.rgb, vec3f(0.2126, 0.7152, 0.0722));
+    let contribution = max(luminance - params.threshold, 0.0) / max(luminance, 0.0001);
+    textureStore(dst_tex, coord, vec4f(color.rgb * contribution, 1.0));
+}
+
+@compute @workgroup_size(8, 8)
+fn downsample(@builtin(global_invocation_id) gid: vec3u) {
+    let coord = vec2i(gid.xy);
+    if (out_of_bounds(coord)) {
+        return;
+    }
+    textureStore(dst_tex, coord, box_average(coord));
+}
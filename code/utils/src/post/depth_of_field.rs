@@ -0,0 +1,335 @@
+use crate::{AnyTexture, BufferObj, compute::GaussianBlur};
+use bytemuck::{Pod, Zeroable};
+
+const WORKGROUP_SIZE: u32 = 8;
+const BLUR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct DofParams {
+    /// 对焦距离（视空间中的线性距离），该距离上的物体最清晰
+    pub focus_distance: f32,
+    /// 光圈大小，越大则离开焦点后模糊得越快，远景的弥散圆最终趋近于它
+    pub aperture: f32,
+    /// 渲染场景所用透视投影的近、远平面，用于线性化深度
+    pub near: f32,
+    pub far: f32,
+}
+
+impl Default for DofParams {
+    fn default() -> Self {
+        Self {
+            focus_distance: 10.0,
+            aperture: 1.0,
+            near: 0.1,
+            far: 100.0,
+        }
+    }
+}
+
+/// 景深（depth of field）后处理
+///
+/// 1. 用 `GaussianBlur` 把颜色模糊成半径为 `max_radius / 2` 与 `max_radius` 的两级；
+/// 2. 合成时逐像素线性化深度：`z = near * far / (far - d * (far - near))`，
+///    其中 `d` 为标准（非反转）透视投影下 `[0, 1]` 的深度值；
+/// 3. 弥散圆（circle of confusion）`coc = clamp(aperture * (z - focus_distance) / z, -1, 1)`，
+///    在清晰、半模糊、全模糊三级之间按 `|coc|` 插值，近似随弥散圆缩放的模糊半径。
+///
+/// 近景与远景：
+/// - 焦点之前 `coc` 为负，越靠近相机增长越快，`z` 小于 `focus_distance / (1 + 1 / aperture)` 后达到最大模糊；
+/// - 焦点之后 `coc` 为正，距离趋于无穷时趋近于 `aperture`，所以 `aperture < 1` 时远景不会达到最大模糊半径；
+/// - 两侧都只按像素自身的弥散圆取模糊结果，模糊的近景不会扩散到其后清晰的背景上，
+///   清晰前景的颜色也会少量渗入其周围模糊的背景中。
+///
+/// # NOTE:
+/// `color` 需要 `TEXTURE_BINDING`；`depth` 为单采样的深度纹理视图，以 `texture_depth_2d` 读取，
+/// MSAA 深度需先用 `DepthResolve` 解析；`output` 作为存储纹理写入，需要 `STORAGE_BINDING` 且格式为 `Rgba16Float`。
+pub struct DepthOfField {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    params: DofParams,
+    params_buf: BufferObj,
+    half_blur: GaussianBlur,
+    full_blur: GaussianBlur,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::ComputePipeline,
+    blurred: Option<(AnyTexture, AnyTexture)>,
+}
+
+#[allow(dead_code)]
+impl DepthOfField {
+    /// `max_radius` 为弥散圆最大时的模糊半径（像素）
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        params: DofParams,
+        max_radius: u32,
+    ) -> Self {
+        let params_buf = BufferObj::create_uniform_buffer(device, &params, Some("dof params"));
+
+        let texture_entry = |binding, sample_type| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Texture {
+                sample_type,
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let float = wgpu::TextureSampleType::Float { filterable: false };
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("depth of field"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                texture_entry(1, float),
+                texture_entry(2, wgpu::TextureSampleType::Depth),
+                texture_entry(3, float),
+                texture_entry(4, float),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 5,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::StorageTexture {
+                        access: wgpu::StorageTextureAccess::WriteOnly,
+                        format: BLUR_FORMAT,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("depth of field"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("depth_of_field.wgsl").into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("depth of field"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: Some("cs_main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        Self {
+            device: device.clone(),
+            queue: queue.clone(),
+            params,
+            params_buf,
+            half_blur: GaussianBlur::new_with_format(device, max_radius / 2, BLUR_FORMAT),
+            full_blur: GaussianBlur::new_with_format(device, max_radius, BLUR_FORMAT),
+            bind_group_layout,
+            pipeline,
+            blurred: None,
+        }
+    }
+
+    pub fn params(&self) -> DofParams {
+        self.params
+    }
+
+    pub fn set_params(&mut self, params: DofParams) {
+        self.params = params;
+        self.write_params();
+    }
+
+    pub fn set_focus_distance(&mut self, focus_distance: f32) {
+        self.params.focus_distance = focus_distance.max(self.params.near);
+        self.write_params();
+    }
+
+    pub fn set_aperture(&mut self, aperture: f32) {
+        self.params.aperture = aperture.max(0.0);
+        self.write_params();
+    }
+
+    fn write_params(&self) {
+        self.queue
+            .write_buffer(&self.params_buf.buffer, 0, bytemuck::bytes_of(&self.params));
+    }
+
+    /// 录制景深的全部计算通道，`color`、`depth`、`output` 三者尺寸需相同
+    pub fn run(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        color: &AnyTexture,
+        depth: &wgpu::TextureView,
+        output: &AnyTexture,
+    ) {
+        assert_eq!(color.size, output.size, "输入输出纹理尺寸需相同");
+        assert_eq!(output.format, BLUR_FORMAT, "输出纹理格式需为 Rgba16Float");
+
+        let size = color.size;
+        if self
+            .blurred
+            .as_ref()
+            .is_none_or(|(half, _)| half.size != size)
+        {
+            let usage = wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::STORAGE_BINDING;
+            let create = |label| {
+                crate::load_texture::empty(
+                    &self.device,
+                    BLUR_FORMAT,
+                    size,
+                    None,
+                    usage,
+                    Some(label),
+                )
+            };
+            self.blurred = Some((create("dof half blurred"), create("dof full blurred")));
+        }
+        let (half, full) = self.blurred.as_ref().unwrap();
+        self.half_blur.run(encoder, color, half);
+        self.full_blur.run(encoder, color, full);
+
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("depth of field"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: self.params_buf.buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&color.tex_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(depth),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::TextureView(&half.tex_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: wgpu::BindingResource::TextureView(&full.tex_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: wgpu::BindingResource::TextureView(&output.tex_view),
+                },
+            ],
+        });
+        let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("depth of field"),
+            timestamp_writes: None,
+        });
+        cpass.set_pipeline(&self.pipeline);
+        cpass.set_bind_group(0, &bind_group, &[]);
+        cpass.dispatch_workgroups(
+            size.width.div_ceil(WORKGROUP_SIZE),
+            size.height.div_ceil(WORKGROUP_SIZE),
+            1,
+        );
+    }
+}
+
+/// 与 `depth_of_field.wgsl` 相同的深度线性化
+pub fn linearize_depth(depth: f32, near: f32, far: f32) -> f32 {
+    near * far / (far - depth * (far - near))
+}
+
+/// 与 `depth_of_field.wgsl` 相同的有符号弥散圆，近景为负、远景为正
+pub fn circle_of_confusion(distance: f32, params: &DofParams) -> f32 {
+    (params.aperture * (distance - params.focus_distance) / distance).clamp(-1.0, 1.0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn coc_along_depth_ramp() {
+        let params = DofParams {
+            aperture: 0.5,
+            ..Default::default()
+        };
+        let (near, far) = (params.near, params.far);
+        assert!((linearize_depth(0.0, near, far) - near).abs() < 1e-5);
+        assert!((linearize_depth(1.0, near, far) - far).abs() < 1e-3);
+
+        let cocs: Vec<f32> = (0..=100)
+            .map(|i| circle_of_confusion(linearize_depth(i as f32 / 100.0, near, far), &params))
+            .collect();
+        // 深度单调增大时弥散圆从近景的 -1 单调变为远景的正值
+        assert_eq!(cocs[0], -1.0);
+        assert!(cocs.windows(2).all(|w| w[0] <= w[1]));
+        assert!(cocs[100] > 0.0 && cocs[100] < params.aperture);
+        assert_eq!(circle_of_confusion(params.focus_distance, &params), 0.0);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn smoke_depth_ramp() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        let size = wgpu::Extent3d {
+            width: 64,
+            height: 32,
+            depth_or_array_layers: 1,
+        };
+        let usage = wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST;
+        let color = crate::load_texture::empty(
+            &device,
+            wgpu::TextureFormat::Rgba8Unorm,
+            size,
+            None,
+            usage,
+            None,
+        );
+        // 只有 Depth16Unorm 可以从缓冲区复制写入，沿 x 方向写入从近到远的深度
+        let depth = crate::load_texture::empty(
+            &device,
+            wgpu::TextureFormat::Depth16Unorm,
+            size,
+            None,
+            usage,
+            None,
+        );
+        let ramp: Vec<u16> = (0..size.height)
+            .flat_map(|_| (0..size.width).map(|x| (x * u16::MAX as u32 / (size.width - 1)) as u16))
+            .collect();
+        queue.write_texture(
+            depth.tex.as_image_copy(),
+            bytemuck::cast_slice(&ramp),
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(size.width * 2),
+                rows_per_image: None,
+            },
+            size,
+        );
+        let output = crate::load_texture::empty(
+            &device,
+            BLUR_FORMAT,
+            size,
+            None,
+            wgpu::TextureUsages::STORAGE_BINDING,
+            None,
+        );
+
+        let mut dof = DepthOfField::new(&device, &queue, DofParams::default(), 6);
+        let mut encoder = device.create_command_encoder(&Default::default());
+        dof.run(&mut encoder, &color, &depth.tex_view, &output);
+        queue.submit(Some(encoder.finish()));
+        crate::poll::poll_wait(&device);
+    }
+}
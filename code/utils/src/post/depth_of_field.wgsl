@@ -0,0 +1,46 @@
+struct DofParams {
+    focus_distance: f32,
+    aperture: f32,
+    near: f32,
+    far: f32,
+};
+
+@group(0) @binding(0) var<uniform> params: DofParams;
+@group(0) @binding(1) var color_tex: texture_2d<f32>;
+@group(0) @binding(2) var depth_tex: texture_depth_2d;
+// 半径为 max_radius / 2 与 max_radius 的两级模糊结果
+@group(0) @binding(3) var half_blurred: texture_2d<f32>;
+@group(0) @binding(4) var full_blurred: texture_2d<f32>;
+@group(0) @binding(5) var output_tex: texture_storage_2d<rgba16float, write>;
+
+// 透视投影下 [0, 1] 的深度值转换为视空间中的线性距离
+fn linearize_depth(depth: f32) -> f32 {
+    return params.near * params.far / (params.far - depth * (params.far - params.near));
+}
+
+// 有符号的弥散圆：焦点之前为负（近景），之后为正（远景），范围 [-1, 1]
+fn circle_of_confusion(distance: f32) -> f32 {
+    return clamp(params.aperture * (distance - params.focus_distance) / distance, -1.0, 1.0);
+}
+
+@compute @workgroup_size(8, 8)
+fn cs_main(@builtin(global_invocation_id) gid: vec3u) {
+    let size = vec2i(textureDimensions(color_tex));
+    let coord = vec2i(gid.xy);
+    if (coord.x >= size.x || coord.y >= size.y) {
+        return;
+    }
+    let depth = textureLoad(depth_tex, coord, 0);
+    let coc = abs(circle_of_confusion(linearize_depth(depth)));
+
+    // 在清晰、半径减半、完整半径三级之间按弥散圆大小插值，近似随弥散圆缩放的模糊半径
+    let sharp = textureLoad(color_tex, coord, 0);
+    let half = textureLoad(half_blurred, coord, 0);
+    let full = textureLoad(full_blurred, coord, 0);
+    let t = coc * 2.0;
+    var color = mix(sharp, half, saturate(t));
+    if t > 1.0 {
+        color = mix(half, full, t - 1.0);
+    }
+    textureStore(output_tex, coord, color);
+}
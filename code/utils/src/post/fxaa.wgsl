@@ -0,0 +1,60 @@
+// 简化版 FXAA：沿亮度梯度的垂直方向（即边缘方向）采样并混合，柔化锯齿
+
+@group(0) @binding(0) var src_texture: texture_2d<f32>;
+@group(0) @binding(1) var src_sampler: sampler;
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) uv: vec2f,
+};
+
+// 覆盖整个屏幕的大三角形，无需顶点缓冲区
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let uv = vec2f(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+    var out: VertexOutput;
+    out.position = vec4f(uv * 2.0 - 1.0, 0.0, 1.0);
+    out.uv = vec2f(uv.x, 1.0 - uv.y);
+    return out;
+}
+
+const REDUCE_MIN: f32 = 1.0 / 128.0;
+const REDUCE_MUL: f32 = 1.0 / 8.0;
+// 沿边缘方向的最大搜索距离（像素）
+const SPAN_MAX: f32 = 8.0;
+const LUMA: vec3f = vec3f(0.299, 0.587, 0.114);
+
+fn sample_rgb(uv: vec2f) -> vec3f {
+    return textureSampleLevel(src_texture, src_sampler, uv, 0.0).rgb;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4f {
+    let texel = 1.0 / vec2f(textureDimensions(src_texture));
+    let center = textureSampleLevel(src_texture, src_sampler, in.uv, 0.0);
+
+    let luma_nw = dot(sample_rgb(in.uv + vec2f(-1.0, -1.0) * texel), LUMA);
+    let luma_ne = dot(sample_rgb(in.uv + vec2f(1.0, -1.0) * texel), LUMA);
+    let luma_sw = dot(sample_rgb(in.uv + vec2f(-1.0, 1.0) * texel), LUMA);
+    let luma_se = dot(sample_rgb(in.uv + vec2f(1.0, 1.0) * texel), LUMA);
+    let luma_m = dot(center.rgb, LUMA);
+    let luma_min = min(luma_m, min(min(luma_nw, luma_ne), min(luma_sw, luma_se)));
+    let luma_max = max(luma_m, max(max(luma_nw, luma_ne), max(luma_sw, luma_se)));
+
+    var dir = vec2f(
+        -((luma_nw + luma_ne) - (luma_sw + luma_se)),
+        (luma_nw + luma_sw) - (luma_ne + luma_se),
+    );
+    let dir_reduce = max((luma_nw + luma_ne + luma_sw + luma_se) * (0.25 * REDUCE_MUL), REDUCE_MIN);
+    let rcp_dir_min = 1.0 / (min(abs(dir.x), abs(dir.y)) + dir_reduce);
+    dir = clamp(dir * rcp_dir_min, vec2f(-SPAN_MAX), vec2f(SPAN_MAX)) * texel;
+
+    let rgb_a = 0.5 * (sample_rgb(in.uv + dir * (1.0 / 3.0 - 0.5)) + sample_rgb(in.uv + dir * (2.0 / 3.0 - 0.5)));
+    let rgb_b = rgb_a * 0.5 + 0.25 * (sample_rgb(in.uv - dir * 0.5) + sample_rgb(in.uv + dir * 0.5));
+    let luma_b = dot(rgb_b, LUMA);
+    // 混合范围过大时越过了边缘，退回较窄的混合结果
+    if luma_b < luma_min || luma_b > luma_max {
+        return vec4f(rgb_a, center.a);
+    }
+    return vec4f(rgb_b, center.a);
+}
@@ -0,0 +1,363 @@
+use crate::AnyTexture;
+use bytemuck::{Pod, Zeroable};
+
+/// 直方图的 bin 数，需与 `luminance_histogram.wgsl` 一致
+pub const HISTOGRAM_BINS: usize = 256;
+const WORKGROUP_SIZE: u32 = 16;
+
+/// 自动曝光的参数
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AutoExposureParams {
+    /// 直方图覆盖的 log2 亮度下限，亮度低于 `2^min_log_luminance` 的像素计入 bin 0
+    pub min_log_luminance: f32,
+    /// 直方图覆盖的 log2 亮度上限，更亮的像素都计入 bin 255
+    pub max_log_luminance: f32,
+    /// 适应速度（1/秒），越大越快接近目标亮度
+    pub adaptation_speed: f32,
+    /// 平均亮度被映射到的目标值，0.18 即中灰
+    pub key_value: f32,
+}
+
+impl Default for AutoExposureParams {
+    fn default() -> Self {
+        Self {
+            min_log_luminance: -8.0,
+            max_log_luminance: 4.0,
+            adaptation_speed: 1.5,
+            key_value: 0.18,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct HistogramParams {
+    min_log_luminance: f32,
+    log_luminance_range: f32,
+    dt: f32,
+    adaptation_speed: f32,
+    key_value: f32,
+    pixel_count: u32,
+    padding: [u32; 2],
+}
+
+/// 色调映射通道读取的曝光数据，着色器中以 `var<uniform>` 绑定 [`LuminanceHistogram::exposure_buffer`]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct Exposure {
+    /// 适应后的平均亮度
+    pub average_luminance: f32,
+    /// 曝光系数，色调映射前把 HDR 颜色乘以它：`key_value / average_luminance`
+    pub exposure: f32,
+    pub padding: [f32; 2],
+}
+
+/// 基于亮度直方图的自动曝光
+///
+/// 1. `build_histogram`：统计 HDR 输入每个像素的亮度，得到 256 个 bin 的 log 亮度直方图；
+/// 2. `average`：对直方图加权求平均得到场景的平均亮度，再按 `adaptation_speed` 随时间向它靠近，
+///    计算出曝光系数写入 `exposure_buffer`。
+///
+/// bin 的映射：
+/// - bin 0：亮度低于 `2^min_log_luminance` 的过暗像素，不参与平均；
+/// - bin 1 ~ 255：`log2(亮度)` 在 `[min_log_luminance, max_log_luminance]` 内线性映射，超出上限的计入 bin 255。
+///
+/// 与对所有像素的 log 亮度直接求平均相比，直方图只需固定大小的归约，并且可以方便地排除过暗的像素。
+///
+/// # NOTE:
+/// `hdr_input` 需要 `TEXTURE_BINDING`，以 `textureLoad` 读取，格式为可读的浮点格式（如 `Rgba16Float`）。
+/// 整个计算都在 GPU 上完成，曝光系数不需要回读到 CPU。
+pub struct LuminanceHistogram {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    params: AutoExposureParams,
+    params_buf: wgpu::Buffer,
+    histogram_buf: wgpu::Buffer,
+    exposure_buf: wgpu::Buffer,
+    bind_group_layout: wgpu::BindGroupLayout,
+    histogram_pipeline: wgpu::ComputePipeline,
+    average_pipeline: wgpu::ComputePipeline,
+}
+
+#[allow(dead_code)]
+impl LuminanceHistogram {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, params: AutoExposureParams) -> Self {
+        let params_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("luminance histogram params"),
+            size: core::mem::size_of::<HistogramParams>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let histogram_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("luminance histogram"),
+            size: (HISTOGRAM_BINS * 4) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        // 同时作为存储缓冲区（计算通道写入）与 uniform 缓冲区（色调映射通道读取）
+        let exposure_buf = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("auto exposure"),
+            size: core::mem::size_of::<Exposure>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::UNIFORM
+                | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        let buffer_entry = |binding, ty| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let storage = wgpu::BufferBindingType::Storage { read_only: false };
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("luminance histogram"),
+            entries: &[
+                buffer_entry(0, wgpu::BufferBindingType::Uniform),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                buffer_entry(2, storage),
+                buffer_entry(3, storage),
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("luminance histogram"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("luminance_histogram.wgsl").into()),
+        });
+        let create_pipeline = |entry_point: &str| {
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(entry_point),
+                layout: Some(&pipeline_layout),
+                module: &shader,
+                entry_point: Some(entry_point),
+                compilation_options: Default::default(),
+                cache: None,
+            })
+        };
+
+        Self {
+            device: device.clone(),
+            queue: queue.clone(),
+            params,
+            params_buf,
+            histogram_buf,
+            exposure_buf,
+            bind_group_layout,
+            histogram_pipeline: create_pipeline("build_histogram"),
+            average_pipeline: create_pipeline("average"),
+        }
+    }
+
+    pub fn params(&self) -> AutoExposureParams {
+        self.params
+    }
+
+    pub fn set_params(&mut self, params: AutoExposureParams) {
+        self.params = params;
+    }
+
+    /// 存放 `Exposure` 的缓冲区，带有 `UNIFORM` 用途，可直接绑定到色调映射通道
+    pub fn exposure_buffer(&self) -> &wgpu::Buffer {
+        &self.exposure_buf
+    }
+
+    /// 本帧的直方图，`HISTOGRAM_BINS` 个 `u32`，带有 `COPY_SRC` 用途以便调试时回读
+    pub fn histogram_buffer(&self) -> &wgpu::Buffer {
+        &self.histogram_buf
+    }
+
+    /// 录制统计直方图与计算曝光的计算通道，`dt` 为距上一帧的时间（秒）
+    pub fn run(&self, encoder: &mut wgpu::CommandEncoder, hdr_input: &AnyTexture, dt: f32) {
+        let size = hdr_input.size;
+        let params = HistogramParams {
+            min_log_luminance: self.params.min_log_luminance,
+            log_luminance_range: log_luminance_range(&self.params),
+            dt,
+            adaptation_speed: self.params.adaptation_speed,
+            key_value: self.params.key_value,
+            pixel_count: size.width * size.height,
+            padding: [0; 2],
+        };
+        self.queue
+            .write_buffer(&self.params_buf, 0, bytemuck::bytes_of(&params));
+
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("luminance histogram"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: self.params_buf.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&hdr_input.tex_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.histogram_buf.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: self.exposure_buf.as_entire_binding(),
+                },
+            ],
+        });
+
+        encoder.clear_buffer(&self.histogram_buf, 0, None);
+        let mut cpass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("luminance histogram"),
+            timestamp_writes: None,
+        });
+        cpass.set_bind_group(0, &bind_group, &[]);
+        cpass.set_pipeline(&self.histogram_pipeline);
+        cpass.dispatch_workgroups(
+            size.width.div_ceil(WORKGROUP_SIZE),
+            size.height.div_ceil(WORKGROUP_SIZE),
+            1,
+        );
+        cpass.set_pipeline(&self.average_pipeline);
+        cpass.dispatch_workgroups(1, 1, 1);
+    }
+}
+
+fn log_luminance_range(params: &AutoExposureParams) -> f32 {
+    (params.max_log_luminance - params.min_log_luminance).max(1e-4)
+}
+
+/// 与 `luminance_histogram.wgsl` 中相同的 bin 映射
+pub fn luminance_bin(color: [f32; 3], params: &AutoExposureParams) -> usize {
+    let luminance = 0.2126 * color[0] + 0.7152 * color[1] + 0.0722 * color[2];
+    if luminance < params.min_log_luminance.exp2() {
+        return 0;
+    }
+    let t = ((luminance.log2() - params.min_log_luminance) / log_luminance_range(params))
+        .clamp(0.0, 1.0);
+    (t * 254.0 + 1.0) as usize
+}
+
+/// 在 CPU 上统计直方图，与 GPU 的结果一致，用于测试与调试
+pub fn luminance_histogram_cpu(
+    pixels: &[[f32; 3]],
+    params: &AutoExposureParams,
+) -> [u32; HISTOGRAM_BINS] {
+    let mut bins = [0; HISTOGRAM_BINS];
+    for color in pixels {
+        bins[luminance_bin(*color, params)] += 1;
+    }
+    bins
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn uniform_luminance_fills_one_bin() {
+        let params = AutoExposureParams::default();
+        // 亮度为 1，log2 为 0，位于 [-8, 4] 的 2/3 处
+        let pixels = vec![[1.0_f32; 3]; 64 * 64];
+        let bins = luminance_histogram_cpu(&pixels, &params);
+        let bin = luminance_bin([1.0; 3], &params);
+        assert_eq!(bin, 170);
+        assert_eq!(bins[bin], 64 * 64);
+        assert_eq!(bins.iter().sum::<u32>(), 64 * 64);
+
+        assert_eq!(luminance_bin([0.0; 3], &params), 0);
+        assert_eq!(luminance_bin([1000.0; 3], &params), 255);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn gpu_histogram_of_uniform_image() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        let size = wgpu::Extent3d {
+            width: 40,
+            height: 24,
+            depth_or_array_layers: 1,
+        };
+        let input = crate::load_texture::empty(
+            &device,
+            wgpu::TextureFormat::Rgba32Float,
+            size,
+            None,
+            wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            None,
+        );
+        let texels = vec![[1.0_f32, 1.0, 1.0, 1.0]; (size.width * size.height) as usize];
+        queue.write_texture(
+            input.tex.as_image_copy(),
+            bytemuck::cast_slice(&texels),
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(size.width * 16),
+                rows_per_image: None,
+            },
+            size,
+        );
+
+        let params = AutoExposureParams::default();
+        let histogram = LuminanceHistogram::new(&device, &queue, params);
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: (HISTOGRAM_BINS * 4 + 16) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&Default::default());
+        histogram.run(&mut encoder, &input, 1.0 / 60.0);
+        let histogram_size = (HISTOGRAM_BINS * 4) as wgpu::BufferAddress;
+        encoder.copy_buffer_to_buffer(
+            histogram.histogram_buffer(),
+            0,
+            &readback,
+            0,
+            histogram_size,
+        );
+        encoder.copy_buffer_to_buffer(
+            histogram.exposure_buffer(),
+            0,
+            &readback,
+            histogram_size,
+            16,
+        );
+        queue.submit(Some(encoder.finish()));
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        crate::poll::poll_wait(&device);
+        let bytes = slice.get_mapped_range().to_vec();
+        let bins: &[u32] = bytemuck::cast_slice(&bytes[..histogram_size as usize]);
+        let exposure: &Exposure = bytemuck::from_bytes(&bytes[histogram_size as usize..]);
+
+        // 所有像素都落在同一个 bin
+        let bin = luminance_bin([1.0; 3], &params);
+        assert_eq!(bins[bin], size.width * size.height);
+        assert_eq!(bins.iter().sum::<u32>(), size.width * size.height);
+        // 首帧直接取目标亮度；bin 的量化误差小于一个 bin 的宽度
+        assert!((exposure.average_luminance.log2()).abs() < 12.0 / 254.0);
+    }
+}
@@ -0,0 +1,85 @@
+struct HistogramParams {
+    min_log_luminance: f32,
+    log_luminance_range: f32,
+    // 本帧的时间间隔（秒）
+    dt: f32,
+    adaptation_speed: f32,
+    key_value: f32,
+    pixel_count: u32,
+    padding: vec2<u32>,
+};
+
+struct Exposure {
+    average_luminance: f32,
+    exposure: f32,
+    padding: vec2f,
+};
+
+@group(0) @binding(0) var<uniform> params: HistogramParams;
+@group(0) @binding(1) var hdr_input: texture_2d<f32>;
+@group(0) @binding(2) var<storage, read_write> histogram: array<atomic<u32>, 256>;
+@group(0) @binding(3) var<storage, read_write> exposure: Exposure;
+
+var<workgroup> local_bins: array<atomic<u32>, 256>;
+var<workgroup> weighted: array<f32, 256>;
+
+// 与 Rust 端 luminance_bin 一致：bin 0 存放过暗的像素，其余按 log2 亮度线性映射到 [1, 255]
+fn luminance_bin(color: vec3f) -> u32 {
+    let luminance = dot(color, vec3f(0.2126, 0.7152, 0.0722));
+    if luminance < exp2(params.min_log_luminance) {
+        return 0u;
+    }
+    let t = clamp((log2(luminance) - params.min_log_luminance) / params.log_luminance_range, 0.0, 1.0);
+    return u32(t * 254.0 + 1.0);
+}
+
+@compute @workgroup_size(16, 16)
+fn build_histogram(
+    @builtin(global_invocation_id) gid: vec3u,
+    @builtin(local_invocation_index) index: u32,
+) {
+    atomicStore(&local_bins[index], 0u);
+    workgroupBarrier();
+
+    let size = textureDimensions(hdr_input);
+    if gid.x < size.x && gid.y < size.y {
+        let color = textureLoad(hdr_input, vec2i(gid.xy), 0).rgb;
+        atomicAdd(&local_bins[luminance_bin(color)], 1u);
+    }
+    workgroupBarrier();
+
+    // 先在工作组内累计，再合并到全局直方图，减少对同一全局地址的原子操作冲突
+    atomicAdd(&histogram[index], atomicLoad(&local_bins[index]));
+}
+
+@compute @workgroup_size(256)
+fn average(@builtin(local_invocation_index) index: u32) {
+    let count = atomicLoad(&histogram[index]);
+    weighted[index] = f32(count) * f32(index);
+    workgroupBarrier();
+
+    for (var stride = 128u; stride > 0u; stride >>= 1u) {
+        if index < stride {
+            weighted[index] += weighted[index + stride];
+        }
+        workgroupBarrier();
+    }
+
+    if index == 0u {
+        // bin 0 中过暗的像素不参与平均，否则大片黑色背景会让画面被过度提亮
+        let valid = max(f32(params.pixel_count) - f32(count), 1.0);
+        let average_bin = max(weighted[0] / valid - 1.0, 0.0);
+        let average_log = average_bin / 254.0 * params.log_luminance_range + params.min_log_luminance;
+        let target_luminance = exp2(average_log);
+
+        // 指数衰减地向目标亮度靠近，模拟人眼的明暗适应；首帧直接取目标值
+        let previous = exposure.average_luminance;
+        let adapted = select(
+            previous + (target_luminance - previous) * (1.0 - exp(-params.dt * params.adaptation_speed)),
+            target_luminance,
+            previous <= 0.0,
+        );
+        exposure.average_luminance = adapted;
+        exposure.exposure = params.key_value / max(adapted, 1e-4);
+    }
+}
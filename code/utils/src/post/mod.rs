@@ -0,0 +1,14 @@
+mod anti_aliasing;
+pub use anti_aliasing::{AntiAliasing, AntiAliasingTargets};
+
+mod bloom;
+pub use bloom::{Bloom, BloomParams, bloom_level_sizes, bright_pass};
+
+mod luminance_histogram;
+pub use luminance_histogram::{
+    AutoExposureParams, Exposure, HISTOGRAM_BINS, LuminanceHistogram, luminance_bin,
+    luminance_histogram_cpu,
+};
+
+mod depth_of_field;
+pub use depth_of_field::{DepthOfField, DofParams, circle_of_confusion, linearize_depth};
@@ -0,0 +1,60 @@
+//! surface 的呈现模式（垂直同步）
+//!
+//! - `Fifo`：垂直同步，所有平台都支持，也是 `AppSurface` 的默认值；
+//! - `Mailbox`：不限帧率地渲染，只呈现最新的一帧，延迟低且没有画面撕裂；
+//! - `Immediate`：不等待垂直同步立即呈现，延迟最低但可能出现画面撕裂。
+//!
+//! # NOTE:
+//! Web 平台只支持 `Fifo`，由浏览器的 `requestAnimationFrame` 决定帧率。
+
+use app_surface::AppSurface;
+use wgpu::PresentMode;
+
+/// 从 surface 支持的呈现模式中选择：支持 `requested` 时使用它，否则打印警告并回退到 `Fifo`
+pub fn select_present_mode(requested: PresentMode, supported: &[PresentMode]) -> PresentMode {
+    // Auto* 模式由 wgpu 在配置时自行回退，总是可用
+    if matches!(requested, PresentMode::AutoVsync | PresentMode::AutoNoVsync)
+        || supported.contains(&requested)
+    {
+        return requested;
+    }
+    log::warn!(
+        "surface does not support present mode {requested:?} (supported: {supported:?}), fall back to Fifo"
+    );
+    PresentMode::Fifo
+}
+
+/// 按 surface 的能力选择并应用呈现模式，返回实际使用的模式
+///
+/// 通常由 [`crate::framework::create_app_surface`] 在创建 surface 时调用。
+pub fn configure_present_mode(app: &mut AppSurface, requested: PresentMode) -> PresentMode {
+    let caps = app.surface.get_capabilities(&app.adapter);
+    let present_mode = select_present_mode(requested, &caps.present_modes);
+    if app.config.present_mode != present_mode {
+        app.config.present_mode = present_mode;
+        app.surface.configure(&app.device, &app.config);
+    }
+    present_mode
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn select_from_caps() {
+        let caps = [PresentMode::Fifo, PresentMode::Mailbox];
+        assert_eq!(
+            select_present_mode(PresentMode::Mailbox, &caps),
+            PresentMode::Mailbox
+        );
+        assert_eq!(
+            select_present_mode(PresentMode::Immediate, &caps),
+            PresentMode::Fifo
+        );
+        assert_eq!(
+            select_present_mode(PresentMode::AutoNoVsync, &caps),
+            PresentMode::AutoNoVsync
+        );
+    }
+}
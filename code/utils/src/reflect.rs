@@ -0,0 +1,319 @@
+//! 通过 naga 反射 WGSL 着色器中的资源绑定，生成对应的 `BindGroupLayout`
+//!
+//! 省去手写 `BindGroupLayoutEntry`（如 tutorial6 中的相机 uniform）的麻烦，
+//! 绑定类型、可见性（使用该绑定的入口点的着色阶段）与缓冲区的最小绑定尺寸都由着色器推断。
+//!
+//! # NOTE:
+//! 以下情况无法仅从 WGSL 推断，会返回 [`ReflectError::Ambiguous`]，需要手写布局：
+//! - 存储纹理的格式没有对应的 `wgpu::TextureFormat` 映射；
+//! - 着色器中声明了但没有任何入口点使用的绑定（可见性未知）。
+//!
+//! 另有两种情况按最常见的用法推断：`texture_2d<f32>` 推断为可过滤，`sampler` 推断为 `Filtering`；
+//! 搭配 `R32Float` 等不可过滤格式时，可先用 [`layout_entries_from_wgsl`] 取得布局项，修改后再自行创建布局。
+
+use naga::{AddressSpace, ImageClass, ImageDimension, ScalarKind, StorageAccess, TypeInner};
+
+/// 反射失败的原因
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReflectError {
+    /// WGSL 解析失败
+    Parse(String),
+    /// 着色器模块验证失败
+    Validation(String),
+    /// 绑定无法推断，需要手写布局
+    Ambiguous { binding: u32, reason: &'static str },
+}
+
+impl core::fmt::Display for ReflectError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ReflectError::Parse(e) => write!(f, "failed to parse wgsl: {e}"),
+            ReflectError::Validation(e) => write!(f, "invalid shader module: {e}"),
+            ReflectError::Ambiguous { binding, reason } => {
+                write!(f, "binding {binding} can not be reflected: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReflectError {}
+
+/// 由 WGSL 源码中 `@group(group_index)` 的所有绑定创建 `BindGroupLayout`
+pub fn layout_from_wgsl(
+    device: &wgpu::Device,
+    wgsl_src: &str,
+    group_index: u32,
+) -> Result<wgpu::BindGroupLayout, ReflectError> {
+    let entries = layout_entries_from_wgsl(wgsl_src, group_index)?;
+    Ok(
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("reflected bind group layout"),
+            entries: &entries,
+        }),
+    )
+}
+
+/// 反射 WGSL 源码中 `@group(group_index)` 的所有绑定，按绑定号升序返回布局项
+pub fn layout_entries_from_wgsl(
+    wgsl_src: &str,
+    group_index: u32,
+) -> Result<Vec<wgpu::BindGroupLayoutEntry>, ReflectError> {
+    let module = naga::front::wgsl::parse_str(wgsl_src)
+        .map_err(|e| ReflectError::Parse(e.emit_to_string(wgsl_src)))?;
+    let info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(&module)
+    .map_err(|e| ReflectError::Validation(e.emit_to_string(wgsl_src)))?;
+
+    let mut entries = vec![];
+    for (handle, var) in module.global_variables.iter() {
+        let Some(binding) = var.binding.as_ref() else {
+            continue;
+        };
+        if binding.group != group_index {
+            continue;
+        }
+        // 使用了该绑定的入口点（包括被其调用的函数）决定可见性
+        let mut visibility = wgpu::ShaderStages::NONE;
+        for (index, entry_point) in module.entry_points.iter().enumerate() {
+            if !info.get_entry_point(index)[handle].is_empty() {
+                visibility |= shader_stage(entry_point.stage);
+            }
+        }
+        if visibility.is_empty() {
+            return Err(ReflectError::Ambiguous {
+                binding: binding.binding,
+                reason: "not used by any entry point",
+            });
+        }
+
+        let inner = &module.types[var.ty].inner;
+        let ty = match var.space {
+            AddressSpace::Uniform => wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: wgpu::BufferSize::new(inner.size(module.to_ctx()) as u64),
+            },
+            AddressSpace::Storage { access } => wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage {
+                    read_only: !access.contains(StorageAccess::STORE),
+                },
+                has_dynamic_offset: false,
+                // 末尾为运行时数组时，尺寸包含一个数组元素
+                min_binding_size: wgpu::BufferSize::new(inner.size(module.to_ctx()) as u64),
+            },
+            AddressSpace::Handle => handle_binding_type(inner, binding.binding)?,
+            _ => {
+                return Err(ReflectError::Ambiguous {
+                    binding: binding.binding,
+                    reason: "unsupported address space",
+                });
+            }
+        };
+        entries.push(wgpu::BindGroupLayoutEntry {
+            binding: binding.binding,
+            visibility,
+            ty,
+            count: None,
+        });
+    }
+    entries.sort_by_key(|entry| entry.binding);
+    Ok(entries)
+}
+
+/// WGSL 源码中片元入口点 `entry_point` 输出的颜色附件数，即最大的 `@location` + 1
+///
+/// 用于检查渲染管线的颜色目标（`targets`）数是否与片元着色器的输出一致，见 `ViewNodeBuilder::with_shader_source`。
+pub fn fragment_output_count(wgsl_src: &str, entry_point: &str) -> Result<u32, ReflectError> {
+    let module = naga::front::wgsl::parse_str(wgsl_src)
+        .map_err(|e| ReflectError::Parse(e.emit_to_string(wgsl_src)))?;
+    let Some(entry) = module
+        .entry_points
+        .iter()
+        .find(|ep| ep.stage == naga::ShaderStage::Fragment && ep.name == entry_point)
+    else {
+        return Err(ReflectError::Validation(format!(
+            "fragment entry point `{entry_point}` not found"
+        )));
+    };
+    let Some(result) = entry.function.result.as_ref() else {
+        return Ok(0);
+    };
+    let location = |binding: &Option<naga::Binding>| match binding {
+        Some(naga::Binding::Location { location, .. }) => Some(*location),
+        _ => None,
+    };
+    let max_location = match &module.types[result.ty].inner {
+        TypeInner::Struct { members, .. } if result.binding.is_none() => {
+            members.iter().filter_map(|m| location(&m.binding)).max()
+        }
+        _ => location(&result.binding),
+    };
+    Ok(max_location.map_or(0, |l| l + 1))
+}
+
+fn shader_stage(stage: naga::ShaderStage) -> wgpu::ShaderStages {
+    match stage {
+        naga::ShaderStage::Vertex => wgpu::ShaderStages::VERTEX,
+        naga::ShaderStage::Fragment => wgpu::ShaderStages::FRAGMENT,
+        naga::ShaderStage::Compute => wgpu::ShaderStages::COMPUTE,
+    }
+}
+
+// 纹理与采样器
+fn handle_binding_type(inner: &TypeInner, binding: u32) -> Result<wgpu::BindingType, ReflectError> {
+    let ty = match *inner {
+        TypeInner::Sampler { comparison: true } => {
+            wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison)
+        }
+        TypeInner::Sampler { comparison: false } => {
+            wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering)
+        }
+        TypeInner::Image {
+            dim,
+            arrayed,
+            class,
+        } => {
+            let view_dimension = view_dimension(dim, arrayed);
+            match class {
+                ImageClass::Sampled { kind, multi } => wgpu::BindingType::Texture {
+                    sample_type: match kind {
+                        ScalarKind::Sint => wgpu::TextureSampleType::Sint,
+                        ScalarKind::Uint => wgpu::TextureSampleType::Uint,
+                        _ => wgpu::TextureSampleType::Float { filterable: !multi },
+                    },
+                    view_dimension,
+                    multisampled: multi,
+                },
+                ImageClass::Depth { multi } => wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Depth,
+                    view_dimension,
+                    multisampled: multi,
+                },
+                ImageClass::Storage { format, access } => wgpu::BindingType::StorageTexture {
+                    access: if access.contains(StorageAccess::LOAD | StorageAccess::STORE) {
+                        wgpu::StorageTextureAccess::ReadWrite
+                    } else if access.contains(StorageAccess::STORE) {
+                        wgpu::StorageTextureAccess::WriteOnly
+                    } else {
+                        wgpu::StorageTextureAccess::ReadOnly
+                    },
+                    format: storage_format(format).ok_or(ReflectError::Ambiguous {
+                        binding,
+                        reason: "unmapped storage texture format",
+                    })?,
+                    view_dimension,
+                },
+            }
+        }
+        _ => {
+            return Err(ReflectError::Ambiguous {
+                binding,
+                reason: "unsupported handle type",
+            });
+        }
+    };
+    Ok(ty)
+}
+
+fn view_dimension(dim: ImageDimension, arrayed: bool) -> wgpu::TextureViewDimension {
+    match (dim, arrayed) {
+        (ImageDimension::D1, _) => wgpu::TextureViewDimension::D1,
+        (ImageDimension::D2, false) => wgpu::TextureViewDimension::D2,
+        (ImageDimension::D2, true) => wgpu::TextureViewDimension::D2Array,
+        (ImageDimension::D3, _) => wgpu::TextureViewDimension::D3,
+        (ImageDimension::Cube, false) => wgpu::TextureViewDimension::Cube,
+        (ImageDimension::Cube, true) => wgpu::TextureViewDimension::CubeArray,
+    }
+}
+
+// 只映射示例中用到的常见存储纹理格式
+fn storage_format(format: naga::StorageFormat) -> Option<wgpu::TextureFormat> {
+    use naga::StorageFormat as S;
+    use wgpu::TextureFormat as T;
+    Some(match format {
+        S::R32Float => T::R32Float,
+        S::R32Uint => T::R32Uint,
+        S::R32Sint => T::R32Sint,
+        S::Rg32Float => T::Rg32Float,
+        S::Rgba8Unorm => T::Rgba8Unorm,
+        S::Rgba8Snorm => T::Rgba8Snorm,
+        S::Rgba8Uint => T::Rgba8Uint,
+        S::Rgba16Float => T::Rgba16Float,
+        S::Rgba32Float => T::Rgba32Float,
+        S::Rgba32Uint => T::Rgba32Uint,
+        S::Bgra8Unorm => T::Bgra8Unorm,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reflect_tutorial6_camera_uniform() {
+        let wgsl = include_str!("../../beginner/tutorial6-uniforms/src/shader.wgsl");
+        let entries = layout_entries_from_wgsl(wgsl, 1).unwrap();
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[0];
+        assert_eq!(entry.binding, 0);
+        // 相机 uniform 只在顶点着色器中使用
+        assert_eq!(entry.visibility, wgpu::ShaderStages::VERTEX);
+        assert_eq!(
+            entry.ty,
+            wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                // mat4x4f
+                min_binding_size: wgpu::BufferSize::new(64),
+            }
+        );
+
+        // group 0 为纹理与采样器
+        let entries = layout_entries_from_wgsl(wgsl, 0).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].visibility, wgpu::ShaderStages::FRAGMENT);
+        assert!(matches!(
+            entries[1].ty,
+            wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering)
+        ));
+    }
+
+    #[test]
+    fn gbuffer_fragment_outputs() {
+        let src = "
+            struct GBuffer {
+                @location(0) albedo: vec4f,
+                @location(1) normal: vec4f,
+                @location(2) position: vec4f,
+            };
+            @fragment fn fs_main() -> GBuffer {
+                return GBuffer(vec4f(1.0), vec4f(0.5), vec4f(0.0));
+            }
+            @fragment fn fs_single() -> @location(0) vec4f {
+                return vec4f(1.0);
+            }
+        ";
+        assert_eq!(fragment_output_count(src, "fs_main"), Ok(3));
+        assert_eq!(fragment_output_count(src, "fs_single"), Ok(1));
+        assert!(fragment_output_count(src, "fs_missing").is_err());
+    }
+
+    #[test]
+    fn unused_binding_is_ambiguous() {
+        let wgsl = "
+            @group(0) @binding(0) var<uniform> unused: vec4f;
+            @compute @workgroup_size(1) fn main() {}
+        ";
+        assert_eq!(
+            layout_entries_from_wgsl(wgsl, 0),
+            Err(ReflectError::Ambiguous {
+                binding: 0,
+                reason: "not used by any entry point",
+            })
+        );
+    }
+}
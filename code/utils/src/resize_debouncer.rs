@@ -0,0 +1,101 @@
+use winit::dpi::PhysicalSize;
+
+/// 窗口大小变化的去抖动记录器
+///
+/// # NOTE:
+/// 当缩放浏览器窗口时, 窗口大小会以高于渲染帧率的频率发生变化，
+/// 如果窗口 size 发生变化就立即调整 surface 大小, 会导致缩放浏览器窗口大小时渲染画面闪烁。
+/// 所以 `set_window_resized` 里只记录最新的尺寸，在 `render` 中通过 `take_if_changed` 每帧最多应用一次。
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ResizeDebouncer {
+    pending: Option<PhysicalSize<u32>>,
+}
+
+impl ResizeDebouncer {
+    pub fn new() -> Self {
+        Self { pending: None }
+    }
+
+    /// 创建时就带有一个待应用的尺寸，用于需要在第一帧执行 resize 逻辑的场景
+    pub fn with_pending(size: PhysicalSize<u32>) -> Self {
+        Self {
+            pending: Some(size),
+        }
+    }
+
+    /// 记录新的窗口尺寸
+    ///
+    /// `current` 为 surface 当前的尺寸：如果新尺寸与其相同，则取消之前记录的待应用尺寸。
+    /// 宽或高为 0（窗口最小化）的尺寸会被忽略。
+    ///
+    /// 两个尺寸也可以是 `[width, height]` 或 `(width, height)`，供 web 端等不依赖 winit 的调用方使用。
+    pub fn record(
+        &mut self,
+        new_size: impl Into<PhysicalSize<u32>>,
+        current: impl Into<PhysicalSize<u32>>,
+    ) {
+        let (new_size, current) = (new_size.into(), current.into());
+        if new_size.width == 0 || new_size.height == 0 {
+            return;
+        }
+        self.pending = if new_size == current {
+            None
+        } else {
+            Some(new_size)
+        };
+    }
+
+    /// 是否有待应用的尺寸
+    pub fn has_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// 取出最新记录的尺寸，没有变化时返回 `None`
+    pub fn take_if_changed(&mut self) -> Option<PhysicalSize<u32>> {
+        self.pending.take()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn only_final_size_is_taken() {
+        let current = PhysicalSize::new(800, 600);
+        let mut debouncer = ResizeDebouncer::new();
+        assert_eq!(debouncer.take_if_changed(), None);
+
+        debouncer.record(PhysicalSize::new(801, 600), current);
+        debouncer.record(PhysicalSize::new(900, 650), current);
+        debouncer.record(PhysicalSize::new(1024, 768), current);
+        assert_eq!(
+            debouncer.take_if_changed(),
+            Some(PhysicalSize::new(1024, 768))
+        );
+        assert_eq!(debouncer.take_if_changed(), None);
+    }
+
+    #[test]
+    fn back_to_current_size_cancels_pending() {
+        let current = PhysicalSize::new(800, 600);
+        let mut debouncer = ResizeDebouncer::new();
+        debouncer.record(PhysicalSize::new(900, 650), current);
+        debouncer.record(PhysicalSize::new(0, 0), current);
+        assert!(debouncer.has_pending());
+        debouncer.record(current, current);
+        assert_eq!(debouncer.take_if_changed(), None);
+    }
+
+    #[test]
+    fn record_from_arrays() {
+        let mut debouncer = ResizeDebouncer::new();
+        debouncer.record([1024_u32, 768], [800_u32, 600]);
+        assert_eq!(
+            debouncer.take_if_changed(),
+            Some(PhysicalSize::new(1024, 768))
+        );
+        debouncer.record([800_u32, 600], [800_u32, 600]);
+        assert!(!debouncer.has_pending());
+    }
+}
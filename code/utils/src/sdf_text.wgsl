@@ -0,0 +1,61 @@
+struct Camera {
+    view_proj: mat4x4f,
+};
+
+struct SdfStyle {
+    outline_color: vec4f,
+    glow_color: vec4f,
+    // 以距离场取值为单位的描边宽度与发光半径，0 表示关闭
+    outline_width: f32,
+    glow_width: f32,
+    padding: vec2f,
+};
+
+@group(0) @binding(0) var<uniform> camera: Camera;
+@group(0) @binding(1) var<uniform> style: SdfStyle;
+@group(0) @binding(2) var atlas: texture_2d<f32>;
+@group(0) @binding(3) var atlas_sampler: sampler;
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) uv: vec2f,
+    @location(1) color: vec4f,
+};
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex_index: u32,
+    @location(0) rect: vec4f,
+    @location(1) uv_rect: vec4f,
+    @location(2) color: vec4f,
+) -> VertexOutput {
+    let corner = vec2f(f32(vertex_index & 1u), f32(vertex_index >> 1u));
+    var out: VertexOutput;
+    out.position = camera.view_proj * vec4f(rect.xy + corner * rect.zw, 0.0, 1.0);
+    out.uv = uv_rect.xy + corner * uv_rect.zw;
+    out.color = color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4f {
+    let distance = textureSample(atlas, atlas_sampler, in.uv).r;
+    // 过渡宽度取一个屏幕像素内距离场的变化量，任意缩放下边缘都只有约 1 像素的抗锯齿
+    let w = max(fwidth(distance), 1e-4);
+    let fill = smoothstep(0.5 - w, 0.5 + w, distance);
+
+    var color = vec4f(in.color.rgb, in.color.a * fill);
+    if style.outline_width > 0.0 {
+        let edge = 0.5 - style.outline_width;
+        let outline = smoothstep(edge - w, edge + w, distance);
+        let rgb = mix(style.outline_color.rgb, in.color.rgb, fill);
+        color = vec4f(rgb, mix(style.outline_color.a * outline, in.color.a, fill));
+    }
+    if style.glow_width > 0.0 {
+        let glow = smoothstep(0.5 - style.outline_width - style.glow_width, 0.5, distance);
+        let glow_a = style.glow_color.a * glow * (1.0 - color.a);
+        let a = color.a + glow_a;
+        color = vec4f((color.rgb * color.a + style.glow_color.rgb * glow_a) / max(a, 1e-4), a);
+    }
+    return color;
+}
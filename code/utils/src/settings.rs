@@ -0,0 +1,206 @@
+//! 示例程序在多次运行之间保留的设置
+//!
+//! 保留的字段见 [`AppSettings`]。`run` 在创建窗口前加载，退出事件循环时保存：
+//! - 原生平台保存为平台配置目录下的 `learn-wgpu/<标题>.toml`
+//!   （Linux 为 `$XDG_CONFIG_HOME` 或 `~/.config`，macOS 为 `~/Library/Application Support`，Windows 为 `%APPDATA%`）；
+//! - Web 平台保存在 `localStorage` 的 `learn-wgpu/<标题>` 键下。关闭网页不会触发事件循环的退出，
+//!   所以框架在按 F2 切换抗锯齿后也会保存一次；app 需要在其它时机保存时可自行调用 [`AppSettings::save`]。
+//!
+//! 文件不存在或内容无效时使用默认值（所有字段都为 `None`），不会让示例程序启动失败。
+//!
+//! # NOTE:
+//! 读写需启用 `serde` 特性；未启用时 [`AppSettings::load`] 总是返回默认值，[`AppSettings::save`] 不做任何事，
+//! `save_settings`/`restore_settings` 仍会被调用，只是设置不会保留到下次运行。
+
+use crate::post::AntiAliasing;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// 相机的位置与朝向
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CameraSettings {
+    pub eye: [f32; 3],
+    pub target: [f32; 3],
+}
+
+/// 在多次运行之间保留的设置，为 `None` 的字段不会覆盖程序的初始值
+///
+/// - `window_position`、`window_size`：窗口的外框位置与内部尺寸（物理像素），只在原生平台上恢复；
+/// - `anti_aliasing`：抗锯齿方式，只对 `WgpuAppAction::anti_aliasing` 返回 `Some` 的 app 生效；
+/// - `present_mode`：由框架写入，下次运行时作为 `RunConfig::present_mode` 的默认值；
+/// - `camera`：框架不知道它如何应用，由 app 在 `WgpuAppAction::save_settings` 中写入、
+///   在 `WgpuAppAction::restore_settings` 中读取。
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
+pub struct AppSettings {
+    pub window_position: Option<[i32; 2]>,
+    pub window_size: Option<[u32; 2]>,
+    pub anti_aliasing: Option<AntiAliasing>,
+    present_mode: Option<String>,
+    pub camera: Option<CameraSettings>,
+}
+
+impl AppSettings {
+    pub fn present_mode(&self) -> Option<wgpu::PresentMode> {
+        match self.present_mode.as_deref()? {
+            "AutoVsync" => Some(wgpu::PresentMode::AutoVsync),
+            "AutoNoVsync" => Some(wgpu::PresentMode::AutoNoVsync),
+            "Fifo" => Some(wgpu::PresentMode::Fifo),
+            "FifoRelaxed" => Some(wgpu::PresentMode::FifoRelaxed),
+            "Immediate" => Some(wgpu::PresentMode::Immediate),
+            "Mailbox" => Some(wgpu::PresentMode::Mailbox),
+            _ => None,
+        }
+    }
+
+    pub fn set_present_mode(&mut self, mode: wgpu::PresentMode) {
+        self.present_mode = Some(format!("{mode:?}"));
+    }
+
+    /// 解析 TOML 文本，解析失败时打印警告并返回默认值
+    #[cfg(feature = "serde")]
+    pub fn from_toml_str(source: &str) -> Self {
+        toml::from_str(source).unwrap_or_else(|e| {
+            log::warn!("invalid settings, fall back to defaults: {e}");
+            Self::default()
+        })
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn to_toml_string(&self) -> String {
+        toml::to_string(self).expect("AppSettings serialize failed")
+    }
+
+    /// 加载 `title` 对应的设置
+    #[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+    pub fn load(title: &str) -> Self {
+        let Some(path) = settings_path(title) else {
+            return Self::default();
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(source) => Self::from_toml_str(&source),
+            // 首次运行时文件不存在
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// 保存 `title` 对应的设置，失败时只打印警告
+    #[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+    pub fn save(&self, title: &str) {
+        let Some(path) = settings_path(title) else {
+            return;
+        };
+        let result = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&path, self.to_toml_string()));
+        if let Err(e) = result {
+            log::warn!("cannot save settings to {}: {e}", path.display());
+        }
+    }
+
+    /// 加载 `title` 对应的设置
+    #[cfg(all(feature = "serde", target_arch = "wasm32"))]
+    pub fn load(title: &str) -> Self {
+        local_storage()
+            .and_then(|storage| storage.get_item(&storage_key(title)).ok().flatten())
+            .map_or_else(Self::default, |source| Self::from_toml_str(&source))
+    }
+
+    /// 保存 `title` 对应的设置，失败时只打印警告
+    #[cfg(all(feature = "serde", target_arch = "wasm32"))]
+    pub fn save(&self, title: &str) {
+        let saved = local_storage().is_some_and(|storage| {
+            storage
+                .set_item(&storage_key(title), &self.to_toml_string())
+                .is_ok()
+        });
+        if !saved {
+            log::warn!("cannot save settings to localStorage");
+        }
+    }
+
+    /// 未启用 `serde` 特性时不读取任何设置
+    #[cfg(not(feature = "serde"))]
+    pub fn load(_title: &str) -> Self {
+        Self::default()
+    }
+
+    /// 未启用 `serde` 特性时不保存
+    #[cfg(not(feature = "serde"))]
+    pub fn save(&self, _title: &str) {}
+}
+
+/// 标题中除字母、数字外的字符替换为 `-`，用作文件名或存储键
+#[cfg(feature = "serde")]
+fn settings_name(title: &str) -> String {
+    title
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+#[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+fn settings_path(title: &str) -> Option<std::path::PathBuf> {
+    let env_dir = |key| std::env::var_os(key).map(std::path::PathBuf::from);
+    let config_dir = if cfg!(target_os = "windows") {
+        env_dir("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        env_dir("HOME").map(|home| home.join("Library/Application Support"))
+    } else {
+        env_dir("XDG_CONFIG_HOME").or_else(|| env_dir("HOME").map(|home| home.join(".config")))
+    }?;
+    Some(
+        config_dir
+            .join("learn-wgpu")
+            .join(settings_name(title) + ".toml"),
+    )
+}
+
+#[cfg(all(feature = "serde", target_arch = "wasm32"))]
+fn storage_key(title: &str) -> String {
+    format!("learn-wgpu/{}", settings_name(title))
+}
+
+#[cfg(all(feature = "serde", target_arch = "wasm32"))]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn serde_round_trip() {
+        let mut settings = AppSettings {
+            window_position: Some([120, -40]),
+            window_size: Some([1280, 720]),
+            anti_aliasing: Some(AntiAliasing::Msaa(4)),
+            present_mode: None,
+            camera: Some(CameraSettings {
+                eye: [0.0, 2.0, 5.0],
+                target: [0.0; 3],
+            }),
+        };
+        settings.set_present_mode(wgpu::PresentMode::Mailbox);
+        let restored = AppSettings::from_toml_str(&settings.to_toml_string());
+        assert_eq!(restored, settings);
+        assert_eq!(restored.present_mode(), Some(wgpu::PresentMode::Mailbox));
+
+        // 缺失的字段与损坏的内容都使用默认值
+        assert_eq!(
+            AppSettings::from_toml_str("anti_aliasing = \"Fxaa\""),
+            AppSettings {
+                anti_aliasing: Some(AntiAliasing::Fxaa),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            AppSettings::from_toml_str("window_size = [1280"),
+            AppSettings::default()
+        );
+        assert_eq!(settings_name("Hilbert Curve: 2"), "Hilbert-Curve--2");
+    }
+}
@@ -0,0 +1,279 @@
+//! 像素风格的 2D 精灵批量渲染
+
+use crate::load_texture::{self, AnyTexture};
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
+
+/// 一个精灵的实例数据
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct SpriteInstance {
+    /// 像素坐标下的 `[x, y, width, height]`，原点在左上角
+    pub rect: [f32; 4],
+    /// 图集上归一化的 `[u, v, width, height]`
+    pub uv_rect: [f32; 4],
+    pub tint: [f32; 4],
+}
+
+impl SpriteInstance {
+    /// 位置与尺寸取整到整像素，保证按整数倍缩放时每个纹素都对齐到屏幕像素
+    pub fn new(rect: [f32; 4], uv_rect: [f32; 4], tint: [f32; 4]) -> Self {
+        Self {
+            rect: rect.map(f32::round),
+            uv_rect,
+            tint,
+        }
+    }
+}
+
+/// 像素坐标的 2D 相机：原点在左上角，y 轴向下，一个单位对应一个物理像素
+pub fn pixel_projection(width: u32, height: u32) -> glam::Mat4 {
+    glam::Mat4::orthographic_rh(0.0, width as f32, height as f32, 0.0, -1.0, 1.0)
+}
+
+/// 把同一个图集上的所有精灵合并为一次实例化绘制
+///
+/// 每帧先调用 `draw_sprite` 收集精灵，再在渲染通道中调用 `flush` 一次性绘制并清空。
+///
+/// 图集的绑定（group 0）：
+/// - binding 0：`pixel_projection` 得到的视图投影矩阵；
+/// - binding 1：图集纹理 `texture_2d<f32>`；
+/// - binding 2：最近邻采样器，放大后的纹素保持锐利的边缘，不会被插值模糊。
+///
+/// # NOTE:
+/// - 只有精灵按整数倍缩放、且位置落在整像素上时才能得到清晰的像素效果，`draw_sprite` 会把位置与尺寸取整；
+/// - 图集不应带 mip 链：缩小显示时最近邻采样会闪烁，像素风格的画面应避免缩小精灵；
+/// - 相邻的图块之间最好留出 1 像素的间隔，避免浮点误差采样到隔壁的图块；
+/// - `flush` 通过 `queue.write_buffer` 上传实例数据，同一次提交中只能调用一次。
+pub struct SpriteBatch {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    atlas_size: (u32, u32),
+    sprites: Vec<SpriteInstance>,
+    instance_buf: wgpu::Buffer,
+    capacity: usize,
+    camera_buf: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    pipeline: wgpu::RenderPipeline,
+}
+
+#[allow(dead_code)]
+impl SpriteBatch {
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        format: wgpu::TextureFormat,
+        atlas: &AnyTexture,
+        viewport_size: (u32, u32),
+    ) -> Self {
+        let camera_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("sprite camera"),
+            contents: bytemuck::bytes_of(
+                &pixel_projection(viewport_size.0, viewport_size.1).to_cols_array_2d(),
+            ),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("sprite atlas"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        // default_sampler 为最近邻采样，边缘 ClampToEdge
+        let sampler = load_texture::default_sampler(device);
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("sprite atlas"),
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: camera_buf.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&atlas.tex_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("sprite"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("sprite.wgsl").into()),
+        });
+        let instance_attributes =
+            wgpu::vertex_attr_array![0 => Float32x4, 1 => Float32x4, 2 => Float32x4];
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("sprite pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: core::mem::size_of::<SpriteInstance>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &instance_attributes,
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        let capacity = 64;
+        Self {
+            device: device.clone(),
+            queue: queue.clone(),
+            atlas_size: (atlas.size.width, atlas.size.height),
+            sprites: Vec::with_capacity(capacity),
+            instance_buf: create_instance_buffer(device, capacity),
+            capacity,
+            camera_buf,
+            bind_group,
+            pipeline,
+        }
+    }
+
+    /// 窗口大小变化时更新相机
+    pub fn resize(&self, viewport_size: (u32, u32)) {
+        self.queue.write_buffer(
+            &self.camera_buf,
+            0,
+            bytemuck::bytes_of(
+                &pixel_projection(viewport_size.0, viewport_size.1).to_cols_array_2d(),
+            ),
+        );
+    }
+
+    /// 把图集上以纹素为单位的区域 `[x, y, width, height]` 转换为归一化的 uv 区域
+    pub fn atlas_uv(&self, texel_rect: [u32; 4]) -> [f32; 4] {
+        let (w, h) = (self.atlas_size.0 as f32, self.atlas_size.1 as f32);
+        [
+            texel_rect[0] as f32 / w,
+            texel_rect[1] as f32 / h,
+            texel_rect[2] as f32 / w,
+            texel_rect[3] as f32 / h,
+        ]
+    }
+
+    /// 添加一个精灵，`rect` 为像素坐标，`uv_rect` 为图集上归一化的区域
+    pub fn draw_sprite(&mut self, rect: [f32; 4], uv_rect: [f32; 4], tint: [f32; 4]) {
+        self.sprites.push(SpriteInstance::new(rect, uv_rect, tint));
+    }
+
+    /// 本帧已添加、尚未绘制的精灵
+    pub fn sprites(&self) -> &[SpriteInstance] {
+        &self.sprites
+    }
+
+    /// 上传实例数据，以一次实例化绘制画出所有精灵，然后清空
+    pub fn flush(&mut self, rpass: &mut wgpu::RenderPass<'_>) {
+        if self.sprites.is_empty() {
+            return;
+        }
+        if self.sprites.len() > self.capacity {
+            self.capacity = self.sprites.len().next_power_of_two();
+            self.instance_buf = create_instance_buffer(&self.device, self.capacity);
+        }
+        self.queue
+            .write_buffer(&self.instance_buf, 0, bytemuck::cast_slice(&self.sprites));
+
+        rpass.set_pipeline(&self.pipeline);
+        rpass.set_bind_group(0, &self.bind_group, &[]);
+        rpass.set_vertex_buffer(0, self.instance_buf.slice(..));
+        rpass.draw(0..4, 0..self.sprites.len() as u32);
+        self.sprites.clear();
+    }
+}
+
+pub(crate) fn create_instance_buffer(device: &wgpu::Device, capacity: usize) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("sprite instances"),
+        size: (capacity * core::mem::size_of::<SpriteInstance>()) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn instance_data_for_two_sprites() {
+        let sprites = [
+            SpriteInstance::new([10.4, 20.6, 16.0, 16.0], [0.0, 0.0, 0.25, 0.25], [1.0; 4]),
+            SpriteInstance::new(
+                [32.0, 0.0, 32.0, 48.0],
+                [0.25, 0.5, 0.25, 0.375],
+                [1.0, 0.0, 0.0, 0.5],
+            ),
+        ];
+        // 位置取整到整像素
+        assert_eq!(sprites[0].rect, [10.0, 21.0, 16.0, 16.0]);
+
+        let data: &[f32] = bytemuck::cast_slice(&sprites);
+        assert_eq!(core::mem::size_of::<SpriteInstance>(), 48);
+        assert_eq!(data.len(), 24);
+        assert_eq!(
+            &data[12..],
+            &[
+                32.0, 0.0, 32.0, 48.0, 0.25, 0.5, 0.25, 0.375, 1.0, 0.0, 0.0, 0.5
+            ]
+        );
+
+        // 左上角为原点，y 轴向下
+        let proj = pixel_projection(64, 64);
+        let ndc = proj.project_point3(glam::Vec3::new(0.0, 64.0, 0.0));
+        assert!((ndc.x + 1.0).abs() < 1e-6 && (ndc.y + 1.0).abs() < 1e-6);
+    }
+}
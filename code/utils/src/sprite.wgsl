@@ -0,0 +1,36 @@
+struct Camera {
+    view_proj: mat4x4f,
+};
+
+@group(0) @binding(0) var<uniform> camera: Camera;
+@group(0) @binding(1) var atlas: texture_2d<f32>;
+@group(0) @binding(2) var atlas_sampler: sampler;
+
+struct VertexOutput {
+    @builtin(position) position: vec4f,
+    @location(0) uv: vec2f,
+    @location(1) tint: vec4f,
+};
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex_index: u32,
+    // 像素坐标下的 x, y, width, height
+    @location(0) rect: vec4f,
+    // 图集上归一化的 u, v, width, height
+    @location(1) uv_rect: vec4f,
+    @location(2) tint: vec4f,
+) -> VertexOutput {
+    // 三角形带的 4 个角：(0, 0), (1, 0), (0, 1), (1, 1)
+    let corner = vec2f(f32(vertex_index & 1u), f32(vertex_index >> 1u));
+    var out: VertexOutput;
+    out.position = camera.view_proj * vec4f(rect.xy + corner * rect.zw, 0.0, 1.0);
+    out.uv = uv_rect.xy + corner * uv_rect.zw;
+    out.tint = tint;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4f {
+    return textureSample(atlas, atlas_sampler, in.uv) * in.tint;
+}
@@ -0,0 +1,87 @@
+/// 获取 surface 纹理失败（`render` 返回 `SurfaceError`）时框架的处理方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SurfaceErrorAction {
+    /// 跳过这一帧，下一帧照常获取；适用于暂时性的错误，如 `Timeout`（显示器休眠、窗口被遮挡时常见）
+    SkipFrame,
+    /// 调用 `WgpuAppAction::reconfigure_surface` 重新配置 surface，下一帧再重试；
+    /// 适用于 surface 与窗口不再匹配的错误，如 `Outdated`、`Lost`
+    Reconfigure,
+    /// 退出事件循环；适用于无法恢复的错误，如 `OutOfMemory`
+    Exit,
+}
+
+/// 按错误类型分别配置的处理方式
+///
+/// 默认值：`Timeout` 跳过本帧，`Outdated` 与 `Lost` 重新配置，`OutOfMemory` 退出，其它错误跳过本帧。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SurfaceErrorPolicy {
+    pub timeout: SurfaceErrorAction,
+    pub outdated: SurfaceErrorAction,
+    pub lost: SurfaceErrorAction,
+    pub out_of_memory: SurfaceErrorAction,
+    pub other: SurfaceErrorAction,
+}
+
+impl Default for SurfaceErrorPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: SurfaceErrorAction::SkipFrame,
+            outdated: SurfaceErrorAction::Reconfigure,
+            lost: SurfaceErrorAction::Reconfigure,
+            out_of_memory: SurfaceErrorAction::Exit,
+            other: SurfaceErrorAction::SkipFrame,
+        }
+    }
+}
+
+impl SurfaceErrorPolicy {
+    /// 所有错误都只打印警告并跳过本帧
+    pub fn skip_all() -> Self {
+        Self {
+            timeout: SurfaceErrorAction::SkipFrame,
+            outdated: SurfaceErrorAction::SkipFrame,
+            lost: SurfaceErrorAction::SkipFrame,
+            out_of_memory: SurfaceErrorAction::SkipFrame,
+            other: SurfaceErrorAction::SkipFrame,
+        }
+    }
+
+    pub fn with_timeout(mut self, action: SurfaceErrorAction) -> Self {
+        self.timeout = action;
+        self
+    }
+
+    /// 错误对应的处理方式
+    pub fn action(&self, error: &wgpu::SurfaceError) -> SurfaceErrorAction {
+        match error {
+            wgpu::SurfaceError::Timeout => self.timeout,
+            wgpu::SurfaceError::Outdated => self.outdated,
+            wgpu::SurfaceError::Lost => self.lost,
+            wgpu::SurfaceError::OutOfMemory => self.out_of_memory,
+            _ => self.other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn action_for_each_error() {
+        use SurfaceErrorAction::*;
+        use wgpu::SurfaceError;
+
+        let policy = SurfaceErrorPolicy::default();
+        assert_eq!(policy.action(&SurfaceError::Timeout), SkipFrame);
+        assert_eq!(policy.action(&SurfaceError::Outdated), Reconfigure);
+        assert_eq!(policy.action(&SurfaceError::Lost), Reconfigure);
+        assert_eq!(policy.action(&SurfaceError::OutOfMemory), Exit);
+        assert_eq!(policy.action(&SurfaceError::Other), SkipFrame);
+
+        // 超时时重新配置
+        let policy = SurfaceErrorPolicy::skip_all().with_timeout(Reconfigure);
+        assert_eq!(policy.action(&SurfaceError::Timeout), Reconfigure);
+        assert_eq!(policy.action(&SurfaceError::Lost), SkipFrame);
+    }
+}
@@ -0,0 +1,74 @@
+//! surface 格式的选择
+//!
+//! 示例程序常把 surface 格式切换为去掉（或加上）`Srgb` 后缀的版本，比如 web 上的 WebGPU canvas 不支持 sRGB 格式。
+//! 但并非所有适配器/surface 都同时支持一个格式的两个版本，直接切换可能得到不受支持的格式，配置 surface 时才报错。
+//! 这里先查询 `surface.get_capabilities().formats`，只有切换后的格式受支持时才切换，否则保留当前格式并打印警告。
+
+use app_surface::AppSurface;
+use wgpu::TextureFormat;
+
+/// 在 `supported` 中选择 `current` 的非 sRGB 版本，不支持时保留 `current`
+pub fn select_non_srgb(current: TextureFormat, supported: &[TextureFormat]) -> TextureFormat {
+    select_variant(current, current.remove_srgb_suffix(), supported)
+}
+
+/// 在 `supported` 中选择 `current` 的 sRGB 版本，不支持时保留 `current`
+pub fn select_srgb(current: TextureFormat, supported: &[TextureFormat]) -> TextureFormat {
+    select_variant(current, current.add_srgb_suffix(), supported)
+}
+
+fn select_variant(
+    current: TextureFormat,
+    candidate: TextureFormat,
+    supported: &[TextureFormat],
+) -> TextureFormat {
+    if candidate == current || supported.contains(&candidate) {
+        return candidate;
+    }
+    log::warn!(
+        "surface does not support {candidate:?} (supported: {supported:?}), keep using {current:?}"
+    );
+    current
+}
+
+/// 按 surface 的能力把 surface 格式切换为非 sRGB 版本，返回实际使用的格式
+///
+/// 用于替代 `app.ctx.update_config_format(app.config.format.remove_srgb_suffix())`。
+pub fn use_non_srgb_format(app: &mut AppSurface) -> TextureFormat {
+    let caps = app.surface.get_capabilities(&app.adapter);
+    let format = select_non_srgb(app.config.format, &caps.formats);
+    if format != app.config.format {
+        app.ctx.update_config_format(format);
+    }
+    format
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn caps_without_srgb_variant() {
+        let caps = [TextureFormat::Bgra8Unorm, TextureFormat::Rgba16Float];
+        assert_eq!(
+            select_non_srgb(TextureFormat::Bgra8Unorm, &caps),
+            TextureFormat::Bgra8Unorm
+        );
+        // 不支持 sRGB 版本时保留原格式
+        assert_eq!(
+            select_srgb(TextureFormat::Bgra8Unorm, &caps),
+            TextureFormat::Bgra8Unorm
+        );
+        assert_eq!(
+            select_non_srgb(
+                TextureFormat::Bgra8UnormSrgb,
+                &[TextureFormat::Bgra8UnormSrgb]
+            ),
+            TextureFormat::Bgra8UnormSrgb
+        );
+        assert_eq!(
+            select_non_srgb(TextureFormat::Bgra8UnormSrgb, &caps),
+            TextureFormat::Bgra8Unorm
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! 分块的 LOD 地形网格
+
+use crate::{Frustum, Plane, node::ViewNode, vertex::PosTex};
+use core::ops::Range;
+use glam::Vec3;
+
+/// 一个地形块
+#[derive(Clone, Debug)]
+pub struct TerrainChunk {
+    /// 块中心（世界坐标）
+    pub center: Vec3,
+    /// 包围球半径，用于视锥体剔除
+    pub radius: f32,
+    /// 各级 LOD 在共享索引缓冲区中的范围，第 0 级最精细
+    pub lod_ranges: Vec<Range<u32>>,
+    /// 本帧选中的 LOD，被剔除时为 `None`
+    pub selected_lod: Option<usize>,
+}
+
+/// 由细分平面组成的网格状地形，每块按与相机的距离选择 LOD
+///
+/// 所有块的顶点与各级索引存放在同一对顶点/索引缓冲区中（见 [`ChunkedPlane::vertices_and_indices`]），
+/// 每级 LOD 使用同一组顶点、以 2 的幂为步长跳过顶点来生成更粗糙的三角形，
+/// 绘制时每个可见块以 `ViewNode::draw_range` 绘制选中的索引范围。
+///
+/// 地形位于 xz 平面（y 向上），第 (i, j) 块的中心为 `((i + 0.5) * chunk_size, 0, (j + 0.5) * chunk_size)` 减去整体的一半，即地形以原点为中心。
+///
+/// # NOTE:
+/// 块的大小是一种权衡：
+/// - 块越小，LOD 的切换越精细、视锥体剔除越准确，但绘制调用（draw call）越多；
+/// - 块越大，绘制调用越少，但一整块只能使用同一级 LOD，远处的部分也会按近处的精度绘制。
+///
+/// 相邻块的 LOD 不同时，接缝处会出现 T 型接点导致的细小裂缝；需要时可通过限制相邻块的 LOD 差或添加裙边解决。
+pub struct ChunkedPlane {
+    /// 每隔多远降一级 LOD
+    pub lod_distance: f32,
+    pub chunks: Vec<TerrainChunk>,
+    vertices: Vec<PosTex>,
+    indices: Vec<u32>,
+}
+
+impl ChunkedPlane {
+    /// 创建 `chunks_x * chunks_z` 个边长为 `chunk_size` 的块，每块细分为 `segments * segments` 格，共 `lod_count` 级 LOD
+    ///
+    /// `segments` 需能被 `2^(lod_count - 1)` 整除，最粗糙的一级才能恰好覆盖整块。
+    pub fn new(
+        chunks_x: u32,
+        chunks_z: u32,
+        chunk_size: f32,
+        segments: u32,
+        lod_count: u32,
+    ) -> Self {
+        let lod_count = lod_count.max(1);
+        assert!(
+            segments > 0 && segments % (1 << (lod_count - 1)) == 0,
+            "segments 需能被 2^(lod_count - 1) 整除"
+        );
+        let (plane_vertices, _) = Plane::new(segments, segments).generate_vertices();
+        let half = chunk_size / 2.0;
+        let origin = Vec3::new(-(chunks_x as f32) * half, 0.0, -(chunks_z as f32) * half);
+
+        let mut vertices =
+            Vec::with_capacity(plane_vertices.len() * (chunks_x * chunks_z) as usize);
+        let mut indices = vec![];
+        let mut chunks = vec![];
+        for i in 0..chunks_x {
+            for j in 0..chunks_z {
+                let center = origin
+                    + Vec3::new(
+                        (i as f32 + 0.5) * chunk_size,
+                        0.0,
+                        (j as f32 + 0.5) * chunk_size,
+                    );
+                let base = vertices.len() as u32;
+                // 平面的 xy 映射到地形的 xz，平面的 +y 朝向 -z
+                vertices.extend(plane_vertices.iter().map(|v| PosTex {
+                    pos: [center.x + v.pos[0] * half, 0.0, center.z - v.pos[1] * half],
+                    tex_coord: v.tex_coord,
+                }));
+                let lod_ranges = (0..lod_count)
+                    .map(|lod| {
+                        let start = indices.len() as u32;
+                        indices.extend(lod_indices(segments, 1 << lod).map(|index| base + index));
+                        start..indices.len() as u32
+                    })
+                    .collect();
+                chunks.push(TerrainChunk {
+                    center,
+                    radius: half * core::f32::consts::SQRT_2,
+                    lod_ranges,
+                    selected_lod: Some(0),
+                });
+            }
+        }
+
+        Self {
+            lod_distance: chunk_size * 2.0,
+            chunks,
+            vertices,
+            indices,
+        }
+    }
+
+    /// 所有块的顶点与各级索引，用于 `ViewNodeBuilder::with_vertices_and_indices`
+    pub fn vertices_and_indices(&self) -> (Vec<PosTex>, Vec<u32>) {
+        (self.vertices.clone(), self.indices.clone())
+    }
+
+    /// 每帧调用：按块中心到相机的距离选择 LOD，传入 `frustum` 时同时剔除视锥体外的块
+    pub fn select_lods(&mut self, camera_pos: Vec3, frustum: Option<&Frustum>) {
+        let lod_distance = self.lod_distance.max(f32::EPSILON);
+        for chunk in self.chunks.iter_mut() {
+            if frustum.is_some_and(|f| !f.intersects_sphere(chunk.center, chunk.radius)) {
+                chunk.selected_lod = None;
+                continue;
+            }
+            // 以到包围球表面的距离计算，相机位于块内时总是使用最精细的一级
+            let distance = (camera_pos.distance(chunk.center) - chunk.radius).max(0.0);
+            let lod = (distance / lod_distance) as usize;
+            chunk.selected_lod = Some(lod.min(chunk.lod_ranges.len() - 1));
+        }
+    }
+
+    /// 本帧需要绘制的索引范围
+    pub fn visible_ranges(&self) -> impl Iterator<Item = Range<u32>> + '_ {
+        self.chunks
+            .iter()
+            .filter_map(|chunk| chunk.selected_lod.map(|lod| chunk.lod_ranges[lod].clone()))
+    }
+
+    /// 以 `node`（由 `vertices_and_indices` 构建）绘制所有可见块
+    pub fn draw<'a, 'b: 'a>(&self, node: &'b ViewNode, rpass: &mut wgpu::RenderPass<'b>) {
+        for range in self.visible_ranges() {
+            node.draw_range(rpass, range, 0, 1);
+        }
+    }
+}
+
+/// 以 `step` 为步长生成一块的三角形索引，顶点按 `Plane` 的布局（逐列排列）
+fn lod_indices(segments: u32, step: u32) -> impl Iterator<Item = u32> {
+    let column = segments + 1;
+    (1..=segments / step).flat_map(move |h| {
+        (1..=segments / step).flat_map(move |v| {
+            let current = h * step * column + v * step;
+            let left = current - step * column;
+            [
+                current,
+                left,
+                left - step,
+                current,
+                left - step,
+                current - step,
+            ]
+        })
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn distant_chunks_are_coarser() {
+        let mut terrain = ChunkedPlane::new(4, 1, 10.0, 8, 3);
+        assert_eq!(terrain.chunks.len(), 4);
+        // 每级的三角形数为上一级的 1/4
+        let lod_len = |lod: usize| terrain.chunks[0].lod_ranges[lod].len();
+        assert_eq!(lod_len(0), 8 * 8 * 6);
+        assert_eq!(lod_len(1), 4 * 4 * 6);
+        assert_eq!(lod_len(2), 2 * 2 * 6);
+
+        // 相机在第 0 块上方，每隔 5 个单位降一级
+        terrain.lod_distance = 5.0;
+        let camera = terrain.chunks[0].center + Vec3::Y;
+        terrain.select_lods(camera, None);
+        let lods: Vec<usize> = terrain
+            .chunks
+            .iter()
+            .map(|chunk| chunk.selected_lod.unwrap())
+            .collect();
+        assert_eq!(lods[0], 0);
+        assert!(lods.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(lods[3], 2);
+        assert_eq!(terrain.visible_ranges().count(), 4);
+    }
+
+    #[test]
+    fn coarse_lod_reuses_corner_vertices() {
+        let indices: Vec<u32> = lod_indices(2, 2).collect();
+        // 3x3 顶点中只用到四个角
+        assert_eq!(indices, vec![8, 2, 0, 8, 0, 6]);
+    }
+}
@@ -0,0 +1,457 @@
+//! 基于有向距离场（SDF）字体的文字渲染
+//!
+//! # 图集格式
+//! - 单通道或多通道纹理均可，只读取 R 通道，需为线性（非 sRGB）格式，如 `R8Unorm`、`Rgba8Unorm`；
+//! - 每个纹素存放到最近字形边缘的有向距离，映射到 [0, 1]：0.5 为边缘，大于 0.5 在字形内部，小于 0.5 在外部；
+//! - 距离场的有效范围（生成时的 spread）决定描边与发光能达到的最大宽度，一般取 4 ~ 8 个纹素；
+//! - 字形之间至少留出 spread 宽度的间隔，否则线性采样会混入相邻字形的距离；
+//! - 字形的位置与度量由 [`SdfFont`] 描述，以生成图集时的像素为单位。
+//!
+//! 与位图字体不同，距离场在放大后仍能通过 `smoothstep` 重建出清晰的边缘，所以一个图集可用于任意字号。
+
+use crate::load_texture::{self, AnyTexture};
+use crate::sprite::{SpriteInstance, create_instance_buffer, pixel_projection};
+use bytemuck::{Pod, Zeroable};
+use std::collections::HashMap;
+use wgpu::util::DeviceExt;
+
+/// 一个字形在图集上的位置与度量
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SdfGlyph {
+    /// 图集上以纹素为单位的区域 `[x, y, width, height]`
+    pub atlas_rect: [u32; 4],
+    /// 字形左上角相对于笔位置（当前行的左上角）的偏移，y 轴向下
+    pub offset: [f32; 2],
+    /// 绘制后笔位置前进的距离
+    pub advance: f32,
+}
+
+/// SDF 字体：图集尺寸与每个字符的字形
+#[derive(Clone, Debug)]
+pub struct SdfFont {
+    pub atlas_size: (u32, u32),
+    /// 生成图集时的字号（像素），`draw_text` 的 `size` 按它换算缩放比例
+    pub font_size: f32,
+    pub line_height: f32,
+    pub glyphs: HashMap<char, SdfGlyph>,
+}
+
+impl SdfFont {
+    /// 等宽的网格图集：`chars` 中的字符从左上角开始按行依次占据 `cell` 大小的单元，每行 `columns` 个
+    pub fn monospace_grid(
+        atlas_size: (u32, u32),
+        cell: (u32, u32),
+        columns: u32,
+        chars: &str,
+    ) -> Self {
+        let glyphs = chars
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                let (col, row) = (i as u32 % columns, i as u32 / columns);
+                let glyph = SdfGlyph {
+                    atlas_rect: [col * cell.0, row * cell.1, cell.0, cell.1],
+                    offset: [0.0, 0.0],
+                    advance: cell.0 as f32,
+                };
+                (c, glyph)
+            })
+            .collect();
+        Self {
+            atlas_size,
+            font_size: cell.1 as f32,
+            line_height: cell.1 as f32,
+            glyphs,
+        }
+    }
+}
+
+/// 把文字排版为字形四边形，`position` 为文字块左上角的像素坐标，`size` 为字号（像素）
+///
+/// 遇到 `\n` 换行；字体中没有的字符不绘制，只前进半个字号。
+pub fn layout_text(
+    font: &SdfFont,
+    text: &str,
+    position: [f32; 2],
+    size: f32,
+    color: [f32; 4],
+) -> Vec<SpriteInstance> {
+    let scale = size / font.font_size;
+    let (atlas_w, atlas_h) = (font.atlas_size.0 as f32, font.atlas_size.1 as f32);
+    let mut pen = position;
+    let mut quads = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        if c == '\n' {
+            pen = [position[0], pen[1] + font.line_height * scale];
+            continue;
+        }
+        let Some(glyph) = font.glyphs.get(&c) else {
+            pen[0] += font.font_size * 0.5 * scale;
+            continue;
+        };
+        let [x, y, w, h] = glyph.atlas_rect;
+        // 空格等没有面积的字形只前进
+        if w > 0 && h > 0 {
+            quads.push(SpriteInstance {
+                rect: [
+                    pen[0] + glyph.offset[0] * scale,
+                    pen[1] + glyph.offset[1] * scale,
+                    w as f32 * scale,
+                    h as f32 * scale,
+                ],
+                uv_rect: [
+                    x as f32 / atlas_w,
+                    y as f32 / atlas_h,
+                    w as f32 / atlas_w,
+                    h as f32 / atlas_h,
+                ],
+                tint: color,
+            });
+        }
+        pen[0] += glyph.advance * scale;
+    }
+    quads
+}
+
+/// 描边与发光的样式
+///
+/// 宽度以距离场的取值为单位（边缘为 0.5），不能超过图集生成时的 spread 所对应的范围。
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SdfTextStyle {
+    pub outline_width: f32,
+    pub outline_color: [f32; 4],
+    pub glow_width: f32,
+    pub glow_color: [f32; 4],
+}
+
+impl SdfTextStyle {
+    pub fn with_outline(mut self, width: f32, color: [f32; 4]) -> Self {
+        self.outline_width = width;
+        self.outline_color = color;
+        self
+    }
+
+    pub fn with_glow(mut self, width: f32, color: [f32; 4]) -> Self {
+        self.glow_width = width;
+        self.glow_color = color;
+        self
+    }
+
+    fn uniform(&self) -> SdfStyleUniform {
+        SdfStyleUniform {
+            outline_color: self.outline_color,
+            glow_color: self.glow_color,
+            outline_width: self.outline_width,
+            glow_width: self.glow_width,
+            padding: [0.0; 2],
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct SdfStyleUniform {
+    outline_color: [f32; 4],
+    glow_color: [f32; 4],
+    outline_width: f32,
+    glow_width: f32,
+    padding: [f32; 2],
+}
+
+/// 以实例化绘制渲染 SDF 文字，任意字号下边缘都清晰
+///
+/// 用法与 `SpriteBatch` 相同：每帧调用 `draw_text` 收集文字，再在渲染通道中调用 `flush`。
+///
+/// # NOTE:
+/// 图集使用线性过滤采样，距离场才能在纹素之间插值；`flush` 同一次提交中只能调用一次。
+pub struct SdfText {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    font: SdfFont,
+    glyphs: Vec<SpriteInstance>,
+    instance_buf: wgpu::Buffer,
+    capacity: usize,
+    camera_buf: wgpu::Buffer,
+    style_buf: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    pipeline: wgpu::RenderPipeline,
+}
+
+#[allow(dead_code)]
+impl SdfText {
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        format: wgpu::TextureFormat,
+        atlas: &AnyTexture,
+        font: SdfFont,
+        viewport_size: (u32, u32),
+    ) -> Self {
+        let camera_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("sdf text camera"),
+            contents: bytemuck::bytes_of(
+                &pixel_projection(viewport_size.0, viewport_size.1).to_cols_array_2d(),
+            ),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let style_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("sdf text style"),
+            contents: bytemuck::bytes_of(&SdfTextStyle::default().uniform()),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let uniform_entry = |binding, visibility| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("sdf text"),
+            entries: &[
+                uniform_entry(0, wgpu::ShaderStages::VERTEX),
+                uniform_entry(1, wgpu::ShaderStages::FRAGMENT),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let sampler = load_texture::bilinear_sampler(device);
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("sdf text"),
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: camera_buf.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: style_buf.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(&atlas.tex_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("sdf text"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("sdf_text.wgsl").into()),
+        });
+        let instance_attributes =
+            wgpu::vertex_attr_array![0 => Float32x4, 1 => Float32x4, 2 => Float32x4];
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("sdf text pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: core::mem::size_of::<SpriteInstance>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &instance_attributes,
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        let capacity = 256;
+        Self {
+            device: device.clone(),
+            queue: queue.clone(),
+            font,
+            glyphs: Vec::with_capacity(capacity),
+            instance_buf: create_instance_buffer(device, capacity),
+            capacity,
+            camera_buf,
+            style_buf,
+            bind_group,
+            pipeline,
+        }
+    }
+
+    pub fn font(&self) -> &SdfFont {
+        &self.font
+    }
+
+    /// 窗口大小变化时更新相机
+    pub fn resize(&self, viewport_size: (u32, u32)) {
+        self.queue.write_buffer(
+            &self.camera_buf,
+            0,
+            bytemuck::bytes_of(
+                &pixel_projection(viewport_size.0, viewport_size.1).to_cols_array_2d(),
+            ),
+        );
+    }
+
+    /// 设置描边与发光，对本批次的所有文字生效
+    pub fn set_style(&self, style: SdfTextStyle) {
+        self.queue
+            .write_buffer(&self.style_buf, 0, bytemuck::bytes_of(&style.uniform()));
+    }
+
+    /// 添加一段文字，`position` 为左上角的像素坐标，`size` 为字号（像素）
+    pub fn draw_text(&mut self, text: &str, position: [f32; 2], size: f32, color: [f32; 4]) {
+        let quads = layout_text(&self.font, text, position, size, color);
+        self.glyphs.extend(quads);
+    }
+
+    /// 上传字形数据，以一次实例化绘制画出所有文字，然后清空
+    pub fn flush(&mut self, rpass: &mut wgpu::RenderPass<'_>) {
+        if self.glyphs.is_empty() {
+            return;
+        }
+        if self.glyphs.len() > self.capacity {
+            self.capacity = self.glyphs.len().next_power_of_two();
+            self.instance_buf = create_instance_buffer(&self.device, self.capacity);
+        }
+        self.queue
+            .write_buffer(&self.instance_buf, 0, bytemuck::cast_slice(&self.glyphs));
+
+        rpass.set_pipeline(&self.pipeline);
+        rpass.set_bind_group(0, &self.bind_group, &[]);
+        rpass.set_vertex_buffer(0, self.instance_buf.slice(..));
+        rpass.draw(0..4, 0..self.glyphs.len() as u32);
+        self.glyphs.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn grid_font() -> SdfFont {
+        // 128x32 的图集，每个字形 32x32
+        SdfFont::monospace_grid((128, 32), (32, 32), 4, "AB C")
+    }
+
+    #[test]
+    fn glyph_quads_for_known_string() {
+        let color = [1.0, 0.5, 0.0, 1.0];
+        let quads = layout_text(&grid_font(), "AB\nC", [10.0, 20.0], 16.0, color);
+        assert_eq!(quads.len(), 3);
+        assert_eq!(quads[0].rect, [10.0, 20.0, 16.0, 16.0]);
+        assert_eq!(quads[0].uv_rect, [0.0, 0.0, 0.25, 1.0]);
+        assert_eq!(quads[1].rect, [26.0, 20.0, 16.0, 16.0]);
+        assert_eq!(quads[1].uv_rect, [0.25, 0.0, 0.25, 1.0]);
+        // 换行后回到行首
+        assert_eq!(quads[2].rect, [10.0, 36.0, 16.0, 16.0]);
+        assert_eq!(quads[2].uv_rect, [0.75, 0.0, 0.25, 1.0]);
+        assert!(quads.iter().all(|q| q.tint == color));
+
+        // 缺失的字符只前进半个字号
+        let quads = layout_text(&grid_font(), "?A", [0.0, 0.0], 32.0, color);
+        assert_eq!(quads[0].rect[0], 16.0);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn build_sdf_text() {
+        let Some((device, queue)) = crate::test_device() else {
+            return;
+        };
+
+        let atlas = load_texture::empty(
+            &device,
+            wgpu::TextureFormat::R8Unorm,
+            wgpu::Extent3d {
+                width: 128,
+                height: 32,
+                depth_or_array_layers: 1,
+            },
+            None,
+            wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            None,
+        );
+        let format = wgpu::TextureFormat::Rgba8Unorm;
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let mut text = SdfText::new(&device, &queue, format, &atlas, grid_font(), (64, 64));
+        text.set_style(
+            SdfTextStyle::default()
+                .with_outline(0.1, [0.0, 0.0, 0.0, 1.0])
+                .with_glow(0.2, [1.0, 1.0, 0.0, 0.5]),
+        );
+        text.draw_text("AB C", [0.0, 0.0], 24.0, [1.0; 4]);
+
+        let target = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: 64,
+                height: 64,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = target.create_view(&Default::default());
+        let mut encoder = device.create_command_encoder(&Default::default());
+        {
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                ..Default::default()
+            });
+            text.flush(&mut rpass);
+        }
+        queue.submit(Some(encoder.finish()));
+        let error = pollster::block_on(device.pop_error_scope());
+        assert!(error.is_none(), "{error:?}");
+    }
+}
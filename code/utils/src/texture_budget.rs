@@ -0,0 +1,142 @@
+use wgpu::TextureFormat;
+
+/// 纹理内存预算：加载带 mip 链的纹理时，按剩余预算决定跳过几个最大的 mip 层级
+///
+/// 跳过顶层 mip 后，纹理从更小的层级开始加载（基础尺寸每跳过一级减半），占用的内存约为原来的 1/4。
+/// 多个纹理共用一个预算，按加载顺序累计用量，先加载的纹理优先保留完整的分辨率。
+///
+/// # NOTE:
+/// 节省内存的代价是画质：纹理被放大到超过基础层级的分辨率时会明显模糊。
+/// 开启各向异性过滤（`anisotropy_clamp > 1`）时，倾斜的表面（如地面）沿长轴方向会采样比正视时更精细的层级，
+/// 所以这些表面比正对相机的表面更早暴露出缺失的顶层 mip；预算紧张时优先给这类纹理保留层级。
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextureBudget {
+    budget_bytes: u64,
+    used_bytes: u64,
+}
+
+#[allow(dead_code)]
+impl TextureBudget {
+    pub fn new(budget_bytes: u64) -> Self {
+        Self {
+            budget_bytes,
+            used_bytes: 0,
+        }
+    }
+
+    pub fn used_bytes(&self) -> u64 {
+        self.used_bytes
+    }
+
+    pub fn remaining_bytes(&self) -> u64 {
+        self.budget_bytes.saturating_sub(self.used_bytes)
+    }
+
+    /// 为尺寸为 `size`、有 `mip_count` 个层级的纹理预留内存，返回需要跳过的顶层 mip 数
+    ///
+    /// 至少保留最小的一个层级：即使只剩最小层级也超出预算，仍会加载它（并打印警告），不会让纹理缺失。
+    /// 压缩格式的基础尺寸必须是块尺寸的整数倍，跳过的层级数也受此限制。
+    pub fn reserve(&mut self, format: TextureFormat, size: (u32, u32), mip_count: u32) -> u32 {
+        let max_skip = max_skip_levels(format, size, mip_count);
+        let remaining = self.remaining_bytes();
+        let skip = (0..=max_skip)
+            .find(|skip| mip_chain_bytes(format, size, mip_count, *skip) <= remaining)
+            .unwrap_or_else(|| {
+                log::warn!(
+                    "texture budget exceeded: {} bytes used of {}",
+                    self.used_bytes,
+                    self.budget_bytes
+                );
+                max_skip
+            });
+        self.used_bytes += mip_chain_bytes(format, size, mip_count, skip);
+        skip
+    }
+
+    /// 纹理被销毁后归还它占用的内存
+    pub fn release(&mut self, bytes: u64) {
+        self.used_bytes = self.used_bytes.saturating_sub(bytes);
+    }
+}
+
+/// 从第 `skip` 级开始的 mip 链占用的字节数
+pub fn mip_chain_bytes(format: TextureFormat, size: (u32, u32), mip_count: u32, skip: u32) -> u64 {
+    (skip..mip_count)
+        .map(|level| mip_level_bytes(format, mip_size(size, level)) as u64)
+        .sum()
+}
+
+/// 第 `level` 级 mip 的尺寸
+pub fn mip_size(size: (u32, u32), level: u32) -> (u32, u32) {
+    ((size.0 >> level).max(1), (size.1 >> level).max(1))
+}
+
+/// 一个 mip 层级的字节数，压缩格式按块计算
+pub(crate) fn mip_level_bytes(format: TextureFormat, (width, height): (u32, u32)) -> u32 {
+    let (rows, bytes_per_row) = block_layout(format, (width, height));
+    rows * bytes_per_row
+}
+
+/// (块的行数, 每行字节数)
+pub(crate) fn block_layout(format: TextureFormat, (width, height): (u32, u32)) -> (u32, u32) {
+    let (block_w, block_h) = format.block_dimensions();
+    let block_size = format.block_copy_size(None).unwrap_or(4);
+    (
+        height.div_ceil(block_h),
+        width.div_ceil(block_w) * block_size,
+    )
+}
+
+// 最多可跳过的层级数：保留最小的层级，且基础尺寸需为块尺寸的整数倍
+fn max_skip_levels(format: TextureFormat, size: (u32, u32), mip_count: u32) -> u32 {
+    let (block_w, block_h) = format.block_dimensions();
+    let mut skip = 0;
+    while skip + 1 < mip_count {
+        let (w, h) = (size.0 >> (skip + 1), size.1 >> (skip + 1));
+        if w == 0 || h == 0 || w % block_w != 0 || h % block_h != 0 {
+            break;
+        }
+        skip += 1;
+    }
+    skip
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tiny_budget_skips_top_mips() {
+        let format = TextureFormat::Rgba8UnormSrgb;
+        // 1024x1024 的完整 mip 链约 5.6 MB
+        let mut budget = TextureBudget::new(100 * 1024);
+        let skip = budget.reserve(format, (1024, 1024), 11);
+        // 128x128 起的 mip 链约 87 KB，256x256 起的约 349 KB
+        assert_eq!(skip, 3);
+        assert_eq!(mip_size((1024, 1024), skip), (128, 128));
+        assert_eq!(
+            budget.used_bytes(),
+            mip_chain_bytes(format, (1024, 1024), 11, 3)
+        );
+
+        // 剩余约 15 KB，64x64 的纹理从 32x32 开始加载
+        assert_eq!(budget.reserve(format, (64, 64), 7), 1);
+        // 预算耗尽时只保留最小的层级
+        assert_eq!(TextureBudget::new(0).reserve(format, (64, 64), 7), 6);
+
+        // 足够的预算下完整加载
+        let mut budget = TextureBudget::new(u64::MAX);
+        assert_eq!(budget.reserve(format, (1024, 1024), 11), 0);
+    }
+
+    #[test]
+    fn compressed_base_stays_block_aligned() {
+        // BC1 以 4x4 为一块、每块 8 字节
+        let format = TextureFormat::Bc1RgbaUnormSrgb;
+        assert_eq!(mip_level_bytes(format, (16, 16)), 16 * 8);
+        assert_eq!(mip_level_bytes(format, (2, 2)), 8);
+        // 基础尺寸不能小于一个块
+        let mut budget = TextureBudget::new(0);
+        assert_eq!(budget.reserve(format, (16, 16), 5), 2);
+    }
+}
@@ -0,0 +1,103 @@
+use instant::Duration;
+
+/// 固定时间步长的累加器
+///
+/// 每帧把实际经过的时间累加起来，再按固定的步长切分出 `update` 的调用次数，
+/// 这样动画/模拟的速度只取决于真实时间，而与显示器刷新率无关。
+///
+/// # NOTE:
+/// 如果单次 `update` 耗时超过步长，累积的时间会越来越多，导致每帧需要执行的步数不断增长（spiral of death）。
+/// 所以每帧最多执行 `max_steps` 步，超出部分的累积时间直接丢弃。
+#[derive(Clone, Copy, Debug)]
+pub struct FixedTimestep {
+    step: Duration,
+    max_steps: u32,
+    accumulator: Duration,
+}
+
+#[allow(dead_code)]
+impl FixedTimestep {
+    pub fn new(step: Duration) -> Self {
+        assert!(!step.is_zero(), "step must be greater than zero");
+        Self {
+            step,
+            max_steps: 5,
+            accumulator: Duration::ZERO,
+        }
+    }
+
+    /// 每秒固定执行 `hz` 次
+    ///
+    /// `hz` 会被限制在 `1..=1_000_000_000` 内：为 0 时按每秒 1 次处理，
+    /// 超过 10 亿时步长会小于 1 纳秒而变为 0。
+    pub fn from_hz(hz: u32) -> Self {
+        Self::new(Duration::from_secs(1) / hz.clamp(1, 1_000_000_000))
+    }
+
+    pub fn with_max_steps(mut self, max_steps: u32) -> Self {
+        self.max_steps = max_steps.max(1);
+        self
+    }
+
+    pub fn step(&self) -> Duration {
+        self.step
+    }
+
+    /// 累加 `dt`，返回本帧需要执行的步数
+    pub fn advance(&mut self, dt: Duration) -> u32 {
+        self.accumulator += dt;
+        let mut steps = 0;
+        while self.accumulator >= self.step {
+            self.accumulator -= self.step;
+            steps += 1;
+            if steps == self.max_steps {
+                // 丢弃无法追上的时间
+                if self.accumulator >= self.step {
+                    self.accumulator = Duration::ZERO;
+                }
+                break;
+            }
+        }
+        steps
+    }
+
+    /// 剩余累积时间占一个步长的比例，用于在两次 update 的状态之间插值渲染
+    pub fn alpha(&self) -> f32 {
+        self.accumulator.as_secs_f32() / self.step.as_secs_f32()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn step_count_follows_wall_clock() {
+        let mut timestep = FixedTimestep::from_hz(60);
+        let frame_times_ms = [4, 4, 4, 4, 8, 16, 33, 7, 20, 50];
+        let mut total_steps = 0;
+        for ms in frame_times_ms {
+            total_steps += timestep.advance(Duration::from_millis(ms));
+        }
+        // 共 150ms，60 Hz 时应执行 9 步
+        assert_eq!(total_steps, 9);
+        assert!((0.0..1.0).contains(&timestep.alpha()));
+    }
+
+    #[test]
+    fn spiral_of_death_guard() {
+        let mut timestep = FixedTimestep::from_hz(60).with_max_steps(3);
+        assert_eq!(timestep.advance(Duration::from_secs(1)), 3);
+        assert_eq!(timestep.alpha(), 0.0);
+        assert_eq!(timestep.advance(Duration::from_millis(1)), 0);
+    }
+
+    #[test]
+    fn from_hz_clamps_out_of_range() {
+        assert_eq!(FixedTimestep::from_hz(0).step(), Duration::from_secs(1));
+        assert_eq!(
+            FixedTimestep::from_hz(u32::MAX).step(),
+            Duration::from_nanos(1)
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use winit::dpi::PhysicalPosition;
+use winit::event::TouchPhase;
+
+/// 框架记录的当前按在屏幕上的所有触点
+///
+/// 每次触摸事件后通过 `WgpuAppAction::touches_changed` 交给 app，
+/// app 可据此还原双指缩放等多点手势，如用 `pinch_distance` 在两帧之间的比值作为缩放倍数。
+#[derive(Clone, Debug, Default)]
+pub struct ActiveTouches {
+    /// 按按下的先后排列
+    touches: Vec<(u64, PhysicalPosition<f64>)>,
+    /// 正在模拟鼠标左键按下的触点
+    primary: Option<u64>,
+}
+
+/// 单指触摸转换成的鼠标事件
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum MouseEmulation {
+    Press(PhysicalPosition<f64>),
+    Move(PhysicalPosition<f64>),
+    Release,
+}
+
+impl ActiveTouches {
+    pub fn len(&self) -> usize {
+        self.touches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.touches.is_empty()
+    }
+
+    /// 按按下的先后遍历触点的 id 与位置
+    pub fn iter(&self) -> impl Iterator<Item = (u64, PhysicalPosition<f64>)> + '_ {
+        self.touches.iter().copied()
+    }
+
+    pub fn get(&self, id: u64) -> Option<PhysicalPosition<f64>> {
+        self.touches
+            .iter()
+            .find(|(touch_id, _)| *touch_id == id)
+            .map(|(_, location)| *location)
+    }
+
+    /// 最先按下的两个触点之间的距离，少于两个触点时为 `None`
+    pub fn pinch_distance(&self) -> Option<f64> {
+        let [(_, a), (_, b), ..] = self.touches.as_slice() else {
+            return None;
+        };
+        Some((a.x - b.x).hypot(a.y - b.y))
+    }
+
+    /// 记录一次触摸事件，返回单指触摸对应的鼠标事件
+    ///
+    /// 只有一个触点时按下、移动、抬起分别对应鼠标左键按下、光标移动、左键抬起；
+    /// 出现第二个触点时先抬起左键，避免双指手势被当成拖动。
+    pub(crate) fn update(
+        &mut self,
+        phase: TouchPhase,
+        id: u64,
+        location: PhysicalPosition<f64>,
+    ) -> Option<MouseEmulation> {
+        match phase {
+            TouchPhase::Started => {
+                self.touches.retain(|(touch_id, _)| *touch_id != id);
+                self.touches.push((id, location));
+                if self.touches.len() == 1 {
+                    self.primary = Some(id);
+                    Some(MouseEmulation::Press(location))
+                } else {
+                    self.primary.take().map(|_| MouseEmulation::Release)
+                }
+            }
+            TouchPhase::Moved => {
+                if let Some(touch) = self
+                    .touches
+                    .iter_mut()
+                    .find(|(touch_id, _)| *touch_id == id)
+                {
+                    touch.1 = location;
+                }
+                (self.primary == Some(id)).then_some(MouseEmulation::Move(location))
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                self.touches.retain(|(touch_id, _)| *touch_id != id);
+                if self.primary == Some(id) {
+                    self.primary = None;
+                    Some(MouseEmulation::Release)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tap_and_pinch() {
+        let at = |x, y| PhysicalPosition::new(x, y);
+        let mut touches = ActiveTouches::default();
+
+        // 单指点击模拟鼠标左键
+        assert_eq!(
+            touches.update(TouchPhase::Started, 1, at(10.0, 10.0)),
+            Some(MouseEmulation::Press(at(10.0, 10.0)))
+        );
+        assert_eq!(
+            touches.update(TouchPhase::Moved, 1, at(12.0, 10.0)),
+            Some(MouseEmulation::Move(at(12.0, 10.0)))
+        );
+        assert_eq!(
+            touches.update(TouchPhase::Ended, 1, at(12.0, 10.0)),
+            Some(MouseEmulation::Release)
+        );
+        assert!(touches.is_empty());
+
+        // 第二个手指按下时抬起模拟的左键，之后的移动只更新触点
+        touches.update(TouchPhase::Started, 2, at(0.0, 0.0));
+        assert_eq!(
+            touches.update(TouchPhase::Started, 3, at(30.0, 40.0)),
+            Some(MouseEmulation::Release)
+        );
+        assert_eq!(touches.pinch_distance(), Some(50.0));
+        assert_eq!(touches.update(TouchPhase::Moved, 2, at(0.0, 20.0)), None);
+        assert_eq!(touches.get(2), Some(at(0.0, 20.0)));
+        assert_eq!(
+            touches.update(TouchPhase::Cancelled, 3, at(30.0, 40.0)),
+            None
+        );
+        assert_eq!(touches.len(), 1);
+        assert_eq!(touches.pinch_distance(), None);
+    }
+}
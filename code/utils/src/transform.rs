@@ -0,0 +1,152 @@
+use glam::{Mat4, Quat, Vec3};
+
+/// 平移 / 旋转 / 缩放（TRS）变换
+///
+/// 组合顺序与 `Mat4::from_scale_rotation_translation` 一致：先缩放，再旋转，最后平移，
+/// 即 `matrix() = T * R * S`，作用于列向量。
+///
+/// # NOTE:
+/// 带非均匀缩放的变换在组合或求逆后可能包含切变（shear），无法精确表示为 TRS，
+/// 此时 `then` 与 `inverse` 的结果只是最接近的 TRS 分解；需要精确结果时请直接使用 `matrix()` 相乘。
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+#[allow(dead_code)]
+impl Transform {
+    pub const IDENTITY: Self = Self {
+        translation: Vec3::ZERO,
+        rotation: Quat::IDENTITY,
+        scale: Vec3::ONE,
+    };
+
+    pub fn new(translation: Vec3, rotation: Quat, scale: Vec3) -> Self {
+        Self {
+            translation,
+            rotation,
+            scale,
+        }
+    }
+
+    pub fn from_translation(translation: Vec3) -> Self {
+        Self {
+            translation,
+            ..Self::IDENTITY
+        }
+    }
+
+    pub fn from_rotation(rotation: Quat) -> Self {
+        Self {
+            rotation,
+            ..Self::IDENTITY
+        }
+    }
+
+    pub fn from_scale(scale: Vec3) -> Self {
+        Self {
+            scale,
+            ..Self::IDENTITY
+        }
+    }
+
+    pub fn with_translation(mut self, translation: Vec3) -> Self {
+        self.translation = translation;
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: Quat) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: Vec3) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// 模型矩阵：`T * R * S`
+    pub fn matrix(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(self.scale, self.rotation, self.translation)
+    }
+
+    /// 从仿射矩阵分解出 TRS
+    pub fn from_matrix(matrix: Mat4) -> Self {
+        let (scale, rotation, translation) = matrix.to_scale_rotation_translation();
+        Self {
+            translation,
+            rotation,
+            scale,
+        }
+    }
+
+    /// 先应用 `self`，再应用 `other`，即 `self.then(other).matrix() == other.matrix() * self.matrix()`
+    pub fn then(&self, other: &Transform) -> Self {
+        Self::from_matrix(other.matrix() * self.matrix())
+    }
+
+    /// 逆变换
+    pub fn inverse(&self) -> Self {
+        Self::from_matrix(self.matrix().inverse())
+    }
+
+    /// 变换一个点（包含平移）
+    pub fn transform_point(&self, point: Vec3) -> Vec3 {
+        self.translation + self.rotation * (self.scale * point)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample() -> (Transform, Transform) {
+        let a = Transform::new(
+            Vec3::new(1.0, -2.0, 3.0),
+            Quat::from_rotation_y(0.7),
+            Vec3::splat(2.0),
+        );
+        let b = Transform::new(
+            Vec3::new(-4.0, 0.5, 1.0),
+            Quat::from_euler(glam::EulerRot::XYZ, 0.3, -0.2, 1.1),
+            Vec3::splat(0.5),
+        );
+        (a, b)
+    }
+
+    #[test]
+    fn decompose_round_trip() {
+        let (a, _) = sample();
+        let t = Transform::from_matrix(a.matrix());
+        assert!(t.translation.abs_diff_eq(a.translation, 1e-5));
+        assert!(t.rotation.abs_diff_eq(a.rotation, 1e-5));
+        assert!(t.scale.abs_diff_eq(a.scale, 1e-5));
+        assert!(t.matrix().abs_diff_eq(a.matrix(), 1e-5));
+    }
+
+    #[test]
+    fn then_and_inverse() {
+        let (a, b) = sample();
+        let ab = a.then(&b);
+        assert!(ab.matrix().abs_diff_eq(b.matrix() * a.matrix(), 1e-4));
+
+        let p = Vec3::new(0.3, 0.6, -0.9);
+        assert!(
+            ab.transform_point(p)
+                .abs_diff_eq(b.transform_point(a.transform_point(p)), 1e-4)
+        );
+        assert!(
+            a.then(&a.inverse())
+                .matrix()
+                .abs_diff_eq(Mat4::IDENTITY, 1e-5)
+        );
+    }
+}
@@ -148,3 +148,226 @@ impl Vertex for PosNormalUvIndex {
         ]
     }
 }
+
+/// 带法线的顶点，用于光照
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct PosNormalTex {
+    pub pos: [f32; 3],
+    pub normal: [f32; 3],
+    pub tex_coord: [f32; 2],
+}
+
+impl PosNormalTex {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 3] =
+        wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x3, 2 => Float32x2];
+
+    pub fn vertex_f32(pos: [f32; 3], normal: [f32; 3], tex_coord: [f32; 2]) -> PosNormalTex {
+        PosNormalTex {
+            pos,
+            normal,
+            tex_coord,
+        }
+    }
+
+    /// 逐顶点步进的缓冲区布局，着色器位置依次为 0（pos）、1（normal）、2（tex_coord）
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: core::mem::size_of::<PosNormalTex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+impl Vertex for PosNormalTex {
+    fn vertex_attributes(offset: u32) -> Vec<wgpu::VertexAttribute> {
+        Self::ATTRIBUTES
+            .iter()
+            .map(|attr| wgpu::VertexAttribute {
+                shader_location: attr.shader_location + offset,
+                ..*attr
+            })
+            .collect()
+    }
+}
+
+/// 按三角形列表的索引计算逐顶点法线，覆盖 `vertices` 原有的法线
+///
+/// 每个顶点的法线为共享它的所有三角形的面法线之和再归一化；面法线未归一化，其长度为三角形面积的两倍，
+/// 所以大三角形的权重更高。三角形按逆时针（`FrontFace::Ccw`）为正面。
+///
+/// # NOTE:
+/// 只有共享同一个顶点的三角形才会被平滑，需要硬边（如立方体的棱）时各个面应使用独立的顶点。
+/// 没有被任何三角形引用或只属于退化三角形的顶点，法线为零向量。
+pub fn compute_normals(vertices: &mut [PosNormalTex], indices: &[u32]) {
+    let mut normals = vec![glam::Vec3::ZERO; vertices.len()];
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| triangle[i] as usize);
+        let [pa, pb, pc] = [a, b, c].map(|i| glam::Vec3::from(vertices[i].pos));
+        let face_normal = (pb - pa).cross(pc - pa);
+        for i in [a, b, c] {
+            normals[i] += face_normal;
+        }
+    }
+    for (vertex, normal) in vertices.iter_mut().zip(normals) {
+        vertex.normal = normal.normalize_or_zero().to_array();
+    }
+}
+
+/// 带切线空间的顶点，用于法线贴图
+///
+/// `tangent.w` 为 ±1，表示副切线的方向：`bitangent = cross(normal, tangent.xyz) * tangent.w`
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct PosNormalTexTangent {
+    pub pos: [f32; 3],
+    pub normal: [f32; 3],
+    pub tex_coord: [f32; 2],
+    pub tangent: [f32; 4],
+}
+
+impl PosNormalTexTangent {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 4] = wgpu::vertex_attr_array![
+        0 => Float32x3, 1 => Float32x3, 2 => Float32x2, 3 => Float32x4
+    ];
+
+    /// 逐顶点步进的缓冲区布局，着色器位置依次为 0（pos）、1（normal）、2（tex_coord）、3（tangent）
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: core::mem::size_of::<PosNormalTexTangent>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+impl Vertex for PosNormalTexTangent {
+    fn vertex_attributes(offset: u32) -> Vec<wgpu::VertexAttribute> {
+        Self::ATTRIBUTES
+            .iter()
+            .map(|attr| wgpu::VertexAttribute {
+                shader_location: attr.shader_location + offset,
+                ..*attr
+            })
+            .collect()
+    }
+}
+
+/// 按三角形列表的索引计算逐顶点切线，覆盖 `vertices` 原有的切线
+///
+/// 先逐三角形由位置与纹理坐标的差求出切线与副切线并累加到三个顶点上，
+/// 再对每个顶点做 Gram-Schmidt 正交化，使切线与法线垂直；`tangent.w` 由累加的副切线的朝向决定。
+///
+/// # NOTE:
+/// 法线需已就绪（如使用 [`compute_normals`] 或模型自带的法线）。
+/// 纹理坐标退化（uv 面积为 0）的三角形不参与累加；没有有效切线的顶点取任意一个与法线垂直的方向。
+pub fn compute_tangents(vertices: &mut [PosNormalTexTangent], indices: &[u32]) {
+    let mut tangents = vec![glam::Vec3::ZERO; vertices.len()];
+    let mut bitangents = vec![glam::Vec3::ZERO; vertices.len()];
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| triangle[i] as usize);
+        let [pa, pb, pc] = [a, b, c].map(|i| glam::Vec3::from(vertices[i].pos));
+        let [ta, tb, tc] = [a, b, c].map(|i| glam::Vec2::from(vertices[i].tex_coord));
+        let (edge1, edge2) = (pb - pa, pc - pa);
+        let (duv1, duv2) = (tb - ta, tc - ta);
+        let det = duv1.x * duv2.y - duv2.x * duv1.y;
+        if det.abs() <= f32::EPSILON {
+            continue;
+        }
+        let r = 1.0 / det;
+        let tangent = (edge1 * duv2.y - edge2 * duv1.y) * r;
+        let bitangent = (edge2 * duv1.x - edge1 * duv2.x) * r;
+        for i in [a, b, c] {
+            tangents[i] += tangent;
+            bitangents[i] += bitangent;
+        }
+    }
+    for (i, vertex) in vertices.iter_mut().enumerate() {
+        let normal = glam::Vec3::from(vertex.normal);
+        let tangent = (tangents[i] - normal * normal.dot(tangents[i]))
+            .try_normalize()
+            .unwrap_or_else(|| normal.any_orthonormal_vector());
+        let handedness = if normal.cross(tangent).dot(bitangents[i]) < 0.0 {
+            -1.0
+        } else {
+            1.0
+        };
+        vertex.tangent = tangent.extend(handedness).to_array();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn normals_of_folded_quad() {
+        // 两个三角形沿 x 轴对折：一个在 xy 平面，一个在 xz 平面
+        let mut vertices = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, -1.0],
+        ]
+        .map(|pos| PosNormalTex::vertex_f32(pos, [0.0; 3], [0.0; 2]));
+        compute_normals(&mut vertices, &[0, 1, 2, 0, 1, 3]);
+
+        assert_eq!(vertices[2].normal, [0.0, 0.0, 1.0]);
+        assert_eq!(vertices[3].normal, [0.0, 1.0, 0.0]);
+        // 共享的棱上两个面法线平均
+        let shared = glam::Vec3::from(vertices[0].normal);
+        assert!(shared.abs_diff_eq(glam::Vec3::new(0.0, 1.0, 1.0).normalize(), 1e-6));
+        assert_eq!(vertices[0].normal, vertices[1].normal);
+
+        assert_eq!(PosNormalTex::desc().array_stride, 32);
+        assert_eq!(PosNormalTex::vertex_attributes(3)[2].shader_location, 5);
+    }
+
+    #[test]
+    fn tangents_of_cube() {
+        // 立方体的 6 个面，每个面 4 个独立顶点；uv 的 u 沿切线、v 沿副切线 cross(normal, tangent)
+        let faces: [(glam::Vec3, glam::Vec3); 6] = [
+            (glam::Vec3::Z, glam::Vec3::X),
+            (glam::Vec3::NEG_Z, glam::Vec3::NEG_X),
+            (glam::Vec3::X, glam::Vec3::NEG_Z),
+            (glam::Vec3::NEG_X, glam::Vec3::Z),
+            (glam::Vec3::Y, glam::Vec3::X),
+            (glam::Vec3::NEG_Y, glam::Vec3::X),
+        ];
+        let mut vertices = vec![];
+        let mut indices = vec![];
+        for (normal, tangent) in faces {
+            let bitangent = normal.cross(tangent);
+            let base = vertices.len() as u32;
+            for (u, v) in [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)] {
+                let pos = normal + tangent * (u * 2.0 - 1.0) + bitangent * (v * 2.0 - 1.0);
+                vertices.push(PosNormalTexTangent {
+                    pos: pos.to_array(),
+                    normal: normal.to_array(),
+                    tex_coord: [u, v],
+                    tangent: [0.0; 4],
+                });
+            }
+            indices.extend([0, 1, 2, 0, 2, 3].map(|i| base + i));
+        }
+        compute_tangents(&mut vertices, &indices);
+
+        for (face, (normal, tangent)) in faces.iter().enumerate() {
+            for vertex in &vertices[face * 4..face * 4 + 4] {
+                let t = glam::Vec4::from(vertex.tangent);
+                assert!(t.truncate().abs_diff_eq(*tangent, 1e-5), "{face}: {t}");
+                assert_eq!(t.w, 1.0);
+                assert!(t.truncate().dot(*normal).abs() < 1e-6);
+            }
+        }
+
+        // 镜像的 uv（u 反向）使副切线反向
+        let mut mirrored = vertices[..4].to_vec();
+        mirrored
+            .iter_mut()
+            .for_each(|v| v.tex_coord[0] = 1.0 - v.tex_coord[0]);
+        compute_tangents(&mut mirrored, &[0, 1, 2, 0, 2, 3]);
+        assert_eq!(mirrored[0].tangent, [-1.0, 0.0, 0.0, -1.0]);
+    }
+}
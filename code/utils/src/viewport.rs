@@ -0,0 +1,207 @@
+use winit::dpi::PhysicalSize;
+
+/// 渲染通道的视口（及裁剪）矩形，单位为像素
+///
+/// 用于分屏、画中画等只渲染到 surface 某个子区域的场景。
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub min_depth: f32,
+    pub max_depth: f32,
+}
+
+impl Viewport {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            min_depth: 0.0,
+            max_depth: 1.0,
+        }
+    }
+
+    /// 覆盖整个 surface 的视口
+    pub fn full(surface: PhysicalSize<u32>) -> Self {
+        Self::new(0.0, 0.0, surface.width as f32, surface.height as f32)
+    }
+
+    /// surface 右下角的一个象限
+    ///
+    /// wgpu 的视口原点在左上角，+y 轴向下
+    pub fn bottom_right_quadrant(surface: PhysicalSize<u32>) -> Self {
+        let half_w = (surface.width / 2) as f32;
+        let half_h = (surface.height / 2) as f32;
+        Self::new(
+            half_w,
+            half_h,
+            surface.width as f32 - half_w,
+            surface.height as f32 - half_h,
+        )
+    }
+
+    /// surface 中居中、宽高比为 `aspect` 的最大矩形（信箱/邮筒模式）
+    ///
+    /// 视口之外的黑边由渲染通道的清屏颜色填充：`LoadOp::Clear` 不受视口与裁剪矩形影响，
+    /// 所以使用黑色清屏后再调用 `apply` 即可。
+    pub fn letterboxed(surface: PhysicalSize<u32>, aspect: f32) -> Self {
+        let (sw, sh) = (surface.width as f32, surface.height as f32);
+        if sw <= 0.0 || sh <= 0.0 || aspect <= 0.0 {
+            return Self::full(surface);
+        }
+        let (width, height) = if sw / sh > aspect {
+            // 窗口更宽：左右留黑边
+            ((sh * aspect).round(), sh)
+        } else {
+            // 窗口更高：上下留黑边
+            (sw, (sw / aspect).round())
+        };
+        Self::new(
+            ((sw - width) / 2.0).floor(),
+            ((sh - height) / 2.0).floor(),
+            width,
+            height,
+        )
+    }
+
+    /// 把窗口中的物理像素坐标（如鼠标位置）转换为视口内的 NDC 坐标
+    ///
+    /// # NOTE:
+    /// 使用信箱模式等非全屏视口时，鼠标坐标需先减去视口偏移、再除以视口尺寸，
+    /// 直接用窗口尺寸换算会得到错误的 NDC 坐标。返回值超出 [-1, 1] 表示位于视口之外（比如黑边上）。
+    pub fn to_ndc(&self, x: f32, y: f32) -> glam::Vec2 {
+        glam::Vec2::new(
+            (x - self.x) / self.width * 2.0 - 1.0,
+            1.0 - (y - self.y) / self.height * 2.0,
+        )
+    }
+
+    pub fn with_depth_range(mut self, min_depth: f32, max_depth: f32) -> Self {
+        self.min_depth = min_depth;
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// 视口宽高比
+    pub fn aspect_ratio(&self) -> f32 {
+        self.width / self.height
+    }
+
+    /// 检查矩形是否完全位于 surface 范围内
+    pub fn is_within(&self, surface: PhysicalSize<u32>) -> bool {
+        self.x >= 0.0
+            && self.y >= 0.0
+            && self.width > 0.0
+            && self.height > 0.0
+            && self.x + self.width <= surface.width as f32
+            && self.y + self.height <= surface.height as f32
+            && (0.0..=1.0).contains(&self.min_depth)
+            && (0.0..=1.0).contains(&self.max_depth)
+            && self.min_depth <= self.max_depth
+    }
+
+    /// 将矩形裁剪到 surface 范围内
+    pub fn clamped_to(&self, surface: PhysicalSize<u32>) -> Self {
+        let (sw, sh) = (surface.width as f32, surface.height as f32);
+        let x = self.x.clamp(0.0, sw);
+        let y = self.y.clamp(0.0, sh);
+        Self {
+            x,
+            y,
+            width: (self.x + self.width).clamp(x, sw) - x,
+            height: (self.y + self.height).clamp(y, sh) - y,
+            min_depth: self.min_depth.clamp(0.0, 1.0),
+            max_depth: self.max_depth.clamp(0.0, 1.0),
+        }
+    }
+
+    /// 裁剪矩形：(x, y, width, height)
+    ///
+    /// 左上与右下边缘分别四舍五入到最近的像素，相邻的两个视口（如分屏的左右两半）不会因截断而
+    /// 留下缝隙或互相重叠；负坐标按 0 处理。
+    pub fn scissor_rect(&self) -> (u32, u32, u32, u32) {
+        let x0 = self.x.round().max(0.0);
+        let y0 = self.y.round().max(0.0);
+        let x1 = (self.x + self.width).round().max(x0);
+        let y1 = (self.y + self.height).round().max(y0);
+        (x0 as u32, y0 as u32, (x1 - x0) as u32, (y1 - y0) as u32)
+    }
+
+    /// 设置渲染通道的视口与裁剪矩形，返回裁剪后的区域是否可见
+    ///
+    /// # NOTE:
+    /// 超出 render target 范围的视口会触发 wgpu 验证错误，所以这里会先把视口裁剪到 `surface` 范围内
+    /// （见 `clamped_to`），超出的部分被直接丢弃而不会报错，调用方可用 `is_within` 事先检查。
+    /// 裁剪后为空（完全在 `surface` 之外）时不修改渲染通道的任何状态并返回 false，
+    /// 此时调用方应跳过绘制，否则会绘制到之前设置的视口中。
+    pub fn apply(&self, rpass: &mut wgpu::RenderPass<'_>, surface: PhysicalSize<u32>) -> bool {
+        let vp = self.clamped_to(surface);
+        let (x, y, w, h) = vp.scissor_rect();
+        if vp.width <= 0.0 || vp.height <= 0.0 || w == 0 || h == 0 {
+            return false;
+        }
+        rpass.set_viewport(vp.x, vp.y, vp.width, vp.height, vp.min_depth, vp.max_depth);
+        rpass.set_scissor_rect(x, y, w, h);
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bottom_right_quadrant() {
+        let surface = PhysicalSize::new(800, 600);
+        let vp = Viewport::bottom_right_quadrant(surface);
+        assert_eq!(vp, Viewport::new(400.0, 300.0, 400.0, 300.0));
+        assert!(vp.is_within(surface));
+        assert_eq!(vp.scissor_rect(), (400, 300, 400, 300));
+    }
+
+    #[test]
+    fn letterbox_16_9_in_4_3() {
+        let surface = PhysicalSize::new(800, 600);
+        let vp = Viewport::letterboxed(surface, 16.0 / 9.0);
+        assert_eq!(vp, Viewport::new(0.0, 75.0, 800.0, 450.0));
+        assert!(vp.is_within(surface));
+        // 视口中心对应 NDC 原点，上边缘对应 y = 1
+        assert_eq!(vp.to_ndc(400.0, 300.0), glam::Vec2::ZERO);
+        assert_eq!(vp.to_ndc(0.0, 75.0), glam::Vec2::new(-1.0, 1.0));
+
+        // 窗口更宽时左右留黑边
+        let vp = Viewport::letterboxed(PhysicalSize::new(1000, 300), 2.0);
+        assert_eq!(vp, Viewport::new(200.0, 0.0, 600.0, 300.0));
+    }
+
+    #[test]
+    fn clamp_out_of_bounds() {
+        let surface = PhysicalSize::new(800, 600);
+        let vp = Viewport::new(600.0, -100.0, 400.0, 400.0);
+        assert!(!vp.is_within(surface));
+        let clamped = vp.clamped_to(surface);
+        assert_eq!(clamped, Viewport::new(600.0, 0.0, 200.0, 300.0));
+        assert!(clamped.is_within(surface));
+    }
+
+    #[test]
+    fn scissor_rect_rounds_edges() {
+        // 截断会得到 (10, 0, 20, 1)，右边缘 30.8 丢失了将近一个像素
+        let vp = Viewport::new(10.4, 0.0, 20.4, 1.0);
+        assert_eq!(vp.scissor_rect(), (10, 0, 21, 1));
+
+        // 相邻的两个视口在 33.5 处相接，裁剪矩形既不留缝也不重叠
+        let (left, right) = (
+            Viewport::new(0.0, 0.0, 33.5, 10.0),
+            Viewport::new(33.5, 0.0, 33.5, 10.0),
+        );
+        let (lx, _, lw, _) = left.scissor_rect();
+        let (rx, _, rw, _) = right.scissor_rect();
+        assert_eq!(lx + lw, rx);
+        assert_eq!(rx + rw, 67);
+    }
+}